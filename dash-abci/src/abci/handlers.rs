@@ -34,10 +34,13 @@
 
 use crate::abci::messages::{
     AfterFinalizeBlockRequest, AfterFinalizeBlockResponse, BlockBeginRequest, BlockBeginResponse,
-    BlockEndRequest, BlockEndResponse, InitChainRequest, InitChainResponse,
+    BlockEndRequest, BlockEndResponse, InfoRequest, InfoResponse, InitChainRequest,
+    InitChainResponse, Serializable,
 };
 use crate::block::{BlockExecutionContext, BlockInfo};
 use crate::execution::fee_pools::epoch::EpochInfo;
+use crate::execution::protocol_version::INITIAL_PROTOCOL_VERSION;
+use rs_drive::drive::batch::GroveDbOpBatch;
 use rs_drive::grovedb::TransactionArg;
 
 use crate::error::execution::ExecutionError;
@@ -46,6 +49,9 @@ use crate::platform::Platform;
 
 /// A trait for handling the Tenderdash ABCI (Application Blockchain Interface).
 pub trait TenderdashAbci {
+    /// Called with JS Drive on Tenderdash handshake to get the last committed block
+    fn info(&self, request: InfoRequest) -> Result<InfoResponse, Error>;
+
     /// Called with JS drive on init chain
     fn init_chain(
         &self,
@@ -75,6 +81,24 @@ pub trait TenderdashAbci {
 }
 
 impl TenderdashAbci for Platform {
+    /// Returns height and app hash of the last committed block
+    fn info(&self, _request: InfoRequest) -> Result<InfoResponse, Error> {
+        let last_block_app_hash = self.drive.fetch_last_app_hash(None)?;
+
+        let response = match last_block_app_hash {
+            Some((last_block_height, last_block_app_hash)) => InfoResponse {
+                last_block_height,
+                last_block_app_hash: Some(last_block_app_hash),
+            },
+            None => InfoResponse {
+                last_block_height: 0,
+                last_block_app_hash: None,
+            },
+        };
+
+        Ok(response)
+    }
+
     /// Creates initial state structure and returns response
     fn init_chain(
         &self,
@@ -85,6 +109,13 @@ impl TenderdashAbci for Platform {
             .create_initial_state_structure(transaction)
             .map_err(Error::Drive)?;
 
+        self.drive
+            .update_protocol_version(INITIAL_PROTOCOL_VERSION, transaction)?;
+
+        // Chain parameters can't be changed during the chain lifetime
+        self.drive
+            .store_platform_config(self.config.to_bytes()?, transaction)?;
+
         let response = InitChainResponse {};
 
         Ok(response)
@@ -96,6 +127,21 @@ impl TenderdashAbci for Platform {
         request: BlockBeginRequest,
         transaction: TransactionArg,
     ) -> Result<BlockBeginResponse, Error> {
+        // Derive previous block time from the last committed block
+        // so an interrupted block is replayed with the same epoch info
+        let previous_block_time_ms = match self.last_block_execution_context.borrow().as_ref() {
+            Some(last_block_execution_context) => {
+                if request.block_height <= last_block_execution_context.block_info.block_height {
+                    return Err(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                        "block height must be greater than the last committed block height",
+                    )));
+                }
+
+                Some(last_block_execution_context.block_info.block_time_ms)
+            }
+            None => None,
+        };
+
         // Set genesis time
         let genesis_time_ms = if request.block_height == 1 {
             self.drive
@@ -111,34 +157,81 @@ impl TenderdashAbci for Platform {
         };
 
         // Init block execution context
-        let block_info = BlockInfo::from_block_begin_request(&request);
+        let block_info = BlockInfo::from_block_begin_request(&request, previous_block_time_ms);
+
+        let epoch_info = EpochInfo::from_genesis_time_and_block_info(
+            genesis_time_ms,
+            &block_info,
+            self.config.epoch_change_time_ms,
+        )?;
+
+        // Chains started with a previous version don't have the state structure added since
+        self.drive.create_missing_state_structure(transaction)?;
+
+        // Masternode list and masternode identities are updated first, so the block is executed
+        // against the masternode list Core had at the block's chain locked height
+        self.update_masternode_list(
+            &request.masternode_list_diff,
+            block_info.block_time_ms,
+            epoch_info.current_epoch_index,
+            transaction,
+        )?;
+
+        // Activate a new protocol version on epoch change if enough proposers support it
+        let protocol_version = self.process_protocol_version_upgrade(&epoch_info, transaction)?;
+
+        // Fetch feature flags once, so they are cached for the whole block
+        let feature_flags = self.get_feature_flags_for_height(request.block_height, transaction)?;
+
+        // Consensus params feature flag may set the application version starting from the block
+        let protocol_version = self.apply_feature_flags_protocol_version(
+            &feature_flags,
+            protocol_version,
+            transaction,
+        )?;
 
-        let epoch_info = EpochInfo::from_genesis_time_and_block_info(genesis_time_ms, &block_info)?;
+        // Fee multiplier is chosen once per block, so it's consistent for all block operations
+        let fee_multiplier = self.get_current_epoch_fee_multiplier(&epoch_info, transaction)?;
 
         let block_execution_context = BlockExecutionContext {
             block_info,
             epoch_info: epoch_info.clone(),
+            protocol_version,
+            fee_multiplier,
+            feature_flags,
         };
 
         self.block_execution_context
             .replace(Some(block_execution_context));
 
+        // Withdrawals which are complete or can't be mined anymore are resolved
+        // before new ones are pooled
+        self.update_withdrawal_transaction_statuses(
+            request.core_chain_locked_height,
+            &request.broadcasted_withdrawal_transaction_indexes,
+            &request.completed_withdrawal_transaction_indexes,
+            transaction,
+        )?;
+
         let unsigned_withdrawal_transaction_bytes = self
             .fetch_and_prepare_unsigned_withdrawal_transactions(
-                request.block_height as u32,
+                request.core_chain_locked_height,
+                request.block_time_ms,
                 request.validator_set_quorum_hash,
                 transaction,
             )?;
 
         let response = BlockBeginResponse {
             epoch_info,
+            protocol_version,
+            fee_multiplier,
             unsigned_withdrawal_transactions: unsigned_withdrawal_transaction_bytes,
         };
 
         Ok(response)
     }
 
-    /// Processes block fees and returns response
+    /// Processes block fees, calculates app hash and returns response
     fn block_end(
         &self,
         request: BlockEndRequest,
@@ -156,12 +249,48 @@ impl TenderdashAbci for Platform {
         let process_block_fees_result = self.process_block_fees(
             &block_execution_context.block_info,
             &block_execution_context.epoch_info,
+            block_execution_context.fee_multiplier,
             &request.fees,
             transaction,
         )?;
 
+        // Store block info and epoch info, so they will be restored
+        // as the last committed ones after restart
+        let mut batch = GroveDbOpBatch::new();
+
+        // Withdrawal requests collected during the block are pooled into withdrawal transactions
+        self.drive
+            .add_pool_withdrawal_requests_operations(transaction, &mut batch)?;
+
+        self.drive
+            .add_update_last_block_execution_context_operations(
+                &mut batch,
+                block_execution_context.block_info.to_bytes()?,
+                block_execution_context.epoch_info.to_bytes()?,
+            );
+
+        self.drive.grove_apply_batch(batch, false, transaction)?;
+
+        if self.drive.config.verify_credit_conservation {
+            self.drive.verify_credit_conservation(transaction)?;
+        }
+
+        // All block state changes are applied, so we can calculate the app hash
+        let app_hash = self.drive.calculate_app_hash(transaction)?;
+
+        self.drive.store_app_hash(
+            block_execution_context.block_info.block_height,
+            app_hash,
+            transaction,
+        )?;
+
         Ok(BlockEndResponse::from_process_block_fees_result(
             &process_block_fees_result,
+            app_hash,
+            block_execution_context
+                .feature_flags
+                .consensus_params_update
+                .clone(),
         ))
     }
 
@@ -173,6 +302,12 @@ impl TenderdashAbci for Platform {
 
         drive_cache.cached_contracts.clear_all_transactional_cache();
 
+        // The block is committed, so it becomes the last one
+        if let Some(block_execution_context) = self.block_execution_context.take() {
+            self.last_block_execution_context
+                .replace(Some(block_execution_context));
+        }
+
         Ok(AfterFinalizeBlockResponse {})
     }
 }
@@ -181,18 +316,29 @@ impl TenderdashAbci for Platform {
 mod tests {
     mod handlers {
         use crate::abci::handlers::TenderdashAbci;
+        use crate::common::helpers::feature_flags::create_test_update_consensus_params_document;
         use crate::common::helpers::fee_pools::create_test_masternode_share_identities_and_documents;
         use chrono::{Duration, Utc};
-        use rs_drive::common::helpers::identities::create_test_masternode_identities;
+        use rs_drive::common::helpers::identities::{
+            create_test_identity, create_test_masternode_identities,
+        };
         use rs_drive::drive::batch::GroveDbOpBatch;
+        use rs_drive::drive::block_info::BlockInfo as DriveBlockInfo;
+        use rs_drive::drive::identity::withdrawal_status::WithdrawalTransactionRecord;
         use rust_decimal::prelude::ToPrimitive;
         use std::ops::Div;
 
         use crate::abci::messages::{
             AfterFinalizeBlockRequest, BlockBeginRequest, BlockEndRequest, FeesAggregate,
-            InitChainRequest,
+            InfoRequest, InitChainRequest,
         };
         use crate::common::helpers::setup::setup_platform;
+        use crate::config::PlatformConfig;
+        use crate::contracts::feature_flags::BlockConsensusParams;
+        use crate::error::execution::ExecutionError;
+        use crate::error::Error;
+        use crate::platform::Platform;
+        use tempfile::TempDir;
 
         #[test]
         fn test_abci_flow() {
@@ -208,14 +354,20 @@ mod tests {
 
             // Init withdrawal requests
             let withdrawals = (0..16)
-                .map(|index: u64| (index.to_be_bytes().to_vec(), vec![index as u8; 32]))
+                .map(|index: u64| {
+                    (
+                        WithdrawalTransactionRecord::new_queued(index, [index as u8; 32], 100),
+                        vec![index as u8; 32],
+                    )
+                })
                 .collect();
 
             let mut batch = GroveDbOpBatch::new();
 
             platform
                 .drive
-                .add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals);
+                .add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals)
+                .expect("to add enqueue operations");
 
             platform
                 .drive
@@ -255,8 +407,6 @@ mod tests {
 
             let block_interval = 86400i64.div(blocks_per_day);
 
-            let mut previous_block_time_ms: Option<u64> = None;
-
             // process blocks
             for day in 0..total_days {
                 for block_num in 0..blocks_per_day {
@@ -279,10 +429,14 @@ mod tests {
                     let block_begin_request = BlockBeginRequest {
                         block_height,
                         block_time_ms,
-                        previous_block_time_ms,
                         proposer_pro_tx_hash: proposers
                             [block_height as usize % (proposers_count as usize)],
                         validator_set_quorum_hash: Default::default(),
+                        proposed_protocol_version: 1,
+                        core_chain_locked_height: 1,
+                        broadcasted_withdrawal_transaction_indexes: vec![],
+                        completed_withdrawal_transaction_indexes: vec![],
+                        masternode_list_diff: Default::default(),
                     };
 
                     let block_begin_response = platform
@@ -294,9 +448,6 @@ mod tests {
                             )
                         });
 
-                    // Set previous block time
-                    previous_block_time_ms = Some(block_time_ms);
-
                     // Should calculate correct current epochs
                     let (epoch_index, epoch_change) = if day > epoch_1_start_day {
                         (1, false)
@@ -446,8 +597,6 @@ mod tests {
 
             let block_interval = 86400i64.div(blocks_per_day);
 
-            let mut previous_block_time_ms: Option<u64> = None;
-
             // process blocks
             for day in [0, 1, 2, 3, 37] {
                 for block_num in 0..blocks_per_day {
@@ -470,10 +619,14 @@ mod tests {
                     let block_begin_request = BlockBeginRequest {
                         block_height,
                         block_time_ms,
-                        previous_block_time_ms,
                         proposer_pro_tx_hash: proposers
                             [block_height as usize % (proposers_count as usize)],
                         validator_set_quorum_hash: Default::default(),
+                        proposed_protocol_version: 1,
+                        core_chain_locked_height: 1,
+                        broadcasted_withdrawal_transaction_indexes: vec![],
+                        completed_withdrawal_transaction_indexes: vec![],
+                        masternode_list_diff: Default::default(),
                     };
 
                     let block_begin_response = platform
@@ -485,9 +638,6 @@ mod tests {
                             )
                         });
 
-                    // Set previous block time
-                    previous_block_time_ms = Some(block_time_ms);
-
                     // Should calculate correct current epochs
                     let (epoch_index, epoch_change) = if day == epoch_2_start_day {
                         if block_num == 0 {
@@ -557,5 +707,450 @@ mod tests {
                 }
             }
         }
+
+        #[test]
+        fn test_last_block_execution_context_is_restored_on_open() {
+            let tmp_dir = TempDir::new().unwrap();
+
+            let genesis_time_ms = Utc::now()
+                .timestamp_millis()
+                .to_u64()
+                .expect("block time can not be before 1970");
+
+            {
+                let platform = Platform::open(tmp_dir.path(), None).expect("should open Platform");
+
+                assert!(platform.last_block_execution_context.borrow().is_none());
+
+                platform
+                    .init_chain(InitChainRequest {}, None)
+                    .expect("should init chain");
+
+                let transaction = platform.drive.grove.start_transaction();
+
+                let block_begin_request = BlockBeginRequest {
+                    block_height: 1,
+                    block_time_ms: genesis_time_ms,
+                    proposer_pro_tx_hash: [1; 32],
+                    validator_set_quorum_hash: Default::default(),
+                    proposed_protocol_version: 1,
+                    core_chain_locked_height: 1,
+                    broadcasted_withdrawal_transaction_indexes: vec![],
+                    completed_withdrawal_transaction_indexes: vec![],
+                    masternode_list_diff: Default::default(),
+                };
+
+                platform
+                    .block_begin(block_begin_request, Some(&transaction))
+                    .expect("should begin process block");
+
+                let block_end_request = BlockEndRequest {
+                    fees: FeesAggregate {
+                        processing_fees: 1600,
+                        storage_fees: 42000,
+                    },
+                };
+
+                platform
+                    .block_end(block_end_request, Some(&transaction))
+                    .expect("should end process block");
+
+                platform
+                    .drive
+                    .commit_transaction(transaction)
+                    .expect("should commit transaction");
+
+                platform
+                    .after_finalize_block(AfterFinalizeBlockRequest {
+                        updated_data_contract_ids: Vec::new(),
+                    })
+                    .expect("should finalize block");
+
+                // Begin the next block, but never commit it
+                let transaction = platform.drive.grove.start_transaction();
+
+                let block_begin_request = BlockBeginRequest {
+                    block_height: 2,
+                    block_time_ms: genesis_time_ms + 100,
+                    proposer_pro_tx_hash: [1; 32],
+                    validator_set_quorum_hash: Default::default(),
+                    proposed_protocol_version: 1,
+                    core_chain_locked_height: 1,
+                    broadcasted_withdrawal_transaction_indexes: vec![],
+                    completed_withdrawal_transaction_indexes: vec![],
+                    masternode_list_diff: Default::default(),
+                };
+
+                platform
+                    .block_begin(block_begin_request, Some(&transaction))
+                    .expect("should begin process block");
+            }
+
+            let platform = Platform::open(tmp_dir.path(), None).expect("should open Platform");
+
+            {
+                let last_block_execution_context = platform.last_block_execution_context.borrow();
+
+                let last_block_execution_context = last_block_execution_context
+                    .as_ref()
+                    .expect("last block execution context should be restored");
+
+                assert_eq!(last_block_execution_context.block_info.block_height, 1);
+                assert_eq!(
+                    last_block_execution_context.block_info.block_time_ms,
+                    genesis_time_ms
+                );
+                assert_eq!(
+                    last_block_execution_context.epoch_info.current_epoch_index,
+                    0
+                );
+            }
+
+            // Replay the interrupted block
+            let transaction = platform.drive.grove.start_transaction();
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 2,
+                block_time_ms: genesis_time_ms + 100,
+                proposer_pro_tx_hash: [1; 32],
+                validator_set_quorum_hash: Default::default(),
+                proposed_protocol_version: 1,
+                core_chain_locked_height: 1,
+                broadcasted_withdrawal_transaction_indexes: vec![],
+                completed_withdrawal_transaction_indexes: vec![],
+                masternode_list_diff: Default::default(),
+            };
+
+            let block_begin_response = platform
+                .block_begin(block_begin_request, Some(&transaction))
+                .expect("should begin process block");
+
+            assert_eq!(block_begin_response.epoch_info.current_epoch_index, 0);
+            assert!(!block_begin_response.epoch_info.is_epoch_change);
+
+            let block_execution_context = platform.block_execution_context.borrow();
+
+            let block_execution_context = block_execution_context
+                .as_ref()
+                .expect("block execution context should be set");
+
+            assert_eq!(
+                block_execution_context.block_info.previous_block_time_ms,
+                Some(genesis_time_ms)
+            );
+        }
+
+        #[test]
+        fn test_info_returns_last_committed_app_hash() {
+            let platform = setup_platform();
+
+            platform
+                .init_chain(InitChainRequest {}, None)
+                .expect("should init chain");
+
+            let info_response = platform.info(InfoRequest {}).expect("should return info");
+
+            assert_eq!(info_response.last_block_height, 0);
+            assert!(info_response.last_block_app_hash.is_none());
+
+            let transaction = platform.drive.grove.start_transaction();
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 1,
+                block_time_ms: Utc::now()
+                    .timestamp_millis()
+                    .to_u64()
+                    .expect("block time can not be before 1970"),
+                proposer_pro_tx_hash: [1; 32],
+                validator_set_quorum_hash: Default::default(),
+                proposed_protocol_version: 1,
+                core_chain_locked_height: 1,
+                broadcasted_withdrawal_transaction_indexes: vec![],
+                completed_withdrawal_transaction_indexes: vec![],
+                masternode_list_diff: Default::default(),
+            };
+
+            platform
+                .block_begin(block_begin_request, Some(&transaction))
+                .expect("should begin process block");
+
+            let block_end_request = BlockEndRequest {
+                fees: FeesAggregate {
+                    processing_fees: 1600,
+                    storage_fees: 42000,
+                },
+            };
+
+            let block_end_response = platform
+                .block_end(block_end_request, Some(&transaction))
+                .expect("should end process block");
+
+            let app_hash = platform
+                .drive
+                .calculate_app_hash(Some(&transaction))
+                .expect("should calculate app hash");
+
+            assert_eq!(block_end_response.app_hash, app_hash);
+
+            platform
+                .drive
+                .commit_transaction(transaction)
+                .expect("should commit transaction");
+
+            platform
+                .after_finalize_block(AfterFinalizeBlockRequest {
+                    updated_data_contract_ids: Vec::new(),
+                })
+                .expect("should finalize block");
+
+            // Committed state must have the same app hash
+            let committed_app_hash = platform
+                .drive
+                .calculate_app_hash(None)
+                .expect("should calculate app hash");
+
+            assert_eq!(committed_app_hash, app_hash);
+
+            let info_response = platform.info(InfoRequest {}).expect("should return info");
+
+            assert_eq!(info_response.last_block_height, 1);
+            assert_eq!(info_response.last_block_app_hash, Some(app_hash));
+        }
+
+        #[test]
+        fn test_block_begin_fails_for_already_committed_height() {
+            let platform = setup_platform();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform
+                .init_chain(InitChainRequest {}, Some(&transaction))
+                .expect("should init chain");
+
+            let genesis_time_ms = Utc::now()
+                .timestamp_millis()
+                .to_u64()
+                .expect("block time can not be before 1970");
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 1,
+                block_time_ms: genesis_time_ms,
+                proposer_pro_tx_hash: [1; 32],
+                validator_set_quorum_hash: Default::default(),
+                proposed_protocol_version: 1,
+                core_chain_locked_height: 1,
+                broadcasted_withdrawal_transaction_indexes: vec![],
+                completed_withdrawal_transaction_indexes: vec![],
+                masternode_list_diff: Default::default(),
+            };
+
+            platform
+                .block_begin(block_begin_request, Some(&transaction))
+                .expect("should begin process block");
+
+            let block_end_request = BlockEndRequest {
+                fees: FeesAggregate {
+                    processing_fees: 1600,
+                    storage_fees: 42000,
+                },
+            };
+
+            platform
+                .block_end(block_end_request, Some(&transaction))
+                .expect("should end process block");
+
+            platform
+                .after_finalize_block(AfterFinalizeBlockRequest {
+                    updated_data_contract_ids: Vec::new(),
+                })
+                .expect("should finalize block");
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 1,
+                block_time_ms: genesis_time_ms + 100,
+                proposer_pro_tx_hash: [1; 32],
+                validator_set_quorum_hash: Default::default(),
+                proposed_protocol_version: 1,
+                core_chain_locked_height: 1,
+                broadcasted_withdrawal_transaction_indexes: vec![],
+                completed_withdrawal_transaction_indexes: vec![],
+                masternode_list_diff: Default::default(),
+            };
+
+            let result = platform.block_begin(block_begin_request, Some(&transaction));
+
+            assert!(matches!(
+                result,
+                Err(Error::Execution(ExecutionError::CorruptedCodeExecution(_)))
+            ));
+        }
+
+        #[test]
+        fn test_block_end_returns_consensus_param_updates_from_feature_flags() {
+            let platform = setup_platform();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform
+                .init_chain(InitChainRequest {}, Some(&transaction))
+                .expect("should init chain");
+
+            let contract = platform.create_feature_flags_contract(Some(&transaction));
+
+            create_test_update_consensus_params_document(
+                &platform.drive,
+                &contract,
+                1,
+                1024,
+                Some(&transaction),
+            );
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 1,
+                block_time_ms: Utc::now()
+                    .timestamp_millis()
+                    .to_u64()
+                    .expect("block time can not be before 1970"),
+                proposer_pro_tx_hash: [1; 32],
+                validator_set_quorum_hash: Default::default(),
+                proposed_protocol_version: 1,
+                core_chain_locked_height: 1,
+                broadcasted_withdrawal_transaction_indexes: vec![],
+                completed_withdrawal_transaction_indexes: vec![],
+                masternode_list_diff: Default::default(),
+            };
+
+            platform
+                .block_begin(block_begin_request, Some(&transaction))
+                .expect("should begin process block");
+
+            let block_end_request = BlockEndRequest {
+                fees: FeesAggregate {
+                    processing_fees: 1600,
+                    storage_fees: 42000,
+                },
+            };
+
+            let block_end_response = platform
+                .block_end(block_end_request, Some(&transaction))
+                .expect("should end process block");
+
+            let consensus_param_updates = block_end_response
+                .consensus_param_updates
+                .expect("should return consensus param updates");
+
+            assert_eq!(
+                consensus_param_updates.block,
+                Some(BlockConsensusParams {
+                    max_bytes: Some(1024),
+                    max_gas: None,
+                })
+            );
+        }
+
+        #[test]
+        fn test_platform_config_is_restored_on_open() {
+            let tmp_dir = TempDir::new().unwrap();
+
+            {
+                let config = PlatformConfig {
+                    epoch_change_time_ms: 60000,
+                    ..Default::default()
+                };
+
+                let platform =
+                    Platform::open(tmp_dir.path(), Some(config)).expect("should open Platform");
+
+                platform
+                    .init_chain(InitChainRequest {}, None)
+                    .expect("should init chain");
+            }
+
+            // Chain parameters of a running chain must not be changed by a local config
+            let platform = Platform::open(tmp_dir.path(), Some(PlatformConfig::default()))
+                .expect("should open Platform");
+
+            assert_eq!(platform.config.epoch_change_time_ms, 60000);
+        }
+
+        #[test]
+        fn test_block_end_verifies_credit_conservation() {
+            let tmp_dir = TempDir::new().unwrap();
+
+            let mut config = PlatformConfig::default();
+
+            config.drive.verify_credit_conservation = true;
+
+            let platform =
+                Platform::open(tmp_dir.path(), Some(config)).expect("should open Platform");
+
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform
+                .init_chain(InitChainRequest {}, Some(&transaction))
+                .expect("should init chain");
+
+            let identity = create_test_identity(&platform.drive, [1; 32], Some(&transaction));
+
+            platform
+                .drive
+                .add_to_identity_balance(
+                    &identity.id.buffer,
+                    1000,
+                    DriveBlockInfo::default(),
+                    true,
+                    Some(&transaction),
+                )
+                .expect("should top up identity");
+
+            // fees debited from the identity during the block
+            platform
+                .drive
+                .remove_from_identity_balance(
+                    &identity.id.buffer,
+                    100,
+                    DriveBlockInfo::default(),
+                    true,
+                    Some(&transaction),
+                )
+                .expect("should debit identity");
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 1,
+                block_time_ms: Utc::now()
+                    .timestamp_millis()
+                    .to_u64()
+                    .expect("block time can not be before 1970"),
+                proposer_pro_tx_hash: [1; 32],
+                validator_set_quorum_hash: Default::default(),
+                proposed_protocol_version: 1,
+                core_chain_locked_height: 1,
+                broadcasted_withdrawal_transaction_indexes: vec![],
+                completed_withdrawal_transaction_indexes: vec![],
+                masternode_list_diff: Default::default(),
+            };
+
+            platform
+                .block_begin(block_begin_request, Some(&transaction))
+                .expect("should begin block");
+
+            let block_end_request = BlockEndRequest {
+                fees: FeesAggregate {
+                    processing_fees: 60,
+                    storage_fees: 40,
+                },
+            };
+
+            platform
+                .block_end(block_end_request, Some(&transaction))
+                .expect("credits should be conserved");
+
+            let credits_ledger = platform
+                .drive
+                .fetch_credits_ledger(Some(&transaction))
+                .expect("should fetch credits ledger");
+
+            assert_eq!(credits_ledger.total_minted, 1000);
+            assert_eq!(credits_ledger.in_pools, 100);
+            assert_eq!(credits_ledger.in_identities, 900);
+        }
     }
 }
@@ -33,12 +33,29 @@
 //! as well as defining and implementing the trait for serializing/deserializing them.
 //!
 
+use crate::contracts::feature_flags::ConsensusParamsUpdate;
 use crate::error::serialization::SerializationError;
 use crate::error::Error;
 use crate::execution::fee_pools::epoch::EpochInfo;
 use crate::execution::fee_pools::process_block_fees::ProcessedBlockFeesResult;
+use rs_drive::drive::masternode_list::MasternodeListDiff;
 use serde::{Deserialize, Serialize};
 
+/// A struct for handling info requests
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoRequest {}
+
+/// A struct for handling info responses
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoResponse {
+    /// Height of the last committed block, 0 if there is no committed blocks yet
+    pub last_block_height: u64,
+    /// App hash of the last committed block
+    pub last_block_app_hash: Option<[u8; 32]>,
+}
+
 /// A struct for handling chain initialization requests
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -57,12 +74,21 @@ pub struct BlockBeginRequest {
     pub block_height: u64,
     /// Block time in ms
     pub block_time_ms: u64,
-    /// Previous block time in ms
-    pub previous_block_time_ms: Option<u64>,
     /// The block proposer's proTxHash
     pub proposer_pro_tx_hash: [u8; 32],
     /// Validator set quorum hash
     pub validator_set_quorum_hash: [u8; 32],
+    /// Protocol version supported by the block proposer
+    pub proposed_protocol_version: u32,
+    /// Core chain locked height
+    pub core_chain_locked_height: u32,
+    /// Indexes of asset unlock transactions seen by Core but not chain locked yet
+    pub broadcasted_withdrawal_transaction_indexes: Vec<u64>,
+    /// Indexes of asset unlock transactions chain locked by Core
+    pub completed_withdrawal_transaction_indexes: Vec<u64>,
+    /// Simplified masternode list changes since the previous block
+    #[serde(default)]
+    pub masternode_list_diff: MasternodeListDiff,
 }
 
 /// A struct for handling block begin responses
@@ -71,6 +97,11 @@ pub struct BlockBeginRequest {
 pub struct BlockBeginResponse {
     /// Fee epoch info
     pub epoch_info: EpochInfo,
+    /// Active protocol version, selects the fee schedule state transitions are charged with
+    pub protocol_version: u32,
+    /// Fee multiplier of the current epoch to be applied to processing costs of operations
+    /// and state transitions
+    pub fee_multiplier: f64,
     /// List of unsigned withdrawal transaction bytes
     pub unsigned_withdrawal_transactions: Vec<Vec<u8>>,
 }
@@ -106,12 +137,19 @@ pub struct BlockEndResponse {
     pub proposers_paid_count: Option<u16>,
     /// Index of the last epoch that marked as paid
     pub paid_epoch_index: Option<u16>,
+    /// App hash of the block
+    pub app_hash: [u8; 32],
+    /// Consensus params to be updated by Tenderdash
+    pub consensus_param_updates: Option<ConsensusParamsUpdate>,
 }
 
 impl BlockEndResponse {
-    /// Retrieves fee info for the block to be implemented in the BlockEndResponse
+    /// Retrieves fee info, app hash and consensus param updates for the block
+    /// to be implemented in the BlockEndResponse
     pub(crate) fn from_process_block_fees_result(
         process_block_fees_result: &ProcessedBlockFeesResult,
+        app_hash: [u8; 32],
+        consensus_param_updates: Option<ConsensusParamsUpdate>,
     ) -> Self {
         let (proposers_paid_count, paid_epoch_index) = process_block_fees_result
             .payouts
@@ -126,6 +164,8 @@ impl BlockEndResponse {
         Self {
             proposers_paid_count,
             paid_epoch_index,
+            app_hash,
+            consensus_param_updates,
         }
     }
 }
@@ -143,6 +183,8 @@ pub struct AfterFinalizeBlockRequest {
 #[serde(rename_all = "camelCase")]
 pub struct AfterFinalizeBlockResponse {}
 
+impl<'a> Serializable<'a> for InfoRequest {}
+impl<'a> Serializable<'a> for InfoResponse {}
 impl<'a> Serializable<'a> for InitChainRequest {}
 impl<'a> Serializable<'a> for InitChainResponse {}
 impl<'a> Serializable<'a> for BlockBeginRequest {}
@@ -27,10 +27,21 @@
 // DEALINGS IN THE SOFTWARE.
 //
 
-use crate::abci::messages::BlockBeginRequest;
+use crate::abci::messages::{BlockBeginRequest, Serializable};
+use crate::contracts::feature_flags::FeatureFlags;
+use crate::error::Error;
 use crate::execution::fee_pools::epoch::EpochInfo;
+use crate::execution::protocol_version::INITIAL_PROTOCOL_VERSION;
+use rs_drive::dpp::state_transition::state_transition_execution_context::StateTransitionExecutionContext;
+use rs_drive::drive::block_execution_context::StoredBlockExecutionContext;
+use rs_drive::drive::block_info::BlockInfo as DriveBlockInfo;
+use rs_drive::drive::Drive;
+use rs_drive::fee_pools::epochs::Epoch;
+use serde::{Deserialize, Serialize};
 
 /// Block info
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct BlockInfo {
     /// Block height
     pub block_height: u64,
@@ -40,24 +51,274 @@ pub struct BlockInfo {
     pub previous_block_time_ms: Option<u64>,
     /// Block proposer's proTxHash
     pub proposer_pro_tx_hash: [u8; 32],
+    /// Protocol version supported by the block proposer,
+    /// the initial protocol version for block info stored without it
+    #[serde(default = "default_proposed_protocol_version")]
+    pub proposed_protocol_version: u32,
+    /// Core chain locked height, zero for block info stored without it
+    #[serde(default)]
+    pub core_chain_locked_height: u32,
+}
+
+fn default_proposed_protocol_version() -> u32 {
+    INITIAL_PROTOCOL_VERSION
 }
 
 impl BlockInfo {
-    /// Given a `BlockBeginRequest` return `BlockInfo`
-    pub fn from_block_begin_request(block_begin_request: &BlockBeginRequest) -> BlockInfo {
+    /// Given a `BlockBeginRequest` and the previous block time return `BlockInfo`
+    pub fn from_block_begin_request(
+        block_begin_request: &BlockBeginRequest,
+        previous_block_time_ms: Option<u64>,
+    ) -> BlockInfo {
         BlockInfo {
             block_height: block_begin_request.block_height,
             block_time_ms: block_begin_request.block_time_ms,
-            previous_block_time_ms: block_begin_request.previous_block_time_ms,
+            previous_block_time_ms,
             proposer_pro_tx_hash: block_begin_request.proposer_pro_tx_hash,
+            proposed_protocol_version: block_begin_request.proposed_protocol_version,
+            core_chain_locked_height: block_begin_request.core_chain_locked_height,
         }
     }
 }
 
+impl<'a> Serializable<'a> for BlockInfo {}
+
 /// Block execution context
 pub struct BlockExecutionContext {
     /// Block info
     pub block_info: BlockInfo,
     /// Epoch info
     pub epoch_info: EpochInfo,
+    /// Active protocol version
+    pub protocol_version: u32,
+    /// Fee multiplier of the current epoch
+    pub fee_multiplier: f64,
+    /// Feature flags enabled at the block height
+    pub feature_flags: FeatureFlags,
+}
+
+impl BlockExecutionContext {
+    /// Restores block execution context from block info and epoch info stored in Drive
+    /// along with the active protocol version and the epoch fee multiplier
+    pub fn from_stored_block_execution_context(
+        stored_block_execution_context: StoredBlockExecutionContext,
+        drive: &Drive,
+    ) -> Result<Self, Error> {
+        let (block_info_bytes, epoch_info_bytes) = stored_block_execution_context;
+
+        let epoch_info = EpochInfo::from_bytes(&epoch_info_bytes)?;
+
+        let protocol_version = drive
+            .get_protocol_version(None)?
+            .unwrap_or(INITIAL_PROTOCOL_VERSION);
+
+        let fee_multiplier =
+            drive.get_epoch_fee_multiplier(&Epoch::new(epoch_info.current_epoch_index), None)?;
+
+        Ok(BlockExecutionContext {
+            block_info: BlockInfo::from_bytes(&block_info_bytes)?,
+            epoch_info,
+            protocol_version,
+            fee_multiplier,
+            // Feature flags are applied only within the block they are enabled at
+            feature_flags: FeatureFlags::default(),
+        })
+    }
+
+    /// Returns Drive block info carrying the fee multiplier and the protocol version
+    /// cached for the block, so fees are calculated without reading them again
+    pub fn drive_block_info(&self) -> DriveBlockInfo {
+        DriveBlockInfo {
+            time_ms: self.block_info.block_time_ms,
+            height: self.block_info.block_height,
+            epoch: Epoch::new_with_fee_multiplier_and_protocol_version(
+                self.epoch_info.current_epoch_index,
+                self.fee_multiplier,
+                self.protocol_version,
+            ),
+        }
+    }
+
+    /// Returns a dpp state transition execution context charging fees with the fee schedule
+    /// of the active protocol version and the fee multiplier of the current epoch,
+    /// so state transitions are validated against the same fees Drive charges
+    pub fn state_transition_execution_context(&self) -> StateTransitionExecutionContext {
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.set_protocol_version(self.protocol_version);
+        execution_context.set_fee_multiplier(self.fee_multiplier);
+        execution_context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod block_info {
+        use crate::abci::messages::Serializable;
+        use crate::block::BlockInfo;
+        use crate::execution::protocol_version::INITIAL_PROTOCOL_VERSION;
+        use serde::Serialize;
+
+        #[test]
+        fn test_deserialize_block_info_stored_without_protocol_version() {
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct StoredBlockInfo {
+                block_height: u64,
+                block_time_ms: u64,
+                previous_block_time_ms: Option<u64>,
+                proposer_pro_tx_hash: [u8; 32],
+                core_chain_locked_height: u32,
+            }
+
+            let mut bytes = vec![];
+
+            ciborium::ser::into_writer(
+                &StoredBlockInfo {
+                    block_height: 2,
+                    block_time_ms: 3,
+                    previous_block_time_ms: Some(1),
+                    proposer_pro_tx_hash: [4; 32],
+                    core_chain_locked_height: 5,
+                },
+                &mut bytes,
+            )
+            .expect("should serialize block info");
+
+            let block_info = BlockInfo::from_bytes(&bytes).expect("should deserialize block info");
+
+            assert_eq!(block_info.block_height, 2);
+            assert_eq!(
+                block_info.proposed_protocol_version,
+                INITIAL_PROTOCOL_VERSION
+            );
+            assert_eq!(block_info.core_chain_locked_height, 5);
+        }
+
+        #[test]
+        fn test_deserialize_block_info_stored_without_core_chain_locked_height() {
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct StoredBlockInfo {
+                block_height: u64,
+                block_time_ms: u64,
+                previous_block_time_ms: Option<u64>,
+                proposer_pro_tx_hash: [u8; 32],
+            }
+
+            let mut bytes = vec![];
+
+            ciborium::ser::into_writer(
+                &StoredBlockInfo {
+                    block_height: 2,
+                    block_time_ms: 3,
+                    previous_block_time_ms: Some(1),
+                    proposer_pro_tx_hash: [4; 32],
+                },
+                &mut bytes,
+            )
+            .expect("should serialize block info");
+
+            let block_info = BlockInfo::from_bytes(&bytes).expect("should deserialize block info");
+
+            assert_eq!(block_info.block_height, 2);
+            assert_eq!(
+                block_info.proposed_protocol_version,
+                INITIAL_PROTOCOL_VERSION
+            );
+            assert_eq!(block_info.core_chain_locked_height, 0);
+        }
+    }
+
+    mod state_transition_execution_context {
+        use crate::block::{BlockExecutionContext, BlockInfo};
+        use crate::contracts::feature_flags::FeatureFlags;
+        use crate::execution::fee_pools::epoch::EpochInfo;
+        use crate::execution::protocol_version::INITIAL_PROTOCOL_VERSION;
+        use rs_drive::costs::storage_cost::StorageCost;
+        use rs_drive::costs::OperationCost;
+        use rs_drive::dpp::state_transition::fee::calculate_operations_fees;
+        use rs_drive::dpp::state_transition::fee::fee_schedule::FeeSchedule;
+        use rs_drive::dpp::state_transition::fee::operations::{Operation, PreCalculatedOperation};
+        use rs_drive::fee::calculate_fee;
+        use rs_drive::fee::op::DriveOperation;
+
+        fn create_block_execution_context(fee_multiplier: f64) -> BlockExecutionContext {
+            BlockExecutionContext {
+                block_info: BlockInfo {
+                    block_height: 1,
+                    block_time_ms: 1,
+                    previous_block_time_ms: None,
+                    proposer_pro_tx_hash: [0; 32],
+                    proposed_protocol_version: INITIAL_PROTOCOL_VERSION,
+                    core_chain_locked_height: 1,
+                },
+                epoch_info: EpochInfo {
+                    current_epoch_index: 0,
+                    previous_epoch_index: None,
+                    is_epoch_change: true,
+                },
+                protocol_version: INITIAL_PROTOCOL_VERSION,
+                fee_multiplier,
+                feature_flags: FeatureFlags::default(),
+            }
+        }
+
+        #[test]
+        fn test_dpp_and_drive_charge_the_same_fees_with_epoch_fee_multiplier() {
+            let block_execution_context = create_block_execution_context(2.5);
+
+            let drive_operations = vec![DriveOperation::CalculatedCostOperation(OperationCost {
+                seek_count: 3,
+                storage_cost: StorageCost {
+                    added_bytes: 10,
+                    replaced_bytes: 7,
+                    ..Default::default()
+                },
+                storage_loaded_bytes: 101,
+                hash_node_calls: 5,
+            })];
+
+            let drive_fee_result = calculate_fee(
+                None,
+                Some(drive_operations),
+                &block_execution_context.drive_block_info().epoch,
+            )
+            .expect("should calculate Drive fees");
+
+            assert_ne!(
+                drive_fee_result.processing_fee,
+                drive_fee_result.base_processing_fee
+            );
+
+            // Drive fees are passed to DPP before the epoch fee multiplier is applied
+            let execution_context = block_execution_context.state_transition_execution_context();
+            execution_context.add_operation(Operation::PreCalculated(PreCalculatedOperation::new(
+                drive_fee_result.storage_fee as i64,
+                drive_fee_result.base_processing_fee as i64,
+            )));
+
+            let dpp_fees = calculate_operations_fees(
+                execution_context.get_operations(),
+                FeeSchedule::for_protocol_version(execution_context.get_protocol_version()),
+                execution_context.get_fee_multiplier(),
+            )
+            .expect("should calculate DPP fees");
+
+            assert_eq!(dpp_fees.storage, drive_fee_result.storage_fee as i64);
+            assert_eq!(dpp_fees.processing, drive_fee_result.processing_fee as i64);
+        }
+
+        #[test]
+        fn test_execution_context_uses_block_protocol_version_and_fee_multiplier() {
+            let block_execution_context = create_block_execution_context(1.5);
+
+            let execution_context = block_execution_context.state_transition_execution_context();
+
+            assert_eq!(
+                execution_context.get_protocol_version(),
+                INITIAL_PROTOCOL_VERSION
+            );
+            assert_eq!(execution_context.get_fee_multiplier(), 1.5);
+        }
+    }
 }
@@ -0,0 +1,148 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Feature flags helper functions.
+//!
+//! This module defines helper functions related to feature flags.
+//!
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use rs_drive::contract::document::Document;
+use rs_drive::contract::Contract;
+use rs_drive::dpp::data_contract::extra::DriveContractExt;
+use rs_drive::drive::block_info::BlockInfo;
+use rs_drive::drive::flags::StorageFlags;
+use rs_drive::drive::object_size_info::DocumentAndContractInfo;
+use rs_drive::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
+use rs_drive::drive::Drive;
+use rs_drive::grovedb::TransactionArg;
+
+use crate::contracts::feature_flags::{
+    UPDATE_CONSENSUS_PARAMS_DOCUMENT_TYPE, UPDATE_FEE_MULTIPLIER_DOCUMENT_TYPE,
+};
+
+/// A function which inserts a test feature flag document into the feature flags contract.
+fn create_test_feature_flag_document(
+    drive: &Drive,
+    contract: &Contract,
+    document_type_name: &str,
+    properties: BTreeMap<String, Value>,
+    transaction: TransactionArg,
+) -> Document {
+    let id = rand::random::<[u8; 32]>();
+
+    let document = Document {
+        id,
+        properties,
+        owner_id: contract.owner_id.buffer,
+    };
+
+    let document_type = contract
+        .document_type_for_name(document_type_name)
+        .expect("expected to get a document type");
+
+    let storage_flags = Some(StorageFlags::SingleEpoch(0));
+
+    let document_cbor = document.to_cbor();
+
+    drive
+        .add_document_for_contract(
+            DocumentAndContractInfo {
+                document_info: DocumentRefAndSerialization((
+                    &document,
+                    &document_cbor,
+                    storage_flags.as_ref(),
+                )),
+                contract,
+                document_type,
+                owner_id: None,
+            },
+            false,
+            BlockInfo::genesis(),
+            true,
+            transaction,
+        )
+        .expect("expected to insert a document successfully");
+
+    document
+}
+
+/// A function which creates a test UPDATE_CONSENSUS_PARAMS_DOCUMENT_TYPE document
+/// updating block max bytes at the given height.
+pub fn create_test_update_consensus_params_document(
+    drive: &Drive,
+    contract: &Contract,
+    enable_at_height: u64,
+    block_max_bytes: i64,
+    transaction: TransactionArg,
+) -> Document {
+    let mut properties: BTreeMap<String, Value> = BTreeMap::new();
+
+    properties.insert(String::from("enableAtHeight"), enable_at_height.into());
+    properties.insert(
+        String::from("block"),
+        Value::Map(vec![(
+            Value::Text(String::from("maxBytes")),
+            block_max_bytes.into(),
+        )]),
+    );
+
+    create_test_feature_flag_document(
+        drive,
+        contract,
+        UPDATE_CONSENSUS_PARAMS_DOCUMENT_TYPE,
+        properties,
+        transaction,
+    )
+}
+
+/// A function which creates a test UPDATE_FEE_MULTIPLIER_DOCUMENT_TYPE document
+/// setting the fee multiplier for the given epoch.
+pub fn create_test_update_fee_multiplier_document(
+    drive: &Drive,
+    contract: &Contract,
+    enable_at_epoch: u16,
+    multiplier: impl Into<Value>,
+    transaction: TransactionArg,
+) -> Document {
+    let mut properties: BTreeMap<String, Value> = BTreeMap::new();
+
+    properties.insert(String::from("enableAtEpoch"), enable_at_epoch.into());
+    properties.insert(String::from("multiplier"), multiplier.into());
+
+    create_test_feature_flag_document(
+        drive,
+        contract,
+        UPDATE_FEE_MULTIPLIER_DOCUMENT_TYPE,
+        properties,
+        transaction,
+    )
+}
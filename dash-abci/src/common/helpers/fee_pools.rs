@@ -135,6 +135,33 @@ pub fn create_test_masternode_share_identities_and_documents(
         .collect()
 }
 
+/// A function which creates a test MN_REWARD_SHARES_DOCUMENT_TYPE document
+/// for each masternode sharing the reward with the same identity.
+pub fn create_test_masternode_share_documents_to_identity(
+    drive: &Drive,
+    contract: &Contract,
+    pro_tx_hashes: &Vec<[u8; 32]>,
+    pay_to_identity: &Identity,
+    percentage: u16,
+    transaction: TransactionArg,
+) -> Vec<Document> {
+    drive
+        .fetch_identities(pro_tx_hashes, transaction)
+        .expect("expected to fetch identities")
+        .iter()
+        .map(|mn_identity| {
+            create_test_mn_share_document(
+                drive,
+                contract,
+                mn_identity,
+                pay_to_identity,
+                percentage,
+                transaction,
+            )
+        })
+        .collect()
+}
+
 /// A function for refetching identities.
 ///
 /// Takes a list of identities, queries the database for them, and returns the query result as a list of identities.
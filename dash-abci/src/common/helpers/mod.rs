@@ -1,3 +1,4 @@
+pub mod feature_flags;
 pub mod fee_pools;
 pub mod setup;
 
@@ -0,0 +1,324 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Platform Config.
+//!
+//! This module defines the `PlatformConfig` struct holding chain parameters which
+//! could be changed for devnets and testnets, as well as the local Drive configuration.
+//!
+
+use crate::abci::messages::Serializable;
+use crate::error::config::ConfigError;
+use crate::error::Error;
+use crate::execution::fee_pools::constants::FEE_DISTRIBUTION_TABLE;
+use crate::execution::fee_pools::epoch::EPOCH_CHANGE_TIME_MS;
+use rs_drive::drive::config::{
+    DriveConfig, DEFAULT_NEGLIGIBLE_REFUND_CREDITS, DEFAULT_NEGLIGIBLE_REFUND_REMAINING_EPOCHS,
+    DEFAULT_WITHDRAWAL_CREDITS_PER_BLOCK_LIMIT, DEFAULT_WITHDRAWAL_CREDITS_PER_DAY_LIMIT,
+};
+use rs_drive::drive::fee_pools::epochs::constants::{EPOCHS_PER_YEAR, PERPETUAL_STORAGE_YEARS};
+use rs_drive::fee_pools::epochs::DEFAULT_FEE_MULTIPLIER;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Default maximum number of withdrawal transactions pooled per block
+pub const DEFAULT_WITHDRAWAL_TRANSACTIONS_QUERY_LIMIT: u16 = 16;
+
+/// Platform configuration.
+///
+/// Chain parameters are persisted at chain initialization, so a node restarted
+/// with a different config keeps using parameters of the chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlatformConfig {
+    /// Local Drive configuration
+    #[serde(skip)]
+    pub drive: DriveConfig,
+
+    /// Lifetime of an epoch in milliseconds
+    pub epoch_change_time_ms: u64,
+
+    /// Number of epochs in a year
+    pub epochs_per_year: u16,
+
+    /// Years of fees charged for perpetual storage
+    pub perpetual_storage_years: u16,
+
+    /// The amount of the perpetual storage fee to be paid out per year. Must add up to 1.
+    pub fee_distribution_table: Vec<Decimal>,
+
+    /// Storage refunds below this amount of credits are negligible
+    pub negligible_refund_credits: u64,
+
+    /// Storage refunds with fewer epochs left to distribute their fees are negligible
+    pub negligible_refund_remaining_epochs: u16,
+
+    /// Fee multiplier used until it's changed with feature flags
+    pub default_original_fee_multiplier: f64,
+
+    /// Maximum number of withdrawal transactions pooled per block
+    pub withdrawal_transactions_query_limit: u16,
+
+    /// Maximum amount of credits withdrawn per block
+    pub withdrawal_credits_per_block_limit: u64,
+
+    /// Maximum amount of credits withdrawn per rolling 24h window
+    pub withdrawal_credits_per_day_limit: u64,
+}
+
+impl Default for PlatformConfig {
+    fn default() -> Self {
+        PlatformConfig {
+            drive: DriveConfig::default(),
+            epoch_change_time_ms: EPOCH_CHANGE_TIME_MS,
+            epochs_per_year: EPOCHS_PER_YEAR,
+            perpetual_storage_years: PERPETUAL_STORAGE_YEARS,
+            fee_distribution_table: FEE_DISTRIBUTION_TABLE.to_vec(),
+            negligible_refund_credits: DEFAULT_NEGLIGIBLE_REFUND_CREDITS,
+            negligible_refund_remaining_epochs: DEFAULT_NEGLIGIBLE_REFUND_REMAINING_EPOCHS,
+            default_original_fee_multiplier: DEFAULT_FEE_MULTIPLIER,
+            withdrawal_transactions_query_limit: DEFAULT_WITHDRAWAL_TRANSACTIONS_QUERY_LIMIT,
+            withdrawal_credits_per_block_limit: DEFAULT_WITHDRAWAL_CREDITS_PER_BLOCK_LIMIT,
+            withdrawal_credits_per_day_limit: DEFAULT_WITHDRAWAL_CREDITS_PER_DAY_LIMIT,
+        }
+    }
+}
+
+impl PlatformConfig {
+    /// Loads chain parameters from a JSON file. Missing parameters are set to defaults.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| Error::Config(ConfigError::FileReading(e.to_string())))?;
+
+        let config: PlatformConfig = serde_json::from_str(&json)
+            .map_err(|e| Error::Config(ConfigError::FileParsing(e.to_string())))?;
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Number of epochs storage fees are distributed to
+    pub fn perpetual_storage_epochs(&self) -> u16 {
+        self.perpetual_storage_years
+            .saturating_mul(self.epochs_per_year)
+    }
+
+    /// Sets chain parameters used by Drive in the local Drive configuration
+    pub fn apply_chain_parameters_to_drive_config(&self, drive_config: &mut DriveConfig) {
+        drive_config.epochs_per_year = self.epochs_per_year;
+        drive_config.perpetual_storage_years = self.perpetual_storage_years;
+        drive_config.fee_distribution_table = self.fee_distribution_table.clone();
+        drive_config.negligible_refund_credits = self.negligible_refund_credits;
+        drive_config.negligible_refund_remaining_epochs = self.negligible_refund_remaining_epochs;
+        drive_config.withdrawal_credits_per_block_limit = self.withdrawal_credits_per_block_limit;
+        drive_config.withdrawal_credits_per_day_limit = self.withdrawal_credits_per_day_limit;
+    }
+
+    /// Returns an error if chain parameters or the Drive config are inconsistent
+    pub fn validate(&self) -> Result<(), Error> {
+        self.drive.validate()?;
+
+        if self.epoch_change_time_ms == 0 {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "epoch change time must be greater than zero",
+            )));
+        }
+
+        if self.epochs_per_year == 0 {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "epochs per year must be greater than zero",
+            )));
+        }
+
+        if self.perpetual_storage_years == 0 {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "perpetual storage years must be greater than zero",
+            )));
+        }
+
+        if self
+            .perpetual_storage_years
+            .checked_mul(self.epochs_per_year)
+            .is_none()
+        {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "perpetual storage epochs are not fitting in a u16",
+            )));
+        }
+
+        if self.fee_distribution_table.len() != self.perpetual_storage_years as usize {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "fee distribution table must have a ratio for every perpetual storage year",
+            )));
+        }
+
+        if self
+            .fee_distribution_table
+            .iter()
+            .any(|ratio| ratio.is_sign_negative())
+        {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "fee distribution table ratios can't be negative",
+            )));
+        }
+
+        if self.fee_distribution_table.iter().sum::<Decimal>() != Decimal::ONE {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "fee distribution table must add up to 1",
+            )));
+        }
+
+        if !self.default_original_fee_multiplier.is_finite()
+            || self.default_original_fee_multiplier <= 0.0
+        {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "default original fee multiplier must be a positive number",
+            )));
+        }
+
+        if self.withdrawal_transactions_query_limit == 0 {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "withdrawal transactions query limit must be greater than zero",
+            )));
+        }
+
+        if self.withdrawal_credits_per_block_limit == 0 {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "withdrawal credits per block limit must be greater than zero",
+            )));
+        }
+
+        if self.withdrawal_credits_per_day_limit < self.withdrawal_credits_per_block_limit {
+            return Err(Error::Config(ConfigError::InvalidParameter(
+                "withdrawal credits per day limit can't be lower than the per block limit",
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Serializable<'a> for PlatformConfig {}
+
+#[cfg(test)]
+mod tests {
+    use crate::abci::messages::Serializable;
+    use crate::config::PlatformConfig;
+    use crate::error::config::ConfigError;
+    use crate::error::Error;
+    use rust_decimal_macros::dec;
+    use std::io::Write;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        PlatformConfig::default()
+            .validate()
+            .expect("default config should be valid");
+    }
+
+    #[test]
+    fn test_distribution_table_must_add_up_to_one() {
+        let mut config = PlatformConfig::default();
+
+        config.fee_distribution_table[0] += dec!(0.00001);
+
+        match config.validate() {
+            Err(Error::Config(ConfigError::InvalidParameter(_))) => {}
+            _ => panic!("should not validate a table not adding up to 1"),
+        }
+    }
+
+    #[test]
+    fn test_distribution_table_must_match_storage_years() {
+        let config = PlatformConfig {
+            perpetual_storage_years: 2,
+            fee_distribution_table: vec![dec!(1)],
+            ..Default::default()
+        };
+
+        match config.validate() {
+            Err(Error::Config(ConfigError::InvalidParameter(_))) => {}
+            _ => panic!("should not validate a table with a wrong length"),
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_per_day_limit_must_cover_per_block_limit() {
+        let config = PlatformConfig {
+            withdrawal_credits_per_block_limit: 2,
+            withdrawal_credits_per_day_limit: 1,
+            ..Default::default()
+        };
+
+        match config.validate() {
+            Err(Error::Config(ConfigError::InvalidParameter(_))) => {}
+            _ => panic!("should not validate a per day limit lower than the per block limit"),
+        }
+    }
+
+    #[test]
+    fn test_from_file() {
+        let mut file = tempfile::NamedTempFile::new().expect("should create a temp file");
+
+        write!(
+            file,
+            r#"{{
+                "epochChangeTimeMs": 60000,
+                "epochsPerYear": 4,
+                "perpetualStorageYears": 2,
+                "feeDistributionTable": ["0.75", "0.25"]
+            }}"#
+        )
+        .expect("should write config");
+
+        let config = PlatformConfig::from_file(file.path()).expect("should load config");
+
+        assert_eq!(config.epoch_change_time_ms, 60000);
+        assert_eq!(config.perpetual_storage_epochs(), 8);
+        assert_eq!(config.fee_distribution_table, vec![dec!(0.75), dec!(0.25)]);
+        assert_eq!(
+            config.withdrawal_transactions_query_limit,
+            PlatformConfig::default().withdrawal_transactions_query_limit
+        );
+
+        let bytes = config.to_bytes().expect("should serialize config");
+
+        let deserialized_config =
+            PlatformConfig::from_bytes(&bytes).expect("should deserialize config");
+
+        assert_eq!(deserialized_config.epochs_per_year, 4);
+        assert_eq!(
+            deserialized_config.fee_distribution_table,
+            config.fee_distribution_table
+        );
+    }
+}
@@ -0,0 +1,357 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Feature flags.
+//!
+//! This module defines structs and functions related to the feature flags contract.
+//!
+//! Feature flags documents are created by the feature flags contract owner to change
+//! network behaviour starting from the height specified in the `enableAtHeight` property.
+//!
+
+use crate::error::execution::ExecutionError;
+use crate::error::Error;
+use crate::platform::Platform;
+use ciborium::value::Value;
+use rs_drive::common::value_to_cbor;
+use rs_drive::contract::document::Document;
+use rs_drive::contract::Contract;
+use rs_drive::dpp::data_contract::extra::DriveContractExt;
+use rs_drive::drive::block_info::BlockInfo;
+use rs_drive::drive::defaults::PROTOCOL_VERSION;
+use rs_drive::drive::flags::StorageFlags;
+use rs_drive::error::query::QueryError;
+use rs_drive::grovedb::TransactionArg;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Feature flags contract ID
+pub const FEATURE_FLAGS_CONTRACT_ID: [u8; 32] = [
+    0xf0, 0x01, 0x00, 0xb0, 0xc1, 0xe3, 0x76, 0x2b, 0x8b, 0xc1, 0x42, 0x1e, 0x11, 0x3c, 0x76, 0xb2,
+    0xa6, 0x35, 0xc5, 0x93, 0x0b, 0x9a, 0xbf, 0x2b, 0x33, 0x65, 0x83, 0xbe, 0x59, 0x87, 0xa7, 0x15,
+];
+
+/// Update consensus params document type
+pub const UPDATE_CONSENSUS_PARAMS_DOCUMENT_TYPE: &str = "updateConsensusParams";
+
+/// Update fee multiplier document type
+pub const UPDATE_FEE_MULTIPLIER_DOCUMENT_TYPE: &str = "updateFeeMultiplier";
+
+/// Block consensus params
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockConsensusParams {
+    /// Max block size in bytes
+    pub max_bytes: Option<i64>,
+    /// Max gas per block
+    pub max_gas: Option<i64>,
+}
+
+/// Version consensus params
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionConsensusParams {
+    /// Application protocol version
+    pub app_version: Option<i64>,
+}
+
+/// Consensus params to be updated by Tenderdash
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusParamsUpdate {
+    /// Block params
+    pub block: Option<BlockConsensusParams>,
+    /// Version params
+    pub version: Option<VersionConsensusParams>,
+}
+
+impl ConsensusParamsUpdate {
+    /// Creates consensus params update from an `updateConsensusParams` feature flag document
+    pub fn from_document(document: &Document) -> Result<Self, Error> {
+        let block = document
+            .properties
+            .get("block")
+            .map(|block| {
+                let block = get_map(block, "block property type is not map")?;
+
+                Ok::<BlockConsensusParams, Error>(BlockConsensusParams {
+                    max_bytes: get_optional_integer(block, "maxBytes")?,
+                    max_gas: get_optional_integer(block, "maxGas")?,
+                })
+            })
+            .transpose()?;
+
+        let version = document
+            .properties
+            .get("version")
+            .map(|version| {
+                let version = get_map(version, "version property type is not map")?;
+
+                Ok::<VersionConsensusParams, Error>(VersionConsensusParams {
+                    app_version: get_optional_integer(version, "appVersion")?,
+                })
+            })
+            .transpose()?;
+
+        Ok(Self { block, version })
+    }
+}
+
+/// Feature flags which are active for the block
+#[derive(Clone, Debug, Default)]
+pub struct FeatureFlags {
+    /// Consensus params which must be updated starting from the block
+    pub consensus_params_update: Option<ConsensusParamsUpdate>,
+}
+
+fn get_map<'a>(value: &'a Value, error: &'static str) -> Result<&'a Vec<(Value, Value)>, Error> {
+    value
+        .as_map()
+        .ok_or(Error::Execution(ExecutionError::DriveIncoherence(error)))
+}
+
+fn get_optional_integer(map: &[(Value, Value)], key: &str) -> Result<Option<i64>, Error> {
+    map.iter()
+        .find(|(map_key, _)| map_key.as_text() == Some(key))
+        .map(|(_, value)| {
+            value
+                .as_integer()
+                .ok_or(Error::Execution(ExecutionError::DriveIncoherence(
+                    "consensus param type is not integer",
+                )))?
+                .try_into()
+                .map_err(|_| {
+                    Error::Execution(ExecutionError::Overflow(
+                        "consensus param cannot be converted to i64",
+                    ))
+                })
+        })
+        .transpose()
+}
+
+/// Gets the fee multiplier from an `updateFeeMultiplier` feature flag document.
+/// The contract schema declares it as a number, so both integers and floats are accepted.
+fn get_fee_multiplier(document: &Document) -> Result<f64, Error> {
+    let multiplier = match document.properties.get("multiplier") {
+        Some(Value::Float(multiplier)) => *multiplier,
+        Some(Value::Integer(multiplier)) => i128::from(*multiplier) as f64,
+        Some(_) => {
+            return Err(Error::Execution(ExecutionError::DriveIncoherence(
+                "multiplier property type is not number",
+            )))
+        }
+        None => {
+            return Err(Error::Execution(ExecutionError::DriveMissingData(
+                "multiplier property is missing",
+            )))
+        }
+    };
+
+    if !multiplier.is_finite() || multiplier <= 0.0 {
+        return Err(Error::Execution(ExecutionError::DriveIncoherence(
+            "multiplier property must be a positive number",
+        )));
+    }
+
+    Ok(multiplier)
+}
+
+impl Platform {
+    /// Queries feature flag documents of the given type with the property equal to the value.
+    /// Returns no documents if the feature flags contract is not created yet.
+    fn query_feature_flag_documents(
+        &self,
+        document_type_name: &str,
+        property: &str,
+        value: u64,
+        transaction: TransactionArg,
+    ) -> Result<Vec<Document>, Error> {
+        let query_json = json!({
+            "where": [
+                [property, "==", value]
+            ],
+        });
+
+        let query_cbor = value_to_cbor(query_json, None);
+
+        let document_cbors = match self.drive.query_documents(
+            &query_cbor,
+            FEATURE_FLAGS_CONTRACT_ID,
+            document_type_name,
+            None,
+            transaction,
+        ) {
+            Ok((document_cbors, _, _)) => document_cbors,
+            Err(rs_drive::error::Error::Query(QueryError::ContractNotFound(_))) => {
+                return Ok(vec![])
+            }
+            Err(e) => return Err(Error::Drive(e)),
+        };
+
+        document_cbors
+            .iter()
+            .map(|cbor| Document::from_cbor(cbor, None, None).map_err(Error::Drive))
+            .collect::<Result<Vec<Document>, Error>>()
+    }
+
+    /// Fetches feature flags enabled at the given block height.
+    pub fn get_feature_flags_for_height(
+        &self,
+        block_height: u64,
+        transaction: TransactionArg,
+    ) -> Result<FeatureFlags, Error> {
+        // enableAtHeight index is unique, so there is one document at most
+        let consensus_params_update = self
+            .query_feature_flag_documents(
+                UPDATE_CONSENSUS_PARAMS_DOCUMENT_TYPE,
+                "enableAtHeight",
+                block_height,
+                transaction,
+            )?
+            .first()
+            .map(ConsensusParamsUpdate::from_document)
+            .transpose()?;
+
+        Ok(FeatureFlags {
+            consensus_params_update,
+        })
+    }
+
+    /// Fetches the fee multiplier enabled at the given epoch if it's specified.
+    pub fn get_fee_multiplier_feature_flag_for_epoch(
+        &self,
+        epoch_index: u16,
+        transaction: TransactionArg,
+    ) -> Result<Option<f64>, Error> {
+        // enableAtEpoch index is unique, so there is one document at most
+        self.query_feature_flag_documents(
+            UPDATE_FEE_MULTIPLIER_DOCUMENT_TYPE,
+            "enableAtEpoch",
+            epoch_index as u64,
+            transaction,
+        )?
+        .first()
+        .map(get_fee_multiplier)
+        .transpose()
+    }
+
+    /// A function to create and apply the feature flags contract.
+    pub fn create_feature_flags_contract(&self, transaction: TransactionArg) -> Contract {
+        let documents: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../feature-flags-contract/schema/feature-flags-documents.json"
+        ))
+        .expect("expected to parse feature flags documents schema");
+
+        let contract_json = json!({
+            "$id": "H9sjb2bHG8t7gq5SwNdqzMWG7KR6sf3CbziFzthCkDD6",
+            "ownerId": "HY1keaRK5bcDmujNCQq5pxNyvAiHHpoHQgLN5ppiu4kh",
+            "$schema": "https://schema.dash.org/dpp-0-4-0/meta/data-contract",
+            "version": 1,
+            "documents": documents
+        });
+
+        let contract_cbor = value_to_cbor(contract_json, Some(PROTOCOL_VERSION));
+
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("expected to deserialize the contract");
+
+        let storage_flags = Some(StorageFlags::SingleEpoch(0));
+
+        self.drive
+            .apply_contract(
+                &contract,
+                contract_cbor,
+                BlockInfo::genesis(),
+                true,
+                storage_flags.as_ref(),
+                transaction,
+            )
+            .expect("expected to apply contract successfully");
+
+        contract
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod get_feature_flags_for_height {
+        use crate::common::helpers::feature_flags::create_test_update_consensus_params_document;
+        use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+        use crate::contracts::feature_flags::{BlockConsensusParams, ConsensusParamsUpdate};
+
+        #[test]
+        fn test_no_feature_flags_without_contract() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let feature_flags = platform
+                .get_feature_flags_for_height(1, Some(&transaction))
+                .expect("should get feature flags");
+
+            assert!(feature_flags.consensus_params_update.is_none());
+        }
+
+        #[test]
+        fn test_consensus_params_update_enabled_at_height() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let contract = platform.create_feature_flags_contract(Some(&transaction));
+
+            create_test_update_consensus_params_document(
+                &platform.drive,
+                &contract,
+                5,
+                1024,
+                Some(&transaction),
+            );
+
+            let feature_flags = platform
+                .get_feature_flags_for_height(4, Some(&transaction))
+                .expect("should get feature flags");
+
+            assert!(feature_flags.consensus_params_update.is_none());
+
+            let feature_flags = platform
+                .get_feature_flags_for_height(5, Some(&transaction))
+                .expect("should get feature flags");
+
+            assert_eq!(
+                feature_flags.consensus_params_update,
+                Some(ConsensusParamsUpdate {
+                    block: Some(BlockConsensusParams {
+                        max_bytes: Some(1024),
+                        max_gas: None,
+                    }),
+                    version: None,
+                })
+            );
+        }
+    }
+}
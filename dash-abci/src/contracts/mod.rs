@@ -1 +1,2 @@
+pub mod feature_flags;
 pub mod reward_shares;
@@ -37,6 +37,7 @@
 //! For example, the address of someone who manages the masternode for the owner.
 //!
 
+use crate::error::execution::ExecutionError;
 use crate::error::Error;
 use crate::platform::Platform;
 use rs_drive::common::value_to_cbor;
@@ -57,6 +58,82 @@ pub const MN_REWARD_SHARES_CONTRACT_ID: [u8; 32] = [
 /// Masternode reward shares document type
 pub const MN_REWARD_SHARES_DOCUMENT_TYPE: &str = "rewardShare";
 
+/// Maximum number of reward shares paid out per masternode
+pub const MAX_REWARD_SHARES_PER_MASTERNODE: usize = 16;
+
+/// Reward shares are expressed in basis points of the masternode reward,
+/// so all shares of a masternode can't sum up to more than this value
+pub const REWARD_SHARES_TOTAL_BASIS_POINTS: u64 = 10000;
+
+/// Masternode reward share
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewardShare {
+    /// Identity receiving the share
+    pub pay_to_id: [u8; 32],
+    /// Share of the masternode reward in basis points
+    pub basis_points: u64,
+}
+
+/// Returns reward shares which are going to be paid out.
+///
+/// Shares are ordered by payee identity, and a share is skipped if it would bring the total
+/// above [`REWARD_SHARES_TOTAL_BASIS_POINTS`]. No more than
+/// [`MAX_REWARD_SHARES_PER_MASTERNODE`] shares are paid out.
+pub fn select_payable_reward_shares(mut shares: Vec<RewardShare>) -> Vec<RewardShare> {
+    shares.sort_by(|a, b| a.pay_to_id.cmp(&b.pay_to_id));
+
+    let mut total_basis_points = 0u64;
+
+    shares
+        .into_iter()
+        .filter(|share| {
+            let new_total_basis_points = total_basis_points.saturating_add(share.basis_points);
+
+            if share.basis_points == 0 || new_total_basis_points > REWARD_SHARES_TOTAL_BASIS_POINTS
+            {
+                return false;
+            }
+
+            total_basis_points = new_total_basis_points;
+
+            true
+        })
+        .take(MAX_REWARD_SHARES_PER_MASTERNODE)
+        .collect()
+}
+
+/// Splits the masternode reward between reward shares and the masternode owner.
+///
+/// Share amounts are floored, so the remainder stays with the masternode owner.
+///
+/// Returns the masternode owner reward and rewards of share payees.
+pub fn split_masternode_reward(
+    masternode_reward: u64,
+    shares: &[RewardShare],
+) -> Result<(u64, Vec<([u8; 32], u64)>), Error> {
+    let mut owner_reward = masternode_reward;
+
+    let share_rewards = shares
+        .iter()
+        .map(|share| {
+            let share_reward: u64 = (masternode_reward as u128 * share.basis_points as u128
+                / REWARD_SHARES_TOTAL_BASIS_POINTS as u128)
+                .try_into()
+                .map_err(|_| Error::Execution(ExecutionError::Overflow("share reward overflow")))?;
+
+            owner_reward = owner_reward
+                .checked_sub(share_reward)
+                .ok_or(Error::Execution(ExecutionError::Overflow(
+                    "reward shares exceed masternode reward",
+                )))?;
+
+            Ok((share.pay_to_id, share_reward))
+        })
+        .collect::<Result<Vec<([u8; 32], u64)>, Error>>()?;
+
+    Ok((owner_reward, share_rewards))
+}
+
 impl Platform {
     /// A function to retrieve a list of the masternode reward shares documents for a list of masternode IDs.
     pub(crate) fn get_reward_shares_list_for_masternode(
@@ -86,6 +163,91 @@ impl Platform {
             .collect::<Result<Vec<Document>, Error>>()
     }
 
+    /// Returns reward shares of the masternode which are going to be paid out.
+    /// Once the stored masternode list is complete, only its valid masternodes share rewards,
+    /// as reward shares are validated against it. Shares to identities which don't exist
+    /// anymore are skipped.
+    pub(crate) fn get_payable_reward_shares_for_masternode(
+        &self,
+        masternode_owner_id: &Vec<u8>,
+        transaction: TransactionArg,
+    ) -> Result<Vec<RewardShare>, Error> {
+        let pro_tx_hash: [u8; 32] = masternode_owner_id.as_slice().try_into().map_err(|_| {
+            Error::Execution(ExecutionError::DriveIncoherence(
+                "masternode proTxHash must be 32 bytes",
+            ))
+        })?;
+
+        // Masternodes missing in the list aren't known to be invalid until Core seeds it
+        if self.drive.is_masternode_list_seeded(transaction)? {
+            let is_valid_masternode = self
+                .drive
+                .fetch_masternode(&pro_tx_hash, transaction)?
+                .map_or(false, |masternode| masternode.is_valid());
+
+            if !is_valid_masternode {
+                return Ok(vec![]);
+            }
+        }
+
+        let documents =
+            self.get_reward_shares_list_for_masternode(masternode_owner_id, transaction)?;
+
+        let mut shares = Vec::with_capacity(documents.len());
+
+        for document in documents {
+            let pay_to_id: [u8; 32] = document
+                .properties
+                .get("payToId")
+                .ok_or(Error::Execution(ExecutionError::DriveMissingData(
+                    "payToId property is missing",
+                )))?
+                .as_bytes()
+                .ok_or(Error::Execution(ExecutionError::DriveIncoherence(
+                    "payToId property type is not bytes",
+                )))?
+                .as_slice()
+                .try_into()
+                .map_err(|_| {
+                    Error::Execution(ExecutionError::DriveIncoherence(
+                        "payToId property must be 32 bytes",
+                    ))
+                })?;
+
+            // The contract property is named `percentage`, but its value is in basis points
+            let basis_points: u64 = document
+                .properties
+                .get("percentage")
+                .ok_or(Error::Execution(ExecutionError::DriveMissingData(
+                    "percentage property is missing",
+                )))?
+                .as_integer()
+                .ok_or(Error::Execution(ExecutionError::DriveIncoherence(
+                    "percentage property type is not integer",
+                )))?
+                .try_into()
+                .map_err(|_| {
+                    Error::Execution(ExecutionError::Overflow(
+                        "percentage property cannot be converted to u64",
+                    ))
+                })?;
+
+            if self
+                .fetch_identity_if_exists(&pay_to_id, transaction)?
+                .is_none()
+            {
+                continue;
+            }
+
+            shares.push(RewardShare {
+                pay_to_id,
+                basis_points,
+            });
+        }
+
+        Ok(select_payable_reward_shares(shares))
+    }
+
     /// A function to create and apply the masternode reward shares contract.
     pub fn create_mn_shares_contract(&self, transaction: TransactionArg) -> Contract {
         let contract_hex = "01000000a56324696458200cace205246693a7c8156523620daa937d2f2247934463eeb01ff7219590958c6724736368656d61783468747470733a2f2f736368656d612e646173682e6f72672f6470702d302d342d302f6d6574612f646174612d636f6e7472616374676f776e65724964582024da2bb09da5b1429f717ac1ce6537126cc65215f1d017e67b65eb252ef964b76776657273696f6e0169646f63756d656e7473a16b7265776172645368617265a66474797065666f626a65637467696e646963657382a3646e616d65716f776e65724964416e64506179546f496466756e69717565f56a70726f7065727469657382a168246f776e6572496463617363a167706179546f496463617363a2646e616d65676f776e657249646a70726f7065727469657381a168246f776e65724964636173636872657175697265648267706179546f49646a70657263656e746167656a70726f70657274696573a267706179546f4964a66474797065656172726179686d61784974656d731820686d696e4974656d73182069627974654172726179f56b6465736372697074696f6e781f4964656e74696669657220746f20736861726520726577617264207769746870636f6e74656e744d656469615479706578216170706c69636174696f6e2f782e646173682e6470702e6964656e7469666965726a70657263656e74616765a4647479706567696e7465676572676d6178696d756d192710676d696e696d756d016b6465736372697074696f6e781a5265776172642070657263656e7461676520746f2073686172656b6465736372697074696f6e78405368617265207370656369666965642070657263656e74616765206f66206d61737465726e6f646520726577617264732077697468206964656e746974696573746164646974696f6e616c50726f70657274696573f4";
@@ -111,3 +273,52 @@ impl Platform {
         contract
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_share(pay_to_id: u8, basis_points: u64) -> RewardShare {
+        RewardShare {
+            pay_to_id: [pay_to_id; 32],
+            basis_points,
+        }
+    }
+
+    #[test]
+    fn test_select_payable_reward_shares_within_total_cap() {
+        let shares = vec![
+            create_share(3, 2000),
+            create_share(1, 5000),
+            create_share(2, 6000),
+            create_share(4, 0),
+        ];
+
+        assert_eq!(
+            select_payable_reward_shares(shares),
+            vec![create_share(1, 5000), create_share(3, 2000)]
+        );
+    }
+
+    #[test]
+    fn test_select_payable_reward_shares_limits_shares_count() {
+        let shares = (0..MAX_REWARD_SHARES_PER_MASTERNODE as u8 + 2)
+            .map(|i| create_share(i, 1))
+            .collect();
+
+        let selected_shares = select_payable_reward_shares(shares);
+
+        assert_eq!(selected_shares.len(), MAX_REWARD_SHARES_PER_MASTERNODE);
+        assert_eq!(selected_shares[0], create_share(0, 1));
+    }
+
+    #[test]
+    fn test_split_masternode_reward_keeps_remainder_for_owner() {
+        let (owner_reward, share_rewards) =
+            split_masternode_reward(999, &[create_share(1, 3333), create_share(2, 3333)])
+                .expect("should split reward");
+
+        assert_eq!(share_rewards, vec![([1; 32], 332), ([2; 32], 332)]);
+        assert_eq!(owner_reward, 335);
+    }
+}
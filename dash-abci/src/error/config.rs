@@ -0,0 +1,15 @@
+/// Config errors
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// Error
+    #[error("invalid platform config parameter: {0}")]
+    InvalidParameter(&'static str),
+
+    /// Error
+    #[error("can't read platform config file: {0}")]
+    FileReading(String),
+
+    /// Error
+    #[error("can't parse platform config file: {0}")]
+    FileParsing(String),
+}
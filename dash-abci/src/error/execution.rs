@@ -24,4 +24,8 @@ pub enum ExecutionError {
     /// Error
     #[error("drive missing data error: {0}")]
     DriveMissingData(&'static str),
+
+    /// Error
+    #[error("unsupported protocol version error: {0}")]
+    UnsupportedProtocolVersion(&'static str),
 }
@@ -1,7 +1,11 @@
+use crate::error::config::ConfigError;
 use crate::error::execution::ExecutionError;
 use crate::error::serialization::SerializationError;
 use rs_drive::error::Error as DriveError;
 
+/// Config errors module
+pub mod config;
+
 /// Execution errors module
 pub mod execution;
 
@@ -20,4 +24,7 @@ pub enum Error {
     /// Error
     #[error("serialization: {0}")]
     Serialization(#[from] SerializationError),
+    /// Error
+    #[error("config: {0}")]
+    Config(#[from] ConfigError),
 }
@@ -35,13 +35,13 @@
 
 use crate::error::execution::ExecutionError;
 use crate::error::Error;
-use crate::execution::fee_pools::constants;
 use crate::platform::Platform;
 use rs_drive::drive::batch::GroveDbOpBatch;
-use rs_drive::drive::fee_pools::epochs::constants::{EPOCHS_PER_YEAR, PERPETUAL_STORAGE_YEARS};
-use rs_drive::fee_pools::epochs::Epoch;
+use rs_drive::drive::fee_pools::storage_fee_cohorts::StorageFeeCohort;
+use rs_drive::fee_pools::{
+    init_storage_fee_cohorts_tree_operation, update_storage_fee_cohort_operation,
+};
 use rs_drive::grovedb::TransactionArg;
-use rs_drive::{error, grovedb};
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 
@@ -51,6 +51,9 @@ pub type StorageDistributionLeftoverCredits = u64;
 impl Platform {
     /// Adds operations to the GroveDB op batch which calculate and distribute storage fees
     /// from the distribution pool to the epoch pools and returns the leftovers.
+    ///
+    /// Shares of the epochs are recorded once per year in the storage fee cohort
+    /// starting at the current epoch, so only one share per perpetual storage year is calculated.
     pub fn add_distribute_storage_fee_distribution_pool_to_epochs_operations(
         &self,
         current_epoch_index: u16,
@@ -73,12 +76,28 @@ impl Platform {
                 ExecutionError::Overflow("storage distribution fees are not fitting in a u64"),
             ))?;
 
-        let epochs_per_year = Decimal::from(EPOCHS_PER_YEAR);
+        let epochs_per_year = Decimal::from(self.config.epochs_per_year);
 
-        for year in 0..PERPETUAL_STORAGE_YEARS {
-            let distribution_for_that_year_ratio = constants::FEE_DISTRIBUTION_TABLE[year as usize];
+        // Fees could be distributed more than once during the epoch
+        let mut cohort = match self
+            .drive
+            .get_storage_fee_cohort(current_epoch_index, transaction)?
+        {
+            Some(cohort) => cohort,
+            None => {
+                // Chains initialized before storage fee cohorts were introduced don't have the tree
+                if !self.drive.has_storage_fee_cohorts_tree(transaction)? {
+                    batch.push(init_storage_fee_cohorts_tree_operation());
+                }
+
+                StorageFeeCohort::new(current_epoch_index, self.config.perpetual_storage_years)
+            }
+        };
 
-            let year_fee_share = storage_distribution_fees * distribution_for_that_year_ratio;
+        for (year, distribution_for_that_year_ratio) in
+            self.config.fee_distribution_table.iter().enumerate()
+        {
+            let year_fee_share = storage_distribution_fees * *distribution_for_that_year_ratio;
 
             let epoch_fee_share_dec = year_fee_share / epochs_per_year;
 
@@ -89,42 +108,34 @@ impl Platform {
                     "storage distribution fees are not fitting in a u64",
                 )))?;
 
-            let year_start_epoch_index = current_epoch_index + EPOCHS_PER_YEAR * year;
-
-            for index in year_start_epoch_index..year_start_epoch_index + EPOCHS_PER_YEAR {
-                let epoch_tree = Epoch::new(index);
+            let cohort_epoch_share = cohort.epoch_shares.get_mut(year).ok_or(Error::Execution(
+                ExecutionError::CorruptedCodeExecution(
+                    "storage fee cohort doesn't have a share for every year",
+                ),
+            ))?;
 
-                let current_epoch_pool_storage_credits = self
-                    .drive
-                    .get_epoch_storage_credits_for_distribution(&epoch_tree, transaction)
-                    .or_else(|e| match e {
-                        // In case if we have a gap between current and previous epochs
-                        // multiple future epochs could be created in the current batch
-                        error::Error::GroveDB(grovedb::Error::PathNotFound(_))
-                        | error::Error::GroveDB(grovedb::Error::PathKeyNotFound(_))
-                        | error::Error::GroveDB(grovedb::Error::PathParentLayerNotFound(_)) => {
-                            Ok(0u64)
-                        }
-                        _ => Err(e),
-                    })?;
-
-                // TODO: It's not convenient and confusing when in one case you should push operation to batch
-                //  and sometimes you pass batch inside to add operations. Also, in future a single operation function
-                //  could become a multiple operations function so you need to change many code. Also, you can't use helpers which batch provides
-                batch.push(
-                    epoch_tree.update_storage_credits_for_distribution_operation(
-                        current_epoch_pool_storage_credits + epoch_fee_share,
-                    ),
-                );
-
-                storage_distribution_leftover_credits = storage_distribution_leftover_credits
-                    .checked_sub(epoch_fee_share)
+            *cohort_epoch_share =
+                cohort_epoch_share
+                    .checked_add(epoch_fee_share)
                     .ok_or(Error::Execution(ExecutionError::Overflow(
-                        "leftover storage not fitting in a u64",
+                        "cohort storage share not fitting in a u64",
                     )))?;
-            }
+
+            let year_distributed_credits = epoch_fee_share
+                .checked_mul(self.config.epochs_per_year as u64)
+                .ok_or(Error::Execution(ExecutionError::Overflow(
+                    "year storage share not fitting in a u64",
+                )))?;
+
+            storage_distribution_leftover_credits = storage_distribution_leftover_credits
+                .checked_sub(year_distributed_credits)
+                .ok_or(Error::Execution(ExecutionError::Overflow(
+                    "leftover storage not fitting in a u64",
+                )))?;
         }
 
+        batch.push(update_storage_fee_cohort_operation(&cohort)?);
+
         Ok(storage_distribution_leftover_credits)
     }
 }
@@ -134,11 +145,20 @@ mod tests {
 
     mod distribute_storage_fee_distribution_pool {
         use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+        use crate::execution::fee_pools::constants::FEE_DISTRIBUTION_TABLE;
         use rs_drive::common::helpers::epoch::get_storage_credits_for_distribution_for_epochs_in_range;
         use rs_drive::drive::batch::GroveDbOpBatch;
+        use rs_drive::drive::fee_pools::epochs::constants::{
+            EPOCHS_PER_YEAR, PERPETUAL_STORAGE_EPOCHS, PERPETUAL_STORAGE_YEARS,
+        };
+        use rs_drive::drive::fee_pools::pools_vec_path;
         use rs_drive::error::drive::DriveError;
         use rs_drive::fee_pools::epochs::Epoch;
+        use rs_drive::fee_pools::epochs_root_tree_key_constants::KEY_STORAGE_FEE_COHORTS;
         use rs_drive::fee_pools::update_storage_fee_distribution_pool_operation;
+        use rs_drive::grovedb::batch::GroveDbOp;
+        use rust_decimal::prelude::ToPrimitive;
+        use rust_decimal::Decimal;
 
         #[test]
         fn test_nothing_to_distribute() {
@@ -393,6 +413,161 @@ mod tests {
                     .collect::<Vec<u64>>()
             );
         }
+
+        /// Distributes storage fees by updating every epoch pool as it was done before cohorts
+        fn distribute_to_every_epoch(
+            storage_pool: u64,
+            current_epoch_index: u16,
+            epoch_storage_fees: &mut [u64],
+        ) -> u64 {
+            let mut leftovers = storage_pool;
+
+            let storage_pool = Decimal::from(storage_pool);
+            let epochs_per_year = Decimal::from(EPOCHS_PER_YEAR);
+
+            for year in 0..PERPETUAL_STORAGE_YEARS {
+                let year_fee_share = storage_pool * FEE_DISTRIBUTION_TABLE[year as usize];
+
+                let epoch_fee_share = (year_fee_share / epochs_per_year)
+                    .floor()
+                    .to_u64()
+                    .expect("epoch share should fit in u64");
+
+                let year_start_epoch_index = current_epoch_index + EPOCHS_PER_YEAR * year;
+
+                for index in year_start_epoch_index..year_start_epoch_index + EPOCHS_PER_YEAR {
+                    epoch_storage_fees[index as usize] += epoch_fee_share;
+
+                    leftovers -= epoch_fee_share;
+                }
+            }
+
+            leftovers
+        }
+
+        #[test]
+        fn test_cohorts_match_distribution_to_every_epoch() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            // (epoch index, storage pool) of every distribution, including two in the same epoch
+            let distributions: [(u16, u64); 5] = [
+                (1, 1_000_003),
+                (7, 987_654_321),
+                (7, 12_345),
+                (25, u64::MAX / 4),
+                (1042, 3_333_333_333),
+            ];
+
+            let last_epoch_index = 1042 + PERPETUAL_STORAGE_EPOCHS;
+
+            let mut batch = GroveDbOpBatch::new();
+
+            // init additional epochs pools as it will be done in epoch_change
+            for i in PERPETUAL_STORAGE_EPOCHS..last_epoch_index {
+                let epoch = Epoch::new(i);
+                epoch.add_init_empty_operations(&mut batch);
+            }
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let mut reference_fees = vec![0u64; last_epoch_index as usize];
+
+            for (epoch_index, storage_pool) in distributions {
+                let mut batch = GroveDbOpBatch::new();
+
+                batch.push(update_storage_fee_distribution_pool_operation(storage_pool));
+
+                platform
+                    .drive
+                    .grove_apply_batch(batch, false, Some(&transaction))
+                    .expect("should apply batch");
+
+                let mut batch = GroveDbOpBatch::new();
+
+                let leftovers = platform
+                    .add_distribute_storage_fee_distribution_pool_to_epochs_operations(
+                        epoch_index,
+                        Some(&transaction),
+                        &mut batch,
+                    )
+                    .expect("should distribute storage fee pool");
+
+                // only the cohort is updated regardless of the number of epochs
+                assert_eq!(batch.len(), 1);
+
+                platform
+                    .drive
+                    .grove_apply_batch(batch, false, Some(&transaction))
+                    .expect("should apply batch");
+
+                let reference_leftovers =
+                    distribute_to_every_epoch(storage_pool, epoch_index, &mut reference_fees);
+
+                assert_eq!(leftovers, reference_leftovers);
+            }
+
+            let storage_fees = get_storage_credits_for_distribution_for_epochs_in_range(
+                &platform.drive,
+                0..last_epoch_index,
+                Some(&transaction),
+            );
+
+            assert_eq!(storage_fees, reference_fees);
+        }
+
+        #[test]
+        fn test_create_storage_fee_cohorts_tree_if_it_does_not_exist() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let epoch_index = 1;
+
+            // Remove the cohorts tree as if the chain was initialized before storage fee cohorts
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(GroveDbOp::delete_tree_run_op(
+                pools_vec_path(),
+                KEY_STORAGE_FEE_COHORTS.to_vec(),
+            ));
+
+            batch.push(update_storage_fee_distribution_pool_operation(1000000));
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            assert!(!platform
+                .drive
+                .has_storage_fee_cohorts_tree(Some(&transaction))
+                .expect("should check storage fee cohorts tree existence"));
+
+            let mut batch = GroveDbOpBatch::new();
+
+            platform
+                .add_distribute_storage_fee_distribution_pool_to_epochs_operations(
+                    epoch_index,
+                    Some(&transaction),
+                    &mut batch,
+                )
+                .expect("should distribute storage fee pool");
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let cohort = platform
+                .drive
+                .get_storage_fee_cohort(epoch_index, Some(&transaction))
+                .expect("should get storage fee cohort");
+
+            assert!(cohort.is_some());
+        }
     }
 
     mod update_storage_fee_distribution_pool {
@@ -33,13 +33,14 @@
 //! information about the current epoch.
 //!
 
+use crate::abci::messages::Serializable;
 use crate::block::BlockInfo;
 use crate::error::execution::ExecutionError;
 use crate::error::Error;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-/// Lifetime of an epoch in milliseconds.
+/// Default lifetime of an epoch in milliseconds.
 pub const EPOCH_CHANGE_TIME_MS: u64 = 1576800000;
 
 /// Info pertinent to the current epoch.
@@ -72,13 +73,20 @@ impl EpochInfo {
         genesis_time_ms: u64,
         block_time_ms: u64,
         previous_block_time_ms: Option<u64>,
+        epoch_change_time_ms: u64,
     ) -> Result<Self, Error> {
         let previous_block_time = match previous_block_time_ms {
             Some(block_time) => block_time,
             None => return Ok(EpochInfo::default()),
         };
 
-        let epoch_change_time = Decimal::from(EPOCH_CHANGE_TIME_MS);
+        if epoch_change_time_ms == 0 {
+            return Err(Error::Execution(ExecutionError::Conversion(
+                "epoch change time must be greater than zero",
+            )));
+        }
+
+        let epoch_change_time = Decimal::from(epoch_change_time_ms);
         let block_time = Decimal::from(block_time_ms);
         let genesis_time = Decimal::from(genesis_time_ms);
         let previous_block_time = Decimal::from(previous_block_time);
@@ -123,28 +131,33 @@ impl EpochInfo {
     pub fn from_genesis_time_and_block_info(
         genesis_time_ms: u64,
         block_info: &BlockInfo,
+        epoch_change_time_ms: u64,
     ) -> Result<Self, Error> {
         Self::calculate(
             genesis_time_ms,
             block_info.block_time_ms,
             block_info.previous_block_time_ms,
+            epoch_change_time_ms,
         )
     }
 }
 
+impl<'a> Serializable<'a> for EpochInfo {}
+
 #[cfg(test)]
 mod test {
 
     mod calculate {
-        use crate::execution::fee_pools::epoch::EpochInfo;
+        use crate::execution::fee_pools::epoch::{EpochInfo, EPOCH_CHANGE_TIME_MS};
 
         #[test]
         fn test_epoch_change_to_0_epoch() {
             let genesis_time_ms: u64 = 1655396517902;
             let block_time_ms: u64 = 1655396517922;
 
-            let epoch_info = EpochInfo::calculate(genesis_time_ms, block_time_ms, None)
-                .expect("should calculate epochs info");
+            let epoch_info =
+                EpochInfo::calculate(genesis_time_ms, block_time_ms, None, EPOCH_CHANGE_TIME_MS)
+                    .expect("should calculate epochs info");
 
             assert_eq!(epoch_info.current_epoch_index, 0);
             assert_eq!(epoch_info.is_epoch_change, true);
@@ -156,9 +169,13 @@ mod test {
             let block_time_ms: u64 = 1655396517922;
             let prev_block_time_ms: u64 = 1655396517912;
 
-            let epoch_info =
-                EpochInfo::calculate(genesis_time_ms, block_time_ms, Some(prev_block_time_ms))
-                    .expect("should calculate epochs info");
+            let epoch_info = EpochInfo::calculate(
+                genesis_time_ms,
+                block_time_ms,
+                Some(prev_block_time_ms),
+                EPOCH_CHANGE_TIME_MS,
+            )
+            .expect("should calculate epochs info");
 
             assert_eq!(epoch_info.current_epoch_index, 0);
             assert_eq!(epoch_info.is_epoch_change, false);
@@ -170,12 +187,35 @@ mod test {
             let prev_block_time_ms: u64 = 1655396517912;
             let block_time_ms: u64 = 1657125244561;
 
-            let epoch_info =
-                EpochInfo::calculate(genesis_time_ms, block_time_ms, Some(prev_block_time_ms))
-                    .expect("should calculate epochs info");
+            let epoch_info = EpochInfo::calculate(
+                genesis_time_ms,
+                block_time_ms,
+                Some(prev_block_time_ms),
+                EPOCH_CHANGE_TIME_MS,
+            )
+            .expect("should calculate epochs info");
 
             assert_eq!(epoch_info.current_epoch_index, 1);
             assert_eq!(epoch_info.is_epoch_change, true);
         }
+
+        #[test]
+        fn test_epoch_change_with_custom_epoch_change_time() {
+            let genesis_time_ms: u64 = 1655396517902;
+            let prev_block_time_ms: u64 = genesis_time_ms + 59_000;
+            let block_time_ms: u64 = genesis_time_ms + 181_000;
+
+            let epoch_info = EpochInfo::calculate(
+                genesis_time_ms,
+                block_time_ms,
+                Some(prev_block_time_ms),
+                60_000,
+            )
+            .expect("should calculate epochs info");
+
+            assert_eq!(epoch_info.current_epoch_index, 3);
+            assert_eq!(epoch_info.previous_epoch_index, Some(0));
+            assert_eq!(epoch_info.is_epoch_change, true);
+        }
     }
 }
@@ -33,12 +33,14 @@
 //!
 
 use crate::abci::messages::FeesAggregate;
+use crate::contracts::reward_shares::split_masternode_reward;
 use crate::error::execution::ExecutionError;
 
 use crate::error::Error;
 use crate::platform::Platform;
 use rs_drive::drive::batch::GroveDbOpBatch;
 use rs_drive::drive::fee_pools::epochs::constants::GENESIS_EPOCH_INDEX;
+use rs_drive::drive::fee_pools::epochs::proposer_payouts::{ProposerPayout, SharePayout};
 use rs_drive::error::fee::FeeError;
 use rs_drive::fee_pools::epochs::Epoch;
 use rs_drive::fee_pools::{
@@ -46,8 +48,7 @@ use rs_drive::fee_pools::{
 };
 use rs_drive::grovedb::TransactionArg;
 use rs_drive::{error, grovedb};
-use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
 
 /// Struct containing the number of proposers to be paid and the index of the epoch
 /// they're to be paid from.
@@ -90,6 +91,23 @@ impl UnpaidEpoch {
     }
 }
 
+/// Adds a reward to the rewards accumulated per identity
+fn add_reward(
+    rewards: &mut BTreeMap<Vec<u8>, u64>,
+    identity_id: Vec<u8>,
+    reward: u64,
+) -> Result<(), Error> {
+    let accumulated_reward = rewards.entry(identity_id).or_default();
+
+    *accumulated_reward = accumulated_reward
+        .checked_add(reward)
+        .ok_or(Error::Execution(ExecutionError::Overflow(
+            "accumulated reward overflow",
+        )))?;
+
+    Ok(())
+}
+
 impl Platform {
     /// Adds operations to the op batch which distribute fees
     /// from the oldest unpaid epoch pool to proposers.
@@ -223,6 +241,11 @@ impl Platform {
     /// Adds operations to the op batch which distribute the fees from an unpaid epoch pool
     /// to the total fees to be paid out to proposers and divides amongst masternode reward shares.
     ///
+    /// Proposer rewards are the floored share of the blocks proposed by proposers paid so far
+    /// minus what was already paid, so remainders go to the following proposers and the epoch
+    /// pool is paid out completely over chunks. Reward share amounts are floored and remainders
+    /// stay with the proposer.
+    ///
     /// Returns the number of proposers to be paid out.
     fn add_epoch_pool_to_proposers_payout_operations(
         &self,
@@ -238,10 +261,13 @@ impl Platform {
             .get_epoch_total_credits_for_distribution(&unpaid_epoch_tree, transaction)
             .map_err(Error::Drive)?;
 
-        let total_fees = Decimal::from(total_fees);
+        let unpaid_epoch_block_count = unpaid_epoch.block_count()? as u128;
 
-        // Calculate block count
-        let unpaid_epoch_block_count = Decimal::from(unpaid_epoch.block_count()?);
+        if unpaid_epoch_block_count == 0 {
+            return Err(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "unpaid epoch must have blocks",
+            )));
+        }
 
         let proposers = self
             .drive
@@ -250,97 +276,88 @@ impl Platform {
 
         let proposers_len = proposers.len() as u16;
 
-        let mut fee_leftovers = dec!(0.0);
-
-        for (i, (proposer_tx_hash, proposed_block_count)) in proposers.iter().enumerate() {
-            let i = i as u16;
-            let proposed_block_count = Decimal::from(*proposed_block_count);
-
-            let mut masternode_reward =
-                (total_fees * proposed_block_count) / unpaid_epoch_block_count;
-
-            let documents =
-                self.get_reward_shares_list_for_masternode(proposer_tx_hash, transaction)?;
-
-            for document in documents {
-                let pay_to_id = document
-                    .properties
-                    .get("payToId")
-                    .ok_or(Error::Execution(ExecutionError::DriveMissingData(
-                        "payToId property is missing",
-                    )))?
-                    .as_bytes()
-                    .ok_or(Error::Execution(ExecutionError::DriveIncoherence(
-                        "payToId property type is not bytes",
-                    )))?;
-
-                // TODO this shouldn't be a percentage we need to update masternode share contract
-                let share_percentage_integer: i64 = document
-                    .properties
-                    .get("percentage")
-                    .ok_or(Error::Execution(ExecutionError::DriveMissingData(
-                        "percentage property is missing",
-                    )))?
-                    .as_integer()
-                    .ok_or(Error::Execution(ExecutionError::DriveIncoherence(
-                        "percentage property type is not integer",
-                    )))?
-                    .try_into()
-                    .map_err(|_| {
-                        Error::Execution(ExecutionError::Overflow(
-                            "percentage property cannot be converted to i64",
-                        ))
-                    })?;
-
-                let share_percentage = Decimal::from(share_percentage_integer) / dec!(10000);
-
-                let reward = masternode_reward * share_percentage;
-
-                let reward_floored = reward.floor();
-
-                // update masternode reward that would be paid later
-                masternode_reward -= reward_floored;
-
-                let reward_floored: u64 = reward_floored.try_into().map_err(|_| {
-                    Error::Execution(ExecutionError::Overflow(
-                        "can't convert reward to i64 from Decimal",
-                    ))
+        // Epochs initialized before payout receipts were introduced don't have the payouts tree
+        if proposers_len > 0
+            && !self
+                .drive
+                .has_epoch_proposer_payouts_tree(&unpaid_epoch_tree, transaction)
+                .map_err(Error::Drive)?
+        {
+            batch.push(unpaid_epoch_tree.init_proposer_payouts_tree_operation());
+        }
+
+        // Proposers paid in previous chunks have payout receipts
+        let mut paid_block_count = self
+            .drive
+            .get_epoch_payouts(&unpaid_epoch_tree, None, u16::MAX, transaction)
+            .map_err(Error::Drive)?
+            .iter()
+            .map(|payout| payout.proposed_block_count as u128)
+            .sum::<u128>();
+
+        let mut paid_fees = total_fees as u128 * paid_block_count / unpaid_epoch_block_count;
+
+        // Rewards are accumulated per identity and paid once, since an identity fetched
+        // from storage doesn't see previous payouts in the uncommitted batch
+        let mut rewards: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+
+        for (proposer_tx_hash, proposed_block_count) in proposers.iter() {
+            paid_block_count += *proposed_block_count as u128;
+
+            let fees_paid_after_proposer =
+                total_fees as u128 * paid_block_count / unpaid_epoch_block_count;
+
+            let masternode_reward: u64 = (fees_paid_after_proposer - paid_fees)
+                .try_into()
+                .map_err(|_| {
+                    Error::Execution(ExecutionError::Overflow("masternode reward overflow"))
                 })?;
 
-                self.add_pay_reward_to_identity_operations(
-                    pay_to_id,
-                    reward_floored,
-                    transaction,
-                    batch,
-                )?;
+            paid_fees = fees_paid_after_proposer;
+
+            let shares =
+                self.get_payable_reward_shares_for_masternode(proposer_tx_hash, transaction)?;
+
+            let (owner_reward, share_rewards) =
+                split_masternode_reward(masternode_reward, &shares)?;
+
+            for (pay_to_id, share_reward) in &share_rewards {
+                add_reward(&mut rewards, pay_to_id.to_vec(), *share_reward)?;
             }
 
-            // Since balance is an integer, we collect rewards remainder
-            // and add leftovers to the latest proposer of the chunk
-            let masternode_reward_floored = masternode_reward.floor();
+            add_reward(&mut rewards, proposer_tx_hash.clone(), owner_reward)?;
 
-            fee_leftovers += masternode_reward - masternode_reward_floored;
+            // Store the payout receipt, since proposers are removed once paid
+            let share_payouts: Vec<SharePayout> = share_rewards
+                .into_iter()
+                .map(|(pay_to_id, amount)| SharePayout { pay_to_id, amount })
+                .collect();
 
-            let masternode_reward_given = if i == proposers_len - 1 {
-                masternode_reward_floored + fee_leftovers
-            } else {
-                masternode_reward_floored
+            let total_reward = share_payouts
+                .iter()
+                .try_fold(owner_reward, |total, share_payout| {
+                    total.checked_add(share_payout.amount)
+                })
+                .ok_or(Error::Execution(ExecutionError::Overflow(
+                    "masternode total reward overflow",
+                )))?;
+
+            let proposer_payout = ProposerPayout {
+                pro_tx_hash: proposer_tx_hash.as_slice().try_into().map_err(|_| {
+                    Error::Execution(ExecutionError::DriveIncoherence(
+                        "proposer proTxHash must be 32 bytes",
+                    ))
+                })?,
+                proposed_block_count: *proposed_block_count,
+                total_reward,
+                share_payouts,
             };
 
-            // Convert to integer, since identity balance is u64
-            let masternode_reward_given: u64 =
-                masternode_reward_given.floor().try_into().map_err(|_| {
-                    Error::Execution(ExecutionError::Overflow(
-                        "can't convert reward to i64 from Decimal",
-                    ))
-                })?;
+            batch.push(unpaid_epoch_tree.update_proposer_payout_operation(&proposer_payout)?);
+        }
 
-            self.add_pay_reward_to_identity_operations(
-                proposer_tx_hash,
-                masternode_reward_given,
-                transaction,
-                batch,
-            )?;
+        for (identity_id, reward) in rewards {
+            self.add_pay_reward_to_identity_operations(&identity_id, reward, transaction, batch)?;
         }
 
         // remove proposers we've paid out
@@ -352,7 +369,8 @@ impl Platform {
         Ok(proposers_len)
     }
 
-    /// Adds operations to an op batch which pay a reward to an identity's balance
+    /// Adds operations to an op batch which pay a reward from the pools to an identity's balance,
+    /// settling the identity debt first
     fn add_pay_reward_to_identity_operations(
         &self,
         id: &[u8],
@@ -362,17 +380,17 @@ impl Platform {
     ) -> Result<(), Error> {
         // We don't need additional verification, since we ensure an identity
         // existence in the data contract triggers in DPP
-        let (mut identity, storage_flags) = self.drive.fetch_identity(id, transaction)?;
-
-        identity.balance = identity
-            .balance
-            .checked_add(reward)
-            .ok_or(Error::Execution(ExecutionError::Overflow(
-                "pay reward overflow",
-            )))?;
+        let credited_to_balance = self
+            .drive
+            .add_add_to_identity_balance_operations(id, reward, transaction, batch)
+            .map_err(Error::Drive)?;
 
         self.drive
-            .add_insert_identity_operations(identity, storage_flags.as_ref(), batch)
+            .add_update_credits_ledger_operations(
+                |credits_ledger| credits_ledger.pay_from_pools(reward, credited_to_balance),
+                transaction,
+                batch,
+            )
             .map_err(Error::Drive)
     }
 
@@ -1166,12 +1184,17 @@ mod tests {
 
     mod add_epoch_pool_to_proposers_payout_operations {
         use crate::common::helpers::fee_pools::{
+            create_test_masternode_share_documents_to_identity,
             create_test_masternode_share_identities_and_documents, refetch_identities,
         };
         use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
         use crate::execution::fee_pools::fee_distribution::UnpaidEpoch;
-        use rs_drive::common::helpers::identities::create_test_masternode_identities_and_add_them_as_epoch_block_proposers;
+        use rs_drive::common::helpers::identities::{
+            create_test_identity,
+            create_test_masternode_identities_and_add_them_as_epoch_block_proposers,
+        };
         use rs_drive::drive::batch::GroveDbOpBatch;
+        use rs_drive::drive::masternode_list::MasternodeListDiff;
         use rs_drive::fee_pools::epochs::Epoch;
         use rust_decimal::Decimal;
         use rust_decimal_macros::dec;
@@ -1300,6 +1323,290 @@ mod tests {
                 assert_eq!(identity.balance, payout_credits);
             }
         }
+
+        #[test]
+        fn test_create_proposer_payouts_tree_if_epoch_does_not_have_it() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform.create_mn_shares_contract(Some(&transaction));
+
+            let proposers_count = 10u16;
+
+            let unpaid_epoch_tree = Epoch::new(0);
+
+            // Initialize the epoch without the proposer payouts tree
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(unpaid_epoch_tree.update_start_block_height_operation(1));
+            batch.push(unpaid_epoch_tree.init_proposers_tree_operation());
+            batch.push(
+                unpaid_epoch_tree.update_processing_credits_for_distribution_operation(10000),
+            );
+            batch.push(unpaid_epoch_tree.update_storage_credits_for_distribution_operation(10000));
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let pro_tx_hashes =
+                create_test_masternode_identities_and_add_them_as_epoch_block_proposers(
+                    &platform.drive,
+                    &unpaid_epoch_tree,
+                    proposers_count,
+                    Some(&transaction),
+                );
+
+            let mut batch = GroveDbOpBatch::new();
+
+            let unpaid_epoch = UnpaidEpoch {
+                epoch_index: 0,
+                start_block_height: 1,
+                end_block_height: 11,
+                next_unpaid_epoch_index: 0,
+            };
+
+            let proposers_paid_count = platform
+                .add_epoch_pool_to_proposers_payout_operations(
+                    &unpaid_epoch,
+                    proposers_count,
+                    Some(&transaction),
+                    &mut batch,
+                )
+                .expect("should distribute fees");
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            assert_eq!(proposers_paid_count, 10);
+
+            let payouts = platform
+                .drive
+                .get_epoch_payouts(&unpaid_epoch_tree, None, u16::MAX, Some(&transaction))
+                .expect("should get epoch payouts");
+
+            assert_eq!(payouts.len(), pro_tx_hashes.len());
+            assert_eq!(
+                payouts
+                    .iter()
+                    .map(|payout| payout.total_reward)
+                    .sum::<u64>(),
+                20000
+            );
+        }
+
+        #[test]
+        fn test_do_not_share_rewards_of_proposers_missing_in_masternode_list() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            // Create masternode reward shares contract
+            let contract = platform.create_mn_shares_contract(Some(&transaction));
+
+            let processing_fees = 10000;
+
+            let unpaid_epoch_tree = Epoch::new(0);
+            let next_epoch_tree = Epoch::new(1);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            unpaid_epoch_tree.add_init_current_operations(1.0, 1, 1, &mut batch);
+
+            batch.push(
+                unpaid_epoch_tree
+                    .update_processing_credits_for_distribution_operation(processing_fees),
+            );
+
+            next_epoch_tree.add_init_current_operations(1.0, 2, 10, &mut batch);
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let pro_tx_hashes =
+                create_test_masternode_identities_and_add_them_as_epoch_block_proposers(
+                    &platform.drive,
+                    &unpaid_epoch_tree,
+                    1,
+                    Some(&transaction),
+                );
+
+            let share_identities_and_documents =
+                create_test_masternode_share_identities_and_documents(
+                    &platform.drive,
+                    &contract,
+                    &pro_tx_hashes,
+                    Some(&transaction),
+                );
+
+            // Masternode left the masternode list after proposing a block
+            platform
+                .drive
+                .apply_masternode_list_diff(
+                    &MasternodeListDiff {
+                        updated_masternodes: vec![],
+                        removed_masternodes: pro_tx_hashes.clone(),
+                        is_full_list: false,
+                    },
+                    Some(&transaction),
+                )
+                .expect("should remove masternode");
+
+            let mut batch = GroveDbOpBatch::new();
+
+            let unpaid_epoch = UnpaidEpoch {
+                epoch_index: 0,
+                start_block_height: 1,
+                end_block_height: 2,
+                next_unpaid_epoch_index: 0,
+            };
+
+            let proposers_paid_count = platform
+                .add_epoch_pool_to_proposers_payout_operations(
+                    &unpaid_epoch,
+                    1,
+                    Some(&transaction),
+                    &mut batch,
+                )
+                .expect("should distribute fees");
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            assert_eq!(proposers_paid_count, 1);
+
+            let paid_mn_identities = platform
+                .drive
+                .fetch_identities(&pro_tx_hashes, Some(&transaction))
+                .expect("expected to get identities");
+
+            assert_eq!(paid_mn_identities[0].balance, processing_fees);
+
+            let share_identities = share_identities_and_documents
+                .iter()
+                .map(|(identity, _)| identity)
+                .collect();
+
+            let refetched_share_identities =
+                refetch_identities(&platform.drive, share_identities, Some(&transaction))
+                    .expect("expected to refresh identities");
+
+            assert_eq!(refetched_share_identities[0].balance, 0);
+        }
+
+        #[test]
+        fn test_accumulate_rewards_shared_with_the_same_identity() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            // Create masternode reward shares contract
+            let contract = platform.create_mn_shares_contract(Some(&transaction));
+
+            let unpaid_epoch_tree = Epoch::new(0);
+            let next_epoch_tree = Epoch::new(1);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            unpaid_epoch_tree.add_init_current_operations(1.0, 1, 1, &mut batch);
+
+            batch.push(
+                unpaid_epoch_tree.update_processing_credits_for_distribution_operation(10001),
+            );
+
+            next_epoch_tree.add_init_current_operations(1.0, 3, 10, &mut batch);
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let pro_tx_hashes =
+                create_test_masternode_identities_and_add_them_as_epoch_block_proposers(
+                    &platform.drive,
+                    &unpaid_epoch_tree,
+                    2,
+                    Some(&transaction),
+                );
+
+            let share_identity = create_test_identity(&platform.drive, [0; 32], Some(&transaction));
+
+            create_test_masternode_share_documents_to_identity(
+                &platform.drive,
+                &contract,
+                &pro_tx_hashes,
+                &share_identity,
+                5000,
+                Some(&transaction),
+            );
+
+            let mut batch = GroveDbOpBatch::new();
+
+            let unpaid_epoch = UnpaidEpoch {
+                epoch_index: 0,
+                start_block_height: 1,
+                end_block_height: 3,
+                next_unpaid_epoch_index: 0,
+            };
+
+            platform
+                .add_epoch_pool_to_proposers_payout_operations(
+                    &unpaid_epoch,
+                    2,
+                    Some(&transaction),
+                    &mut batch,
+                )
+                .expect("should distribute fees");
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let (share_identity, _) = platform
+                .drive
+                .fetch_identity(&[0; 32], Some(&transaction))
+                .expect("should fetch share identity");
+
+            // Both shares are paid
+            assert_eq!(share_identity.balance, 5000);
+
+            let mut pro_tx_hashes = pro_tx_hashes;
+            pro_tx_hashes.sort();
+
+            let (first_proposer_identity, _) = platform
+                .drive
+                .fetch_identity(&pro_tx_hashes[0], Some(&transaction))
+                .expect("should fetch proposer identity");
+
+            assert_eq!(first_proposer_identity.balance, 2500);
+
+            // Leftover credit is paid to the last proposer
+            let (last_proposer_identity, _) = platform
+                .drive
+                .fetch_identity(&pro_tx_hashes[1], Some(&transaction))
+                .expect("should fetch proposer identity");
+
+            assert_eq!(last_proposer_identity.balance, 2501);
+
+            let payouts = platform
+                .drive
+                .get_epoch_payouts(&unpaid_epoch_tree, None, 10, Some(&transaction))
+                .expect("should get epoch payouts");
+
+            assert_eq!(payouts.len(), 2);
+            assert_eq!(payouts[1].pro_tx_hash, pro_tx_hashes[1]);
+            assert_eq!(payouts[1].proposed_block_count, 1);
+            assert_eq!(payouts[1].total_reward, 5001);
+            assert_eq!(payouts[1].owner_reward(), 2501);
+            assert_eq!(payouts[1].share_payouts[0].pay_to_id, [0; 32]);
+            assert_eq!(payouts[1].share_payouts[0].amount, 2500);
+        }
     }
 
     mod add_distribute_block_fees_into_pools_operations {
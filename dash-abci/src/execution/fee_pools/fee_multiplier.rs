@@ -0,0 +1,228 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Fee multiplier.
+//!
+//! This module defines functions choosing the fee multiplier applied to costs
+//! of operations performed in an epoch.
+//!
+
+use crate::error::Error;
+use crate::execution::fee_pools::epoch::EpochInfo;
+use crate::platform::Platform;
+use rs_drive::fee_pools::epochs::Epoch;
+use rs_drive::grovedb::TransactionArg;
+
+impl Platform {
+    /// Returns the fee multiplier of the current epoch.
+    ///
+    /// On epoch change the fee multiplier is taken from the feature flags contract if
+    /// it's specified for the new epoch, otherwise the previous epoch fee multiplier is kept.
+    pub fn get_current_epoch_fee_multiplier(
+        &self,
+        epoch_info: &EpochInfo,
+        transaction: TransactionArg,
+    ) -> Result<f64, Error> {
+        if !epoch_info.is_epoch_change {
+            let current_epoch = Epoch::new(epoch_info.current_epoch_index);
+
+            return Ok(self
+                .drive
+                .get_epoch_fee_multiplier(&current_epoch, transaction)?);
+        }
+
+        if let Some(fee_multiplier) = self.get_fee_multiplier_feature_flag_for_epoch(
+            epoch_info.current_epoch_index,
+            transaction,
+        )? {
+            return Ok(fee_multiplier);
+        }
+
+        match epoch_info.previous_epoch_index {
+            Some(previous_epoch_index) => Ok(self
+                .drive
+                .get_epoch_fee_multiplier(&Epoch::new(previous_epoch_index), transaction)?),
+            None => Ok(self.config.default_original_fee_multiplier),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod get_current_epoch_fee_multiplier {
+        use crate::common::helpers::feature_flags::create_test_update_fee_multiplier_document;
+        use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+        use crate::error::execution::ExecutionError;
+        use crate::error::Error;
+        use crate::execution::fee_pools::epoch::EpochInfo;
+        use ciborium::value::Value;
+        use rs_drive::drive::batch::GroveDbOpBatch;
+        use rs_drive::fee_pools::epochs::Epoch;
+        use rs_drive::fee_pools::epochs::DEFAULT_FEE_MULTIPLIER;
+
+        #[test]
+        fn test_default_fee_multiplier_on_genesis() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 0,
+                previous_epoch_index: None,
+                is_epoch_change: true,
+            };
+
+            let fee_multiplier = platform
+                .get_current_epoch_fee_multiplier(&epoch_info, Some(&transaction))
+                .expect("should get fee multiplier");
+
+            assert_eq!(fee_multiplier, DEFAULT_FEE_MULTIPLIER);
+        }
+
+        #[test]
+        fn test_previous_epoch_fee_multiplier_is_kept() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(Epoch::new(0).update_fee_multiplier_operation(3.0));
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 1,
+                previous_epoch_index: Some(0),
+                is_epoch_change: true,
+            };
+
+            let fee_multiplier = platform
+                .get_current_epoch_fee_multiplier(&epoch_info, Some(&transaction))
+                .expect("should get fee multiplier");
+
+            assert_eq!(fee_multiplier, 3.0);
+        }
+
+        #[test]
+        fn test_fee_multiplier_from_feature_flag() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let contract = platform.create_feature_flags_contract(Some(&transaction));
+
+            create_test_update_fee_multiplier_document(
+                &platform.drive,
+                &contract,
+                1,
+                1.5,
+                Some(&transaction),
+            );
+
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(Epoch::new(0).update_fee_multiplier_operation(3.0));
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 1,
+                previous_epoch_index: Some(0),
+                is_epoch_change: true,
+            };
+
+            let fee_multiplier = platform
+                .get_current_epoch_fee_multiplier(&epoch_info, Some(&transaction))
+                .expect("should get fee multiplier");
+
+            assert_eq!(fee_multiplier, 1.5);
+        }
+
+        #[test]
+        fn test_integer_fee_multiplier_from_feature_flag() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let contract = platform.create_feature_flags_contract(Some(&transaction));
+
+            create_test_update_fee_multiplier_document(
+                &platform.drive,
+                &contract,
+                1,
+                2,
+                Some(&transaction),
+            );
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 1,
+                previous_epoch_index: Some(0),
+                is_epoch_change: true,
+            };
+
+            let fee_multiplier = platform
+                .get_current_epoch_fee_multiplier(&epoch_info, Some(&transaction))
+                .expect("should get fee multiplier");
+
+            assert_eq!(fee_multiplier, 2.0);
+        }
+
+        #[test]
+        fn test_error_if_fee_multiplier_feature_flag_is_invalid() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let contract = platform.create_feature_flags_contract(Some(&transaction));
+
+            create_test_update_fee_multiplier_document(
+                &platform.drive,
+                &contract,
+                1,
+                Value::Text(String::from("2")),
+                Some(&transaction),
+            );
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 1,
+                previous_epoch_index: Some(0),
+                is_epoch_change: true,
+            };
+
+            let result = platform.get_current_epoch_fee_multiplier(&epoch_info, Some(&transaction));
+
+            assert!(matches!(
+                result,
+                Err(Error::Execution(ExecutionError::DriveIncoherence(_)))
+            ));
+        }
+    }
+}
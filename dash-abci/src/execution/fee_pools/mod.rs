@@ -2,4 +2,5 @@ pub mod constants;
 pub mod distribute_storage_pool;
 pub mod epoch;
 pub mod fee_distribution;
+pub mod fee_multiplier;
 pub mod process_block_fees;
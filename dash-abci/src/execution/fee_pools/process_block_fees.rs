@@ -35,16 +35,14 @@
 
 use crate::abci::messages::FeesAggregate;
 use crate::block::BlockInfo;
+use crate::error::execution::ExecutionError;
 use crate::error::Error;
-use crate::execution::fee_pools::constants::DEFAULT_ORIGINAL_FEE_MULTIPLIER;
 use crate::execution::fee_pools::distribute_storage_pool::StorageDistributionLeftoverCredits;
 use crate::execution::fee_pools::epoch::EpochInfo;
 use crate::execution::fee_pools::fee_distribution::{FeesInPools, ProposersPayouts};
 use crate::platform::Platform;
 use rs_drive::drive::batch::GroveDbOpBatch;
-use rs_drive::drive::fee_pools::epochs::constants::{
-    GENESIS_EPOCH_INDEX, PERPETUAL_STORAGE_EPOCHS,
-};
+use rs_drive::drive::fee_pools::epochs::constants::GENESIS_EPOCH_INDEX;
 use rs_drive::fee_pools::epochs::Epoch;
 use rs_drive::grovedb::TransactionArg;
 use std::option::Option::None;
@@ -80,6 +78,7 @@ impl Platform {
         &self,
         block_info: &BlockInfo,
         epoch_info: &EpochInfo,
+        fee_multiplier: f64,
         transaction: TransactionArg,
         batch: &mut GroveDbOpBatch,
     ) -> Result<Option<StorageDistributionLeftoverCredits>, Error> {
@@ -89,15 +88,16 @@ impl Platform {
             .map_or(GENESIS_EPOCH_INDEX, |i| i + 1);
 
         for epoch_index in last_initiated_epoch_index..=epoch_info.current_epoch_index {
-            let next_thousandth_epoch = Epoch::new(epoch_index + PERPETUAL_STORAGE_EPOCHS);
-            next_thousandth_epoch.add_init_empty_without_storage_operations(batch);
+            let next_thousandth_epoch =
+                Epoch::new(epoch_index + self.config.perpetual_storage_epochs());
+            next_thousandth_epoch.add_init_empty_operations(batch);
         }
 
         // init current epoch pool for processing
         let current_epoch = Epoch::new(epoch_info.current_epoch_index);
 
         current_epoch.add_init_current_operations(
-            DEFAULT_ORIGINAL_FEE_MULTIPLIER, // TODO use a data contract to choose the fee multiplier
+            fee_multiplier,
             block_info.block_height,
             block_info.block_time_ms,
             batch,
@@ -127,6 +127,7 @@ impl Platform {
         &self,
         block_info: &BlockInfo,
         epoch_info: &EpochInfo,
+        fee_multiplier: f64,
         block_fees: &FeesAggregate,
         transaction: TransactionArg,
     ) -> Result<ProcessedBlockFeesResult, Error> {
@@ -138,6 +139,7 @@ impl Platform {
             self.add_process_epoch_change_operations(
                 block_info,
                 epoch_info,
+                fee_multiplier,
                 transaction,
                 &mut batch,
             )?
@@ -160,6 +162,21 @@ impl Platform {
             transaction,
         )?);
 
+        // Epochs started before protocol version voting was introduced don't have the votes tree
+        if !epoch_info.is_epoch_change
+            && !self
+                .drive
+                .has_epoch_protocol_version_votes_tree(&current_epoch, transaction)
+                .map_err(Error::Drive)?
+        {
+            batch.push(current_epoch.init_protocol_version_votes_tree_operation());
+        }
+
+        batch.push(current_epoch.update_protocol_version_vote_operation(
+            &block_info.proposer_pro_tx_hash,
+            block_info.proposed_protocol_version,
+        ));
+
         // Distribute fees from unpaid epoch pool to proposers
 
         // Since start_block_height for current epoch is batched and not committed yet
@@ -187,6 +204,19 @@ impl Platform {
             &mut batch,
         )?;
 
+        let block_fees_sum = block_fees
+            .processing_fees
+            .checked_add(block_fees.storage_fees)
+            .ok_or(Error::Execution(ExecutionError::Overflow(
+                "overflow when adding block fees",
+            )))?;
+
+        self.drive.add_update_credits_ledger_operations(
+            |credits_ledger| credits_ledger.add_to_pools(block_fees_sum),
+            transaction,
+            &mut batch,
+        )?;
+
         self.drive.grove_apply_batch(batch, false, transaction)?;
 
         Ok(ProcessedBlockFeesResult {
@@ -201,7 +231,11 @@ mod tests {
     mod add_process_epoch_change_operations {
         use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
         use chrono::Utc;
+        use rs_drive::drive::batch::GroveDbOpBatch;
         use rs_drive::drive::fee_pools::epochs::constants::GENESIS_EPOCH_INDEX;
+        use rs_drive::fee_pools::epochs::epoch_key_constants::KEY_PROTOCOL_VERSION_VOTES;
+        use rs_drive::fee_pools::epochs::Epoch;
+        use rs_drive::grovedb::batch::GroveDbOp;
         use rust_decimal::prelude::ToPrimitive;
 
         mod helpers {
@@ -212,6 +246,7 @@ mod tests {
             use rs_drive::drive::batch::GroveDbOpBatch;
             use rs_drive::drive::fee_pools::epochs::constants::PERPETUAL_STORAGE_EPOCHS;
             use rs_drive::fee_pools::epochs::Epoch;
+            use rs_drive::fee_pools::epochs::DEFAULT_FEE_MULTIPLIER;
             use rs_drive::grovedb::TransactionArg;
 
             /// Process and validate an epoch change
@@ -263,11 +298,16 @@ mod tests {
                     block_time_ms,
                     previous_block_time_ms,
                     proposer_pro_tx_hash,
+                    proposed_protocol_version: 1,
+                    core_chain_locked_height: 1,
                 };
 
-                let epoch_info =
-                    EpochInfo::from_genesis_time_and_block_info(genesis_time_ms, &block_info)
-                        .expect("should calculate epoch info");
+                let epoch_info = EpochInfo::from_genesis_time_and_block_info(
+                    genesis_time_ms,
+                    &block_info,
+                    EPOCH_CHANGE_TIME_MS,
+                )
+                .expect("should calculate epoch info");
 
                 let mut batch = GroveDbOpBatch::new();
 
@@ -275,6 +315,7 @@ mod tests {
                     .add_process_epoch_change_operations(
                         &block_info,
                         &epoch_info,
+                        DEFAULT_FEE_MULTIPLIER,
                         transaction,
                         &mut batch,
                     )
@@ -406,6 +447,7 @@ mod tests {
             use crate::platform::Platform;
             use rs_drive::drive::fee_pools::epochs::constants::GENESIS_EPOCH_INDEX;
             use rs_drive::fee_pools::epochs::Epoch;
+            use rs_drive::fee_pools::epochs::DEFAULT_FEE_MULTIPLIER;
             use rs_drive::grovedb::TransactionArg;
 
             /// Process and validate block fees
@@ -428,11 +470,16 @@ mod tests {
                     block_time_ms,
                     previous_block_time_ms,
                     proposer_pro_tx_hash,
+                    proposed_protocol_version: 1,
+                    core_chain_locked_height: 1,
                 };
 
-                let epoch_info =
-                    EpochInfo::from_genesis_time_and_block_info(genesis_time_ms, &block_info)
-                        .expect("should calculate epoch info");
+                let epoch_info = EpochInfo::from_genesis_time_and_block_info(
+                    genesis_time_ms,
+                    &block_info,
+                    EPOCH_CHANGE_TIME_MS,
+                )
+                .expect("should calculate epoch info");
 
                 let block_fees = FeesAggregate {
                     processing_fees: 1000,
@@ -440,7 +487,13 @@ mod tests {
                 };
 
                 let distribute_storage_pool_result = platform
-                    .process_block_fees(&block_info, &epoch_info, &block_fees, transaction)
+                    .process_block_fees(
+                        &block_info,
+                        &epoch_info,
+                        DEFAULT_FEE_MULTIPLIER,
+                        &block_fees,
+                        transaction,
+                    )
                     .expect("should process block fees");
 
                 // Should process epoch change
@@ -634,5 +687,75 @@ mod tests {
                 Some(&transaction),
             );
         }
+
+        #[test]
+        fn test_create_protocol_version_votes_tree_if_current_epoch_does_not_have_it() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform.create_mn_shares_contract(Some(&transaction));
+
+            let proposers =
+                create_test_masternode_identities(&platform.drive, 2, Some(&transaction));
+
+            let genesis_time_ms = Utc::now()
+                .timestamp_millis()
+                .to_u64()
+                .expect("block time can not be before 1970");
+
+            let block_info = helpers::process_and_validate_block_fees(
+                &platform,
+                genesis_time_ms,
+                GENESIS_EPOCH_INDEX,
+                1,
+                None,
+                proposers[0],
+                Some(&transaction),
+            );
+
+            // Remove the votes tree as if the epoch was started before protocol version voting
+            let current_epoch = Epoch::new(GENESIS_EPOCH_INDEX);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.add_delete(
+                current_epoch.get_protocol_version_votes_vec_path(),
+                proposers[0].to_vec(),
+            );
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(GroveDbOp::delete_tree_run_op(
+                current_epoch.get_vec_path(),
+                KEY_PROTOCOL_VERSION_VOTES.to_vec(),
+            ));
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            helpers::process_and_validate_block_fees(
+                &platform,
+                genesis_time_ms,
+                GENESIS_EPOCH_INDEX,
+                2,
+                Some(block_info.block_time_ms),
+                proposers[1],
+                Some(&transaction),
+            );
+
+            let votes = platform
+                .drive
+                .get_epoch_protocol_version_votes(&current_epoch, Some(&transaction))
+                .expect("should get protocol version votes");
+
+            assert_eq!(votes.get(&1), Some(&1));
+        }
     }
 }
@@ -0,0 +1,616 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Masternode Identities.
+//!
+//! Every registered masternode has an owner identity and an operator identity
+//! derived deterministically from the simplified masternode list. The owner identity id
+//! is the masternode proTxHash, so masternode rewards are paid to it.
+//! Identity keys follow masternode keys and are disabled once the masternode is removed.
+//!
+
+use crate::error::execution::ExecutionError;
+use crate::error::Error;
+use crate::platform::Platform;
+use rs_drive::dpp::identifier::Identifier;
+use rs_drive::dpp::identity::{Identity, IdentityPublicKey, KeyType, Purpose, SecurityLevel};
+use rs_drive::dpp::util::hash::hash;
+use rs_drive::dpp::version::LATEST_VERSION;
+use rs_drive::drive::batch::GroveDbOpBatch;
+use rs_drive::drive::flags::StorageFlags;
+use rs_drive::drive::masternode_list::{MasternodeListDiff, MasternodeListEntry};
+use rs_drive::grovedb::TransactionArg;
+
+/// Returns the id of the masternode owner identity
+pub fn masternode_owner_identity_id(pro_tx_hash: &[u8; 32]) -> [u8; 32] {
+    *pro_tx_hash
+}
+
+/// Returns the id of the masternode operator identity.
+/// A new operator identity is used once the operator key is changed.
+pub fn masternode_operator_identity_id(
+    pro_tx_hash: &[u8; 32],
+    operator_public_key: &[u8],
+) -> Result<[u8; 32], Error> {
+    let mut payload = pro_tx_hash.to_vec();
+    payload.extend_from_slice(operator_public_key);
+
+    hash(payload).try_into().map_err(|_| {
+        Error::Execution(ExecutionError::CorruptedCodeExecution(
+            "operator identity id must be 32 bytes",
+        ))
+    })
+}
+
+/// Returns the owner identity key of the masternode
+fn masternode_owner_identity_key(masternode: &MasternodeListEntry, id: u64) -> IdentityPublicKey {
+    IdentityPublicKey {
+        id,
+        key_type: KeyType::ECDSA_HASH160,
+        data: masternode.owner_key_hash.to_vec(),
+        purpose: Purpose::AUTHENTICATION,
+        security_level: SecurityLevel::MASTER,
+        read_only: true,
+        disabled_at: None,
+        signature: Default::default(),
+    }
+}
+
+/// Returns the operator identity key of the masternode
+fn masternode_operator_identity_key(masternode: &MasternodeListEntry) -> IdentityPublicKey {
+    IdentityPublicKey {
+        id: 0,
+        key_type: KeyType::BLS12_381,
+        data: masternode.operator_public_key.clone(),
+        purpose: Purpose::AUTHENTICATION,
+        security_level: SecurityLevel::CRITICAL,
+        read_only: true,
+        disabled_at: None,
+        signature: Default::default(),
+    }
+}
+
+/// Returns the id for a new key of the identity
+fn next_identity_key_id(identity: &Identity) -> u64 {
+    identity
+        .public_keys
+        .iter()
+        .map(|key| key.id + 1)
+        .max()
+        .unwrap_or_default()
+}
+
+/// Disables all enabled identity keys
+fn disable_identity_keys(identity: &mut Identity, block_time_ms: u64) {
+    for key in identity.public_keys.iter_mut() {
+        if key.disabled_at.is_none() {
+            key.disabled_at = Some(block_time_ms);
+        }
+    }
+}
+
+impl Platform {
+    /// Fetches an identity with its storage flags if it exists
+    pub(crate) fn fetch_identity_if_exists(
+        &self,
+        id: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Option<(Identity, Option<StorageFlags>)>, Error> {
+        match self.drive.fetch_identity(id, transaction) {
+            Ok(identity) => Ok(Some(identity)),
+            Err(rs_drive::error::Error::GroveDB(
+                rs_drive::grovedb::Error::PathKeyNotFound(_)
+                | rs_drive::grovedb::Error::PathNotFound(_)
+                | rs_drive::grovedb::Error::PathParentLayerNotFound(_),
+            )) => Ok(None),
+            Err(e) => Err(Error::Drive(e)),
+        }
+    }
+
+    /// Adds operations to the op batch which insert a new identity with the given key
+    fn add_create_masternode_identity_operations(
+        &self,
+        id: [u8; 32],
+        key: IdentityPublicKey,
+        epoch_index: u16,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        let identity = Identity {
+            id: Identifier::new(id),
+            revision: 0,
+            balance: 0,
+            protocol_version: LATEST_VERSION,
+            public_keys: vec![key],
+            asset_lock_proof: None,
+            metadata: None,
+        };
+
+        let storage_flags = StorageFlags::new_single_epoch(epoch_index, None);
+
+        self.drive
+            .add_insert_identity_operations(identity, Some(&storage_flags), batch)
+            .map_err(Error::Drive)
+    }
+
+    /// Adds operations to the op batch which create, rotate keys of or disable
+    /// masternode owner and operator identities according to the masternode list diff.
+    ///
+    /// Must be called before the diff is applied to the masternode list,
+    /// since previous masternode keys are read from it.
+    pub fn add_update_masternode_identities_operations(
+        &self,
+        masternode_list_diff: &MasternodeListDiff,
+        block_time_ms: u64,
+        epoch_index: u16,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        for masternode in &masternode_list_diff.updated_masternodes {
+            let owner_identity_id = masternode_owner_identity_id(&masternode.pro_tx_hash);
+            let operator_identity_id = masternode_operator_identity_id(
+                &masternode.pro_tx_hash,
+                &masternode.operator_public_key,
+            )?;
+
+            let maybe_previous_masternode = self
+                .drive
+                .fetch_masternode(&masternode.pro_tx_hash, transaction)?;
+
+            let previous_masternode = match maybe_previous_masternode {
+                Some(previous_masternode) => previous_masternode,
+                None => {
+                    // Newly registered masternode
+                    self.add_create_or_enable_masternode_identity_operations(
+                        owner_identity_id,
+                        masternode_owner_identity_key(masternode, 0),
+                        epoch_index,
+                        transaction,
+                        batch,
+                    )?;
+
+                    self.add_create_or_enable_masternode_identity_operations(
+                        operator_identity_id,
+                        masternode_operator_identity_key(masternode),
+                        epoch_index,
+                        transaction,
+                        batch,
+                    )?;
+
+                    continue;
+                }
+            };
+
+            if previous_masternode.owner_key_hash != masternode.owner_key_hash {
+                let (mut owner_identity, storage_flags) = self
+                    .fetch_identity_if_exists(&owner_identity_id, transaction)?
+                    .ok_or(Error::Execution(ExecutionError::DriveIncoherence(
+                        "masternode owner identity must exist",
+                    )))?;
+
+                disable_identity_keys(&mut owner_identity, block_time_ms);
+
+                let next_key_id = next_identity_key_id(&owner_identity);
+
+                owner_identity
+                    .public_keys
+                    .push(masternode_owner_identity_key(masternode, next_key_id));
+
+                owner_identity.revision += 1;
+
+                self.drive.add_update_identity_operations(
+                    owner_identity,
+                    storage_flags.as_ref(),
+                    batch,
+                )?;
+            }
+
+            if previous_masternode.operator_public_key != masternode.operator_public_key {
+                self.add_disable_masternode_operator_identity_operations(
+                    &previous_masternode,
+                    block_time_ms,
+                    transaction,
+                    batch,
+                )?;
+
+                // The operator may rotate back to a previous key with an existing identity
+                self.add_create_or_enable_masternode_identity_operations(
+                    operator_identity_id,
+                    masternode_operator_identity_key(masternode),
+                    epoch_index,
+                    transaction,
+                    batch,
+                )?;
+            }
+        }
+
+        for pro_tx_hash in &masternode_list_diff.removed_masternodes {
+            let previous_masternode = match self.drive.fetch_masternode(pro_tx_hash, transaction)? {
+                Some(previous_masternode) => previous_masternode,
+                None => continue,
+            };
+
+            let owner_identity_id = masternode_owner_identity_id(pro_tx_hash);
+
+            if let Some((mut owner_identity, storage_flags)) =
+                self.fetch_identity_if_exists(&owner_identity_id, transaction)?
+            {
+                disable_identity_keys(&mut owner_identity, block_time_ms);
+
+                owner_identity.revision += 1;
+
+                self.drive.add_update_identity_operations(
+                    owner_identity,
+                    storage_flags.as_ref(),
+                    batch,
+                )?;
+            }
+
+            self.add_disable_masternode_operator_identity_operations(
+                &previous_masternode,
+                block_time_ms,
+                transaction,
+                batch,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds operations to the op batch which create the masternode identity with the given key
+    /// or enable the key of the identity if it already exists
+    fn add_create_or_enable_masternode_identity_operations(
+        &self,
+        id: [u8; 32],
+        key: IdentityPublicKey,
+        epoch_index: u16,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        let (mut identity, storage_flags) = match self.fetch_identity_if_exists(&id, transaction)? {
+            Some(identity) => identity,
+            None => {
+                return self.add_create_masternode_identity_operations(id, key, epoch_index, batch)
+            }
+        };
+
+        match identity
+            .public_keys
+            .iter_mut()
+            .find(|identity_key| identity_key.data == key.data)
+        {
+            Some(identity_key) => identity_key.disabled_at = None,
+            None => {
+                let id = next_identity_key_id(&identity);
+
+                identity.public_keys.push(IdentityPublicKey { id, ..key });
+            }
+        }
+
+        identity.revision += 1;
+
+        self.drive
+            .add_update_identity_operations(identity, storage_flags.as_ref(), batch)
+            .map_err(Error::Drive)
+    }
+
+    /// Adds operations to the op batch which disable keys of the masternode operator identity
+    fn add_disable_masternode_operator_identity_operations(
+        &self,
+        masternode: &MasternodeListEntry,
+        block_time_ms: u64,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        let operator_identity_id = masternode_operator_identity_id(
+            &masternode.pro_tx_hash,
+            &masternode.operator_public_key,
+        )?;
+
+        if let Some((mut operator_identity, storage_flags)) =
+            self.fetch_identity_if_exists(&operator_identity_id, transaction)?
+        {
+            disable_identity_keys(&mut operator_identity, block_time_ms);
+
+            operator_identity.revision += 1;
+
+            self.drive.add_update_identity_operations(
+                operator_identity,
+                storage_flags.as_ref(),
+                batch,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates masternode identities and the masternode list according to the masternode list diff
+    pub fn update_masternode_list(
+        &self,
+        masternode_list_diff: &MasternodeListDiff,
+        block_time_ms: u64,
+        epoch_index: u16,
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        if masternode_list_diff.is_empty() {
+            return Ok(());
+        }
+
+        let mut batch = GroveDbOpBatch::new();
+
+        self.add_update_masternode_identities_operations(
+            masternode_list_diff,
+            block_time_ms,
+            epoch_index,
+            transaction,
+            &mut batch,
+        )?;
+
+        self.drive.add_apply_masternode_list_diff_operations(
+            masternode_list_diff,
+            transaction,
+            &mut batch,
+        )?;
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        self.drive
+            .grove_apply_batch(batch, false, transaction)
+            .map_err(Error::Drive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+    use rs_drive::drive::block_info::BlockInfo as DriveBlockInfo;
+    use rs_drive::drive::masternode_list::MasternodeStatus;
+
+    fn create_masternode(owner_key_hash: u8, operator_public_key: u8) -> MasternodeListEntry {
+        MasternodeListEntry {
+            pro_tx_hash: [1; 32],
+            owner_key_hash: [owner_key_hash; 20],
+            voting_key_hash: [2; 20],
+            payout_script: vec![3; 25],
+            operator_public_key: vec![operator_public_key; 48],
+            status: MasternodeStatus::Valid,
+        }
+    }
+
+    fn update_masternode_list(
+        platform: &Platform,
+        masternode_list_diff: MasternodeListDiff,
+        block_time_ms: u64,
+        transaction: TransactionArg,
+    ) {
+        platform
+            .update_masternode_list(&masternode_list_diff, block_time_ms, 0, transaction)
+            .expect("should update masternode list");
+    }
+
+    #[test]
+    fn test_masternode_identities_lifecycle() {
+        let platform = setup_platform_with_initial_state_structure();
+        let transaction = platform.drive.grove.start_transaction();
+
+        // Register
+        update_masternode_list(
+            &platform,
+            MasternodeListDiff {
+                updated_masternodes: vec![create_masternode(4, 5)],
+                removed_masternodes: vec![],
+                is_full_list: false,
+            },
+            1000,
+            Some(&transaction),
+        );
+
+        let (owner_identity, _) = platform
+            .drive
+            .fetch_identity(&[1; 32], Some(&transaction))
+            .expect("should create owner identity");
+
+        assert_eq!(owner_identity.public_keys.len(), 1);
+        assert_eq!(owner_identity.public_keys[0].data, vec![4; 20]);
+
+        let first_operator_identity_id = masternode_operator_identity_id(&[1; 32], &[5; 48])
+            .expect("should derive operator identity id");
+
+        let (operator_identity, _) = platform
+            .drive
+            .fetch_identity(&first_operator_identity_id, Some(&transaction))
+            .expect("should create operator identity");
+
+        assert_eq!(operator_identity.public_keys[0].data, vec![5; 48]);
+
+        // Rotate keys
+        update_masternode_list(
+            &platform,
+            MasternodeListDiff {
+                updated_masternodes: vec![create_masternode(6, 7)],
+                removed_masternodes: vec![],
+                is_full_list: false,
+            },
+            2000,
+            Some(&transaction),
+        );
+
+        let (owner_identity, _) = platform
+            .drive
+            .fetch_identity(&[1; 32], Some(&transaction))
+            .expect("should fetch owner identity");
+
+        assert_eq!(owner_identity.public_keys.len(), 2);
+        assert_eq!(owner_identity.public_keys[0].disabled_at, Some(2000));
+        assert_eq!(owner_identity.public_keys[1].id, 1);
+        assert_eq!(owner_identity.public_keys[1].data, vec![6; 20]);
+        assert_eq!(owner_identity.public_keys[1].disabled_at, None);
+
+        let (first_operator_identity, _) = platform
+            .drive
+            .fetch_identity(&first_operator_identity_id, Some(&transaction))
+            .expect("should fetch previous operator identity");
+
+        assert_eq!(
+            first_operator_identity.public_keys[0].disabled_at,
+            Some(2000)
+        );
+
+        let second_operator_identity_id = masternode_operator_identity_id(&[1; 32], &[7; 48])
+            .expect("should derive operator identity id");
+
+        let (second_operator_identity, _) = platform
+            .drive
+            .fetch_identity(&second_operator_identity_id, Some(&transaction))
+            .expect("should create new operator identity");
+
+        assert_eq!(second_operator_identity.public_keys[0].disabled_at, None);
+
+        // Remove
+        update_masternode_list(
+            &platform,
+            MasternodeListDiff {
+                updated_masternodes: vec![],
+                removed_masternodes: vec![[1; 32]],
+                is_full_list: false,
+            },
+            3000,
+            Some(&transaction),
+        );
+
+        let (owner_identity, _) = platform
+            .drive
+            .fetch_identity(&[1; 32], Some(&transaction))
+            .expect("should fetch owner identity");
+
+        assert_eq!(owner_identity.public_keys[1].disabled_at, Some(3000));
+
+        let (second_operator_identity, _) = platform
+            .drive
+            .fetch_identity(&second_operator_identity_id, Some(&transaction))
+            .expect("should fetch operator identity");
+
+        assert_eq!(
+            second_operator_identity.public_keys[0].disabled_at,
+            Some(3000)
+        );
+
+        let masternode = platform
+            .drive
+            .fetch_masternode(&[1; 32], Some(&transaction))
+            .expect("should fetch masternode");
+
+        assert!(masternode.is_none());
+    }
+    #[test]
+    fn test_masternode_identities_keep_debt_and_reuse_previous_operator_identity() {
+        let platform = setup_platform_with_initial_state_structure();
+        let transaction = platform.drive.grove.start_transaction();
+
+        update_masternode_list(
+            &platform,
+            MasternodeListDiff {
+                updated_masternodes: vec![create_masternode(4, 5)],
+                removed_masternodes: vec![],
+                is_full_list: false,
+            },
+            1000,
+            Some(&transaction),
+        );
+
+        platform
+            .drive
+            .remove_from_identity_balance(
+                &[1; 32],
+                100,
+                DriveBlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should debit owner identity");
+
+        // Rotate keys
+        update_masternode_list(
+            &platform,
+            MasternodeListDiff {
+                updated_masternodes: vec![create_masternode(6, 7)],
+                removed_masternodes: vec![],
+                is_full_list: false,
+            },
+            2000,
+            Some(&transaction),
+        );
+
+        let owner_debt = platform
+            .drive
+            .fetch_identity_debt(&[1; 32], Some(&transaction))
+            .expect("should fetch owner identity debt");
+
+        assert_eq!(owner_debt, 100);
+
+        // Rotate the operator key back
+        update_masternode_list(
+            &platform,
+            MasternodeListDiff {
+                updated_masternodes: vec![create_masternode(6, 5)],
+                removed_masternodes: vec![],
+                is_full_list: false,
+            },
+            3000,
+            Some(&transaction),
+        );
+
+        let first_operator_identity_id = masternode_operator_identity_id(&[1; 32], &[5; 48])
+            .expect("should derive operator identity id");
+
+        let (first_operator_identity, _) = platform
+            .drive
+            .fetch_identity(&first_operator_identity_id, Some(&transaction))
+            .expect("should fetch previous operator identity");
+
+        assert_eq!(first_operator_identity.public_keys.len(), 1);
+        assert_eq!(first_operator_identity.public_keys[0].disabled_at, None);
+        assert_eq!(first_operator_identity.revision, 2);
+
+        let second_operator_identity_id = masternode_operator_identity_id(&[1; 32], &[7; 48])
+            .expect("should derive operator identity id");
+
+        let (second_operator_identity, _) = platform
+            .drive
+            .fetch_identity(&second_operator_identity_id, Some(&transaction))
+            .expect("should fetch operator identity");
+
+        assert_eq!(
+            second_operator_identity.public_keys[0].disabled_at,
+            Some(3000)
+        );
+    }
+}
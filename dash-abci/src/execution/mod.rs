@@ -1,2 +1,6 @@
 /// Fee pools module
 pub mod fee_pools;
+/// Masternode identities module
+pub mod masternode_identities;
+/// Protocol version upgrade module
+pub mod protocol_version;
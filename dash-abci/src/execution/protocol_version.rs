@@ -0,0 +1,407 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Protocol Version Upgrade.
+//!
+//! Block proposers signal the protocol version they support. Votes are tallied per epoch,
+//! and a new protocol version is activated on the next epoch change once enough
+//! proposers of the previous epoch support it. A consensus params feature flag updating
+//! the application version activates it at the flag's height.
+//!
+
+use crate::contracts::feature_flags::FeatureFlags;
+use crate::error::execution::ExecutionError;
+use crate::error::Error;
+use crate::execution::fee_pools::epoch::EpochInfo;
+use crate::platform::Platform;
+use rs_drive::dpp::version::LATEST_VERSION;
+use rs_drive::fee_pools::epochs::Epoch;
+use rs_drive::grovedb::TransactionArg;
+use std::collections::BTreeMap;
+
+pub use rs_drive::dpp::version::INITIAL_PROTOCOL_VERSION;
+
+/// Percentage of the epoch's block proposers which must support a protocol version
+/// to activate it
+pub const PROTOCOL_VERSION_UPGRADE_PERCENTAGE_NEEDED: u64 = 75;
+
+/// Returns the highest protocol version above the current one which is supported
+/// by enough proposers. Proposers supporting a protocol version support all previous ones.
+pub fn choose_protocol_version_to_activate(
+    votes: &BTreeMap<u32, u64>,
+    current_protocol_version: u32,
+) -> Option<u32> {
+    let total_votes: u64 = votes.values().sum();
+
+    if total_votes == 0 {
+        return None;
+    }
+
+    let mut supporting_votes = 0u64;
+
+    // Iterate from the highest version to accumulate support for lower versions
+    for (protocol_version, count) in votes.iter().rev() {
+        if *protocol_version <= current_protocol_version {
+            break;
+        }
+
+        supporting_votes += count;
+
+        if supporting_votes * 100 >= total_votes * PROTOCOL_VERSION_UPGRADE_PERCENTAGE_NEEDED {
+            return Some(*protocol_version);
+        }
+    }
+
+    None
+}
+
+impl Platform {
+    /// Returns the active protocol version
+    pub fn get_protocol_version(&self, transaction: TransactionArg) -> Result<u32, Error> {
+        Ok(self
+            .drive
+            .get_protocol_version(transaction)?
+            .unwrap_or(INITIAL_PROTOCOL_VERSION))
+    }
+
+    /// Tallies protocol version votes of the previous epoch on epoch change and activates
+    /// a new protocol version if the threshold is met.
+    ///
+    /// Returns the active protocol version for the current block.
+    pub fn process_protocol_version_upgrade(
+        &self,
+        epoch_info: &EpochInfo,
+        transaction: TransactionArg,
+    ) -> Result<u32, Error> {
+        let current_protocol_version = self.get_protocol_version(transaction)?;
+
+        let previous_epoch_index = match epoch_info.previous_epoch_index {
+            Some(previous_epoch_index) if epoch_info.is_epoch_change => previous_epoch_index,
+            _ => return Ok(current_protocol_version),
+        };
+
+        let votes = self
+            .drive
+            .get_epoch_protocol_version_votes(&Epoch::new(previous_epoch_index), transaction)?;
+
+        let new_protocol_version =
+            match choose_protocol_version_to_activate(&votes, current_protocol_version) {
+                Some(new_protocol_version) => new_protocol_version,
+                None => return Ok(current_protocol_version),
+            };
+
+        if new_protocol_version > LATEST_VERSION {
+            return Err(Error::Execution(
+                ExecutionError::UnsupportedProtocolVersion(
+                    "network activated a protocol version which is not supported by this node",
+                ),
+            ));
+        }
+
+        self.drive
+            .update_protocol_version(new_protocol_version, transaction)?;
+
+        Ok(new_protocol_version)
+    }
+
+    /// Activates the application version set by the consensus params feature flag
+    /// enabled at the block, so Platform executes the block with the protocol version
+    /// Tenderdash is told to use.
+    ///
+    /// Returns the active protocol version for the current block.
+    pub fn apply_feature_flags_protocol_version(
+        &self,
+        feature_flags: &FeatureFlags,
+        protocol_version: u32,
+        transaction: TransactionArg,
+    ) -> Result<u32, Error> {
+        let app_version = match feature_flags
+            .consensus_params_update
+            .as_ref()
+            .and_then(|consensus_params_update| consensus_params_update.version.as_ref())
+            .and_then(|version| version.app_version)
+        {
+            Some(app_version) => app_version,
+            None => return Ok(protocol_version),
+        };
+
+        let new_protocol_version: u32 = app_version.try_into().map_err(|_| {
+            Error::Execution(ExecutionError::Overflow(
+                "app version cannot be converted to protocol version",
+            ))
+        })?;
+
+        if new_protocol_version == protocol_version {
+            return Ok(protocol_version);
+        }
+
+        if new_protocol_version > LATEST_VERSION {
+            return Err(Error::Execution(
+                ExecutionError::UnsupportedProtocolVersion(
+                    "feature flag activated a protocol version which is not supported by this node",
+                ),
+            ));
+        }
+
+        self.drive
+            .update_protocol_version(new_protocol_version, transaction)?;
+
+        Ok(new_protocol_version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod choose_protocol_version_to_activate {
+        use crate::execution::protocol_version::choose_protocol_version_to_activate;
+        use std::collections::BTreeMap;
+
+        #[test]
+        fn test_no_upgrade_without_votes() {
+            let votes = BTreeMap::new();
+
+            assert_eq!(choose_protocol_version_to_activate(&votes, 1), None);
+        }
+
+        #[test]
+        fn test_no_upgrade_if_threshold_is_not_met() {
+            let votes = BTreeMap::from([(1, 26), (2, 74)]);
+
+            assert_eq!(choose_protocol_version_to_activate(&votes, 1), None);
+        }
+
+        #[test]
+        fn test_upgrade_if_threshold_is_met() {
+            let votes = BTreeMap::from([(1, 25), (2, 75)]);
+
+            assert_eq!(choose_protocol_version_to_activate(&votes, 1), Some(2));
+        }
+
+        #[test]
+        fn test_higher_versions_support_lower_ones() {
+            let votes = BTreeMap::from([(1, 20), (2, 40), (3, 40)]);
+
+            assert_eq!(choose_protocol_version_to_activate(&votes, 1), Some(2));
+        }
+
+        #[test]
+        fn test_no_downgrade() {
+            let votes = BTreeMap::from([(1, 100)]);
+
+            assert_eq!(choose_protocol_version_to_activate(&votes, 2), None);
+        }
+    }
+
+    mod process_protocol_version_upgrade {
+        use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+        use crate::error::execution::ExecutionError;
+        use crate::error::Error;
+        use crate::execution::fee_pools::epoch::EpochInfo;
+        use rs_drive::drive::batch::GroveDbOpBatch;
+        use rs_drive::fee_pools::epochs::Epoch;
+
+        #[test]
+        fn test_protocol_version_is_kept_without_epoch_change() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 0,
+                previous_epoch_index: None,
+                is_epoch_change: false,
+            };
+
+            let protocol_version = platform
+                .process_protocol_version_upgrade(&epoch_info, Some(&transaction))
+                .expect("should process protocol version upgrade");
+
+            assert_eq!(protocol_version, 1);
+        }
+
+        #[test]
+        fn test_error_if_unsupported_protocol_version_is_activated() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let epoch = Epoch::new(0);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(epoch.init_protocol_version_votes_tree_operation());
+
+            for i in 0..4u8 {
+                batch.push(epoch.update_protocol_version_vote_operation(&[i; 32], u32::MAX));
+            }
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 1,
+                previous_epoch_index: Some(0),
+                is_epoch_change: true,
+            };
+
+            let result = platform.process_protocol_version_upgrade(&epoch_info, Some(&transaction));
+
+            assert!(matches!(
+                result,
+                Err(Error::Execution(
+                    ExecutionError::UnsupportedProtocolVersion(_)
+                ))
+            ));
+        }
+
+        #[test]
+        fn test_protocol_version_is_kept_if_proposers_support_it() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let epoch = Epoch::new(0);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            batch.push(epoch.init_protocol_version_votes_tree_operation());
+
+            for i in 0..4u8 {
+                batch.push(epoch.update_protocol_version_vote_operation(&[i; 32], 1));
+            }
+
+            platform
+                .drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let epoch_info = EpochInfo {
+                current_epoch_index: 1,
+                previous_epoch_index: Some(0),
+                is_epoch_change: true,
+            };
+
+            let protocol_version = platform
+                .process_protocol_version_upgrade(&epoch_info, Some(&transaction))
+                .expect("should process protocol version upgrade");
+
+            assert_eq!(protocol_version, 1);
+        }
+    }
+
+    mod apply_feature_flags_protocol_version {
+        use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+        use crate::contracts::feature_flags::{
+            ConsensusParamsUpdate, FeatureFlags, VersionConsensusParams,
+        };
+        use crate::error::execution::ExecutionError;
+        use crate::error::Error;
+
+        fn create_feature_flags(app_version: Option<i64>) -> FeatureFlags {
+            FeatureFlags {
+                consensus_params_update: Some(ConsensusParamsUpdate {
+                    block: None,
+                    version: Some(VersionConsensusParams { app_version }),
+                }),
+            }
+        }
+
+        #[test]
+        fn test_protocol_version_is_kept_without_app_version_flag() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform
+                .drive
+                .update_protocol_version(2, Some(&transaction))
+                .expect("should update protocol version");
+
+            for feature_flags in [FeatureFlags::default(), create_feature_flags(None)] {
+                let protocol_version = platform
+                    .apply_feature_flags_protocol_version(&feature_flags, 2, Some(&transaction))
+                    .expect("should apply feature flags");
+
+                assert_eq!(protocol_version, 2);
+            }
+
+            assert_eq!(
+                platform
+                    .get_protocol_version(Some(&transaction))
+                    .expect("should get protocol version"),
+                2
+            );
+        }
+
+        #[test]
+        fn test_app_version_flag_activates_protocol_version() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform
+                .drive
+                .update_protocol_version(2, Some(&transaction))
+                .expect("should update protocol version");
+
+            let protocol_version = platform
+                .apply_feature_flags_protocol_version(
+                    &create_feature_flags(Some(1)),
+                    2,
+                    Some(&transaction),
+                )
+                .expect("should apply feature flags");
+
+            assert_eq!(protocol_version, 1);
+
+            assert_eq!(
+                platform
+                    .get_protocol_version(Some(&transaction))
+                    .expect("should get protocol version"),
+                1
+            );
+        }
+
+        #[test]
+        fn test_error_if_app_version_flag_is_not_supported() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            let result = platform.apply_feature_flags_protocol_version(
+                &create_feature_flags(Some(u32::MAX as i64)),
+                1,
+                Some(&transaction),
+            );
+
+            assert!(matches!(
+                result,
+                Err(Error::Execution(
+                    ExecutionError::UnsupportedProtocolVersion(_)
+                ))
+            ));
+        }
+    }
+}
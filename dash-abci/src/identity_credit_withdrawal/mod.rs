@@ -2,6 +2,7 @@ use dashcore::{
     blockdata::transaction::special_transaction::asset_unlock::request_info::AssetUnlockRequestInfo,
     hashes::Hash, QuorumHash,
 };
+use rs_drive::drive::batch::GroveDbOpBatch;
 use rs_drive::query::TransactionArg;
 
 use crate::{
@@ -9,20 +10,53 @@ use crate::{
     platform::Platform,
 };
 
-const WITHDRAWAL_TRANSACTIONS_QUERY_LIMIT: u16 = 16;
-
 impl Platform {
+    /// Moves pooled and broadcasted withdrawal transactions to the next status
+    /// according to Core state at the given core chain locked height
+    pub fn update_withdrawal_transaction_statuses(
+        &self,
+        core_chain_locked_height: u32,
+        broadcasted_indexes: &[u64],
+        completed_indexes: &[u64],
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        let mut batch = GroveDbOpBatch::new();
+
+        self.drive
+            .add_update_withdrawal_transaction_statuses_operations(
+                core_chain_locked_height,
+                broadcasted_indexes,
+                completed_indexes,
+                transaction,
+                &mut batch,
+            )?;
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        self.drive
+            .grove_apply_batch(batch, false, transaction)
+            .map_err(Error::Drive)
+    }
+
     /// Prepares a list of an unsigned withdrawal transaction bytes
     pub fn fetch_and_prepare_unsigned_withdrawal_transactions(
         &self,
-        block_height: u32,
+        core_chain_locked_height: u32,
+        block_time_ms: u64,
         validator_set_quorum_hash: [u8; 32],
         transaction: TransactionArg,
     ) -> Result<Vec<Vec<u8>>, Error> {
-        // Get 16 latest withdrawal transactions from the queue
-        let withdrawal_transactions = self
-            .drive
-            .dequeue_withdrawal_transactions(WITHDRAWAL_TRANSACTIONS_QUERY_LIMIT, transaction)?;
+        // Get up to the configured number of oldest withdrawal transactions from the queue within
+        // withdrawal limits and mark them as pooled, so they are tracked until they are complete
+        // or expired
+        let withdrawal_transactions = self.drive.dequeue_withdrawal_transactions(
+            self.config.withdrawal_transactions_query_limit,
+            core_chain_locked_height,
+            block_time_ms,
+            transaction,
+        )?;
 
         // Appending request_height and quorum_hash to withdrwal transaction
         // and pass it to JS Drive for singing and broadcasting
@@ -30,7 +64,7 @@ impl Platform {
             .into_iter()
             .map(|(_, bytes)| {
                 let request_info = AssetUnlockRequestInfo {
-                    request_height: block_height,
+                    request_height: core_chain_locked_height,
                     quorum_hash: QuorumHash::hash(&validator_set_quorum_hash),
                 };
 
@@ -19,6 +19,9 @@ mod block;
 /// Common functions module
 pub mod common;
 
+/// Config module
+pub mod config;
+
 /// Contracts module
 pub mod contracts;
 
@@ -30,9 +30,10 @@
 //! Platform Init
 //!
 
+use crate::abci::messages::Serializable;
 use crate::block::BlockExecutionContext;
+use crate::config::PlatformConfig;
 use crate::error::Error;
-use rs_drive::drive::config::DriveConfig;
 use rs_drive::drive::Drive;
 use std::cell::RefCell;
 use std::path::Path;
@@ -41,17 +42,101 @@ use std::path::Path;
 pub struct Platform {
     /// Drive
     pub drive: Drive,
+    /// Platform config
+    pub config: PlatformConfig,
     /// Block execution context
     pub block_execution_context: RefCell<Option<BlockExecutionContext>>,
+    /// Block execution context of the last committed block
+    pub last_block_execution_context: RefCell<Option<BlockExecutionContext>>,
 }
 
 impl Platform {
     /// Open Platform with Drive and block execution context.
-    pub fn open<P: AsRef<Path>>(path: P, config: Option<DriveConfig>) -> Result<Self, Error> {
-        let drive = Drive::open(path, config).map_err(Error::Drive)?;
+    /// Block execution context of the last committed block is restored from Drive.
+    /// Chain parameters persisted at chain initialization take precedence over the given config.
+    pub fn open<P: AsRef<Path>>(path: P, config: Option<PlatformConfig>) -> Result<Self, Error> {
+        let mut config = config.unwrap_or_default();
+
+        config.validate()?;
+
+        let mut drive_config = config.drive.clone();
+        config.apply_chain_parameters_to_drive_config(&mut drive_config);
+        config.drive = drive_config;
+
+        let mut drive = Drive::open(path, Some(config.drive.clone())).map_err(Error::Drive)?;
+
+        if let Some(stored_config_bytes) =
+            drive.fetch_platform_config(None).map_err(Error::Drive)?
+        {
+            let stored_config = PlatformConfig::from_bytes(&stored_config_bytes)?;
+
+            config = PlatformConfig {
+                drive: config.drive,
+                ..stored_config
+            };
+
+            config.apply_chain_parameters_to_drive_config(&mut drive.config);
+
+            // Platform config keeps the same Drive config as Drive
+            config.drive = drive.config.clone();
+        }
+
+        let last_block_execution_context = drive
+            .fetch_last_block_execution_context(None)
+            .map_err(Error::Drive)?
+            .map(|stored_block_execution_context| {
+                BlockExecutionContext::from_stored_block_execution_context(
+                    stored_block_execution_context,
+                    &drive,
+                )
+            })
+            .transpose()?;
+
         Ok(Platform {
             drive,
+            config,
             block_execution_context: RefCell::new(None),
+            last_block_execution_context: RefCell::new(last_block_execution_context),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::abci::messages::Serializable;
+    use crate::config::PlatformConfig;
+    use crate::platform::Platform;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_platform_config_keeps_drive_config_with_stored_chain_parameters() {
+        let tmp_dir = TempDir::new().unwrap();
+
+        let platform = Platform::open(tmp_dir.path(), None).expect("should open Platform");
+
+        platform
+            .drive
+            .create_initial_state_structure(None)
+            .expect("should create root tree successfully");
+
+        let stored_config = PlatformConfig {
+            negligible_refund_credits: 1,
+            ..Default::default()
+        };
+
+        platform
+            .drive
+            .store_platform_config(
+                stored_config.to_bytes().expect("should serialize config"),
+                None,
+            )
+            .expect("should store config");
+
+        drop(platform);
+
+        let platform = Platform::open(tmp_dir.path(), None).expect("should open Platform");
+
+        assert_eq!(platform.drive.config.negligible_refund_credits, 1);
+        assert_eq!(platform.config.drive.negligible_refund_credits, 1);
+    }
+}
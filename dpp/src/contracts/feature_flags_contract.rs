@@ -2,6 +2,7 @@ use super::SystemIDs;
 
 pub mod types {
     pub const UPDATE_CONSENSUS_PARAMS: &str = "updateConsensusParams";
+    pub const UPDATE_FEE_MULTIPLIER: &str = "updateFeeMultiplier";
 }
 
 pub fn system_ids() -> SystemIDs {
@@ -11,6 +11,7 @@ use super::{DataTriggerExecutionContext, DataTriggerExecutionResult};
 
 const PROPERTY_BLOCK_HEIGHT: &str = "height";
 const PROPERTY_ENABLE_AT_HEIGHT: &str = "enableAtHeight";
+const PROPERTY_MULTIPLIER: &str = "multiplier";
 
 pub async fn create_feature_flag_data_trigger<'a, SR>(
     document_transition: &DocumentTransition,
@@ -71,16 +72,82 @@ where
     Ok(result)
 }
 
+pub async fn create_fee_multiplier_feature_flag_data_trigger<'a, SR>(
+    document_transition: &DocumentTransition,
+    context: &DataTriggerExecutionContext<'a, SR>,
+    top_level_identity: Option<&Identifier>,
+) -> Result<DataTriggerExecutionResult, anyhow::Error>
+where
+    SR: StateRepositoryLike,
+{
+    let mut result = DataTriggerExecutionResult::default();
+    if context.state_transition_execution_context.is_dry_run() {
+        return Ok(result);
+    }
+
+    let top_level_identity = top_level_identity.context("Top Level Identity must be defined")?;
+
+    let dt_create = match document_transition {
+        DocumentTransition::Create(d) => d,
+        _ => bail!(
+            "the Document Transition {} isn't 'CREATE'",
+            get_from_transition!(document_transition, id)
+        ),
+    };
+    let data = dt_create.data.as_ref().ok_or_else(|| {
+        anyhow!(
+            "data isn't defined in Data Transition '{}'",
+            dt_create.base.id
+        )
+    })?;
+
+    if context.owner_id != top_level_identity {
+        let err = create_error(
+            context,
+            dt_create,
+            "This Identity can't activate selected feature flag".to_string(),
+        );
+        result.add_error(err.into());
+        return Ok(result);
+    }
+
+    let multiplier = data.get_f64(PROPERTY_MULTIPLIER)?;
+
+    if !multiplier.is_finite() || multiplier <= 0.0 {
+        let err = create_error(
+            context,
+            dt_create,
+            "Fee multiplier must be a positive number".to_string(),
+        );
+        result.add_error(err.into());
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod test {
-    use super::create_feature_flag_data_trigger;
+    use super::{
+        create_feature_flag_data_trigger, create_fee_multiplier_feature_flag_data_trigger,
+    };
     use crate::{
         data_trigger::DataTriggerExecutionContext,
-        document::document_transition::DocumentTransition,
+        document::document_transition::{DocumentCreateTransition, DocumentTransition},
         state_repository::MockStateRepositoryLike,
         state_transition::state_transition_execution_context::StateTransitionExecutionContext,
-        tests::fixtures::get_data_contract_fixture,
+        tests::{fixtures::get_data_contract_fixture, utils::generate_random_identifier_struct},
     };
+    use serde_json::json;
+
+    fn create_fee_multiplier_transition(multiplier: f64) -> DocumentTransition {
+        DocumentTransition::Create(DocumentCreateTransition {
+            data: Some(json!({
+                "enableAtEpoch": 1,
+                "multiplier": multiplier,
+            })),
+            ..Default::default()
+        })
+    }
 
     #[tokio::test]
     async fn should_successfully_execute_on_dry_run() {
@@ -106,4 +173,91 @@ mod test {
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn should_return_an_error_if_fee_multiplier_owner_is_not_top_level_identity() {
+        let transition_execution_context = StateTransitionExecutionContext::default();
+        let state_repository = MockStateRepositoryLike::new();
+        let data_contract = get_data_contract_fixture(None);
+        let owner_id = data_contract.owner_id().to_owned();
+        let top_level_identity = generate_random_identifier_struct();
+
+        let document_transition = create_fee_multiplier_transition(2.0);
+        let data_trigger_context = DataTriggerExecutionContext {
+            data_contract: &data_contract,
+            owner_id: &owner_id,
+            state_repository: &state_repository,
+            state_transition_execution_context: &transition_execution_context,
+        };
+
+        let result = create_fee_multiplier_feature_flag_data_trigger(
+            &document_transition,
+            &data_trigger_context,
+            Some(&top_level_identity),
+        )
+        .await
+        .expect("the execution result should be returned");
+
+        assert!(!result.is_ok());
+        assert_eq!(
+            "This Identity can't activate selected feature flag",
+            result.get_errors()[0].to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_an_error_if_fee_multiplier_is_not_positive() {
+        let transition_execution_context = StateTransitionExecutionContext::default();
+        let state_repository = MockStateRepositoryLike::new();
+        let data_contract = get_data_contract_fixture(None);
+        let owner_id = data_contract.owner_id().to_owned();
+
+        let document_transition = create_fee_multiplier_transition(0.0);
+        let data_trigger_context = DataTriggerExecutionContext {
+            data_contract: &data_contract,
+            owner_id: &owner_id,
+            state_repository: &state_repository,
+            state_transition_execution_context: &transition_execution_context,
+        };
+
+        let result = create_fee_multiplier_feature_flag_data_trigger(
+            &document_transition,
+            &data_trigger_context,
+            Some(&owner_id),
+        )
+        .await
+        .expect("the execution result should be returned");
+
+        assert!(!result.is_ok());
+        assert_eq!(
+            "Fee multiplier must be a positive number",
+            result.get_errors()[0].to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_pass_if_fee_multiplier_is_created_by_top_level_identity() {
+        let transition_execution_context = StateTransitionExecutionContext::default();
+        let state_repository = MockStateRepositoryLike::new();
+        let data_contract = get_data_contract_fixture(None);
+        let owner_id = data_contract.owner_id().to_owned();
+
+        let document_transition = create_fee_multiplier_transition(1.5);
+        let data_trigger_context = DataTriggerExecutionContext {
+            data_contract: &data_contract,
+            owner_id: &owner_id,
+            state_repository: &state_repository,
+            state_transition_execution_context: &transition_execution_context,
+        };
+
+        let result = create_fee_multiplier_feature_flag_data_trigger(
+            &document_transition,
+            &data_trigger_context,
+            Some(&owner_id),
+        )
+        .await
+        .expect("the execution result should be returned");
+
+        assert!(result.is_ok());
+    }
 }
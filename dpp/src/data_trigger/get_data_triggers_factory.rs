@@ -116,7 +116,7 @@ fn data_triggers() -> Result<Vec<DataTrigger>, ProtocolError> {
             document_type: feature_flags_contract::types::UPDATE_CONSENSUS_PARAMS.to_string(),
             transition_action: Action::Create,
             data_trigger_kind: DataTriggerKind::CrateFeatureFlag,
-            top_level_identity: Some(feature_flags_owner_id),
+            top_level_identity: Some(feature_flags_owner_id.clone()),
         },
         DataTrigger {
             data_contract_id: feature_flags_data_contract_id.clone(),
@@ -126,12 +126,33 @@ fn data_triggers() -> Result<Vec<DataTrigger>, ProtocolError> {
             top_level_identity: None,
         },
         DataTrigger {
-            data_contract_id: feature_flags_data_contract_id,
+            data_contract_id: feature_flags_data_contract_id.clone(),
             document_type: feature_flags_contract::types::UPDATE_CONSENSUS_PARAMS.to_string(),
             transition_action: Action::Delete,
             data_trigger_kind: DataTriggerKind::DataTriggerReject,
             top_level_identity: None,
         },
+        DataTrigger {
+            data_contract_id: feature_flags_data_contract_id.clone(),
+            document_type: feature_flags_contract::types::UPDATE_FEE_MULTIPLIER.to_string(),
+            transition_action: Action::Create,
+            data_trigger_kind: DataTriggerKind::CreateFeeMultiplierFeatureFlag,
+            top_level_identity: Some(feature_flags_owner_id),
+        },
+        DataTrigger {
+            data_contract_id: feature_flags_data_contract_id.clone(),
+            document_type: feature_flags_contract::types::UPDATE_FEE_MULTIPLIER.to_string(),
+            transition_action: Action::Replace,
+            data_trigger_kind: DataTriggerKind::DataTriggerReject,
+            top_level_identity: None,
+        },
+        DataTrigger {
+            data_contract_id: feature_flags_data_contract_id,
+            document_type: feature_flags_contract::types::UPDATE_FEE_MULTIPLIER.to_string(),
+            transition_action: Action::Delete,
+            data_trigger_kind: DataTriggerKind::DataTriggerReject,
+            top_level_identity: None,
+        },
         DataTrigger {
             data_contract_id: master_node_reward_shares_contract_id.clone(),
             document_type: feature_flags_contract::types::UPDATE_CONSENSUS_PARAMS.to_string(),
@@ -12,7 +12,9 @@ use crate::{
 
 use self::dashpay_data_triggers::create_contact_request_data_trigger;
 use self::dpns_triggers::create_domain_data_trigger;
-use self::feature_flags_data_triggers::create_feature_flag_data_trigger;
+use self::feature_flags_data_triggers::{
+    create_feature_flag_data_trigger, create_fee_multiplier_feature_flag_data_trigger,
+};
 use self::reward_share_data_triggers::create_masternode_reward_shares_data_trigger;
 
 mod data_trigger_execution_context;
@@ -41,6 +43,7 @@ pub enum DataTriggerKind {
     DataTriggerRewardShare,
     DataTriggerReject,
     CrateFeatureFlag,
+    CreateFeeMultiplierFeatureFlag,
 }
 
 pub struct DataTrigger {
@@ -119,6 +122,14 @@ where
         DataTriggerKind::CrateFeatureFlag => {
             create_feature_flag_data_trigger(document_transition, context, identifier).await
         }
+        DataTriggerKind::CreateFeeMultiplierFeatureFlag => {
+            create_fee_multiplier_feature_flag_data_trigger(
+                document_transition,
+                context,
+                identifier,
+            )
+            .await
+        }
         DataTriggerKind::DataTriggerReject => {
             reject_data_trigger(document_transition, context, identifier).await
         }
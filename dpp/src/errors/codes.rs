@@ -174,6 +174,7 @@ impl ErrorWithCode for FeeError {
     fn get_code(&self) -> u32 {
         match *self {
             Self::BalanceIsNotEnoughError { .. } => 3000,
+            Self::IdentityInDebtError { .. } => 3001,
         }
     }
 }
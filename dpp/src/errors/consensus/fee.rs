@@ -4,4 +4,7 @@ use thiserror::Error;
 pub enum FeeError {
     #[error("Current credits balance {balance} is not enough to pay {fee} fee")]
     BalanceIsNotEnoughError { balance: u64, fee: i64 },
+
+    #[error("Identity has a debt of {debt} credits which must be settled first")]
+    IdentityInDebtError { debt: u64 },
 }
@@ -131,6 +131,9 @@ pub enum ProtocolError {
     #[error("Public key is disabled")]
     PublicKeyIsDisabledError { public_key: IdentityPublicKey },
 
+    #[error("Invalid fee multiplier {fee_multiplier}: must be a finite number above zero")]
+    InvalidFeeMultiplierError { fee_multiplier: f64 },
+
     #[error("Document was not provided for apply of state transition")]
     DocumentNotProvided {
         document_transition: DocumentTransition,
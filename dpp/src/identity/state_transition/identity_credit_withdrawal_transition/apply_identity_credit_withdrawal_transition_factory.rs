@@ -1,16 +1,9 @@
-use anyhow::{anyhow, Result};
-use dashcore::{
-    blockdata::transaction::special_transaction::asset_unlock::unqualified_asset_unlock::{
-        AssetUnlockBasePayload, AssetUnlockBaseTransactionInfo,
-    },
-    consensus::Encodable,
-    Script, TxOut,
-};
+use anyhow::Result;
 use lazy_static::__Deref;
 
 use crate::{
-    identity::convert_credits_to_satoshi, prelude::Identity, state_repository::StateRepositoryLike,
-    state_transition::StateTransitionLike,
+    state_repository::StateRepositoryLike,
+    state_transition::{StateTransitionConvert, StateTransitionLike},
 };
 
 use super::IdentityCreditWithdrawalTransition;
@@ -34,55 +27,21 @@ where
         &self,
         state_transition: &IdentityCreditWithdrawalTransition,
     ) -> Result<()> {
-        let latest_withdrawal_index = self
-            .state_repository
-            .fetch_latest_withdrawal_transaction_index()
-            .await?;
-
-        let output_script: Script = state_transition.output_script.deref().clone();
-
-        let tx_out = TxOut {
-            value: convert_credits_to_satoshi(state_transition.amount),
-            script_pubkey: output_script,
-        };
-
-        let withdrawal_transaction = AssetUnlockBaseTransactionInfo {
-            version: 1,
-            lock_time: 0,
-            output: vec![tx_out],
-            base_payload: AssetUnlockBasePayload {
-                version: 1,
-                index: latest_withdrawal_index + 1,
-                fee: state_transition.core_fee,
-            },
-        };
-
-        let mut transaction_buffer: Vec<u8> = vec![];
-
-        withdrawal_transaction
-            .consensus_encode(&mut transaction_buffer)
-            .map_err(|e| anyhow!(e))?;
+        let transition_id = state_transition.hash(false)?;
 
+        // Withdrawal requests are pooled into asset unlock transactions at the end of the block
+        // according to the pooling mode. The request is enqueued in the same batch
+        // with the identity balance debit
         self.state_repository
-            .enqueue_withdrawal_transaction(latest_withdrawal_index, transaction_buffer)
-            .await?;
-
-        let maybe_existing_identity: Option<Identity> = self
-            .state_repository
-            .fetch_identity(
+            .apply_identity_credit_withdrawal(
+                &transition_id,
                 &state_transition.identity_id,
+                state_transition.amount,
+                state_transition.core_fee,
+                state_transition.pooling,
+                state_transition.output_script.deref().as_bytes(),
                 state_transition.get_execution_context(),
             )
-            .await?;
-
-        let mut existing_identity =
-            maybe_existing_identity.ok_or_else(|| anyhow!("Identity not found"))?;
-
-        existing_identity.reduce_balance(state_transition.amount);
-
-        // TODO: we need to be able to batch state repository operations
-        self.state_repository
-            .update_identity(&existing_identity, state_transition.get_execution_context())
             .await
     }
 }
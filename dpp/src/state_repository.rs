@@ -6,7 +6,7 @@ use mockall::{automock, predicate::*};
 use serde_json::Value as JsonValue;
 
 use crate::{
-    prelude::*,
+    identity::state_transition::identity_credit_withdrawal_transition::Pooling, prelude::*,
     state_transition::state_transition_execution_context::StateTransitionExecutionContext,
 };
 
@@ -85,6 +85,13 @@ pub trait StateRepositoryLike: Send + Sync {
     where
         T: for<'de> serde::de::Deserialize<'de> + 'static;
 
+    /// Fetch the debt of the Identity, which is zero if the Identity has no debt
+    async fn fetch_identity_debt(
+        &self,
+        id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<u64>;
+
     /// Store Public Key hashes and Identity id pair
     async fn store_identity_public_key_hashes(
         &self,
@@ -151,10 +158,17 @@ pub trait StateRepositoryLike: Send + Sync {
     // Get latest (in a queue) withdrawal transaction index
     async fn fetch_latest_withdrawal_transaction_index(&self) -> AnyResult<u64>;
 
-    // Enqueue withdrawal transaction
-    async fn enqueue_withdrawal_transaction(
+    // Enqueue withdrawal request to be pooled into a withdrawal transaction at the end of the block
+    // and debit the identity balance atomically
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_identity_credit_withdrawal(
         &self,
-        index: u64,
-        transaction_bytes: Vec<u8>,
+        transition_id: &[u8],
+        identity_id: &Identifier,
+        amount: u64,
+        core_fee: u32,
+        pooling: Pooling,
+        output_script: &[u8],
+        execution_context: &StateTransitionExecutionContext,
     ) -> AnyResult<()>;
 }
@@ -51,7 +51,7 @@ pub trait StateTransitionLike:
     /// set a new signature
     fn set_signature(&mut self, signature: Vec<u8>);
     /// Calculates the ST fee in credits
-    fn calculate_fee(&self) -> i64 {
+    fn calculate_fee(&self) -> Result<i64, ProtocolError> {
         calculate_state_transition_fee(self)
     }
 
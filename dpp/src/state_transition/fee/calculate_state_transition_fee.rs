@@ -1,15 +1,22 @@
-use crate::state_transition::StateTransitionLike;
+use crate::{state_transition::StateTransitionLike, ProtocolError};
 
-use super::{calculate_operations_fees, constants::DEFAULT_USER_TIP};
+use super::{calculate_operations_fees, constants::DEFAULT_USER_TIP, fee_schedule::FeeSchedule};
 
-pub fn calculate_state_transition_fee(state_transition: &impl StateTransitionLike) -> i64 {
+pub fn calculate_state_transition_fee(
+    state_transition: &impl StateTransitionLike,
+) -> Result<i64, ProtocolError> {
     let execution_context = state_transition.get_execution_context();
-    let fee = calculate_operations_fees(execution_context.get_operations());
+    // The fee schedule is chosen by the active protocol version of the block and not by
+    // the protocol version of the state transition, which is set by the user
+    let fee_schedule = FeeSchedule::for_protocol_version(execution_context.get_protocol_version());
+    let fee = calculate_operations_fees(
+        execution_context.get_operations(),
+        fee_schedule,
+        execution_context.get_fee_multiplier(),
+    )?;
 
-    // Is not implemented yet
-    let storage_refund = 0;
-
-    (fee.storage + fee.processing) + DEFAULT_USER_TIP - storage_refund
+    // Storage refunds are paid by Drive to the identity balance and don't reduce the fee
+    Ok((fee.storage + fee.processing) + DEFAULT_USER_TIP)
 }
 
 #[cfg(test)]
@@ -26,7 +33,7 @@ mod test {
             StateTransitionLike,
         },
         tests::fixtures::identity_create_transition_fixture_json,
-        NativeBlsModule,
+        NativeBlsModule, ProtocolError,
     };
 
     use super::calculate_state_transition_fee;
@@ -53,7 +60,40 @@ mod test {
         )));
         state_transition.set_execution_context(execution_context);
 
-        let result = calculate_state_transition_fee(&state_transition);
-        assert_eq!(13616, result)
+        let result =
+            calculate_state_transition_fee(&state_transition).expect("should calculate fee");
+        assert_eq!(299224, result)
+    }
+
+    #[test]
+    fn should_apply_fee_multiplier_to_processing_fees_only() {
+        let mut state_transition =
+            IdentityCreateTransition::new(identity_create_transition_fixture_json(None)).unwrap();
+
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.add_operation(Operation::Read(ReadOperation::new(10)));
+        execution_context.add_operation(Operation::Write(WriteOperation::new(5, 5)));
+        execution_context.set_fee_multiplier(2.0);
+        state_transition.set_execution_context(execution_context);
+
+        let result =
+            calculate_state_transition_fee(&state_transition).expect("should calculate fee");
+        assert_eq!((12400 + 10000) * 2 + 270000, result)
+    }
+
+    #[test]
+    fn should_reject_not_positive_fee_multiplier() {
+        let mut state_transition =
+            IdentityCreateTransition::new(identity_create_transition_fixture_json(None)).unwrap();
+
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.add_operation(Operation::Read(ReadOperation::new(10)));
+        execution_context.set_fee_multiplier(0.0);
+        state_transition.set_execution_context(execution_context);
+
+        assert!(matches!(
+            calculate_state_transition_fee(&state_transition),
+            Err(ProtocolError::InvalidFeeMultiplierError { .. })
+        ));
     }
 }
@@ -0,0 +1,108 @@
+use crate::identity::KeyType;
+
+/// Costs of operations charged by both Drive and DPP.
+/// A new schedule is added to `FEE_SCHEDULES` to change fees with a protocol upgrade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSchedule {
+    /// Protocol version the schedule is active since
+    pub since_protocol_version: u32,
+
+    pub storage_disk_usage_credit_per_byte: u64,
+    pub storage_processing_credit_per_byte: u64,
+    pub storage_load_credit_per_byte: u64,
+    pub non_storage_load_credit_per_byte: u64,
+    pub query_credit_per_byte: u64,
+    pub storage_seek_cost: u64,
+
+    pub sha256_cost: u64,
+    pub sha256_2_cost: u64,
+    pub blake3_cost: u64,
+
+    pub simple_arithmetic_operation_cost: u64,
+    pub complex_arithmetic_operation_cost: u64,
+    pub modular_arithmetic_operation_cost: u64,
+
+    pub base_state_transition_processing_fee: u64,
+    pub read_base_processing_cost: u64,
+    pub write_base_processing_cost: u64,
+    pub delete_base_processing_cost: u64,
+
+    pub ecdsa_secp256k1_signature_verify_cost: u64,
+    pub bls12_381_signature_verify_cost: u64,
+    pub ecdsa_hash160_signature_verify_cost: u64,
+    pub bip13_script_hash_signature_verify_cost: u64,
+}
+
+pub const FEE_SCHEDULE_V1: FeeSchedule = FeeSchedule {
+    since_protocol_version: 1,
+
+    storage_disk_usage_credit_per_byte: 27000,
+    storage_processing_credit_per_byte: 400,
+    storage_load_credit_per_byte: 400,
+    non_storage_load_credit_per_byte: 30,
+    query_credit_per_byte: 10,
+    storage_seek_cost: 4000,
+
+    sha256_cost: 4000,
+    sha256_2_cost: 8000,
+    blake3_cost: 1000,
+
+    simple_arithmetic_operation_cost: 12,
+    complex_arithmetic_operation_cost: 20,
+    modular_arithmetic_operation_cost: 32,
+
+    base_state_transition_processing_fee: 10000, // 84000
+    read_base_processing_cost: 8400,
+    write_base_processing_cost: 6000,  // 60000
+    delete_base_processing_cost: 2000, // 20000
+
+    ecdsa_secp256k1_signature_verify_cost: 3000,
+    bls12_381_signature_verify_cost: 6000,
+    ecdsa_hash160_signature_verify_cost: 3000,
+    bip13_script_hash_signature_verify_cost: 6000,
+};
+
+/// Fee schedules ordered by the protocol version they are active since
+pub static FEE_SCHEDULES: [FeeSchedule; 1] = [FEE_SCHEDULE_V1];
+
+impl FeeSchedule {
+    /// Returns the latest schedule activated at or before the given protocol version
+    pub fn for_protocol_version(protocol_version: u32) -> &'static FeeSchedule {
+        FEE_SCHEDULES
+            .iter()
+            .rev()
+            .find(|schedule| schedule.since_protocol_version <= protocol_version)
+            .unwrap_or(&FEE_SCHEDULES[0])
+    }
+
+    pub fn signature_verify_cost(&self, key_type: KeyType) -> u64 {
+        match key_type {
+            KeyType::ECDSA_SECP256K1 => self.ecdsa_secp256k1_signature_verify_cost,
+            KeyType::BLS12_381 => self.bls12_381_signature_verify_cost,
+            KeyType::ECDSA_HASH160 => self.ecdsa_hash160_signature_verify_cost,
+            KeyType::BIP13_SCRIPT_HASH => self.bip13_script_hash_signature_verify_cost,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FeeSchedule, FEE_SCHEDULES, FEE_SCHEDULE_V1};
+
+    #[test]
+    fn should_select_schedule_by_protocol_version() {
+        assert_eq!(FeeSchedule::for_protocol_version(0), &FEE_SCHEDULE_V1);
+        assert_eq!(FeeSchedule::for_protocol_version(1), &FEE_SCHEDULE_V1);
+        assert_eq!(
+            FeeSchedule::for_protocol_version(u32::MAX),
+            FEE_SCHEDULES.last().unwrap()
+        );
+    }
+
+    #[test]
+    fn schedules_should_be_ordered_by_protocol_version() {
+        assert!(FEE_SCHEDULES
+            .windows(2)
+            .all(|pair| pair[0].since_protocol_version < pair[1].since_protocol_version));
+    }
+}
@@ -1,33 +1,62 @@
 use std::borrow::Borrow;
 
+use crate::ProtocolError;
+
 use self::{
-    constants::FEE_MULTIPLIER,
+    fee_schedule::FeeSchedule,
     operations::{Operation, OperationLike},
 };
 
 pub mod calculate_state_transition_fee;
 pub mod constants;
+pub mod fee_schedule;
 pub mod operations;
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Fees {
-    storage: i64,
-    processing: i64,
+    pub storage: i64,
+    pub processing: i64,
 }
 
+/// Calculates fees of the operations using the fee schedule. The fee multiplier of the epoch
+/// is applied to processing fees only, storage fees are paid at the fee schedule price.
+/// Removed bytes don't reduce the fees: their refunds are paid by Drive based on the epochs
+/// the bytes were paid in.
 pub fn calculate_operations_fees(
     operations: impl IntoIterator<Item = impl Borrow<Operation>>,
-) -> Fees {
+    fee_schedule: &FeeSchedule,
+    fee_multiplier: f64,
+) -> Result<Fees, ProtocolError> {
     let mut fees = Fees::default();
 
     for operation in operations.into_iter() {
         let operation = operation.borrow();
-        fees.processing += operation.get_processing_cost();
-        fees.storage += operation.get_storage_cost();
+        fees.processing += operation.get_processing_cost(fee_schedule);
+        fees.storage += operation.get_storage_cost(fee_schedule).max(0);
+    }
+
+    fees.processing = apply_fee_multiplier(fees.processing, fee_multiplier)?;
+
+    Ok(fees)
+}
+
+/// Applies the fee multiplier to the cost, rounding up
+pub fn apply_fee_multiplier(cost: i64, fee_multiplier: f64) -> Result<i64, ProtocolError> {
+    if !fee_multiplier.is_finite() || fee_multiplier <= 0.0 {
+        return Err(ProtocolError::InvalidFeeMultiplierError { fee_multiplier });
+    }
+
+    if fee_multiplier == 1.0 {
+        return Ok(cost);
     }
 
-    fees.storage *= FEE_MULTIPLIER;
-    fees.processing *= FEE_MULTIPLIER;
+    let multiplied_cost = (cost as f64 * fee_multiplier).ceil();
+
+    if multiplied_cost >= i64::MAX as f64 {
+        return Err(ProtocolError::Generic(String::from(
+            "fee multiplier cost overflow",
+        )));
+    }
 
-    fees
+    Ok(multiplied_cost as i64)
 }
@@ -1,9 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::OperationLike;
-use crate::state_transition::fee::constants::{
-    DELETE_BASE_PROCESSING_COST, PROCESSING_CREDIT_PER_BYTE, STORAGE_CREDIT_PER_BYTE,
-};
+use crate::state_transition::fee::fee_schedule::FeeSchedule;
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -22,13 +20,14 @@ impl DeleteOperation {
 }
 
 impl OperationLike for DeleteOperation {
-    fn get_processing_cost(&self) -> i64 {
-        (DELETE_BASE_PROCESSING_COST
-            + ((self.key_size as i64 + self.value_size as i64) * PROCESSING_CREDIT_PER_BYTE))
-            as i64
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> i64 {
+        fee_schedule.delete_base_processing_cost as i64
+            + ((self.key_size as i64 + self.value_size as i64)
+                * fee_schedule.storage_processing_credit_per_byte as i64)
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        -((self.key_size + self.value_size) as i64 * STORAGE_CREDIT_PER_BYTE)
+    fn get_storage_cost(&self, fee_schedule: &FeeSchedule) -> i64 {
+        -((self.key_size + self.value_size) as i64
+            * fee_schedule.storage_disk_usage_credit_per_byte as i64)
     }
 }
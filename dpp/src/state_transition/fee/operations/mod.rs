@@ -13,12 +13,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 pub use write_operation::*;
 
+use super::fee_schedule::FeeSchedule;
+
 mod signature_verification_operation;
 pub use signature_verification_operation::*;
 
-pub const STORAGE_CREDIT_PER_BYTE: i64 = 5000;
-pub const STORAGE_PROCESSING_CREDIT_PER_BYTE: i64 = 5000;
-
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum Operation {
@@ -31,30 +30,30 @@ pub enum Operation {
 
 pub trait OperationLike {
     /// Get CPU cost of the operation
-    fn get_processing_cost(&self) -> i64;
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> i64;
     /// Get storage cost of the operation
-    fn get_storage_cost(&self) -> i64;
+    fn get_storage_cost(&self, fee_schedule: &FeeSchedule) -> i64;
 }
 
 macro_rules! call_method {
-    ($operation_type:expr, $method:ident ) => {
+    ($operation_type:expr, $method:ident, $fee_schedule:expr ) => {
         match $operation_type {
-            Operation::Read(op) => op.$method(),
-            Operation::Write(op) => op.$method(),
-            Operation::Delete(op) => op.$method(),
-            Operation::PreCalculated(op) => op.$method(),
-            Operation::SignatureVerification(op) => op.$method(),
+            Operation::Read(op) => op.$method($fee_schedule),
+            Operation::Write(op) => op.$method($fee_schedule),
+            Operation::Delete(op) => op.$method($fee_schedule),
+            Operation::PreCalculated(op) => op.$method($fee_schedule),
+            Operation::SignatureVerification(op) => op.$method($fee_schedule),
         }
     };
 }
 
 impl OperationLike for Operation {
-    fn get_processing_cost(&self) -> i64 {
-        call_method!(self, get_processing_cost)
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> i64 {
+        call_method!(self, get_processing_cost, fee_schedule)
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        call_method!(self, get_storage_cost)
+    fn get_storage_cost(&self, fee_schedule: &FeeSchedule) -> i64 {
+        call_method!(self, get_storage_cost, fee_schedule)
     }
 }
 
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use super::OperationLike;
+use crate::state_transition::fee::fee_schedule::FeeSchedule;
 
+/// Costs calculated by Drive. The processing cost is taken before the epoch fee multiplier,
+/// which is applied to it along with the processing costs of other operations.
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct PreCalculatedOperation {
@@ -19,11 +22,11 @@ impl PreCalculatedOperation {
 }
 
 impl OperationLike for PreCalculatedOperation {
-    fn get_processing_cost(&self) -> i64 {
+    fn get_processing_cost(&self, _fee_schedule: &FeeSchedule) -> i64 {
         self.processing_cost
     }
 
-    fn get_storage_cost(&self) -> i64 {
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> i64 {
         self.storage_cost
     }
 }
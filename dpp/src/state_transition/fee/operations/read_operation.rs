@@ -2,9 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use super::OperationLike;
 
-use crate::state_transition::fee::constants::{
-    PROCESSING_CREDIT_PER_BYTE, READ_BASE_PROCESSING_COST,
-};
+use crate::state_transition::fee::fee_schedule::FeeSchedule;
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -19,11 +17,12 @@ impl ReadOperation {
 }
 
 impl OperationLike for ReadOperation {
-    fn get_processing_cost(&self) -> i64 {
-        READ_BASE_PROCESSING_COST + (self.value_size as i64 * PROCESSING_CREDIT_PER_BYTE)
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> i64 {
+        fee_schedule.read_base_processing_cost as i64
+            + (self.value_size as i64 * fee_schedule.storage_load_credit_per_byte as i64)
     }
 
-    fn get_storage_cost(&self) -> i64 {
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> i64 {
         0
     }
 }
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::OperationLike;
-use crate::{identity::KeyType, state_transition::fee::constants::signature_verify_cost};
+use crate::{identity::KeyType, state_transition::fee::fee_schedule::FeeSchedule};
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -16,11 +16,11 @@ impl SignatureVerificationOperation {
 }
 
 impl OperationLike for SignatureVerificationOperation {
-    fn get_processing_cost(&self) -> i64 {
-        signature_verify_cost(self.signature_type)
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> i64 {
+        fee_schedule.signature_verify_cost(self.signature_type) as i64
     }
 
-    fn get_storage_cost(&self) -> i64 {
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> i64 {
         0
     }
 }
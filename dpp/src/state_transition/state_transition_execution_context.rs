@@ -1,6 +1,8 @@
 use std::sync::{Arc, Mutex};
 
-use super::fee::operations::Operation;
+use crate::version::LATEST_VERSION;
+
+use super::fee::{constants::DEFAULT_FEE_MULTIPLIER, operations::Operation};
 
 #[derive(Debug, Clone, Default)]
 pub struct StateTransitionExecutionContext {
@@ -12,6 +14,8 @@ struct StateTransitionContextInner {
     actual_operations: Vec<Operation>,
     dry_run_operations: Vec<Operation>,
     is_dry_run: bool,
+    fee_multiplier: Option<f64>,
+    protocol_version: Option<u32>,
 }
 
 impl StateTransitionExecutionContext {
@@ -73,4 +77,29 @@ impl StateTransitionExecutionContext {
         let inner = self.inner.lock().unwrap();
         inner.is_dry_run
     }
+
+    /// Set the fee multiplier of the epoch the state transition is executed in
+    pub fn set_fee_multiplier(&self, fee_multiplier: f64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.fee_multiplier = Some(fee_multiplier);
+    }
+
+    /// Returns the fee multiplier of the epoch or the default one if it isn't set
+    pub fn get_fee_multiplier(&self) -> f64 {
+        let inner = self.inner.lock().unwrap();
+        inner.fee_multiplier.unwrap_or(DEFAULT_FEE_MULTIPLIER)
+    }
+
+    /// Set the active protocol version of the block the state transition is executed in
+    pub fn set_protocol_version(&self, protocol_version: u32) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.protocol_version = Some(protocol_version);
+    }
+
+    /// Returns the active protocol version of the block or the latest one if it isn't set,
+    /// e.g. when fees are estimated outside of a block
+    pub fn get_protocol_version(&self) -> u32 {
+        let inner = self.inner.lock().unwrap();
+        inner.protocol_version.unwrap_or(LATEST_VERSION)
+    }
 }
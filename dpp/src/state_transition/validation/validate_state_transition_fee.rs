@@ -77,6 +77,10 @@ where
                 balance + identity.get_balance()
             }
             StateTransition::DataContractCreate(st) => {
+                if let Some(error) = self.validate_identity_owner_has_no_debt(st).await? {
+                    result.add_error(error);
+                    return Ok(result);
+                }
                 let balance = self.get_identity_owner_balance(st).await?;
                 if execution_context.is_dry_run() {
                     return Ok(result);
@@ -84,6 +88,10 @@ where
                 balance
             }
             StateTransition::DataContractUpdate(st) => {
+                if let Some(error) = self.validate_identity_owner_has_no_debt(st).await? {
+                    result.add_error(error);
+                    return Ok(result);
+                }
                 let balance = self.get_identity_owner_balance(st).await?;
                 if execution_context.is_dry_run() {
                     return Ok(result);
@@ -91,6 +99,10 @@ where
                 balance
             }
             StateTransition::DocumentsBatch(st) => {
+                if let Some(error) = self.validate_identity_owner_has_no_debt(st).await? {
+                    result.add_error(error);
+                    return Ok(result);
+                }
                 let balance = self.get_identity_owner_balance(st).await?;
                 if execution_context.is_dry_run() {
                     return Ok(result);
@@ -99,6 +111,10 @@ where
             }
 
             StateTransition::IdentityUpdate(st) => {
+                if let Some(error) = self.validate_identity_owner_has_no_debt(st).await? {
+                    result.add_error(error);
+                    return Ok(result);
+                }
                 let balance = self.get_identity_owner_balance(st).await?;
                 if execution_context.is_dry_run() {
                     return Ok(result);
@@ -114,8 +130,7 @@ where
             return Ok(result);
         }
 
-        let fee = state_transition.calculate_fee();
-        // ? make sure Fee cannot be negative and refunds are handled differently
+        let fee = state_transition.calculate_fee()?;
         if (balance as i64) < fee {
             result.add_error(FeeError::BalanceIsNotEnoughError { balance, fee })
         }
@@ -123,6 +138,22 @@ where
         Ok(result)
     }
 
+    async fn validate_identity_owner_has_no_debt(
+        &self,
+        st: &impl StateTransitionIdentitySigned,
+    ) -> Result<Option<FeeError>, ProtocolError> {
+        let debt = self
+            .state_repository
+            .fetch_identity_debt(st.get_owner_id(), st.get_execution_context())
+            .await?;
+
+        if debt > 0 {
+            return Ok(Some(FeeError::IdentityInDebtError { debt }));
+        }
+
+        Ok(None)
+    }
+
     async fn get_identity_owner_balance(
         &self,
         st: &impl StateTransitionIdentitySigned,
@@ -188,12 +219,15 @@ mod test {
         state_repository_mock
             .expect_fetch_identity()
             .returning(move |_, _| Ok(Some(identity.clone())));
+        state_repository_mock
+            .expect_fetch_identity_debt()
+            .returning(|_, _| Ok(0));
 
         let data_contract = get_data_contract_fixture(None);
         let data_contract_create_transition = DataContractCreateTransition {
             entropy: data_contract.entropy().to_owned(),
             data_contract,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -221,12 +255,15 @@ mod test {
         state_repository_mock
             .expect_fetch_identity()
             .returning(move |_, _| Ok(Some(identity.clone())));
+        state_repository_mock
+            .expect_fetch_identity_debt()
+            .returning(|_, _| Ok(0));
 
         let data_contract = get_data_contract_fixture(None);
         let data_contract_create_transition = DataContractCreateTransition {
             entropy: data_contract.entropy().to_owned(),
             data_contract,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -247,6 +284,9 @@ mod test {
         state_repository_mock
             .expect_fetch_identity()
             .returning(move |_, _| Ok(Some(identity.clone())));
+        state_repository_mock
+            .expect_fetch_identity_debt()
+            .returning(|_, _| Ok(0));
 
         let data_contract = get_data_contract_fixture(None);
         let documents =
@@ -255,7 +295,7 @@ mod test {
         let documents_batch_transition = DocumentsBatchTransition {
             owner_id: data_contract.owner_id().to_owned(),
             transitions,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -283,6 +323,9 @@ mod test {
         state_repository_mock
             .expect_fetch_identity()
             .returning(move |_, _| Ok(Some(identity.clone())));
+        state_repository_mock
+            .expect_fetch_identity_debt()
+            .returning(|_, _| Ok(0));
 
         let data_contract = get_data_contract_fixture(None);
         let documents =
@@ -291,7 +334,7 @@ mod test {
         let documents_batch_transition = DocumentsBatchTransition {
             owner_id: data_contract.owner_id().to_owned(),
             transitions,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -312,12 +355,15 @@ mod test {
         state_repository_mock
             .expect_fetch_identity()
             .returning(move |_, _| Ok(Some(identity.clone())));
+        state_repository_mock
+            .expect_fetch_identity_debt()
+            .returning(|_, _| Ok(0));
 
         let data_contract = get_data_contract_fixture(None);
         let documents =
             get_documents_fixture_with_owner_id_from_contract(data_contract.clone()).unwrap();
         let transitions = get_document_transitions_fixture([(Action::Create, documents)]);
-        let execution_context = execution_context_with_cost(40, 5);
+        let execution_context = execution_context_with_cost(80, 10);
         execution_context.enable_dry_run();
 
         let documents_batch_transition = DocumentsBatchTransition {
@@ -346,7 +392,7 @@ mod test {
 
         let mut identity_topup_transition =
             IdentityTopUpTransition::new(identity_topup_transition_fixture_json(None)).unwrap();
-        identity_topup_transition.set_execution_context(execution_context_with_cost(45000000, 5));
+        identity_topup_transition.set_execution_context(execution_context_with_cost(90000000, 10));
 
         let validator = StateTransitionFeeValidator::new(Arc::new(state_repository_mock));
         let result = validator
@@ -377,4 +423,41 @@ mod test {
             ProtocolError::InvalidStateTransitionTypeError
         ))
     }
+
+    #[tokio::test]
+    async fn documents_batch_transition_should_return_invalid_result_if_identity_has_debt() {
+        let mut identity = identity_fixture();
+        let mut state_repository_mock = MockStateRepositoryLike::new();
+
+        identity.balance = 90;
+        state_repository_mock
+            .expect_fetch_identity()
+            .returning(move |_, _| Ok(Some(identity.clone())));
+        state_repository_mock
+            .expect_fetch_identity_debt()
+            .returning(|_, _| Ok(5));
+
+        let data_contract = get_data_contract_fixture(None);
+        let documents =
+            get_documents_fixture_with_owner_id_from_contract(data_contract.clone()).unwrap();
+        let transitions = get_document_transitions_fixture([(Action::Create, documents)]);
+        let documents_batch_transition = DocumentsBatchTransition {
+            owner_id: data_contract.owner_id().to_owned(),
+            transitions,
+            execution_context: execution_context_with_cost(40, 5),
+            ..Default::default()
+        };
+
+        let validator = StateTransitionFeeValidator::new(Arc::new(state_repository_mock));
+        let result = validator
+            .validate(&documents_batch_transition.into())
+            .await
+            .expect("the validation result should be returned");
+
+        let fee_error = get_fee_error_from_result(&result, 0);
+        assert!(matches!(
+            fee_error,
+            FeeError::IdentityInDebtError { debt } if *debt == 5
+        ));
+    }
 }
@@ -3,9 +3,9 @@ mod apply_identity_credit_withdrawal_transition_factory {
     use crate::{
         identity::state_transition::identity_credit_withdrawal_transition::{
             apply_identity_credit_withdrawal_transition_factory::ApplyIdentityCreditWithdrawalTransition,
-            IdentityCreditWithdrawalTransition,
+            IdentityCreditWithdrawalTransition, Pooling,
         },
-        prelude::{Identifier, Identity},
+        prelude::Identifier,
         state_repository::MockStateRepositoryLike,
     };
 
@@ -14,44 +14,21 @@ mod apply_identity_credit_withdrawal_transition_factory {
         let mut state_repository = MockStateRepositoryLike::default();
 
         state_repository
-            .expect_fetch_latest_withdrawal_transaction_index()
+            .expect_apply_identity_credit_withdrawal()
             .times(1)
-            // trying to use values other than default to check they are actually set
-            .returning(|| anyhow::Ok(42));
-
-        state_repository
-            .expect_enqueue_withdrawal_transaction()
-            .withf(|index, _| *index == 42)
-            .returning(|_, _| anyhow::Ok(()));
-
-        state_repository
-            .expect_fetch_identity::<Identity>()
-            .times(1)
-            .withf(|id, _| *id == Identifier::default())
-            .returning(|_, _| {
-                let mut identity = Identity::default();
-
-                identity.set_balance(42);
-
-                anyhow::Ok(Some(identity))
-            });
-
-        state_repository
-            .expect_update_identity()
-            .times(1)
-            .withf(|identity, _| {
-                let id_match = *identity.get_id() == Identifier::default();
-                let balance_match = identity.get_balance() == (42 - 10);
-
-                id_match && balance_match
+            .withf(|_, identity_id, amount, _, pooling, _, _| {
+                *identity_id == Identifier::default()
+                    && *amount == 10
+                    && *pooling == Pooling::Standard
             })
-            .returning(|_, _| anyhow::Ok(()));
+            .returning(|_, _, _, _, _, _, _| anyhow::Ok(()));
 
         let applier = ApplyIdentityCreditWithdrawalTransition::new(state_repository);
 
         let mut state_transition = IdentityCreditWithdrawalTransition::default();
 
         state_transition.amount = 10;
+        state_transition.pooling = Pooling::Standard;
 
         match applier
             .apply_identity_credit_withdrawal_transition(&state_transition)
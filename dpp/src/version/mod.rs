@@ -7,6 +7,8 @@ pub use protocol_version_validator::ProtocolVersionValidator;
 mod protocol_version_validator;
 
 pub const LATEST_VERSION: u32 = 1;
+/// Protocol version of the chain on genesis
+pub const INITIAL_PROTOCOL_VERSION: u32 = 1;
 
 lazy_static! {
     pub static ref COMPATIBILITY_MAP: HashMap<u32, u32> = {
@@ -34,6 +34,7 @@
 
 use crate::drive::batch::GroveDbOpBatch;
 use crate::drive::block_info::BlockInfo;
+use crate::drive::masternode_list::{MasternodeListDiff, MasternodeListEntry, MasternodeStatus};
 use crate::drive::Drive;
 use crate::fee_pools::epochs::Epoch;
 use dpp::identifier::Identifier;
@@ -117,6 +118,7 @@ pub fn create_test_masternode_identities_and_add_them_as_epoch_block_proposers(
 }
 
 /// Creates a list of test Masternode identities of size `count` with random data
+/// and registers them as valid masternodes in the masternode list
 pub fn create_test_masternode_identities(
     drive: &Drive,
     count: u16,
@@ -124,13 +126,30 @@ pub fn create_test_masternode_identities(
 ) -> Vec<[u8; 32]> {
     let mut identity_ids: Vec<[u8; 32]> = Vec::with_capacity(count as usize);
 
+    let mut masternode_list_diff = MasternodeListDiff::default();
+
     for _ in 0..count {
         let proposer_pro_tx_hash: [u8; 32] = rand::random();
 
         create_test_identity(drive, proposer_pro_tx_hash, transaction);
 
         identity_ids.push(proposer_pro_tx_hash);
+
+        masternode_list_diff
+            .updated_masternodes
+            .push(MasternodeListEntry {
+                pro_tx_hash: proposer_pro_tx_hash,
+                owner_key_hash: rand::random(),
+                voting_key_hash: rand::random(),
+                payout_script: vec![],
+                operator_public_key: vec![],
+                status: MasternodeStatus::Valid,
+            });
     }
 
+    drive
+        .apply_masternode_list_diff(&masternode_list_diff, transaction)
+        .expect("should register masternodes");
+
     identity_ids
 }
@@ -0,0 +1,283 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! App Hash.
+//!
+//! This module defines functions to calculate and persist app hashes of committed blocks.
+//! App hashes are kept in auxiliary storage, so storing them doesn't change the state root hash.
+//!
+
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use grovedb::TransactionArg;
+
+/// Auxiliary storage key of the last stored app hash
+const AUX_KEY_LAST_APP_HASH: &[u8] = b"last_app_hash";
+/// Auxiliary storage key prefix of app hashes by height
+const AUX_KEY_APP_HASH_PREFIX: &[u8] = b"app_hash";
+
+/// Block height and its app hash
+pub type BlockAppHash = (u64, [u8; 32]);
+
+/// Returns the auxiliary storage key of a slot for a block height.
+/// Slots are reused, so only the app hashes of the last N blocks are kept.
+fn app_hash_slot_key(block_height: u64, app_hashes_to_keep: u64) -> Vec<u8> {
+    let slot = block_height % app_hashes_to_keep;
+
+    let mut key = AUX_KEY_APP_HASH_PREFIX.to_vec();
+    key.extend_from_slice(&slot.to_be_bytes());
+    key
+}
+
+/// Serializes block height and app hash
+fn encode_block_app_hash(block_height: u64, app_hash: [u8; 32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(40);
+    bytes.extend_from_slice(&block_height.to_be_bytes());
+    bytes.extend_from_slice(&app_hash);
+    bytes
+}
+
+/// Deserializes block height and app hash
+fn decode_block_app_hash(bytes: &[u8]) -> Result<BlockAppHash, Error> {
+    if bytes.len() != 40 {
+        return Err(Error::Drive(DriveError::CorruptedAppHash(
+            "stored app hash must be 40 bytes",
+        )));
+    }
+
+    let (block_height_bytes, app_hash_bytes) = bytes.split_at(8);
+
+    let block_height = u64::from_be_bytes(block_height_bytes.try_into().map_err(|_| {
+        Error::Drive(DriveError::CorruptedAppHash(
+            "stored block height must be an u64",
+        ))
+    })?);
+
+    let app_hash: [u8; 32] = app_hash_bytes.try_into().map_err(|_| {
+        Error::Drive(DriveError::CorruptedAppHash(
+            "stored app hash must be 32 bytes",
+        ))
+    })?;
+
+    Ok((block_height, app_hash))
+}
+
+impl Drive {
+    /// Calculates the app hash, which is the root hash of GroveDB
+    pub fn calculate_app_hash(&self, transaction: TransactionArg) -> Result<[u8; 32], Error> {
+        self.grove
+            .root_hash(transaction)
+            .unwrap()
+            .map_err(Error::GroveDB)
+    }
+
+    /// Stores the app hash of a block. Only app hashes of the last
+    /// `app_hashes_to_keep` blocks are kept.
+    pub fn store_app_hash(
+        &self,
+        block_height: u64,
+        app_hash: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        let value = encode_block_app_hash(block_height, app_hash);
+
+        self.grove
+            .put_aux(
+                &app_hash_slot_key(block_height, self.config.app_hashes_to_keep),
+                &value,
+                None,
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)?;
+
+        self.grove
+            .put_aux(AUX_KEY_LAST_APP_HASH, &value, None, transaction)
+            .unwrap()
+            .map_err(Error::GroveDB)
+    }
+
+    /// Returns the app hash of a block if it's among the last stored ones
+    pub fn fetch_app_hash(
+        &self,
+        block_height: u64,
+        transaction: TransactionArg,
+    ) -> Result<Option<[u8; 32]>, Error> {
+        let bytes = self
+            .grove
+            .get_aux(
+                &app_hash_slot_key(block_height, self.config.app_hashes_to_keep),
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)?;
+
+        match bytes {
+            Some(bytes) => {
+                let (stored_block_height, app_hash) = decode_block_app_hash(&bytes)?;
+
+                // The slot could be reused by another block
+                if stored_block_height == block_height {
+                    Ok(Some(app_hash))
+                } else {
+                    Ok(None)
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the height and the app hash of the last stored block
+    pub fn fetch_last_app_hash(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Option<BlockAppHash>, Error> {
+        self.grove
+            .get_aux(AUX_KEY_LAST_APP_HASH, transaction)
+            .unwrap()
+            .map_err(Error::GroveDB)?
+            .map(|bytes| decode_block_app_hash(&bytes))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::{setup_drive, setup_drive_with_initial_state_structure};
+    use crate::drive::config::DriveConfig;
+    use crate::drive::Drive;
+    use crate::error::drive::DriveError;
+    use crate::error::Error;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_and_fetch_app_hash() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let app_hash = drive
+            .calculate_app_hash(Some(&transaction))
+            .expect("should calculate app hash");
+
+        drive
+            .store_app_hash(1, app_hash, Some(&transaction))
+            .expect("should store app hash");
+
+        // App hashes are stored in aux storage so state must not change
+        let app_hash_after_store = drive
+            .calculate_app_hash(Some(&transaction))
+            .expect("should calculate app hash");
+
+        assert_eq!(app_hash, app_hash_after_store);
+
+        let stored_app_hash = drive
+            .fetch_app_hash(1, Some(&transaction))
+            .expect("should fetch app hash");
+
+        assert_eq!(stored_app_hash, Some(app_hash));
+
+        let last_app_hash = drive
+            .fetch_last_app_hash(Some(&transaction))
+            .expect("should fetch last app hash");
+
+        assert_eq!(last_app_hash, Some((1, app_hash)));
+    }
+
+    #[test]
+    fn test_returns_none_if_no_app_hash_stored() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let stored_app_hash = drive
+            .fetch_app_hash(1, None)
+            .expect("should fetch app hash");
+
+        assert!(stored_app_hash.is_none());
+
+        let last_app_hash = drive
+            .fetch_last_app_hash(None)
+            .expect("should fetch last app hash");
+
+        assert!(last_app_hash.is_none());
+    }
+
+    #[test]
+    fn test_keeps_only_last_app_hashes() {
+        let drive = setup_drive(Some(DriveConfig {
+            app_hashes_to_keep: 3,
+            ..Default::default()
+        }));
+
+        for block_height in 1..=5u64 {
+            drive
+                .store_app_hash(block_height, [block_height as u8; 32], None)
+                .expect("should store app hash");
+        }
+
+        for block_height in 1..=2u64 {
+            let stored_app_hash = drive
+                .fetch_app_hash(block_height, None)
+                .expect("should fetch app hash");
+
+            assert!(stored_app_hash.is_none());
+        }
+
+        for block_height in 3..=5u64 {
+            let stored_app_hash = drive
+                .fetch_app_hash(block_height, None)
+                .expect("should fetch app hash");
+
+            assert_eq!(stored_app_hash, Some([block_height as u8; 32]));
+        }
+
+        let last_app_hash = drive
+            .fetch_last_app_hash(None)
+            .expect("should fetch last app hash");
+
+        assert_eq!(last_app_hash, Some((5, [5; 32])));
+    }
+
+    #[test]
+    fn test_drive_with_no_app_hashes_to_keep_is_not_opened() {
+        let tmp_dir = TempDir::new().unwrap();
+
+        let result = Drive::open(
+            tmp_dir,
+            Some(DriveConfig {
+                app_hashes_to_keep: 0,
+                ..Default::default()
+            }),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Drive(DriveError::InvalidConfig(_)))
+        ));
+    }
+}
@@ -31,14 +31,16 @@ use crate::contract::document::Document;
 use crate::contract::Contract;
 use crate::drive::block_info::BlockInfo;
 use crate::drive::flags::StorageFlags;
-use crate::drive::object_size_info::DocumentAndContractInfo;
 use crate::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
+use crate::drive::object_size_info::{DeletionCostEstimation, DocumentAndContractInfo};
 use crate::drive::Drive;
+use crate::error::fee::FeeError;
 use crate::error::Error;
 use crate::fee::op::DriveOperation;
 use crate::fee::{calculate_fee, FeeResult};
 use dpp::data_contract::extra::DriveContractExt;
 use grovedb::TransactionArg;
+use std::collections::BTreeMap;
 
 /// A converter that will get Drive Operations from High Level Operations
 pub trait DriveOperationConverter {
@@ -314,6 +316,7 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
                 document_type_name,
                 owner_id,
                 apply,
+                DeletionCostEstimation::WorstCase,
                 transaction,
             ),
             DocumentOperationType::DeleteDocumentForContractCbor {
@@ -329,6 +332,7 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
                     document_type_name,
                     owner_id,
                     apply,
+                    DeletionCostEstimation::WorstCase,
                     transaction,
                 )
             }
@@ -458,6 +462,40 @@ pub enum DriveOperationType<'a> {
     // IdentityOperation(IdentityOperationType<'a>),
 }
 
+impl DriveOperationType<'_> {
+    /// Returns the identity paying for bytes added by the operation, if it's known
+    fn owner_id(&self) -> Option<[u8; 32]> {
+        match self {
+            DriveOperationType::ContractOperation(ContractOperationType::ApplyContractCbor {
+                storage_flags,
+                ..
+            }) => storage_flags
+                .and_then(|storage_flags| storage_flags.owner_id())
+                .copied(),
+            DriveOperationType::ContractOperation(
+                ContractOperationType::ApplyContractWithSerialization { contract, .. },
+            ) => Some(contract.owner_id.to_buffer()),
+            DriveOperationType::DocumentOperation(
+                DocumentOperationType::AddDocumentForContract {
+                    document_and_contract_info,
+                    ..
+                },
+            ) => document_and_contract_info.owner_id,
+            DriveOperationType::DocumentOperation(
+                DocumentOperationType::AddSerializedDocumentForSerializedContract {
+                    owner_id, ..
+                }
+                | DocumentOperationType::AddSerializedDocumentForContract { owner_id, .. }
+                | DocumentOperationType::DeleteDocumentForContract { owner_id, .. }
+                | DocumentOperationType::DeleteDocumentForContractCbor { owner_id, .. }
+                | DocumentOperationType::UpdateDocumentForContractCbor { owner_id, .. }
+                | DocumentOperationType::UpdateSerializedDocumentForContract { owner_id, .. }
+                | DocumentOperationType::UpdateDocumentForContract { owner_id, .. },
+            ) => *owner_id,
+        }
+    }
+}
+
 impl DriveOperationConverter for DriveOperationType<'_> {
     fn to_drive_operations(
         self,
@@ -493,23 +531,82 @@ impl Drive {
         block_info: &BlockInfo,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
-        let mut drive_operations = vec![];
+        // Storage usage is updated once the operations are applied,
+        // so both are applied in one transaction to keep them atomic
+        if apply && transaction.is_none() {
+            let transaction = self.grove.start_transaction();
+
+            let fee_result =
+                self.apply_drive_operations(operations, apply, block_info, Some(&transaction))?;
+
+            {
+                let mut cache = self.cache.borrow_mut();
+
+                cache
+                    .cached_contracts
+                    .merge_transactional_cache(&transaction);
+
+                cache
+                    .cached_contracts
+                    .clear_transactional_cache(&transaction);
+            }
+
+            self.commit_transaction(transaction)?;
+
+            return Ok(fee_result);
+        }
+
+        let mut fee_result = FeeResult::default();
+
+        // Added bytes are known per applied operation, so they are tracked
+        // in the storage usage of the operation owner
+        let mut added_bytes_by_owners: BTreeMap<[u8; 32], u64> = BTreeMap::new();
+
         for drive_op in operations {
-            drive_operations.append(&mut drive_op.to_drive_operations(
-                self,
+            let owner_id = drive_op.owner_id();
+
+            let drive_operations =
+                drive_op.to_drive_operations(self, apply, block_info, transaction)?;
+
+            let mut cost_operations = vec![];
+            self.apply_batch_drive_operations(
                 apply,
-                block_info,
                 transaction,
-            )?);
+                drive_operations,
+                &mut cost_operations,
+            )?;
+
+            let operation_fee_result =
+                calculate_fee(None, Some(cost_operations), &block_info.epoch)?;
+
+            if let Some(owner_id) = owner_id {
+                let added_bytes = added_bytes_by_owners.entry(owner_id).or_default();
+
+                *added_bytes = added_bytes
+                    .checked_add(operation_fee_result.added_bytes as u64)
+                    .ok_or(Error::Fee(FeeError::Overflow("added bytes overflow error")))?;
+            }
+
+            fee_result.checked_add_assign(operation_fee_result)?;
         }
-        let mut cost_operations = vec![];
-        self.apply_batch_drive_operations(
+
+        let mut storage_usage_operations = vec![];
+        self.update_identities_storage_usage_and_add_to_operations(
+            &added_bytes_by_owners,
+            &fee_result,
+            &block_info.epoch,
             apply,
             transaction,
-            drive_operations,
-            &mut cost_operations,
+            &mut storage_usage_operations,
         )?;
-        calculate_fee(None, Some(cost_operations), &block_info.epoch)
+
+        fee_result.checked_add_assign(calculate_fee(
+            None,
+            Some(storage_usage_operations),
+            &block_info.epoch,
+        )?)?;
+
+        Ok(fee_result)
     }
 }
 
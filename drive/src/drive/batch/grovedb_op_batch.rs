@@ -33,7 +33,7 @@
 //!
 
 use crate::drive::flags::StorageFlags;
-use grovedb::batch::{key_info::KeyInfo, GroveDbOp, GroveDbOpConsistencyResults, KeyInfoPath};
+use grovedb::batch::{key_info::KeyInfo, GroveDbOp, GroveDbOpConsistencyResults, KeyInfoPath, Op};
 use grovedb::Element;
 
 /// A batch of GroveDB operations as a vector.
@@ -149,6 +149,33 @@ impl GroveDbOpBatch {
         ));
     }
 
+    /// Gets the element of a pending `Insert` operation at the specified path and key
+    pub fn get_pending_insert_element(&self, path: &[Vec<u8>], key: &[u8]) -> Option<&Element> {
+        self.operations
+            .iter()
+            .rev()
+            .find_map(|operation| match &operation.op {
+                Op::Insert { element }
+                    if operation.key.as_slice() == key && operation.path.to_path() == path =>
+                {
+                    Some(element)
+                }
+                _ => None,
+            })
+    }
+
+    /// Adds an `Insert` operation with an element to a list of GroveDB ops,
+    /// replacing a pending `Insert` operation at the same path and key
+    pub fn add_or_replace_insert(&mut self, path: Vec<Vec<u8>>, key: Vec<u8>, element: Element) {
+        self.operations.retain(|operation| {
+            !(matches!(operation.op, Op::Insert { .. })
+                && operation.key.as_slice() == key.as_slice()
+                && operation.path.to_path() == path)
+        });
+
+        self.add_insert(path, key, element);
+    }
+
     /// Verify consistency of operations
     pub fn verify_consistency_of_operations(&self) -> GroveDbOpConsistencyResults {
         GroveDbOp::verify_consistency_of_operations(&self.operations)
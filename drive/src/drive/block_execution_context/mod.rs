@@ -0,0 +1,183 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Block Execution Context.
+//!
+//! This module defines functions to persist the info of the last committed block
+//! and its epoch in the misc tree, so block processing can be resumed after a restart.
+//!
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use grovedb::{Element, TransactionArg};
+
+/// Key of the last committed block info in the misc tree
+pub(crate) const KEY_LAST_BLOCK_INFO: &[u8; 1] = b"b";
+/// Key of the last committed epoch info in the misc tree
+pub(crate) const KEY_LAST_EPOCH_INFO: &[u8; 1] = b"e";
+
+/// Serialized block info and epoch info of the last committed block
+pub type StoredBlockExecutionContext = (Vec<u8>, Vec<u8>);
+
+impl Drive {
+    /// Adds operations to the batch which store serialized block info and epoch info
+    /// of the block being processed. They become the last committed ones on commit.
+    pub fn add_update_last_block_execution_context_operations(
+        &self,
+        batch: &mut GroveDbOpBatch,
+        block_info_bytes: Vec<u8>,
+        epoch_info_bytes: Vec<u8>,
+    ) {
+        batch.add_insert(
+            vec![vec![RootTree::Misc as u8]],
+            KEY_LAST_BLOCK_INFO.to_vec(),
+            Element::Item(block_info_bytes, None),
+        );
+
+        batch.add_insert(
+            vec![vec![RootTree::Misc as u8]],
+            KEY_LAST_EPOCH_INFO.to_vec(),
+            Element::Item(epoch_info_bytes, None),
+        );
+    }
+
+    /// Returns serialized block info and epoch info of the last committed block.
+    /// Returns `None` if no block was processed yet.
+    pub fn fetch_last_block_execution_context(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Option<StoredBlockExecutionContext>, Error> {
+        let block_info_bytes = match self.fetch_misc_item(KEY_LAST_BLOCK_INFO, transaction)? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        let epoch_info_bytes = self
+            .fetch_misc_item(KEY_LAST_EPOCH_INFO, transaction)?
+            .ok_or(Error::Drive(DriveError::CorruptedBlockExecutionContext(
+                "last epoch info must be stored along with last block info",
+            )))?;
+
+        Ok(Some((block_info_bytes, epoch_info_bytes)))
+    }
+
+    /// Returns an item from the misc tree
+    fn fetch_misc_item(
+        &self,
+        key: &[u8; 1],
+        transaction: TransactionArg,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let element = self
+            .grove
+            .get(
+                [Into::<&[u8; 1]>::into(RootTree::Misc).as_slice()],
+                key.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map(Some)
+            .or_else(|e| match e {
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_) => Ok(None),
+                _ => Err(e),
+            })?;
+
+        match element {
+            Some(Element::Item(bytes, _)) => Ok(Some(bytes)),
+            Some(_) => Err(Error::Drive(DriveError::CorruptedBlockExecutionContext(
+                "stored block execution context must be an item",
+            ))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::{setup_drive, setup_drive_with_initial_state_structure};
+    use crate::drive::batch::GroveDbOpBatch;
+
+    #[test]
+    fn test_returns_none_if_initial_structure_is_not_initiated() {
+        let drive = setup_drive(None);
+
+        let stored_context = drive
+            .fetch_last_block_execution_context(None)
+            .expect("should fetch last block execution context");
+
+        assert!(stored_context.is_none());
+    }
+
+    #[test]
+    fn test_returns_none_if_no_block_was_processed() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let stored_context = drive
+            .fetch_last_block_execution_context(None)
+            .expect("should fetch last block execution context");
+
+        assert!(stored_context.is_none());
+    }
+
+    #[test]
+    fn test_update_last_block_execution_context() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let mut batch = GroveDbOpBatch::new();
+
+        drive.add_update_last_block_execution_context_operations(
+            &mut batch,
+            vec![1, 2, 3],
+            vec![4, 5],
+        );
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let (block_info_bytes, epoch_info_bytes) = drive
+            .fetch_last_block_execution_context(Some(&transaction))
+            .expect("should fetch last block execution context")
+            .expect("should be present");
+
+        assert_eq!(block_info_bytes, vec![1, 2, 3]);
+        assert_eq!(epoch_info_bytes, vec![4, 5]);
+
+        // Not committed yet
+        let stored_context = drive
+            .fetch_last_block_execution_context(None)
+            .expect("should fetch last block execution context");
+
+        assert!(stored_context.is_none());
+    }
+}
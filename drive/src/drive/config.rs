@@ -31,6 +31,12 @@
 //!
 
 use crate::drive::config::DriveEncoding::DriveCbor;
+use crate::drive::fee_pools::epochs::constants::{
+    EPOCHS_PER_YEAR, FEE_DISTRIBUTION_TABLE, PERPETUAL_STORAGE_YEARS,
+};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use rust_decimal::Decimal;
 
 /// Boolean if GroveDB batching is enabled by default
 pub const DEFAULT_GROVE_BATCHING_ENABLED: bool = true;
@@ -40,8 +46,21 @@ pub const DEFAULT_GROVE_BATCHING_CONSISTENCY_VERIFICATION_ENABLED: bool = false;
 pub const DEFAULT_GROVE_HAS_RAW_ENABLED: bool = true;
 /// Default maximum number of contracts in cache
 pub const DEFAULT_DATA_CONTRACTS_CACHE_SIZE: u64 = 500;
+/// Default number of last blocks to keep app hashes for
+pub const DEFAULT_APP_HASHES_TO_KEEP: u64 = 1000;
+/// Boolean if credit conservation is verified at the end of the block by default
+pub const DEFAULT_VERIFY_CREDIT_CONSERVATION_ENABLED: bool = false;
+/// Default maximum amount of credits withdrawn per block (500 Dash)
+pub const DEFAULT_WITHDRAWAL_CREDITS_PER_BLOCK_LIMIT: u64 = 50_000_000_000_000;
+/// Default maximum amount of credits withdrawn per rolling 24h window (2000 Dash)
+pub const DEFAULT_WITHDRAWAL_CREDITS_PER_DAY_LIMIT: u64 = 200_000_000_000_000;
+/// Default amount of credits below which a storage refund is negligible (the price of a stored byte)
+pub const DEFAULT_NEGLIGIBLE_REFUND_CREDITS: u64 = 27_000;
+/// Default number of remaining epochs below which a storage refund is negligible (a year)
+pub const DEFAULT_NEGLIGIBLE_REFUND_REMAINING_EPOCHS: u16 = EPOCHS_PER_YEAR;
 
 /// Encoding for Drive
+#[derive(Clone, Debug)]
 pub enum DriveEncoding {
     /// Drive CBOR
     DriveCbor,
@@ -50,6 +69,7 @@ pub enum DriveEncoding {
 }
 
 /// Drive configuration struct
+#[derive(Clone, Debug)]
 pub struct DriveConfig {
     /// Boolean if batching is enabled
     pub batching_enabled: bool,
@@ -71,6 +91,42 @@ pub struct DriveConfig {
 
     /// Maximum number of contracts in block candidate cache
     pub data_contracts_transactional_cache_size: u64,
+
+    /// Number of last blocks to keep app hashes for
+    pub app_hashes_to_keep: u64,
+
+    /// Maximum amount of credits withdrawn per block.
+    /// Set from the chain parameters persisted in the platform config.
+    pub withdrawal_credits_per_block_limit: u64,
+
+    /// Maximum amount of credits withdrawn per rolling 24h window.
+    /// Set from the chain parameters persisted in the platform config.
+    pub withdrawal_credits_per_day_limit: u64,
+
+    /// Number of epochs in a year
+    pub epochs_per_year: u16,
+
+    /// Years of fees charged for perpetual storage
+    pub perpetual_storage_years: u16,
+
+    /// The amount of the perpetual storage fee paid out per year,
+    /// used to calculate storage refunds.
+    /// Set from the chain parameters persisted in the platform config.
+    pub fee_distribution_table: Vec<Decimal>,
+
+    /// Storage refunds below this amount of credits are negligible,
+    /// so their epochs are compacted in storage flags.
+    /// Set from the chain parameters persisted in the platform config.
+    pub negligible_refund_credits: u64,
+
+    /// Storage refunds with fewer epochs left to distribute their fees are negligible,
+    /// so their epochs are compacted in storage flags.
+    /// Set from the chain parameters persisted in the platform config.
+    pub negligible_refund_remaining_epochs: u16,
+
+    /// Boolean if credit conservation is verified at the end of the block.
+    /// Should be enabled only for debugging and tests since it scans all identities.
+    pub verify_credit_conservation: bool,
 }
 
 impl Default for DriveConfig {
@@ -84,6 +140,15 @@ impl Default for DriveConfig {
             encoding: DriveCbor,
             data_contracts_global_cache_size: DEFAULT_DATA_CONTRACTS_CACHE_SIZE,
             data_contracts_transactional_cache_size: DEFAULT_DATA_CONTRACTS_CACHE_SIZE,
+            app_hashes_to_keep: DEFAULT_APP_HASHES_TO_KEEP,
+            withdrawal_credits_per_block_limit: DEFAULT_WITHDRAWAL_CREDITS_PER_BLOCK_LIMIT,
+            withdrawal_credits_per_day_limit: DEFAULT_WITHDRAWAL_CREDITS_PER_DAY_LIMIT,
+            epochs_per_year: EPOCHS_PER_YEAR,
+            perpetual_storage_years: PERPETUAL_STORAGE_YEARS,
+            fee_distribution_table: FEE_DISTRIBUTION_TABLE.to_vec(),
+            negligible_refund_credits: DEFAULT_NEGLIGIBLE_REFUND_CREDITS,
+            negligible_refund_remaining_epochs: DEFAULT_NEGLIGIBLE_REFUND_REMAINING_EPOCHS,
+            verify_credit_conservation: DEFAULT_VERIFY_CREDIT_CONSERVATION_ENABLED,
         }
     }
 }
@@ -97,6 +162,12 @@ impl DriveConfig {
         }
     }
 
+    /// Number of epochs storage fees are distributed to
+    pub fn perpetual_storage_epochs(&self) -> u16 {
+        self.perpetual_storage_years
+            .saturating_mul(self.epochs_per_year)
+    }
+
     /// Default `DriveConfig` settings with batching disabled
     pub fn default_without_batches() -> Self {
         DriveConfig {
@@ -104,4 +175,15 @@ impl DriveConfig {
             ..Default::default()
         }
     }
+
+    /// Validates the config
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.app_hashes_to_keep == 0 {
+            return Err(Error::Drive(DriveError::InvalidConfig(
+                "app hashes to keep must be greater than zero",
+            )));
+        }
+
+        Ok(())
+    }
 }
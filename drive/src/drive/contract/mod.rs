@@ -213,7 +213,13 @@ impl Drive {
             &mut drive_operations,
         )?;
 
-        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+        self.calculate_fee_and_update_identities_storage_usage(
+            Some(contract.owner_id.to_buffer()),
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Adds a contract to storage using `add_contract_to_storage`
@@ -407,7 +413,13 @@ impl Drive {
             .cached_contracts
             .insert(updated_contract_fetch_info, transaction);
 
-        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+        self.calculate_fee_and_update_identities_storage_usage(
+            Some(contract.owner_id.to_buffer()),
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Updates a contract.
@@ -829,8 +841,13 @@ impl Drive {
             &mut cost_operations,
         )?;
         cost_operations.push(CalculatedCostOperation(fetch_cost));
-        let fees = calculate_fee(None, Some(cost_operations), &block_info.epoch)?;
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            Some(contract.owner_id.to_buffer()),
+            cost_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Gets the operations for applying a contract
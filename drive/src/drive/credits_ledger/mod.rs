@@ -0,0 +1,591 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Credits Ledger.
+//!
+//! This module defines the system credits ledger stored in the misc tree. The ledger records
+//! how many credits were minted by identity top ups and burned by withdrawals, and how many
+//! existing credits are held in fee pools and in identity balances.
+//!
+//! Fees debited from identities reach the pools at the end of the block, while debts of
+//! identities are paid to pools before they are settled, so the books balance when
+//! `minted + identity debts == burned + in pools + in identities` at the end of a block.
+//!
+//! Credits in pools are the storage fee distribution pool and the credits of epochs which
+//! are not paid out yet, including their shares of storage fee cohorts.
+//!
+
+use std::ops::RangeFull;
+
+use dpp::identity::Identity;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::fee_pools::epochs::constants::GENESIS_EPOCH_INDEX;
+use crate::drive::identity::balance::IDENTITY_DEBT_KEY;
+use crate::drive::identity::IDENTITY_KEY;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::identity::IdentityError;
+use crate::error::Error;
+use crate::fee_pools::epochs::epoch_key_constants::EPOCH_STORAGE_OFFSET;
+use crate::fee_pools::epochs::Epoch;
+
+/// Key of the credits ledger in the misc tree
+pub(crate) const KEY_CREDITS_LEDGER: &[u8; 1] = b"l";
+
+/// Size of the serialized credits ledger
+const CREDITS_LEDGER_SIZE: usize = 32;
+
+/// System credits ledger.
+/// Values are signed, so inconsistent books are reported by the conservation check
+/// instead of failing the operation which moved credits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CreditsLedger {
+    /// Credits created by identity top ups
+    pub total_minted: i64,
+    /// Credits destroyed by withdrawals
+    pub total_burned: i64,
+    /// Credits held in fee pools
+    pub in_pools: i64,
+    /// Credits held in identity balances
+    pub in_identities: i64,
+}
+
+fn to_signed(amount: u64) -> Result<i64, Error> {
+    i64::try_from(amount).map_err(|_| {
+        Error::Drive(DriveError::CorruptedCodeExecution(
+            "credits amount overflow",
+        ))
+    })
+}
+
+fn checked_add(value: i64, amount: u64) -> Result<i64, Error> {
+    value
+        .checked_add(to_signed(amount)?)
+        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+            "credits ledger overflow",
+        )))
+}
+
+fn checked_sub(value: i64, amount: u64) -> Result<i64, Error> {
+    value
+        .checked_sub(to_signed(amount)?)
+        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+            "credits ledger overflow",
+        )))
+}
+
+impl CreditsLedger {
+    /// Records credits created by a top up. The part of the amount which is not credited
+    /// to the balance settles the identity debt.
+    pub fn mint(&mut self, amount: u64, credited_to_balance: u64) -> Result<(), Error> {
+        self.total_minted = checked_add(self.total_minted, amount)?;
+        self.in_identities = checked_add(self.in_identities, credited_to_balance)?;
+        Ok(())
+    }
+
+    /// Records credits destroyed by a withdrawal from an identity balance
+    pub fn burn(&mut self, amount: u64) -> Result<(), Error> {
+        self.total_burned = checked_add(self.total_burned, amount)?;
+        self.in_identities = checked_sub(self.in_identities, amount)?;
+        Ok(())
+    }
+
+    /// Records a refund of withdrawn credits which were never unlocked on Core
+    pub fn revert_burn(&mut self, amount: u64, credited_to_balance: u64) -> Result<(), Error> {
+        self.total_burned = checked_sub(self.total_burned, amount)?;
+        self.in_identities = checked_add(self.in_identities, credited_to_balance)?;
+        Ok(())
+    }
+
+    /// Records credits debited from identity balances
+    pub fn remove_from_identities(&mut self, amount: u64) -> Result<(), Error> {
+        self.in_identities = checked_sub(self.in_identities, amount)?;
+        Ok(())
+    }
+
+    /// Records block fees added to the pools
+    pub fn add_to_pools(&mut self, amount: u64) -> Result<(), Error> {
+        self.in_pools = checked_add(self.in_pools, amount)?;
+        Ok(())
+    }
+
+    /// Records a payout from the pools to an identity
+    pub fn pay_from_pools(&mut self, amount: u64, credited_to_balance: u64) -> Result<(), Error> {
+        self.in_pools = checked_sub(self.in_pools, amount)?;
+        self.in_identities = checked_add(self.in_identities, credited_to_balance)?;
+        Ok(())
+    }
+
+    /// Serializes the ledger
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(CREDITS_LEDGER_SIZE);
+
+        bytes.extend_from_slice(&self.total_minted.to_be_bytes());
+        bytes.extend_from_slice(&self.total_burned.to_be_bytes());
+        bytes.extend_from_slice(&self.in_pools.to_be_bytes());
+        bytes.extend_from_slice(&self.in_identities.to_be_bytes());
+
+        bytes
+    }
+
+    /// Deserializes the ledger
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != CREDITS_LEDGER_SIZE {
+            return Err(Error::Drive(DriveError::CorruptedSerialization(
+                "credits ledger must be 32 bytes",
+            )));
+        }
+
+        let value_at = |i: usize| {
+            let mut value = [0u8; 8];
+            value.copy_from_slice(&bytes[i * 8..(i + 1) * 8]);
+            i64::from_be_bytes(value)
+        };
+
+        Ok(CreditsLedger {
+            total_minted: value_at(0),
+            total_burned: value_at(1),
+            in_pools: value_at(2),
+            in_identities: value_at(3),
+        })
+    }
+}
+
+impl Drive {
+    /// Fetches the credits ledger, which is empty before any credits are moved
+    pub fn fetch_credits_ledger(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<CreditsLedger, Error> {
+        let element = match self
+            .grove
+            .get(
+                [Into::<&[u8; 1]>::into(RootTree::Misc).as_slice()],
+                KEY_CREDITS_LEDGER.as_slice(),
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_)) => return Ok(CreditsLedger::default()),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        if let Element::Item(bytes, _) = element {
+            CreditsLedger::from_bytes(&bytes)
+        } else {
+            Err(Error::Drive(DriveError::CorruptedCreditsLedgerNotItem(
+                "credits ledger must be an item",
+            )))
+        }
+    }
+
+    /// Adds an operation to the op batch which updates the credits ledger.
+    /// The ledger already updated in the batch is used, so credits
+    /// can be moved multiple times within one batch.
+    pub fn add_update_credits_ledger_operations<F>(
+        &self,
+        update: F,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(&mut CreditsLedger) -> Result<(), Error>,
+    {
+        let path = vec![vec![RootTree::Misc as u8]];
+
+        let mut credits_ledger =
+            match batch.get_pending_insert_element(&path, KEY_CREDITS_LEDGER.as_slice()) {
+                Some(Element::Item(bytes, _)) => CreditsLedger::from_bytes(bytes)?,
+                Some(_) => {
+                    return Err(Error::Drive(DriveError::CorruptedCreditsLedgerNotItem(
+                        "credits ledger must be an item",
+                    )))
+                }
+                None => self.fetch_credits_ledger(transaction)?,
+            };
+
+        update(&mut credits_ledger)?;
+
+        batch.add_or_replace_insert(
+            path,
+            KEY_CREDITS_LEDGER.to_vec(),
+            Element::Item(credits_ledger.to_bytes(), None),
+        );
+
+        Ok(())
+    }
+
+    /// Verifies that the credits ledger matches identity balances and pool balances,
+    /// and that no credits were created or destroyed outside of top ups and withdrawals.
+    /// Should be called at the end of the block, once block fees are added to the pools.
+    pub fn verify_credit_conservation(&self, transaction: TransactionArg) -> Result<(), Error> {
+        let credits_ledger = self.fetch_credits_ledger(transaction)?;
+
+        let mut total_balances: i128 = 0;
+
+        for identity_bytes in self.fetch_identities_subtree_items(IDENTITY_KEY, transaction)? {
+            let identity = Identity::from_buffer(identity_bytes.as_slice()).map_err(|_| {
+                Error::Identity(IdentityError::IdentitySerialization(
+                    "failed to de-serialize identity from CBOR",
+                ))
+            })?;
+
+            total_balances += identity.balance as i128;
+        }
+
+        let mut total_debts: i128 = 0;
+
+        for debt_bytes in self.fetch_identities_subtree_items(IDENTITY_DEBT_KEY, transaction)? {
+            let debt = u64::from_be_bytes(debt_bytes.as_slice().try_into().map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "identity debt must be u64",
+                ))
+            })?);
+
+            total_debts += debt as i128;
+        }
+
+        if credits_ledger.in_identities as i128 != total_balances {
+            return Err(Error::Drive(DriveError::CorruptedCreditsNotBalanced(
+                "identity balances don't match credits in identities",
+            )));
+        }
+
+        if credits_ledger.in_pools as i128 != self.calculate_credits_in_pools(transaction)? {
+            return Err(Error::Drive(DriveError::CorruptedCreditsNotBalanced(
+                "pool balances don't match credits in pools",
+            )));
+        }
+
+        let sources = credits_ledger.total_minted as i128 + total_debts;
+        let destinations = credits_ledger.total_burned as i128
+            + credits_ledger.in_pools as i128
+            + credits_ledger.in_identities as i128;
+
+        if sources != destinations {
+            return Err(Error::Drive(DriveError::CorruptedCreditsNotBalanced(
+                "minted credits don't match burned credits and credits in pools and identities",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Sums credits held in the storage fee distribution pool and in epochs which are
+    /// not paid out yet. Rewards already paid from the partially paid epoch are deducted.
+    fn calculate_credits_in_pools(&self, transaction: TransactionArg) -> Result<i128, Error> {
+        let mut credits_in_pools =
+            self.get_aggregate_storage_fees_from_distribution_pool(transaction)? as i128;
+
+        for epoch_index in GENESIS_EPOCH_INDEX..u16::MAX - EPOCH_STORAGE_OFFSET {
+            let epoch_tree = Epoch::new(epoch_index);
+
+            if !self.is_epoch_tree_exists(&epoch_tree, transaction)? {
+                break;
+            }
+
+            // Credits for distribution are removed once the epoch is paid out
+            let storage_credits =
+                match self.get_epoch_storage_credits_for_distribution(&epoch_tree, transaction) {
+                    Ok(storage_credits) => storage_credits,
+                    Err(Error::GroveDB(grovedb::Error::PathKeyNotFound(_))) => continue,
+                    Err(e) => return Err(e),
+                };
+
+            // Processing credits are set once the epoch gets block fees
+            let processing_credits = self
+                .get_epoch_processing_credits_for_distribution(&epoch_tree, transaction)
+                .or_else(|e| match e {
+                    Error::GroveDB(grovedb::Error::PathKeyNotFound(_)) => Ok(0),
+                    _ => Err(e),
+                })?;
+
+            credits_in_pools += storage_credits as i128 + processing_credits as i128;
+        }
+
+        let unpaid_epoch_tree = Epoch::new(self.get_unpaid_epoch_index(transaction)?);
+
+        for payout in self.get_epoch_payouts(&unpaid_epoch_tree, None, u16::MAX, transaction)? {
+            credits_in_pools -= payout.total_reward as i128;
+        }
+
+        Ok(credits_in_pools)
+    }
+
+    /// Fetches items stored at the given key in the subtrees of all identities
+    fn fetch_identities_subtree_items(
+        &self,
+        key: [u8; 1],
+        transaction: TransactionArg,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut query = Query::new();
+
+        query.insert_item(QueryItem::RangeFull(RangeFull));
+        query.set_subquery_key(key.to_vec());
+
+        let path_query = PathQuery::new(
+            vec![vec![RootTree::Identities as u8]],
+            SizedQuery::new(query, None, None),
+        );
+
+        let (items, _) = self.grove_get_path_query(&path_query, transaction, &mut vec![])?;
+
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::fee_pools::epochs::proposer_payouts::ProposerPayout;
+    use crate::drive::fee_pools::storage_fee_cohorts::StorageFeeCohort;
+    use crate::fee_pools::{
+        update_storage_fee_cohort_operation, update_storage_fee_distribution_pool_operation,
+        update_unpaid_epoch_index_operation,
+    };
+
+    #[test]
+    fn test_credits_ledger_serialization() {
+        let credits_ledger = CreditsLedger {
+            total_minted: 1000,
+            total_burned: 100,
+            in_pools: 300,
+            in_identities: 600,
+        };
+
+        let deserialized_credits_ledger = CreditsLedger::from_bytes(&credits_ledger.to_bytes())
+            .expect("should deserialize credits ledger");
+
+        assert_eq!(deserialized_credits_ledger, credits_ledger);
+    }
+
+    #[test]
+    fn test_credit_conservation_with_top_ups_debts_and_pools() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        drive
+            .add_to_identity_balance(&identity.id.buffer, 100, BlockInfo::default(), true, None)
+            .expect("should top up identity");
+
+        drive
+            .remove_from_identity_balance(
+                &identity.id.buffer,
+                150,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("should debit identity");
+
+        // fees reach the pools at the end of the block
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(update_storage_fee_distribution_pool_operation(100));
+
+        let epoch_tree = Epoch::new(GENESIS_EPOCH_INDEX);
+
+        batch.push(epoch_tree.update_processing_credits_for_distribution_operation(50));
+
+        drive
+            .add_update_credits_ledger_operations(
+                |credits_ledger| credits_ledger.add_to_pools(150),
+                None,
+                &mut batch,
+            )
+            .expect("should update credits ledger");
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        drive
+            .verify_credit_conservation(None)
+            .expect("credits should be conserved");
+
+        let credits_ledger = drive
+            .fetch_credits_ledger(None)
+            .expect("should fetch credits ledger");
+
+        assert_eq!(
+            credits_ledger,
+            CreditsLedger {
+                total_minted: 100,
+                total_burned: 0,
+                in_pools: 150,
+                in_identities: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_credit_conservation_fails_for_unrecorded_balance_change() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let mut identity = create_test_identity(&drive, [1; 32], None);
+
+        identity.balance = 100;
+
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_update_identity_operations(identity, None, &mut batch)
+            .expect("should update identity");
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        assert!(matches!(
+            drive.verify_credit_conservation(None),
+            Err(Error::Drive(DriveError::CorruptedCreditsNotBalanced(_)))
+        ));
+    }
+
+    #[test]
+    fn test_credit_conservation_fails_for_unrecorded_pool_change() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(update_storage_fee_distribution_pool_operation(100));
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        assert!(matches!(
+            drive.verify_credit_conservation(None),
+            Err(Error::Drive(DriveError::CorruptedCreditsNotBalanced(_)))
+        ));
+    }
+
+    #[test]
+    fn test_credits_in_pools_include_cohorts_and_exclude_paid_rewards() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let epoch_tree = Epoch::new(GENESIS_EPOCH_INDEX);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        epoch_tree.add_init_current_operations(1.0, 1, 1, &mut batch);
+
+        batch.push(epoch_tree.update_processing_credits_for_distribution_operation(1000));
+
+        let mut cohort =
+            StorageFeeCohort::new(GENESIS_EPOCH_INDEX, drive.config.perpetual_storage_years);
+
+        cohort.epoch_shares[0] = 10;
+
+        batch.push(update_storage_fee_cohort_operation(&cohort).expect("should create operation"));
+
+        batch.push(
+            epoch_tree
+                .update_proposer_payout_operation(&ProposerPayout {
+                    pro_tx_hash: [1; 32],
+                    proposed_block_count: 1,
+                    total_reward: 300,
+                    share_payouts: vec![],
+                })
+                .expect("should create operation"),
+        );
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        let credits_in_pools = drive
+            .calculate_credits_in_pools(None)
+            .expect("should calculate credits in pools");
+
+        // the cohort share of every epoch of the first year is not paid out yet
+        let cohort_credits = 10 * drive.config.epochs_per_year as i128;
+
+        assert_eq!(credits_in_pools, 1000 + cohort_credits - 300);
+
+        // paid out epochs hold no credits
+        let mut batch = GroveDbOpBatch::new();
+
+        epoch_tree.add_mark_as_paid_operations(&mut batch);
+
+        batch.push(update_unpaid_epoch_index_operation(GENESIS_EPOCH_INDEX + 1));
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        let credits_in_pools = drive
+            .calculate_credits_in_pools(None)
+            .expect("should calculate credits in pools");
+
+        assert_eq!(credits_in_pools, cohort_credits - 10);
+    }
+
+    #[test]
+    fn test_credits_ledger_updates_are_combined_within_batch() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_update_credits_ledger_operations(
+                |credits_ledger| credits_ledger.add_to_pools(100),
+                None,
+                &mut batch,
+            )
+            .expect("should update credits ledger");
+
+        drive
+            .add_update_credits_ledger_operations(
+                |credits_ledger| credits_ledger.add_to_pools(50),
+                None,
+                &mut batch,
+            )
+            .expect("should update credits ledger");
+
+        assert_eq!(batch.len(), 1);
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        let credits_ledger = drive
+            .fetch_credits_ledger(None)
+            .expect("should fetch credits ledger");
+
+        assert_eq!(credits_ledger.in_pools, 150);
+    }
+}
@@ -41,9 +41,9 @@ use crate::drive::defaults::CONTRACT_DOCUMENTS_PATH_HEIGHT;
 use crate::drive::document::{contract_document_type_path, contract_documents_primary_key_path};
 use crate::drive::flags::StorageFlags;
 use crate::drive::object_size_info::DocumentInfo::{DocumentSize, DocumentWithoutSerialization};
-use crate::drive::object_size_info::DriveKeyInfo;
 use crate::drive::object_size_info::DriveKeyInfo::Key;
 use crate::drive::object_size_info::KeyValueInfo::KeyRefRequest;
+use crate::drive::object_size_info::{DeletionCostEstimation, DriveKeyInfo};
 use crate::drive::Drive;
 use crate::error::document::DocumentError;
 use crate::error::drive::DriveError;
@@ -74,8 +74,13 @@ impl Drive {
             transaction,
             &mut drive_operations,
         )?;
-        let fees = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            owner_id,
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Deletes a document and returns the associated fee.
@@ -137,9 +142,13 @@ impl Drive {
             &mut drive_operations,
         )?;
 
-        let fees = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
-
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            owner_id,
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Deletes a document.
@@ -159,11 +168,39 @@ impl Drive {
             document_type_name,
             owner_id,
             apply,
+            DeletionCostEstimation::WorstCase,
             transaction,
         )?;
         self.apply_batch_drive_operations(apply, transaction, batch_operations, drive_operations)
     }
 
+    /// Estimates the fee for deleting a document of the document type
+    /// without reading the state, based on the max size and indices of the document type.
+    pub fn estimated_fee_for_document_deletion_with_name(
+        &self,
+        contract: &Contract,
+        document_type_name: &str,
+        estimation: DeletionCostEstimation,
+        block_info: BlockInfo,
+    ) -> Result<FeeResult, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        // the document id is only used as a key of the max size
+        let batch_operations = self.delete_document_for_contract_operations(
+            [0; 32],
+            contract,
+            document_type_name,
+            None,
+            false,
+            estimation,
+            None,
+        )?;
+
+        self.apply_batch_drive_operations(false, None, batch_operations, &mut drive_operations)?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+
     /// Prepares the operations for deleting a document.
     pub(crate) fn delete_document_for_contract_operations(
         &self,
@@ -172,6 +209,7 @@ impl Drive {
         document_type_name: &str,
         owner_id: Option<[u8; 32]>,
         apply: bool,
+        estimation: DeletionCostEstimation,
         transaction: TransactionArg,
     ) -> Result<Vec<DriveOperation>, Error> {
         let mut batch_operations: Vec<DriveOperation> = vec![];
@@ -237,8 +275,9 @@ impl Drive {
         self.batch_delete(
             contract_documents_primary_key_path,
             document_id.as_slice(),
-            true,
             true, // not a tree, irrelevant
+            apply,
+            query_stateless_max_value_size.map(|max_value_size| max_value_size as u32),
             transaction,
             &mut batch_operations,
         )?;
@@ -301,6 +340,7 @@ impl Drive {
                     document_id.as_slice(),
                     Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
                     apply,
+                    estimation,
                     transaction,
                     &mut batch_operations,
                 )?;
@@ -311,6 +351,7 @@ impl Drive {
                     &[0],
                     Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
                     apply,
+                    estimation,
                     transaction,
                     &mut batch_operations,
                 )?;
@@ -338,9 +379,9 @@ mod tests {
     use crate::drive::object_size_info::DocumentAndContractInfo;
     use crate::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
     use crate::drive::Drive;
-    use crate::fee::default_costs::STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
     use crate::fee_pools::epochs::Epoch;
     use crate::query::DriveQuery;
+    use dpp::state_transition::fee::fee_schedule::FEE_SCHEDULE_V1;
 
     #[test]
     fn test_add_and_remove_family_one_document_no_transaction() {
@@ -1030,7 +1071,8 @@ mod tests {
             )
             .expect("expected to insert a document successfully");
 
-        let added_bytes = fee_result.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes =
+            fee_result.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         // We added 1756 bytes
         assert_eq!(added_bytes, 1668);
 
@@ -1187,4 +1229,32 @@ mod tests {
 
         assert_eq!(results.len(), 0);
     }
+
+    #[test]
+    fn test_average_case_deletion_fee_is_lower_than_worst_case() {
+        let (drive, dashpay_cbor) = setup_dashpay("delete", true);
+
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None)
+            .expect("expected to deserialize the contract");
+
+        let worst_case_fee_result = drive
+            .estimated_fee_for_document_deletion_with_name(
+                &contract,
+                "contactRequest",
+                DeletionCostEstimation::WorstCase,
+                BlockInfo::default(),
+            )
+            .expect("expected to estimate the deletion");
+
+        let average_case_fee_result = drive
+            .estimated_fee_for_document_deletion_with_name(
+                &contract,
+                "contactRequest",
+                DeletionCostEstimation::AverageCase,
+                BlockInfo::default(),
+            )
+            .expect("expected to estimate the deletion");
+
+        assert!(average_case_fee_result.processing_fee < worst_case_fee_result.processing_fee);
+    }
 }
@@ -62,7 +62,7 @@ use crate::drive::{defaults, Drive};
 use crate::error::drive::DriveError;
 use crate::error::Error;
 use crate::fee::op::DriveOperation;
-use crate::fee::{calculate_fee, FeeResult};
+use crate::fee::FeeResult;
 
 use crate::common::encode::encode_unsigned_integer;
 use crate::contract::document::Document;
@@ -436,9 +436,13 @@ impl Drive {
             &mut drive_operations,
         )?;
 
-        let fees = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
-
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            owner_id,
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Adds a document to a contract.
@@ -450,6 +454,8 @@ impl Drive {
         apply: bool,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
+        let owner_id = document_and_contract_info.owner_id;
+
         let mut drive_operations: Vec<DriveOperation> = vec![];
         self.add_document_for_contract_apply_and_add_to_operations(
             document_and_contract_info,
@@ -459,8 +465,13 @@ impl Drive {
             transaction,
             &mut drive_operations,
         )?;
-        let fees = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            owner_id,
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Performs the operations to add a document to a contract.
@@ -795,8 +806,8 @@ mod tests {
     use crate::drive::object_size_info::DocumentAndContractInfo;
     use crate::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
     use crate::drive::Drive;
-    use crate::fee::default_costs::STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
     use crate::fee::op::DriveOperation;
+    use dpp::state_transition::fee::fee_schedule::FEE_SCHEDULE_V1;
 
     #[test]
     fn test_add_dashpay_documents_no_transaction() {
@@ -948,6 +959,7 @@ mod tests {
             processing_fee,
             removed_bytes_from_identities,
             removed_bytes_from_system,
+            ..
         } = drive
             .add_serialized_document_for_contract(
                 &dashpay_cr_serialized_document,
@@ -962,7 +974,7 @@ mod tests {
             )
             .expect("expected to insert a document successfully");
 
-        let added_bytes = storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes = storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         assert_eq!(3211, added_bytes);
         assert_eq!(2804600, processing_fee);
     }
@@ -997,6 +1009,7 @@ mod tests {
             processing_fee,
             removed_bytes_from_identities,
             removed_bytes_from_system,
+            ..
         } = drive
             .add_serialized_document_for_contract(
                 &dashpay_cr_serialized_document,
@@ -1011,7 +1024,7 @@ mod tests {
             )
             .expect("expected to insert a document successfully");
 
-        let added_bytes = storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes = storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         assert_eq!(1414, added_bytes);
         assert_eq!(1862400, processing_fee);
     }
@@ -1046,6 +1059,7 @@ mod tests {
             processing_fee,
             removed_bytes_from_identities,
             removed_bytes_from_system,
+            ..
         } = drive
             .add_serialized_document_for_contract(
                 &dashpay_cr_serialized_document,
@@ -1060,7 +1074,7 @@ mod tests {
             )
             .expect("expected to insert a document successfully");
 
-        let added_bytes = storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes = storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         assert_eq!(1414, added_bytes);
         assert_eq!(191877800, processing_fee);
     }
@@ -1252,6 +1266,7 @@ mod tests {
             processing_fee,
             removed_bytes_from_identities,
             removed_bytes_from_system,
+            ..
         } = drive
             .add_document_for_contract(
                 DocumentAndContractInfo {
@@ -1271,7 +1286,7 @@ mod tests {
             )
             .expect("expected to insert a document successfully");
 
-        let added_bytes = storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes = storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         assert_eq!(1966, added_bytes);
         assert_eq!(2538000, processing_fee);
 
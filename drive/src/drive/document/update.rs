@@ -50,12 +50,14 @@ use crate::drive::object_size_info::DocumentInfo::{
 };
 use crate::drive::object_size_info::KeyValueInfo::KeyRefRequest;
 use crate::drive::object_size_info::PathKeyElementInfo::PathKeyElement;
-use crate::drive::object_size_info::{DocumentAndContractInfo, DriveKeyInfo, PathKeyInfo};
+use crate::drive::object_size_info::{
+    DeletionCostEstimation, DocumentAndContractInfo, DriveKeyInfo, PathKeyInfo,
+};
 use crate::drive::Drive;
 use crate::error::drive::DriveError;
 use crate::error::Error;
 use crate::fee::op::DriveOperation;
-use crate::fee::{calculate_fee, FeeResult};
+use crate::fee::FeeResult;
 
 use crate::drive::block_info::BlockInfo;
 use crate::error::document::DocumentError;
@@ -136,9 +138,13 @@ impl Drive {
             &mut drive_operations,
         )?;
 
-        let fees = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
-
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            owner_id,
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Updates a serialized document and returns the associated fee.
@@ -200,8 +206,13 @@ impl Drive {
             transaction,
             &mut drive_operations,
         )?;
-        let fees = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
-        Ok(fees)
+        self.calculate_fee_and_update_identities_storage_usage(
+            owner_id,
+            drive_operations,
+            &block_info.epoch,
+            apply,
+            transaction,
+        )
     }
 
     /// Updates a document.
@@ -514,6 +525,7 @@ impl Drive {
                             document.id.as_slice(),
                             Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
                             apply,
+                            DeletionCostEstimation::WorstCase,
                             transaction,
                             &mut batch_operations,
                         )?;
@@ -524,6 +536,7 @@ impl Drive {
                             &[0],
                             Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
                             apply,
+                            DeletionCostEstimation::WorstCase,
                             transaction,
                             &mut batch_operations,
                         )?;
@@ -604,9 +617,9 @@ mod tests {
     use crate::drive::object_size_info::DocumentAndContractInfo;
     use crate::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
     use crate::drive::{defaults, Drive};
-    use crate::fee::default_costs::STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
     use crate::fee_pools::epochs::Epoch;
     use crate::query::DriveQuery;
+    use dpp::state_transition::fee::fee_schedule::FEE_SCHEDULE_V1;
 
     #[test]
     fn test_create_and_update_document_same_transaction() {
@@ -1320,7 +1333,8 @@ mod tests {
             true,
             transaction.as_ref(),
         );
-        let original_bytes = original_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let original_bytes =
+            original_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         let expected_added_bytes = if using_history {
             //Explanation for 1350
 
@@ -1480,7 +1494,8 @@ mod tests {
                 true,
                 transaction.as_ref(),
             );
-            let original_bytes = original_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+            let original_bytes =
+                original_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
             assert_eq!(original_bytes, expected_added_bytes);
         }
 
@@ -1495,7 +1510,8 @@ mod tests {
         );
         // we both add and remove bytes
         // this is because trees are added because of indexes, and also removed
-        let added_bytes = update_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes =
+            update_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
 
         let expected_added_bytes = if using_history { 363 } else { 1 };
         assert_eq!(added_bytes, expected_added_bytes);
@@ -1564,7 +1580,8 @@ mod tests {
             true,
             transaction.as_ref(),
         );
-        let original_bytes = original_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let original_bytes =
+            original_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         let expected_added_bytes = if using_history { 1350 } else { 1006 };
         assert_eq!(original_bytes, expected_added_bytes);
         if !using_history {
@@ -1592,7 +1609,8 @@ mod tests {
                 true,
                 transaction.as_ref(),
             );
-            let original_bytes = original_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+            let original_bytes =
+                original_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
             assert_eq!(original_bytes, expected_added_bytes);
         }
         // now let's update it
@@ -1607,7 +1625,8 @@ mod tests {
         );
         // we both add and remove bytes
         // this is because trees are added because of indexes, and also removed
-        let added_bytes = update_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes =
+            update_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         let removed_bytes = update_fees
             .removed_bytes_from_identities
             .get(&owner_id)
@@ -1727,7 +1746,8 @@ mod tests {
             false,
             transaction.as_ref(),
         );
-        let original_bytes = original_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let original_bytes =
+            original_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
         let expected_added_bytes = if using_history {
             //Explanation for 1350
 
@@ -1872,7 +1892,8 @@ mod tests {
         );
         // we both add and remove bytes
         // this is because trees are added because of indexes, and also removed
-        let added_bytes = update_fees.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+        let added_bytes =
+            update_fees.storage_fee / FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte;
 
         let expected_added_bytes = if using_history { 1351 } else { 1007 };
         assert_eq!(added_bytes, expected_added_bytes);
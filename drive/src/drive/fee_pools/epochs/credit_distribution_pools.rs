@@ -43,6 +43,8 @@ use crate::fee_pools::epochs::epoch_key_constants;
 
 impl Drive {
     /// Gets the amount of storage credits to be distributed for the Epoch.
+    ///
+    /// Includes credits stored in the epoch and credits distributed to it by storage fee cohorts.
     pub fn get_epoch_storage_credits_for_distribution(
         &self,
         epoch_tree: &Epoch,
@@ -58,19 +60,26 @@ impl Drive {
             .unwrap()
             .map_err(Error::GroveDB)?;
 
-        if let Element::Item(item, _) = element {
-            Ok(u64::from_be_bytes(item.as_slice().try_into().map_err(
-                |_| {
-                    Error::Fee(FeeError::CorruptedStorageFeeInvalidItemLength(
-                        "epochs storage fee is not u64",
-                    ))
-                },
-            )?))
+        let epoch_storage_credits = if let Element::Item(item, _) = element {
+            u64::from_be_bytes(item.as_slice().try_into().map_err(|_| {
+                Error::Fee(FeeError::CorruptedStorageFeeInvalidItemLength(
+                    "epochs storage fee is not u64",
+                ))
+            })?)
         } else {
-            Err(Error::Fee(FeeError::CorruptedStorageFeeNotItem(
+            return Err(Error::Fee(FeeError::CorruptedStorageFeeNotItem(
                 "epochs storage fee must be an item",
+            )));
+        };
+
+        let cohorts_storage_credits =
+            self.get_epoch_storage_credits_from_cohorts(epoch_tree, transaction)?;
+
+        epoch_storage_credits
+            .checked_add(cohorts_storage_credits)
+            .ok_or(Error::Fee(FeeError::Overflow(
+                "overflow getting epoch storage credits for distribution",
             )))
-        }
     }
 
     /// Gets the amount of processing fees to be distributed for the Epoch.
@@ -105,7 +114,7 @@ impl Drive {
     }
 
     /// Gets the Fee Multiplier for the Epoch.
-    pub(crate) fn get_epoch_fee_multiplier(
+    pub fn get_epoch_fee_multiplier(
         &self,
         epoch_tree: &Epoch,
         transaction: TransactionArg,
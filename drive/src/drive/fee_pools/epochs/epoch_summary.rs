@@ -0,0 +1,482 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Epoch Summary.
+//!
+//! This module implements functions in Drive returning a summary of epochs
+//! for explorers and other clients.
+//!
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{Range, RangeInclusive};
+
+use grovedb::query_result_type::QueryResultType::QueryPathKeyElementTrioResultType;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+use serde::{Deserialize, Serialize};
+
+use crate::drive::fee_pools::pools_vec_path;
+use crate::drive::Drive;
+use crate::error::fee::FeeError;
+use crate::error::Error;
+use crate::fee_pools::epochs::epoch_key_constants::{
+    KEY_FEE_MULTIPLIER, KEY_POOL_PROCESSING_FEES, KEY_POOL_STORAGE_FEES, KEY_PROPOSERS,
+    KEY_START_BLOCK_HEIGHT, KEY_START_TIME,
+};
+use crate::fee_pools::epochs::paths::{decode_epoch_index_key, encode_epoch_index_key};
+use crate::fee_pools::epochs::Epoch;
+use crate::fee_pools::epochs_root_tree_key_constants::KEY_STORAGE_FEE_COHORTS;
+
+/// Summary of the epoch's state
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochSummary {
+    /// Epoch index
+    pub index: u16,
+    /// Time of the epoch's first block, if the epoch has started
+    pub start_time_ms: Option<u64>,
+    /// Height of the epoch's first block, if the epoch has started
+    pub start_block_height: Option<u64>,
+    /// Fee multiplier of the epoch, if the epoch has started
+    pub fee_multiplier: Option<f64>,
+    /// Processing credits to be distributed to the epoch's proposers
+    pub processing_credits_for_distribution: u64,
+    /// Storage credits to be distributed to the epoch's proposers
+    pub storage_credits_for_distribution: u64,
+    /// Number of proposers which are not paid yet
+    pub proposer_count: u32,
+    /// Whether the epoch's proposers were paid out
+    pub is_paid: bool,
+}
+
+impl EpochSummary {
+    fn new(index: u16) -> Self {
+        Self {
+            index,
+            start_time_ms: None,
+            start_block_height: None,
+            fee_multiplier: None,
+            processing_credits_for_distribution: 0,
+            storage_credits_for_distribution: 0,
+            proposer_count: 0,
+            is_paid: false,
+        }
+    }
+}
+
+/// Returns a path query for epoch values and proposers of the epochs within the given query item
+/// and, if given, the storage fee cohorts within the cohorts query item
+fn epochs_info_path_query(
+    epochs_query_item: QueryItem,
+    cohorts_query_item: Option<QueryItem>,
+) -> PathQuery {
+    let mut proposers_query = Query::new();
+    proposers_query.insert_all();
+
+    let mut epoch_query = Query::new();
+    epoch_query.insert_key(KEY_START_BLOCK_HEIGHT.to_vec());
+    epoch_query.insert_key(KEY_PROPOSERS.to_vec());
+    epoch_query.insert_key(KEY_POOL_PROCESSING_FEES.to_vec());
+    epoch_query.insert_key(KEY_POOL_STORAGE_FEES.to_vec());
+    epoch_query.insert_key(KEY_START_TIME.to_vec());
+    epoch_query.insert_key(KEY_FEE_MULTIPLIER.to_vec());
+    epoch_query.add_conditional_subquery(
+        QueryItem::Key(KEY_PROPOSERS.to_vec()),
+        None,
+        Some(proposers_query),
+    );
+
+    let mut epochs_query = Query::new();
+    epochs_query.insert_item(epochs_query_item);
+    epochs_query.set_subquery(epoch_query);
+
+    if let Some(cohorts_query_item) = cohorts_query_item {
+        let mut cohorts_query = Query::new();
+        cohorts_query.insert_item(cohorts_query_item);
+
+        epochs_query.insert_key(KEY_STORAGE_FEE_COHORTS.to_vec());
+        epochs_query.add_conditional_subquery(
+            QueryItem::Key(KEY_STORAGE_FEE_COHORTS.to_vec()),
+            None,
+            Some(cohorts_query),
+        );
+    }
+
+    PathQuery::new(pools_vec_path(), SizedQuery::new(epochs_query, None, None))
+}
+
+/// Returns a query item for epoch keys within the given inclusive range
+fn epochs_query_item(epoch_indices: RangeInclusive<u16>) -> Result<QueryItem, Error> {
+    let start_key = encode_epoch_index_key(*epoch_indices.start())?.to_vec();
+    let end_key = encode_epoch_index_key(*epoch_indices.end())?.to_vec();
+
+    Ok(QueryItem::RangeInclusive(start_key..=end_key))
+}
+
+/// Returns the inclusive range of epoch indices within the given range
+/// or `None` if the range is empty
+fn epochs_inclusive_range(epoch_indices: Range<u16>) -> Option<RangeInclusive<u16>> {
+    if epoch_indices.is_empty() {
+        return None;
+    }
+
+    Some(epoch_indices.start..=epoch_indices.end - 1)
+}
+
+/// Reads a big endian u64 from the epoch item
+fn u64_from_item(element: Element, error: FeeError) -> Result<u64, Error> {
+    if let Element::Item(item, _) = element {
+        Ok(u64::from_be_bytes(
+            item.as_slice().try_into().map_err(|_| Error::Fee(error))?,
+        ))
+    } else {
+        Err(Error::Fee(error))
+    }
+}
+
+impl Drive {
+    /// Returns a summary of the given epoch or `None` if the epoch tree doesn't exist
+    pub fn get_epoch_info(
+        &self,
+        epoch_index: u16,
+        transaction: TransactionArg,
+    ) -> Result<Option<EpochSummary>, Error> {
+        let query_item = epochs_query_item(epoch_index..=epoch_index)?;
+
+        Ok(self
+            .get_epochs_info_for_query_item(query_item, transaction)?
+            .pop())
+    }
+
+    /// Returns summaries of existing epochs within the given range ordered by epoch index
+    pub fn get_epochs_info(
+        &self,
+        epoch_indices: Range<u16>,
+        transaction: TransactionArg,
+    ) -> Result<Vec<EpochSummary>, Error> {
+        let epoch_indices = match epochs_inclusive_range(epoch_indices) {
+            Some(epoch_indices) => epoch_indices,
+            None => return Ok(vec![]),
+        };
+
+        let query_item = epochs_query_item(epoch_indices)?;
+
+        self.get_epochs_info_for_query_item(query_item, transaction)
+    }
+
+    /// Proves values and proposers of existing epochs within the given range along with
+    /// the storage fee cohorts distributing storage credits to them.
+    /// Returns an empty proof if the range is empty.
+    pub fn prove_epochs_info(
+        &self,
+        epoch_indices: Range<u16>,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let epoch_indices = match epochs_inclusive_range(epoch_indices) {
+            Some(epoch_indices) => epoch_indices,
+            None => return Ok(vec![]),
+        };
+
+        let first_cohort_epoch_index = epoch_indices
+            .start()
+            .saturating_sub(self.config.perpetual_storage_epochs().saturating_sub(1));
+
+        let cohorts_query_item =
+            epochs_query_item(first_cohort_epoch_index..=*epoch_indices.end())?;
+
+        let path_query =
+            epochs_info_path_query(epochs_query_item(epoch_indices)?, Some(cohorts_query_item));
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+
+    fn get_epochs_info_for_query_item(
+        &self,
+        query_item: QueryItem,
+        transaction: TransactionArg,
+    ) -> Result<Vec<EpochSummary>, Error> {
+        let path_query = epochs_info_path_query(query_item, None);
+
+        let path_key_elements = match self
+            .grove
+            .query_raw(&path_query, QueryPathKeyElementTrioResultType, transaction)
+            .unwrap()
+        {
+            Ok((query_result, _)) => query_result.to_path_key_elements(),
+            Err(grovedb::Error::PathNotFound(_))
+            | Err(grovedb::Error::PathParentLayerNotFound(_))
+            | Err(grovedb::Error::PathKeyNotFound(_)) => vec![],
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        let mut summaries: BTreeMap<u16, EpochSummary> = BTreeMap::new();
+        let mut epochs_with_processing_pool: BTreeSet<u16> = BTreeSet::new();
+        let mut epochs_with_storage_pool: BTreeSet<u16> = BTreeSet::new();
+
+        for (path, key, element) in path_key_elements {
+            let epoch_key = path
+                .get(1)
+                .ok_or(Error::Fee(FeeError::CorruptedEpochSummary(
+                    "epoch summary element must be inside of an epoch tree",
+                )))?;
+
+            let epoch_index = decode_epoch_index_key(epoch_key)?;
+
+            let summary = summaries
+                .entry(epoch_index)
+                .or_insert_with(|| EpochSummary::new(epoch_index));
+
+            // Elements of the proposers tree
+            if path.len() > 2 {
+                summary.proposer_count += 1;
+
+                continue;
+            }
+
+            match key.as_slice() {
+                key if key == KEY_START_TIME => {
+                    summary.start_time_ms = Some(u64_from_item(
+                        element,
+                        FeeError::CorruptedStartTimeLength(),
+                    )?);
+                }
+                key if key == KEY_START_BLOCK_HEIGHT => {
+                    summary.start_block_height = Some(u64_from_item(
+                        element,
+                        FeeError::CorruptedStartBlockHeightItemLength(),
+                    )?);
+                }
+                key if key == KEY_POOL_PROCESSING_FEES => {
+                    epochs_with_processing_pool.insert(epoch_index);
+
+                    summary.processing_credits_for_distribution = u64_from_item(
+                        element,
+                        FeeError::CorruptedProcessingFeeInvalidItemLength(
+                            "epochs processing fee is not u64",
+                        ),
+                    )?;
+                }
+                key if key == KEY_POOL_STORAGE_FEES => {
+                    epochs_with_storage_pool.insert(epoch_index);
+
+                    summary.storage_credits_for_distribution = u64_from_item(
+                        element,
+                        FeeError::CorruptedStorageFeeInvalidItemLength(
+                            "epochs storage fee is not u64",
+                        ),
+                    )?;
+                }
+                key if key == KEY_FEE_MULTIPLIER => {
+                    if let Element::Item(item, _) = element {
+                        summary.fee_multiplier = Some(f64::from_be_bytes(
+                            item.as_slice().try_into().map_err(|_| {
+                                Error::Fee(FeeError::CorruptedMultiplierInvalidItemLength(
+                                    "epochs multiplier item have an invalid length",
+                                ))
+                            })?,
+                        ));
+                    } else {
+                        return Err(Error::Fee(FeeError::CorruptedMultiplierNotItem(
+                            "epochs multiplier must be an item",
+                        )));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        summaries
+            .into_values()
+            .map(|mut summary| {
+                // Processing fees are written on every block of the epoch and removed
+                // together with the proposers once the epoch is paid out
+                summary.is_paid = summary.start_time_ms.is_some()
+                    && !epochs_with_processing_pool.contains(&summary.index);
+
+                // Storage fee cohorts are accounted until the epoch is paid out
+                if epochs_with_storage_pool.contains(&summary.index) {
+                    let epoch_tree = Epoch::new(summary.index);
+
+                    let cohorts_storage_credits =
+                        self.get_epoch_storage_credits_from_cohorts(&epoch_tree, transaction)?;
+
+                    summary.storage_credits_for_distribution = summary
+                        .storage_credits_for_distribution
+                        .checked_add(cohorts_storage_credits)
+                        .ok_or(Error::Fee(FeeError::Overflow(
+                            "overflow getting epoch storage credits for distribution",
+                        )))?;
+                }
+
+                Ok(summary)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::drive::fee_pools::storage_fee_cohorts::StorageFeeCohort;
+    use crate::fee_pools::update_storage_fee_cohort_operation;
+    use grovedb::GroveDb;
+
+    #[test]
+    fn test_get_epochs_info() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let paid_epoch = Epoch::new(0);
+        let current_epoch = Epoch::new(1);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        paid_epoch.add_init_current_operations(1.0, 1, 1000, &mut batch);
+        batch.push(paid_epoch.update_processing_credits_for_distribution_operation(100));
+        batch.push(paid_epoch.update_proposer_block_count_operation(&[1; 32], 1));
+
+        current_epoch.add_init_current_operations(2.0, 2, 2000, &mut batch);
+        batch.push(current_epoch.update_processing_credits_for_distribution_operation(300));
+        batch.push(current_epoch.update_storage_credits_for_distribution_operation(400));
+        batch.push(current_epoch.update_proposer_block_count_operation(&[1; 32], 1));
+        batch.push(current_epoch.update_proposer_block_count_operation(&[2; 32], 3));
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let mut batch = GroveDbOpBatch::new();
+
+        paid_epoch.add_mark_as_paid_operations(&mut batch);
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let summaries = drive
+            .get_epochs_info(0..3, Some(&transaction))
+            .expect("should get epochs info");
+
+        assert_eq!(
+            summaries,
+            vec![
+                EpochSummary {
+                    index: 0,
+                    start_time_ms: Some(1000),
+                    start_block_height: Some(1),
+                    fee_multiplier: Some(1.0),
+                    processing_credits_for_distribution: 0,
+                    storage_credits_for_distribution: 0,
+                    proposer_count: 0,
+                    is_paid: true,
+                },
+                EpochSummary {
+                    index: 1,
+                    start_time_ms: Some(2000),
+                    start_block_height: Some(2),
+                    fee_multiplier: Some(2.0),
+                    processing_credits_for_distribution: 300,
+                    storage_credits_for_distribution: 400,
+                    proposer_count: 2,
+                    is_paid: false,
+                },
+                EpochSummary::new(2),
+            ]
+        );
+
+        let summary = drive
+            .get_epoch_info(1, Some(&transaction))
+            .expect("should get epoch info");
+
+        assert_eq!(summary.as_ref(), summaries.get(1));
+
+        let proof = drive
+            .prove_epochs_info(0..3, Some(&transaction))
+            .expect("should prove epochs info");
+
+        assert!(!proof.is_empty());
+    }
+
+    #[test]
+    fn test_prove_epochs_info_with_storage_fee_cohorts() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let epoch = Epoch::new(0);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        epoch.add_init_current_operations(1.0, 1, 1000, &mut batch);
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let path_query = epochs_info_path_query(
+            epochs_query_item(0..=1).expect("should create epochs query item"),
+            Some(epochs_query_item(0..=1).expect("should create cohorts query item")),
+        );
+
+        let proof = drive
+            .prove_epochs_info(0..2, Some(&transaction))
+            .expect("should prove epochs info");
+
+        let (_, elements) =
+            GroveDb::verify_query(proof.as_slice(), &path_query).expect("should verify proof");
+
+        let mut cohort = StorageFeeCohort::new(0, drive.config.perpetual_storage_years);
+        cohort.epoch_shares[0] = 100;
+
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(
+            update_storage_fee_cohort_operation(&cohort).expect("should create cohort operation"),
+        );
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let proof = drive
+            .prove_epochs_info(0..2, Some(&transaction))
+            .expect("should prove epochs info");
+
+        let (_, elements_with_cohort) =
+            GroveDb::verify_query(proof.as_slice(), &path_query).expect("should verify proof");
+
+        assert_eq!(elements_with_cohort.len(), elements.len() + 1);
+
+        let proof = drive
+            .prove_epochs_info(1..1, Some(&transaction))
+            .expect("should prove empty range");
+
+        assert!(proof.is_empty());
+    }
+}
@@ -39,7 +39,10 @@ use grovedb::TransactionArg;
 /// Constants module
 pub mod constants;
 pub mod credit_distribution_pools;
+pub mod epoch_summary;
+pub mod proposer_payouts;
 pub mod proposers;
+pub mod protocol_version_votes;
 pub mod start_block;
 pub mod start_time;
 
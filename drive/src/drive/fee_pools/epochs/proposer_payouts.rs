@@ -0,0 +1,285 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Epoch Proposer Payouts.
+//!
+//! This module implements functions in Drive relevant to payout receipts
+//! of the epoch's block proposers.
+//!
+
+use std::ops::{RangeFrom, RangeFull};
+
+use bincode::Options;
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+use serde::{Deserialize, Serialize};
+
+use crate::drive::Drive;
+use crate::error::fee::FeeError;
+use crate::error::Error;
+use crate::fee_pools::epochs::epoch_key_constants::KEY_PROPOSER_PAYOUTS;
+use crate::fee_pools::epochs::Epoch;
+
+/// Reward paid to a masternode reward share recipient
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SharePayout {
+    /// Identity receiving the share
+    pub pay_to_id: [u8; 32],
+    /// Paid credits
+    pub amount: u64,
+}
+
+/// Payout receipt of the epoch's block proposer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposerPayout {
+    /// Proposer's proTxHash
+    pub pro_tx_hash: [u8; 32],
+    /// Number of blocks proposed in the epoch
+    pub proposed_block_count: u64,
+    /// Total masternode reward including reward shares
+    pub total_reward: u64,
+    /// Rewards paid to reward share recipients
+    pub share_payouts: Vec<SharePayout>,
+}
+
+impl ProposerPayout {
+    /// Serializes the proposer payout
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialize(self)
+            .map_err(|_| {
+                Error::Fee(FeeError::CorruptedProposerPayoutSerialization(
+                    "unable to serialize proposer payout",
+                ))
+            })
+    }
+
+    /// Deserializes the proposer payout
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .deserialize(bytes)
+            .map_err(|_| {
+                Error::Fee(FeeError::CorruptedProposerPayoutSerialization(
+                    "unable to deserialize proposer payout",
+                ))
+            })
+    }
+
+    /// Returns the reward paid to the masternode owner
+    pub fn owner_reward(&self) -> u64 {
+        self.share_payouts
+            .iter()
+            .fold(self.total_reward, |reward, share_payout| {
+                reward.saturating_sub(share_payout.amount)
+            })
+    }
+}
+
+/// Returns a path query for the epoch's proposer payouts starting from the given proTxHash
+fn epoch_payouts_path_query(
+    epoch_tree: &Epoch,
+    start_pro_tx_hash: Option<[u8; 32]>,
+    limit: u16,
+) -> PathQuery {
+    let mut query = Query::new();
+
+    match start_pro_tx_hash {
+        Some(start_pro_tx_hash) => query.insert_item(QueryItem::RangeFrom(RangeFrom {
+            start: start_pro_tx_hash.to_vec(),
+        })),
+        None => query.insert_item(QueryItem::RangeFull(RangeFull)),
+    }
+
+    PathQuery::new(
+        epoch_tree.get_proposer_payouts_vec_path(),
+        SizedQuery::new(query, Some(limit), None),
+    )
+}
+
+impl Drive {
+    /// Checks if the epoch has a proposer payouts tree. Epochs initialized
+    /// before payout receipts were introduced don't have one.
+    pub fn has_epoch_proposer_payouts_tree(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<bool, Error> {
+        self.grove
+            .has_raw(
+                epoch_tree.get_path(),
+                KEY_PROPOSER_PAYOUTS.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)
+    }
+
+    /// Returns payout receipts of the epoch's block proposers ordered by proTxHash,
+    /// starting from the given proTxHash
+    pub fn get_epoch_payouts(
+        &self,
+        epoch_tree: &Epoch,
+        start_pro_tx_hash: Option<[u8; 32]>,
+        limit: u16,
+        transaction: TransactionArg,
+    ) -> Result<Vec<ProposerPayout>, Error> {
+        let path_query = epoch_payouts_path_query(epoch_tree, start_pro_tx_hash, limit);
+
+        let key_elements = match self
+            .grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+        {
+            Ok((query_result, _)) => query_result.to_key_elements(),
+            Err(grovedb::Error::PathNotFound(_))
+            | Err(grovedb::Error::PathParentLayerNotFound(_))
+            | Err(grovedb::Error::PathKeyNotFound(_)) => vec![],
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        key_elements
+            .into_iter()
+            .map(|(_, element)| match element {
+                Element::Item(item, _) => ProposerPayout::from_bytes(&item),
+                _ => Err(Error::Fee(FeeError::CorruptedProposerPayoutNotItem(
+                    "epochs proposer payout must be an item",
+                ))),
+            })
+            .collect()
+    }
+
+    /// Proves payout receipts of the epoch's block proposers starting from the given proTxHash
+    pub fn prove_epoch_payouts(
+        &self,
+        epoch_tree: &Epoch,
+        start_pro_tx_hash: Option<[u8; 32]>,
+        limit: u16,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let path_query = epoch_payouts_path_query(epoch_tree, start_pro_tx_hash, limit);
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+
+    fn create_payout(pro_tx_hash: u8) -> ProposerPayout {
+        ProposerPayout {
+            pro_tx_hash: [pro_tx_hash; 32],
+            proposed_block_count: 2,
+            total_reward: 1000,
+            share_payouts: vec![SharePayout {
+                pay_to_id: [9; 32],
+                amount: 300,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_get_epoch_payouts() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let epoch = Epoch::new(0);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        epoch.add_init_current_operations(1.0, 1, 1, &mut batch);
+
+        for pro_tx_hash in 1..=3 {
+            batch.push(
+                epoch
+                    .update_proposer_payout_operation(&create_payout(pro_tx_hash))
+                    .expect("should create payout operation"),
+            );
+        }
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let payouts = drive
+            .get_epoch_payouts(&epoch, Some([2; 32]), 10, Some(&transaction))
+            .expect("should get epoch payouts");
+
+        assert_eq!(payouts, vec![create_payout(2), create_payout(3)]);
+        assert_eq!(payouts[0].owner_reward(), 700);
+
+        let payouts = drive
+            .get_epoch_payouts(&epoch, None, 1, Some(&transaction))
+            .expect("should get epoch payouts");
+
+        assert_eq!(payouts, vec![create_payout(1)]);
+
+        let proof = drive
+            .prove_epoch_payouts(&epoch, None, 10, Some(&transaction))
+            .expect("should prove epoch payouts");
+
+        assert!(!proof.is_empty());
+    }
+
+    #[test]
+    fn test_has_epoch_proposer_payouts_tree() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let epoch = Epoch::new(0);
+
+        let has_tree = drive
+            .has_epoch_proposer_payouts_tree(&epoch, Some(&transaction))
+            .expect("should check proposer payouts tree existence");
+
+        assert!(!has_tree);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(epoch.init_proposer_payouts_tree_operation());
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let has_tree = drive
+            .has_epoch_proposer_payouts_tree(&epoch, Some(&transaction))
+            .expect("should check proposer payouts tree existence");
+
+        assert!(has_tree);
+    }
+}
@@ -0,0 +1,199 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Epoch Protocol Version Votes.
+//!
+//! This module implements functions in Drive relevant to protocol versions
+//! supported by block proposers.
+//!
+
+use std::collections::BTreeMap;
+
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::Drive;
+use crate::error::fee::FeeError;
+use crate::error::Error;
+use crate::fee_pools::epochs::epoch_key_constants::KEY_PROTOCOL_VERSION_VOTES;
+use crate::fee_pools::epochs::Epoch;
+
+impl Drive {
+    /// Checks if the epoch has a protocol version votes tree. Epochs initialized
+    /// before protocol version voting was introduced don't have one.
+    pub fn has_epoch_protocol_version_votes_tree(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<bool, Error> {
+        self.grove
+            .has_raw(
+                epoch_tree.get_path(),
+                KEY_PROTOCOL_VERSION_VOTES.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)
+    }
+
+    /// Returns the number of the Epoch's block proposers for each supported protocol version.
+    /// Each proposer is counted once with the last protocol version it signaled.
+    pub fn get_epoch_protocol_version_votes(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<BTreeMap<u32, u64>, Error> {
+        let mut query = Query::new();
+        query.insert_all();
+
+        let path_query = PathQuery::new(
+            epoch_tree.get_protocol_version_votes_vec_path(),
+            SizedQuery::new(query, None, None),
+        );
+
+        let key_elements = match self
+            .grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+        {
+            Ok((query_result, _)) => query_result.to_key_elements(),
+            Err(grovedb::Error::PathNotFound(_))
+            | Err(grovedb::Error::PathParentLayerNotFound(_))
+            | Err(grovedb::Error::PathKeyNotFound(_)) => vec![],
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        let mut votes = BTreeMap::new();
+
+        for (_, element) in key_elements {
+            if let Element::Item(item, _) = element {
+                let protocol_version =
+                    u32::from_be_bytes(item.as_slice().try_into().map_err(|_| {
+                        Error::Fee(FeeError::CorruptedProtocolVersionVoteItemLength(
+                            "epochs protocol version vote item have an invalid length",
+                        ))
+                    })?);
+
+                *votes.entry(protocol_version).or_insert(0) += 1;
+            } else {
+                return Err(Error::Fee(FeeError::CorruptedProtocolVersionVoteNotItem(
+                    "epochs protocol version vote must be an item",
+                )));
+            }
+        }
+
+        Ok(votes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::fee_pools::epochs::Epoch;
+
+    #[test]
+    fn test_returns_empty_votes_if_votes_tree_is_not_initialized() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let epoch = Epoch::new(1);
+
+        let votes = drive
+            .get_epoch_protocol_version_votes(&epoch, Some(&transaction))
+            .expect("should get protocol version votes");
+
+        assert!(votes.is_empty());
+    }
+
+    #[test]
+    fn test_last_vote_of_proposer_is_counted() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let epoch = Epoch::new(1);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(epoch.init_protocol_version_votes_tree_operation());
+
+        batch.push(epoch.update_protocol_version_vote_operation(&[1; 32], 1));
+        batch.push(epoch.update_protocol_version_vote_operation(&[2; 32], 1));
+        batch.push(epoch.update_protocol_version_vote_operation(&[3; 32], 2));
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let mut batch = GroveDbOpBatch::new();
+
+        // Proposer changes its vote
+        batch.push(epoch.update_protocol_version_vote_operation(&[1; 32], 2));
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let votes = drive
+            .get_epoch_protocol_version_votes(&epoch, Some(&transaction))
+            .expect("should get protocol version votes");
+
+        assert_eq!(votes.len(), 2);
+        assert_eq!(votes.get(&1), Some(&1));
+        assert_eq!(votes.get(&2), Some(&2));
+    }
+
+    #[test]
+    fn test_has_epoch_protocol_version_votes_tree() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let epoch = Epoch::new(0);
+
+        let has_tree = drive
+            .has_epoch_protocol_version_votes_tree(&epoch, Some(&transaction))
+            .expect("should check protocol version votes tree existence");
+
+        assert!(!has_tree);
+
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(epoch.init_protocol_version_votes_tree_operation());
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let has_tree = drive
+            .has_epoch_protocol_version_votes_tree(&epoch, Some(&transaction))
+            .expect("should check protocol version votes tree existence");
+
+        assert!(has_tree);
+    }
+}
@@ -28,10 +28,13 @@
 //
 
 use crate::drive::RootTree;
-use crate::fee_pools::epochs_root_tree_key_constants::KEY_STORAGE_FEE_POOL;
+use crate::fee_pools::epochs_root_tree_key_constants::{
+    KEY_STORAGE_FEE_COHORTS, KEY_STORAGE_FEE_POOL,
+};
 
 /// Epochs module
 pub mod epochs;
+pub mod storage_fee_cohorts;
 pub mod storage_fee_distribution_pool;
 pub mod unpaid_epoch;
 
@@ -58,6 +61,22 @@ pub fn aggregate_storage_fees_distribution_pool_vec_path() -> Vec<Vec<u8>> {
     vec![vec![RootTree::Pools as u8], KEY_STORAGE_FEE_POOL.to_vec()]
 }
 
+/// Returns the path to the storage fee distribution cohorts.
+pub fn storage_fee_cohorts_path() -> [&'static [u8]; 2] {
+    [
+        Into::<&[u8; 1]>::into(RootTree::Pools),
+        KEY_STORAGE_FEE_COHORTS,
+    ]
+}
+
+/// Returns the path to the storage fee distribution cohorts as a mutable vector.
+pub fn storage_fee_cohorts_vec_path() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::Pools as u8],
+        KEY_STORAGE_FEE_COHORTS.to_vec(),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
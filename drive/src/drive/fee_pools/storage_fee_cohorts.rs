@@ -0,0 +1,273 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Storage Fee Distribution Cohorts.
+//!
+//! Storage fees collected during an epoch are distributed once, on the next epoch change,
+//! over the following perpetual storage epochs. Instead of updating every one of
+//! those epochs, the per-epoch share of each year is stored once as a cohort keyed by the
+//! epoch the distribution starts at. Storage credits of an epoch are derived from the cohorts
+//! covering it when the epoch is paid out.
+//!
+
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::fee_pools::{pools_path, storage_fee_cohorts_path, storage_fee_cohorts_vec_path};
+use crate::drive::Drive;
+use crate::error::fee::FeeError;
+use crate::error::Error;
+use crate::fee_pools::epochs::paths::{decode_epoch_index_key, encode_epoch_index_key};
+use crate::fee_pools::epochs::Epoch;
+use crate::fee_pools::epochs_root_tree_key_constants::KEY_STORAGE_FEE_COHORTS;
+
+/// Storage fees distributed over perpetual storage epochs starting from the given epoch
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageFeeCohort {
+    /// Index of the first epoch the fees are distributed to
+    pub start_epoch_index: u16,
+    /// Credits distributed to every epoch of each perpetual storage year
+    pub epoch_shares: Vec<u64>,
+}
+
+impl StorageFeeCohort {
+    /// Creates an empty cohort starting at the given epoch
+    pub fn new(start_epoch_index: u16, perpetual_storage_years: u16) -> Self {
+        Self {
+            start_epoch_index,
+            epoch_shares: vec![0; perpetual_storage_years as usize],
+        }
+    }
+
+    /// Returns credits distributed by the cohort to the given epoch
+    pub fn epoch_share(&self, epoch_index: u16, epochs_per_year: u16) -> u64 {
+        if epoch_index < self.start_epoch_index || epochs_per_year == 0 {
+            return 0;
+        }
+
+        let year = (epoch_index - self.start_epoch_index) / epochs_per_year;
+
+        self.epoch_shares
+            .get(year as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Serializes the cohort's epoch shares
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.epoch_shares
+            .iter()
+            .flat_map(|epoch_share| epoch_share.to_be_bytes())
+            .collect()
+    }
+
+    /// Deserializes epoch shares of the cohort starting at the given epoch
+    pub fn from_bytes(start_epoch_index: u16, bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() % 8 != 0 {
+            return Err(Error::Fee(FeeError::CorruptedStorageFeeCohortItemLength(
+                "storage fee cohort item have an invalid length",
+            )));
+        }
+
+        let epoch_shares = bytes
+            .chunks_exact(8)
+            .map(|chunk| {
+                chunk.try_into().map(u64::from_be_bytes).map_err(|_| {
+                    Error::Fee(FeeError::CorruptedStorageFeeCohortItemLength(
+                        "storage fee cohort share is not u64",
+                    ))
+                })
+            })
+            .collect::<Result<Vec<u64>, Error>>()?;
+
+        Ok(Self {
+            start_epoch_index,
+            epoch_shares,
+        })
+    }
+}
+
+impl Drive {
+    /// Checks if the storage fee cohorts tree exists. Chains initialized
+    /// before storage fee cohorts were introduced don't have one.
+    pub fn has_storage_fee_cohorts_tree(&self, transaction: TransactionArg) -> Result<bool, Error> {
+        self.grove
+            .has_raw(
+                pools_path(),
+                KEY_STORAGE_FEE_COHORTS.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)
+    }
+
+    /// Returns the storage fee cohort starting at the given epoch
+    pub fn get_storage_fee_cohort(
+        &self,
+        start_epoch_index: u16,
+        transaction: TransactionArg,
+    ) -> Result<Option<StorageFeeCohort>, Error> {
+        let element = match self
+            .grove
+            .get(
+                storage_fee_cohorts_path(),
+                &encode_epoch_index_key(start_epoch_index)?,
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_))
+            | Err(grovedb::Error::PathNotFound(_))
+            | Err(grovedb::Error::PathParentLayerNotFound(_)) => return Ok(None),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        if let Element::Item(item, _) = element {
+            Ok(Some(StorageFeeCohort::from_bytes(
+                start_epoch_index,
+                &item,
+            )?))
+        } else {
+            Err(Error::Fee(FeeError::CorruptedStorageFeeCohortNotItem(
+                "storage fee cohort must be an item",
+            )))
+        }
+    }
+
+    /// Returns storage credits distributed to the epoch by all cohorts covering it
+    pub fn get_epoch_storage_credits_from_cohorts(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<u64, Error> {
+        let first_cohort_epoch_index = epoch_tree
+            .index
+            .saturating_sub(self.config.perpetual_storage_epochs().saturating_sub(1));
+
+        let mut query = Query::new();
+        query.insert_range_inclusive(
+            encode_epoch_index_key(first_cohort_epoch_index)?.to_vec()..=epoch_tree.key.to_vec(),
+        );
+
+        let path_query = PathQuery::new(
+            storage_fee_cohorts_vec_path(),
+            SizedQuery::new(query, None, None),
+        );
+
+        let key_elements = match self
+            .grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+        {
+            Ok((query_result, _)) => query_result.to_key_elements(),
+            Err(grovedb::Error::PathNotFound(_))
+            | Err(grovedb::Error::PathParentLayerNotFound(_))
+            | Err(grovedb::Error::PathKeyNotFound(_)) => vec![],
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        key_elements
+            .into_iter()
+            .try_fold(0u64, |storage_credits, (key, element)| {
+                let cohort = if let Element::Item(item, _) = element {
+                    StorageFeeCohort::from_bytes(decode_epoch_index_key(&key)?, &item)?
+                } else {
+                    return Err(Error::Fee(FeeError::CorruptedStorageFeeCohortNotItem(
+                        "storage fee cohort must be an item",
+                    )));
+                };
+
+                storage_credits
+                    .checked_add(cohort.epoch_share(epoch_tree.index, self.config.epochs_per_year))
+                    .ok_or(Error::Fee(FeeError::Overflow(
+                        "overflow getting epoch storage credits from cohorts",
+                    )))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::fee_pools::update_storage_fee_cohort_operation;
+
+    #[test]
+    fn test_get_epoch_storage_credits_from_cohorts() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let perpetual_storage_years = drive.config.perpetual_storage_years;
+
+        let mut first_cohort = StorageFeeCohort::new(0, perpetual_storage_years);
+        first_cohort.epoch_shares[0] = 100;
+        first_cohort.epoch_shares[1] = 10;
+
+        let mut second_cohort = StorageFeeCohort::new(5, perpetual_storage_years);
+        second_cohort.epoch_shares[0] = 1000;
+        second_cohort.epoch_shares[perpetual_storage_years as usize - 1] = 1;
+
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(
+            update_storage_fee_cohort_operation(&first_cohort)
+                .expect("should create cohort operation"),
+        );
+        batch.push(
+            update_storage_fee_cohort_operation(&second_cohort)
+                .expect("should create cohort operation"),
+        );
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let cohort = drive
+            .get_storage_fee_cohort(5, Some(&transaction))
+            .expect("should get cohort");
+
+        assert_eq!(cohort, Some(second_cohort));
+
+        let storage_credits: Vec<u64> = [0, 5, 20, 25, 999, 1004, 1005]
+            .into_iter()
+            .map(|epoch_index| {
+                drive
+                    .get_epoch_storage_credits_from_cohorts(
+                        &Epoch::new(epoch_index),
+                        Some(&transaction),
+                    )
+                    .expect("should get storage credits")
+            })
+            .collect();
+
+        assert_eq!(storage_credits, vec![100, 1100, 1010, 10, 1, 1, 0]);
+    }
+}
@@ -43,6 +43,7 @@ use std::collections::BTreeMap;
 
 use crate::error::storage_flags::StorageFlagsError;
 use crate::error::Error;
+use crate::fee::refunds::StorageRefundParameters;
 
 type EpochIndex = u16;
 
@@ -81,6 +82,68 @@ impl StorageFlags {
         }
     }
 
+    /// Creates storage flags from their parts.
+    /// Flags without bytes added in other epochs are single epoch flags.
+    fn from_parts(
+        base_epoch: BaseEpoch,
+        other_epoch_bytes: BTreeMap<EpochIndex, BytesAddedInEpoch>,
+        owner_id: Option<&OwnerId>,
+    ) -> Self {
+        match (owner_id, other_epoch_bytes.is_empty()) {
+            (None, true) => SingleEpoch(base_epoch),
+            (Some(owner_id), true) => SingleEpochOwned(base_epoch, *owner_id),
+            (None, false) => MultiEpoch(base_epoch, other_epoch_bytes),
+            (Some(owner_id), false) => MultiEpochOwned(base_epoch, other_epoch_bytes, *owner_id),
+        }
+    }
+
+    /// Removes epochs without bytes or with a negligible refund from the epoch map.
+    ///
+    /// Bytes of removed epochs are merged into the base epoch. The base epoch is older
+    /// than any epoch of the map, so refunds calculated from the compacted flags are lower
+    /// by at most the negligible refund of every removed epoch.
+    fn compact_epoch_bytes(
+        other_epoch_bytes: &mut BTreeMap<EpochIndex, BytesAddedInEpoch>,
+        current_epoch_index: EpochIndex,
+        refund_parameters: &StorageRefundParameters,
+    ) {
+        other_epoch_bytes.retain(|epoch_index, bytes_added| {
+            *bytes_added > 0
+                && !refund_parameters.is_refund_negligible(
+                    *bytes_added,
+                    *epoch_index,
+                    current_epoch_index,
+                )
+        });
+    }
+
+    /// Compacts storage flags by merging epochs with a negligible refund into the base epoch
+    pub fn compact(
+        self,
+        current_epoch_index: EpochIndex,
+        refund_parameters: &StorageRefundParameters,
+    ) -> Self {
+        match self {
+            MultiEpoch(base_epoch, mut other_epoch_bytes) => {
+                Self::compact_epoch_bytes(
+                    &mut other_epoch_bytes,
+                    current_epoch_index,
+                    refund_parameters,
+                );
+                Self::from_parts(base_epoch, other_epoch_bytes, None)
+            }
+            MultiEpochOwned(base_epoch, mut other_epoch_bytes, owner_id) => {
+                Self::compact_epoch_bytes(
+                    &mut other_epoch_bytes,
+                    current_epoch_index,
+                    refund_parameters,
+                );
+                Self::from_parts(base_epoch, other_epoch_bytes, Some(&owner_id))
+            }
+            storage_flags => storage_flags,
+        }
+    }
+
     fn combine_owner_id<'a>(&'a self, rhs: &'a Self) -> Result<Option<&'a OwnerId>, Error> {
         if let Some(our_owner_id) = self.owner_id() {
             if let Some(other_owner_id) = rhs.owner_id() {
@@ -126,22 +189,26 @@ impl StorageFlags {
         }
     }
 
-    fn combine_same_base_epoch(&self, rhs: Self) -> Result<Self, Error> {
+    fn combine_same_base_epoch(
+        &self,
+        rhs: Self,
+        refund_parameters: &StorageRefundParameters,
+    ) -> Result<Self, Error> {
         let base_epoch = *self.base_epoch();
         let owner_id = self.combine_owner_id(&rhs)?;
-        let other_epoch_bytes = self.combine_non_base_epoch_bytes(&rhs);
-
-        match (owner_id, other_epoch_bytes) {
-            (None, None) => Ok(SingleEpoch(base_epoch)),
-            (Some(owner_id), None) => Ok(SingleEpochOwned(base_epoch, *owner_id)),
-            (None, Some(other_epoch_bytes)) => Ok(MultiEpoch(base_epoch, other_epoch_bytes)),
-            (Some(owner_id), Some(other_epoch_bytes)) => {
-                Ok(MultiEpochOwned(base_epoch, other_epoch_bytes, *owner_id))
-            }
-        }
+        let mut other_epoch_bytes = self.combine_non_base_epoch_bytes(&rhs).unwrap_or_default();
+
+        Self::compact_epoch_bytes(&mut other_epoch_bytes, *rhs.base_epoch(), refund_parameters);
+
+        Ok(Self::from_parts(base_epoch, other_epoch_bytes, owner_id))
     }
 
-    fn combine_with_higher_base_epoch(&self, rhs: Self, added_bytes: u32) -> Result<Self, Error> {
+    fn combine_with_higher_base_epoch(
+        &self,
+        rhs: Self,
+        added_bytes: u32,
+        refund_parameters: &StorageRefundParameters,
+    ) -> Result<Self, Error> {
         let base_epoch = *self.base_epoch();
         let epoch_with_adding_bytes = rhs.base_epoch();
         let owner_id = self.combine_owner_id(&rhs)?;
@@ -154,18 +221,23 @@ impl StorageFlags {
             }
         };
 
-        match owner_id {
-            None => Ok(MultiEpoch(base_epoch, other_epoch_bytes)),
-            Some(owner_id) => Ok(MultiEpochOwned(base_epoch, other_epoch_bytes, *owner_id)),
-        }
+        Self::compact_epoch_bytes(
+            &mut other_epoch_bytes,
+            *epoch_with_adding_bytes,
+            refund_parameters,
+        );
+
+        Ok(Self::from_parts(base_epoch, other_epoch_bytes, owner_id))
     }
 
     fn combine_with_higher_base_epoch_remove_bytes(
         self,
         rhs: Self,
         removed_bytes: &StorageRemovedBytes,
+        refund_parameters: &StorageRefundParameters,
     ) -> Result<Self, Error> {
         let base_epoch = *self.base_epoch();
+        let current_epoch = *rhs.base_epoch();
         let owner_id = self.combine_owner_id(&rhs)?;
         let mut other_epoch_bytes = self.combine_non_base_epoch_bytes(&rhs).unwrap_or_default();
         if let SectionedStorageRemoval(sectioned_bytes_by_identifier) = removed_bytes {
@@ -188,14 +260,28 @@ impl StorageFlags {
             sectioned_bytes
                 .iter()
                 .try_for_each(|(epoch, removed_bytes)| {
-                    let bytes_added_in_epoch =
-                        other_epoch_bytes
-                            .get_mut(&(*epoch as u16))
-                            .ok_or(Error::StorageFlags(
+                    let epoch = *epoch as u16;
+                    let bytes_added_in_epoch = match other_epoch_bytes.get_mut(&epoch) {
+                        Some(bytes_added_in_epoch) => bytes_added_in_epoch,
+                        // bytes of the base epoch and of compacted epochs are not in the map,
+                        // and the refund of bytes removed from a compacted epoch is negligible
+                        None if epoch == base_epoch
+                            || refund_parameters.is_refund_negligible(
+                                *removed_bytes,
+                                epoch,
+                                current_epoch,
+                            ) =>
+                        {
+                            return Ok(())
+                        }
+                        None => {
+                            return Err(Error::StorageFlags(
                                 StorageFlagsError::RemovingAtEpochWithNoAssociatedStorage(
                                     "can not remove bytes when there is no epoch",
                                 ),
-                            ))?;
+                            ))
+                        }
+                    };
                     *bytes_added_in_epoch =
                         bytes_added_in_epoch.checked_sub(*removed_bytes).ok_or(
                             Error::StorageFlags(StorageFlagsError::StorageFlagsOverflow(
@@ -206,10 +292,9 @@ impl StorageFlags {
                 })?;
         }
 
-        match owner_id {
-            None => Ok(MultiEpoch(base_epoch, other_epoch_bytes)),
-            Some(owner_id) => Ok(MultiEpochOwned(base_epoch, other_epoch_bytes, *owner_id)),
-        }
+        Self::compact_epoch_bytes(&mut other_epoch_bytes, current_epoch, refund_parameters);
+
+        Ok(Self::from_parts(base_epoch, other_epoch_bytes, owner_id))
     }
 
     /// Optional combine added bytes
@@ -217,10 +302,11 @@ impl StorageFlags {
         ours: Option<Self>,
         theirs: Self,
         added_bytes: u32,
+        refund_parameters: &StorageRefundParameters,
     ) -> Result<Self, Error> {
         match ours {
             None => Ok(theirs),
-            Some(ours) => Ok(ours.combine_added_bytes(theirs, added_bytes)?),
+            Some(ours) => Ok(ours.combine_added_bytes(theirs, added_bytes, refund_parameters)?),
         }
     }
 
@@ -229,18 +315,29 @@ impl StorageFlags {
         ours: Option<Self>,
         theirs: Self,
         removed_bytes: &StorageRemovedBytes,
+        refund_parameters: &StorageRefundParameters,
     ) -> Result<Self, Error> {
         match ours {
             None => Ok(theirs),
-            Some(ours) => Ok(ours.combine_removed_bytes(theirs, removed_bytes)?),
+            Some(ours) => {
+                Ok(ours.combine_removed_bytes(theirs, removed_bytes, refund_parameters)?)
+            }
         }
     }
 
-    /// Combine added bytes
-    pub fn combine_added_bytes(self, rhs: Self, added_bytes: u32) -> Result<Self, Error> {
+    /// Combine added bytes.
+    /// The new flags are in the current epoch, and epochs with a negligible refund are compacted.
+    pub fn combine_added_bytes(
+        self,
+        rhs: Self,
+        added_bytes: u32,
+        refund_parameters: &StorageRefundParameters,
+    ) -> Result<Self, Error> {
         match self.base_epoch().cmp(rhs.base_epoch()) {
-            Ordering::Equal => self.combine_same_base_epoch(rhs),
-            Ordering::Less => self.combine_with_higher_base_epoch(rhs, added_bytes),
+            Ordering::Equal => self.combine_same_base_epoch(rhs, refund_parameters),
+            Ordering::Less => {
+                self.combine_with_higher_base_epoch(rhs, added_bytes, refund_parameters)
+            }
             Ordering::Greater => Err(Error::StorageFlags(
                 StorageFlagsError::MergingStorageFlagsWithDifferentBaseEpoch(
                     "can not merge with new item in older base epoch",
@@ -249,15 +346,21 @@ impl StorageFlags {
         }
     }
 
-    /// Combine removed bytes
+    /// Combine removed bytes.
+    /// The new flags are in the current epoch, and epochs with a negligible refund are compacted.
     pub fn combine_removed_bytes(
         self,
         rhs: Self,
         removed_bytes: &StorageRemovedBytes,
+        refund_parameters: &StorageRefundParameters,
     ) -> Result<Self, Error> {
         match self.base_epoch().cmp(rhs.base_epoch()) {
-            Ordering::Equal => self.combine_same_base_epoch(rhs),
-            Ordering::Less => self.combine_with_higher_base_epoch_remove_bytes(rhs, removed_bytes),
+            Ordering::Equal => self.combine_same_base_epoch(rhs, refund_parameters),
+            Ordering::Less => self.combine_with_higher_base_epoch_remove_bytes(
+                rhs,
+                removed_bytes,
+                refund_parameters,
+            ),
             Ordering::Greater => Err(Error::StorageFlags(
                 StorageFlagsError::MergingStorageFlagsWithDifferentBaseEpoch(
                     "can not merge with new item in older base epoch",
@@ -450,7 +553,7 @@ impl StorageFlags {
                     "multi epoch must have enough bytes for the base epoch",
                 ))
             })?);
-            let mut offset = 3;
+            let mut offset = 35;
             let mut bytes_per_epoch: BTreeMap<u16, u32> = BTreeMap::default();
             while offset + 2 < len {
                 // 2 for epoch size
@@ -647,3 +750,115 @@ impl StorageFlags {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// Refund parameters compacting only epochs which fees were fully distributed
+    fn distributed_fees_refund_parameters() -> StorageRefundParameters {
+        StorageRefundParameters {
+            epochs_per_year: 10,
+            fee_distribution_table: vec![dec!(1)],
+            storage_credits_per_byte: 1000,
+            negligible_refund_credits: 0,
+            negligible_refund_remaining_epochs: 0,
+        }
+    }
+
+    #[test]
+    fn test_multi_epoch_owned_serialization() {
+        let storage_flags = MultiEpochOwned(1, BTreeMap::from([(3, 100), (5, 300)]), [7; 32]);
+
+        let deserialized_storage_flags = StorageFlags::deserialize(&storage_flags.serialize())
+            .expect("should deserialize storage flags");
+
+        assert_eq!(deserialized_storage_flags, Some(storage_flags));
+    }
+
+    #[test]
+    fn test_combine_added_bytes_compacts_epochs_without_refund_value() {
+        let old_storage_flags = MultiEpochOwned(0, BTreeMap::from([(2, 100), (8, 50)]), [7; 32]);
+
+        let new_storage_flags = SingleEpochOwned(12, [7; 32]);
+
+        let combined_storage_flags = old_storage_flags
+            .combine_added_bytes(new_storage_flags, 20, &distributed_fees_refund_parameters())
+            .expect("should combine storage flags");
+
+        assert_eq!(
+            combined_storage_flags,
+            MultiEpochOwned(0, BTreeMap::from([(8, 50), (12, 20)]), [7; 32])
+        );
+
+        let compacted_storage_flags =
+            combined_storage_flags.compact(30, &distributed_fees_refund_parameters());
+
+        assert_eq!(compacted_storage_flags, SingleEpochOwned(0, [7; 32]));
+    }
+
+    #[test]
+    fn test_combine_removed_bytes_from_base_and_compacted_epochs() {
+        let old_storage_flags = MultiEpoch(0, BTreeMap::from([(2, 100), (8, 50)]));
+
+        let new_storage_flags = SingleEpoch(12);
+
+        let mut removed_bytes_per_epoch: IntMap<u32> = IntMap::default();
+
+        removed_bytes_per_epoch.insert(0, 10);
+        removed_bytes_per_epoch.insert(2, 30);
+        removed_bytes_per_epoch.insert(8, 50);
+
+        let removed_bytes =
+            SectionedStorageRemoval(BTreeMap::from([([0; 32], removed_bytes_per_epoch)]));
+
+        let combined_storage_flags = old_storage_flags
+            .combine_removed_bytes(
+                new_storage_flags,
+                &removed_bytes,
+                &distributed_fees_refund_parameters(),
+            )
+            .expect("should combine storage flags");
+
+        assert_eq!(combined_storage_flags, SingleEpoch(0));
+    }
+
+    #[test]
+    fn test_combine_added_bytes_compacts_epochs_with_negligible_refund() {
+        let refund_parameters = StorageRefundParameters {
+            epochs_per_year: 20,
+            fee_distribution_table: vec![dec!(0.2); 5],
+            storage_credits_per_byte: 1000,
+            negligible_refund_credits: 5000,
+            negligible_refund_remaining_epochs: 10,
+        };
+
+        let old_storage_flags = MultiEpoch(0, BTreeMap::from([(20, 8), (30, 500)]));
+
+        let combined_storage_flags = old_storage_flags
+            .combine_added_bytes(SingleEpoch(70), 20, &refund_parameters)
+            .expect("should combine storage flags");
+
+        // 4000 credits would be refunded for bytes of epoch 20, so they are merged into the base epoch
+        assert_eq!(refund_parameters.calculate_refund(8, 20, 70), 4000);
+        assert_eq!(
+            combined_storage_flags,
+            MultiEpoch(0, BTreeMap::from([(30, 500), (70, 20)]))
+        );
+
+        // the refund is lower by less than the negligible amount after compaction
+        let lost_refund = refund_parameters.calculate_refund(8, 20, 70)
+            - refund_parameters.calculate_refund(8, 0, 70);
+
+        assert!(lost_refund < refund_parameters.negligible_refund_credits);
+
+        // bytes of epoch 30 have fewer than 10 epochs left to distribute their fees
+        let compacted_storage_flags = combined_storage_flags.compact(125, &refund_parameters);
+
+        assert_eq!(
+            compacted_storage_flags,
+            MultiEpoch(0, BTreeMap::from([(70, 20)]))
+        );
+    }
+}
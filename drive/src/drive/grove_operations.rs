@@ -51,12 +51,17 @@ use crate::drive::object_size_info::PathKeyElementInfo::{
 use crate::drive::object_size_info::PathKeyInfo::{
     PathFixedSizeKey, PathFixedSizeKeyRef, PathKey, PathKeyRef, PathKeySize,
 };
-use crate::drive::object_size_info::{DriveKeyInfo, KeyValueInfo, PathKeyElementInfo, PathKeyInfo};
+use crate::drive::object_size_info::{
+    DeletionCostEstimation, DriveKeyInfo, KeyValueInfo, PathKeyElementInfo, PathKeyInfo,
+};
 use crate::drive::Drive;
 use crate::error::drive::DriveError;
 use crate::error::Error;
 use crate::fee::op::DriveOperation::{CalculatedCostOperation, CostCalculationQueryOperation};
 use crate::fee::op::{DriveOperation, SizesOfQueryOperation};
+use crate::fee::refunds::StorageRefundParameters;
+use dpp::state_transition::fee::fee_schedule::FeeSchedule;
+use dpp::version::INITIAL_PROTOCOL_VERSION;
 use grovedb::operations::delete::DeleteOptions;
 use grovedb::operations::insert::InsertOptions;
 use grovedb::query_result_type::{QueryResultElements, QueryResultType};
@@ -466,13 +471,11 @@ impl Drive {
                 .delete(path_iter, key, Some(options), transaction);
             push_drive_operation_result(cost_context, drive_operations)
         } else {
-            // TODO this is wrong
-            drive_operations.push(DriveOperation::for_delete_path_key_value_size(
-                path,
-                key.len() as u16,
-                0,
-                1,
-            ));
+            let path_iter: Vec<&[u8]> = path.iter().map(|x| x.as_slice()).collect();
+            let worst_case_cost =
+                self.grove
+                    .worst_case_deletion_cost(path_iter, key, MAX_ELEMENT_SIZE);
+            drive_operations.push(CalculatedCostOperation(worst_case_cost));
             Ok(())
         }
     }
@@ -863,12 +866,14 @@ impl Drive {
     }
 
     /// Pushes a "delete element" operation to `drive_operations`.
+    /// If apply is false, the deletion cost is estimated for an element of the max value size
     pub(crate) fn batch_delete<'a, 'c, P>(
         &'a self,
         path: P,
         key: &'c [u8],
         only_delete_tree_if_empty: bool,
         apply: bool,
+        max_value_size: Option<u32>,
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<(), Error>
@@ -902,10 +907,7 @@ impl Drive {
             let worst_case_cost = self.grove.worst_case_deletion_cost(
                 path,
                 key,
-                MAX_ELEMENT_SIZE, // only_delete_tree_if_empty,
-                                  // true,
-                                  // &current_batch_operations,
-                                  // transaction,
+                max_value_size.unwrap_or(MAX_ELEMENT_SIZE),
             );
 
             let cost_context = Ok(worst_case_cost).wrap_with_cost(OperationCost::default());
@@ -919,12 +921,15 @@ impl Drive {
     }
 
     /// Pushes a "delete up tree while empty" operation to `drive_operations`.
+    /// If apply is false, deletions of the element and, depending on the estimation,
+    /// of the trees above it are pushed as worst case operations
     pub(crate) fn batch_delete_up_tree_while_empty<'a, 'c>(
         &'a self,
         path: Vec<DriveKeyInfo>,
         key: &'c [u8],
         stop_path_height: Option<u16>,
         apply: bool,
+        estimation: DeletionCostEstimation,
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<(), Error> {
@@ -962,15 +967,36 @@ impl Drive {
                     .for_each(|op| drive_operations.push(DriveOperation::GroveOperation(op)))
             }
         } else {
-            // TODO: Not implemented
-            // let cost_context = GroveDb::worst_case_delete_operations_for_delete_up_tree_while_empty(
-            //     path,
-            //     key,
-            //     stop_path_height,
-            //     true,
-            //     current_batch_operations.operations,
-            //     transaction,
-            // );
+            // Only sizes of keys matter for estimation, so unknown keys are replaced
+            // with keys of the max size
+            let mut path: Vec<KeyInfo> = path
+                .into_iter()
+                .map(|key_info| match key_info {
+                    Key(key) => KeyInfo::KnownKey(key),
+                    KeyRef(key_ref) => KeyInfo::KnownKey(key_ref.to_vec()),
+                    KeySize(key_size) => KeyInfo::KnownKey(vec![0; key_size as usize]),
+                })
+                .collect();
+            let mut key = KeyInfo::KnownKey(key.to_vec());
+            let stop_path_height = stop_path_height.unwrap_or_default() as usize;
+
+            loop {
+                drive_operations.push(DriveOperation::GroveOperation(
+                    GroveDbOp::delete_worst_case_op(KeyInfoPath::from_vec(path.clone()), key),
+                ));
+
+                if estimation == DeletionCostEstimation::AverageCase
+                    || path.len() <= stop_path_height
+                {
+                    break;
+                }
+
+                // the tree holding the deleted element becomes empty and is deleted as well
+                match path.pop() {
+                    Some(tree_key) => key = tree_key,
+                    None => break,
+                }
+            }
         }
 
         Ok(())
@@ -1027,6 +1053,16 @@ impl Drive {
                 }
             }
 
+            // Epochs with a negligible refund are compacted when storage flags are combined
+            let protocol_version = self
+                .get_protocol_version(transaction)?
+                .unwrap_or(INITIAL_PROTOCOL_VERSION);
+
+            let refund_parameters = StorageRefundParameters::from_config(
+                &self.config,
+                FeeSchedule::for_protocol_version(protocol_version),
+            );
+
             let cost_context = self.grove.apply_batch_with_element_flags_update(
                 ops.operations,
                 Some(BatchApplyOptions {
@@ -1055,6 +1091,7 @@ impl Drive {
                                     maybe_old_storage_flags,
                                     new_storage_flags,
                                     cost.added_bytes,
+                                    &refund_parameters,
                                 ).map_err(|_| GroveError::JustInTimeElementFlagsClientError("drive could not combine storage flags (new flags were bigger)"))?;
                             let combined_flags = combined_storage_flags.to_element_flags();
                             // it's possible they got bigger in the same epoch
@@ -1072,6 +1109,7 @@ impl Drive {
                                     maybe_old_storage_flags,
                                     new_storage_flags,
                                     &cost.removed_bytes,
+                                    &refund_parameters,
                                 ).map_err(|_| GroveError::JustInTimeElementFlagsClientError("drive could not combine storage flags (new flags were smaller)"))?;
                             let combined_flags = combined_storage_flags.to_element_flags();
                             // it's possible they got bigger in the same epoch
@@ -0,0 +1,481 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Identity Balance.
+//!
+//! This module defines functions within the Drive struct to credit and debit identity balances.
+//! When a debited amount exceeds the balance, the rest is recorded as a debt item in the
+//! identity subtree. The debt is settled first from the next credits of the identity,
+//! and its state transitions are rejected while the debt exists.
+//!
+
+use dpp::identity::Identity;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::block_info::BlockInfo;
+use crate::drive::flags::StorageFlags;
+use crate::drive::identity::{identity_path_vec, IDENTITY_KEY};
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::identity::IdentityError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+use crate::fee::{calculate_fee, FeeResult};
+
+/// Key of the identity debt item in the identity subtree
+pub const IDENTITY_DEBT_KEY: [u8; 1] = [1];
+
+impl Drive {
+    /// Fetches the debt of the identity, which is zero if the identity has no debt
+    pub fn fetch_identity_debt(
+        &self,
+        identity_id: &[u8],
+        transaction: TransactionArg,
+    ) -> Result<u64, Error> {
+        let element = match self
+            .grove
+            .get(
+                [
+                    Into::<&[u8; 1]>::into(RootTree::Identities).as_slice(),
+                    identity_id,
+                ],
+                &IDENTITY_DEBT_KEY,
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_)) => return Ok(0),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        if let Element::Item(bytes, _) = element {
+            let debt = u64::from_be_bytes(bytes.as_slice().try_into().map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "identity debt must be u64",
+                ))
+            })?);
+
+            Ok(debt)
+        } else {
+            Err(Error::Drive(DriveError::CorruptedIdentityDebtNotItem(
+                "identity debt must be an item",
+            )))
+        }
+    }
+
+    /// Fetches the identity along with its debt
+    pub fn fetch_identity_with_debt(
+        &self,
+        identity_id: &[u8],
+        transaction: TransactionArg,
+    ) -> Result<(Identity, u64), Error> {
+        let (identity, _) = self.fetch_identity(identity_id, transaction)?;
+
+        let debt = self.fetch_identity_debt(identity_id, transaction)?;
+
+        Ok((identity, debt))
+    }
+
+    /// Proves the identity along with its debt
+    pub fn prove_identity(
+        &self,
+        identity_id: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut query = Query::new();
+
+        query.insert_key(IDENTITY_KEY.to_vec());
+        query.insert_key(IDENTITY_DEBT_KEY.to_vec());
+
+        let path_query = PathQuery::new(
+            identity_path_vec(identity_id),
+            SizedQuery::new(query, None, None),
+        );
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+
+    /// Returns an error if the identity has a debt, since identities
+    /// can't apply state transitions until their debt is settled
+    pub fn verify_identity_has_no_debt(
+        &self,
+        identity_id: &[u8],
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        if self.fetch_identity_debt(identity_id, transaction)? > 0 {
+            return Err(Error::Identity(IdentityError::IdentityInDebt(
+                "identity must settle its debt before applying state transitions",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Adds operations to the op batch to credit the identity.
+    /// The debt of the identity is settled first and the rest is added to its balance.
+    /// Returns the amount credited to the balance.
+    pub fn add_add_to_identity_balance_operations(
+        &self,
+        identity_id: &[u8],
+        amount: u64,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<u64, Error> {
+        let (mut identity, storage_flags) = self.fetch_identity(identity_id, transaction)?;
+
+        let previous_debt = self.fetch_identity_debt(identity_id, transaction)?;
+
+        let settled_debt = previous_debt.min(amount);
+
+        let credited_to_balance = amount - settled_debt;
+
+        identity.balance =
+            identity
+                .balance
+                .checked_add(credited_to_balance)
+                .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                    "identity balance overflow",
+                )))?;
+
+        self.add_update_identity_debt_operations(
+            identity_id,
+            previous_debt,
+            previous_debt - settled_debt,
+            storage_flags.as_ref(),
+            batch,
+        );
+
+        self.add_update_identity_operations(identity, storage_flags.as_ref(), batch)?;
+
+        Ok(credited_to_balance)
+    }
+
+    /// Credits the identity with newly minted credits, settling its debt first
+    pub fn add_to_identity_balance(
+        &self,
+        identity_id: &[u8],
+        amount: u64,
+        block_info: BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let mut batch = GroveDbOpBatch::new();
+
+        let credited_to_balance = self.add_add_to_identity_balance_operations(
+            identity_id,
+            amount,
+            transaction,
+            &mut batch,
+        )?;
+
+        self.add_update_credits_ledger_operations(
+            |credits_ledger| credits_ledger.mint(amount, credited_to_balance),
+            transaction,
+            &mut batch,
+        )?;
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_grovedb_operations(apply, transaction, batch, &mut drive_operations)?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+
+    /// Adds operations to the op batch to debit the identity.
+    /// The part of the amount which the balance can't cover is added to the identity debt.
+    /// Returns the amount paid from the balance.
+    pub fn add_remove_from_identity_balance_operations(
+        &self,
+        identity_id: &[u8],
+        amount: u64,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<u64, Error> {
+        let (mut identity, storage_flags) = self.fetch_identity(identity_id, transaction)?;
+
+        let previous_debt = self.fetch_identity_debt(identity_id, transaction)?;
+
+        let paid_from_balance = identity.balance.min(amount);
+
+        identity.balance -= paid_from_balance;
+
+        let debt = previous_debt
+            .checked_add(amount - paid_from_balance)
+            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "identity debt overflow",
+            )))?;
+
+        self.add_update_identity_debt_operations(
+            identity_id,
+            previous_debt,
+            debt,
+            storage_flags.as_ref(),
+            batch,
+        );
+
+        self.add_update_identity_operations(identity, storage_flags.as_ref(), batch)?;
+
+        Ok(paid_from_balance)
+    }
+
+    /// Debits the identity, recording a debt if its balance is insufficient
+    pub fn remove_from_identity_balance(
+        &self,
+        identity_id: &[u8],
+        amount: u64,
+        block_info: BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let mut batch = GroveDbOpBatch::new();
+
+        let paid_from_balance = self.add_remove_from_identity_balance_operations(
+            identity_id,
+            amount,
+            transaction,
+            &mut batch,
+        )?;
+
+        self.add_update_credits_ledger_operations(
+            |credits_ledger| credits_ledger.remove_from_identities(paid_from_balance),
+            transaction,
+            &mut batch,
+        )?;
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_grovedb_operations(apply, transaction, batch, &mut drive_operations)?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+
+    /// Charges the fees of an executed state transition from the identity balance.
+    /// The part of the fees which the balance can't cover is recorded as the identity debt.
+    pub fn charge_identity_fees(
+        &self,
+        identity_id: &[u8],
+        fee_result: &FeeResult,
+        block_info: BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let fees = fee_result
+            .storage_fee
+            .checked_add(fee_result.processing_fee)
+            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "identity fees overflow",
+            )))?;
+
+        self.remove_from_identity_balance(identity_id, fees, block_info, apply, transaction)
+    }
+
+    /// Adds operations to the op batch to store the new debt of the identity
+    /// or to remove the debt item once the debt is settled
+    fn add_update_identity_debt_operations(
+        &self,
+        identity_id: &[u8],
+        previous_debt: u64,
+        debt: u64,
+        storage_flags: Option<&StorageFlags>,
+        batch: &mut GroveDbOpBatch,
+    ) {
+        if debt > 0 {
+            if debt != previous_debt {
+                batch.add_insert(
+                    identity_path_vec(identity_id),
+                    IDENTITY_DEBT_KEY.to_vec(),
+                    Element::Item(
+                        debt.to_be_bytes().to_vec(),
+                        StorageFlags::map_to_some_element_flags(storage_flags),
+                    ),
+                );
+            }
+        } else if previous_debt > 0 {
+            batch.add_delete(identity_path_vec(identity_id), IDENTITY_DEBT_KEY.to_vec());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::block_info::BlockInfo;
+    use crate::error::identity::IdentityError;
+    use crate::error::Error;
+    use crate::fee::FeeResult;
+
+    #[test]
+    fn test_debit_over_balance_creates_debt() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        drive
+            .add_to_identity_balance(&identity.id.buffer, 100, BlockInfo::default(), true, None)
+            .expect("should credit identity");
+
+        drive
+            .remove_from_identity_balance(
+                &identity.id.buffer,
+                150,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("should debit identity");
+
+        let (stored_identity, debt) = drive
+            .fetch_identity_with_debt(&identity.id.buffer, None)
+            .expect("should fetch identity");
+
+        assert_eq!(stored_identity.balance, 0);
+        assert_eq!(debt, 50);
+    }
+
+    #[test]
+    fn test_credit_settles_debt_first() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        drive
+            .remove_from_identity_balance(
+                &identity.id.buffer,
+                100,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("should debit identity");
+
+        drive
+            .add_to_identity_balance(&identity.id.buffer, 60, BlockInfo::default(), true, None)
+            .expect("should credit identity");
+
+        let (stored_identity, debt) = drive
+            .fetch_identity_with_debt(&identity.id.buffer, None)
+            .expect("should fetch identity");
+
+        assert_eq!(stored_identity.balance, 0);
+        assert_eq!(debt, 40);
+
+        drive
+            .add_to_identity_balance(&identity.id.buffer, 100, BlockInfo::default(), true, None)
+            .expect("should credit identity");
+
+        let (stored_identity, debt) = drive
+            .fetch_identity_with_debt(&identity.id.buffer, None)
+            .expect("should fetch identity");
+
+        assert_eq!(stored_identity.balance, 60);
+        assert_eq!(debt, 0);
+    }
+
+    #[test]
+    fn test_charge_identity_fees_over_balance_creates_debt() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        drive
+            .add_to_identity_balance(&identity.id.buffer, 100, BlockInfo::default(), true, None)
+            .expect("should credit identity");
+
+        let fee_result = FeeResult {
+            storage_fee: 80,
+            processing_fee: 30,
+            ..Default::default()
+        };
+
+        drive
+            .charge_identity_fees(
+                &identity.id.buffer,
+                &fee_result,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("should charge fees");
+
+        let (stored_identity, debt) = drive
+            .fetch_identity_with_debt(&identity.id.buffer, None)
+            .expect("should fetch identity");
+
+        assert_eq!(stored_identity.balance, 0);
+        assert_eq!(debt, 10);
+    }
+
+    #[test]
+    fn test_identity_in_debt_is_rejected() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        drive
+            .verify_identity_has_no_debt(&identity.id.buffer, None)
+            .expect("identity should have no debt");
+
+        drive
+            .remove_from_identity_balance(&identity.id.buffer, 10, BlockInfo::default(), true, None)
+            .expect("should debit identity");
+
+        match drive.verify_identity_has_no_debt(&identity.id.buffer, None) {
+            Err(Error::Identity(IdentityError::IdentityInDebt(_))) => {}
+            _ => panic!("expected identity in debt error"),
+        }
+
+        drive
+            .add_to_identity_balance(&identity.id.buffer, 10, BlockInfo::default(), true, None)
+            .expect("should credit identity");
+
+        drive
+            .verify_identity_has_no_debt(&identity.id.buffer, None)
+            .expect("identity should have no debt");
+    }
+
+    #[test]
+    fn test_prove_identity() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        drive
+            .remove_from_identity_balance(&identity.id.buffer, 10, BlockInfo::default(), true, None)
+            .expect("should debit identity");
+
+        let proof = drive
+            .prove_identity(&identity.id.buffer, None)
+            .expect("should prove identity");
+
+        assert!(!proof.is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! This module defines functions within the Drive struct related to applying
+//! identity credit withdrawals.
+//!
+
+use dpp::identity::state_transition::identity_credit_withdrawal_transition::Pooling;
+use grovedb::TransactionArg;
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::block_info::BlockInfo;
+use crate::drive::identity::withdrawal_pooling::WithdrawalRequest;
+use crate::drive::Drive;
+use crate::error::identity::IdentityError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+use crate::fee::{calculate_fee, FeeResult};
+
+impl Drive {
+    /// Applies an identity credit withdrawal in a single batch with the identity balance debit.
+    ///
+    /// A withdrawal which is never pooled increments the withdrawal index counter and enqueues
+    /// its asset unlock transaction in the same batch. Other withdrawals are enqueued as requests,
+    /// since they can be merged with withdrawals of later transitions of the block, so the counter
+    /// is incremented and their asset unlock transaction is enqueued when requests are pooled
+    /// at the end of the block.
+    pub fn apply_identity_credit_withdrawal(
+        &self,
+        request: WithdrawalRequest,
+        block_info: BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        // Larger withdrawals would never fit into the withdrawal limits
+        if request.amount > self.config.withdrawal_credits_per_block_limit {
+            return Err(Error::Identity(IdentityError::WithdrawalAmountAboveLimit(
+                "withdrawal amount exceeds the per block withdrawal limit",
+            )));
+        }
+
+        self.verify_identity_has_no_debt(&request.identity_id, transaction)?;
+
+        let (mut identity, storage_flags) =
+            self.fetch_identity(&request.identity_id, transaction)?;
+
+        identity.balance = identity
+            .balance
+            .checked_sub(request.amount)
+            .ok_or(Error::Identity(IdentityError::IdentityInsufficientBalance(
+                "identity doesn't have enough balance for the withdrawal",
+            )))?;
+
+        let mut batch = GroveDbOpBatch::new();
+
+        if request.pooling == Pooling::Never {
+            self.add_enqueue_unpooled_withdrawal_operations(&request, transaction, &mut batch)?;
+        } else {
+            self.add_enqueue_withdrawal_request_operations(&mut batch, &request)?;
+        }
+
+        self.add_update_identity_operations(identity, storage_flags.as_ref(), &mut batch)?;
+
+        self.add_update_credits_ledger_operations(
+            |credits_ledger| credits_ledger.burn(request.amount),
+            transaction,
+            &mut batch,
+        )?;
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_grovedb_operations(apply, transaction, batch, &mut drive_operations)?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::{setup_drive, setup_drive_with_initial_state_structure};
+    use crate::drive::config::DriveConfig;
+
+    fn create_withdrawal_request(amount: u64, pooling: Pooling) -> WithdrawalRequest {
+        WithdrawalRequest {
+            transition_id: [2; 32],
+            identity_id: [1; 32],
+            amount,
+            core_fee: 1,
+            pooling,
+            output_script: vec![1; 23],
+        }
+    }
+
+    #[test]
+    fn test_apply_identity_credit_withdrawal() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        drive
+            .add_to_identity_balance(
+                &identity.id.buffer,
+                1000,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should add to identity balance");
+
+        let fee_result = drive
+            .apply_identity_credit_withdrawal(
+                create_withdrawal_request(400, Pooling::Standard),
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should apply withdrawal");
+
+        assert!(fee_result.storage_fee > 0);
+
+        let (identity, _) = drive
+            .fetch_identity(&[1; 32], Some(&transaction))
+            .expect("should fetch identity");
+
+        assert_eq!(identity.balance, 600);
+
+        let requests = drive
+            .fetch_withdrawal_requests(Some(&transaction))
+            .expect("should fetch withdrawal requests");
+
+        assert_eq!(
+            requests,
+            vec![create_withdrawal_request(400, Pooling::Standard)]
+        );
+    }
+
+    #[test]
+    fn test_apply_identity_credit_withdrawal_never_pooled() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        drive
+            .add_to_identity_balance(
+                &identity.id.buffer,
+                1000,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should add to identity balance");
+
+        drive
+            .apply_identity_credit_withdrawal(
+                create_withdrawal_request(400, Pooling::Never),
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should apply withdrawal");
+
+        let (identity, _) = drive
+            .fetch_identity(&[1; 32], Some(&transaction))
+            .expect("should fetch identity");
+
+        assert_eq!(identity.balance, 600);
+
+        let requests = drive
+            .fetch_withdrawal_requests(Some(&transaction))
+            .expect("should fetch withdrawal requests");
+
+        assert!(requests.is_empty());
+
+        let latest_index = drive
+            .fetch_latest_withdrawal_transaction_index(Some(&transaction))
+            .expect("should fetch latest index");
+
+        assert_eq!(latest_index, 1);
+
+        let index = drive
+            .fetch_withdrawal_transaction_index_by_transition(&[2; 32], Some(&transaction))
+            .expect("should fetch transition mapping");
+
+        assert_eq!(index, Some(1));
+
+        let withdrawal_transactions = drive
+            .dequeue_withdrawal_transactions(16, 1, 1, Some(&transaction))
+            .expect("should dequeue withdrawal transactions");
+
+        assert_eq!(withdrawal_transactions.len(), 1);
+    }
+
+    #[test]
+    fn test_apply_identity_credit_withdrawal_with_insufficient_balance() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        let result = drive.apply_identity_credit_withdrawal(
+            create_withdrawal_request(400, Pooling::Standard),
+            BlockInfo::default(),
+            true,
+            Some(&transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(IdentityError::IdentityInsufficientBalance(
+                _
+            )))
+        ));
+
+        let requests = drive
+            .fetch_withdrawal_requests(Some(&transaction))
+            .expect("should fetch withdrawal requests");
+
+        assert!(requests.is_empty());
+    }
+
+    #[test]
+    fn test_apply_identity_credit_withdrawal_above_per_block_limit() {
+        let drive = setup_drive(Some(DriveConfig {
+            withdrawal_credits_per_block_limit: 300,
+            ..Default::default()
+        }));
+
+        drive
+            .create_initial_state_structure(None)
+            .expect("should create root tree successfully");
+
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        drive
+            .add_to_identity_balance(
+                &identity.id.buffer,
+                1000,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should add to identity balance");
+
+        let result = drive.apply_identity_credit_withdrawal(
+            create_withdrawal_request(400, Pooling::Standard),
+            BlockInfo::default(),
+            true,
+            Some(&transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(IdentityError::WithdrawalAmountAboveLimit(
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_apply_identity_credit_withdrawal_with_debt() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        drive
+            .remove_from_identity_balance(
+                &[1; 32],
+                100,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should debit identity");
+
+        let result = drive.apply_identity_credit_withdrawal(
+            create_withdrawal_request(400, Pooling::Standard),
+            BlockInfo::default(),
+            true,
+            Some(&transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(IdentityError::IdentityInDebt(_)))
+        ));
+    }
+}
@@ -32,6 +32,8 @@
 //! fetching identities from the subtree.
 //!
 
+use std::cmp::Ordering;
+
 use dpp::identity::Identity;
 use grovedb::query_result_type::QueryResultType::QueryElementResultType;
 use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
@@ -46,9 +48,21 @@ use crate::error::Error;
 use crate::fee::op::DriveOperation;
 use crate::fee::{calculate_fee, FeeResult};
 
+pub mod balance;
+pub mod credit_withdrawal;
+pub mod storage_usage;
+pub mod withdrawal_limits;
+pub mod withdrawal_pooling;
 pub mod withdrawal_queue;
+pub mod withdrawal_queue_query;
+pub mod withdrawal_status;
+
+pub(crate) const IDENTITY_KEY: [u8; 1] = [0];
 
-const IDENTITY_KEY: [u8; 1] = [0];
+/// Returns the path to the subtree of the identity
+pub(crate) fn identity_path_vec(identity_id: &[u8]) -> Vec<Vec<u8>> {
+    vec![vec![RootTree::Identities as u8], identity_id.to_vec()]
+}
 
 impl Drive {
     /// Adds operations to the op batch to insert a new identity in the `Identities` subtree
@@ -91,7 +105,34 @@ impl Drive {
         Ok(())
     }
 
+    /// Adds an operation to the op batch to replace the serialized identity
+    /// keeping the other items of its subtree.
+    pub fn add_update_identity_operations(
+        &self,
+        identity: Identity,
+        storage_flags: Option<&StorageFlags>,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        let identity_bytes = identity.to_buffer().map_err(|_| {
+            Error::Identity(IdentityError::IdentitySerialization(
+                "failed to serialize identity to CBOR",
+            ))
+        })?;
+
+        batch.add_insert(
+            identity_path_vec(&identity.id.buffer),
+            IDENTITY_KEY.to_vec(),
+            Element::Item(
+                identity_bytes,
+                StorageFlags::map_to_some_element_flags(storage_flags),
+            ),
+        );
+
+        Ok(())
+    }
+
     /// Inserts a new identity to the `Identities` subtree.
+    /// Its balance is minted by the asset lock of the identity.
     pub fn insert_identity(
         &self,
         identity: Identity,
@@ -100,10 +141,59 @@ impl Drive {
         storage_flags: Option<&StorageFlags>,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
+        if self.has_identity(&identity.id.buffer, transaction)? {
+            return Err(Error::Identity(IdentityError::IdentityAlreadyExists(
+                "use update_identity to change an existing identity",
+            )));
+        }
+
         let mut batch = GroveDbOpBatch::new();
 
+        let balance = identity.balance;
+
         self.add_insert_identity_operations(identity, storage_flags, &mut batch)?;
 
+        if balance > 0 {
+            // the initial balance is minted by the asset lock of the identity
+            self.add_update_credits_ledger_operations(
+                |credits_ledger| credits_ledger.mint(balance, balance),
+                transaction,
+                &mut batch,
+            )?;
+        }
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_grovedb_operations(apply, transaction, batch, &mut drive_operations)?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+
+    /// Replaces an existing identity keeping its balance, debt and storage usage.
+    /// The balance must be changed with `add_to_identity_balance` and
+    /// `remove_from_identity_balance` so the credits ledger stays balanced.
+    pub fn update_identity(
+        &self,
+        identity: Identity,
+        block_info: BlockInfo,
+        apply: bool,
+        storage_flags: Option<&StorageFlags>,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let (stored_identity, _) = self.fetch_identity(&identity.id.buffer, transaction)?;
+
+        if stored_identity.balance != identity.balance {
+            return Err(Error::Identity(
+                IdentityError::InvalidIdentityBalanceChange(
+                    "identity balance must be changed with balance operations",
+                ),
+            ));
+        }
+
+        let mut batch = GroveDbOpBatch::new();
+
+        self.add_update_identity_operations(identity, storage_flags, &mut batch)?;
+
         let mut drive_operations: Vec<DriveOperation> = vec![];
 
         self.apply_batch_grovedb_operations(apply, transaction, batch, &mut drive_operations)?;
@@ -111,6 +201,97 @@ impl Drive {
         calculate_fee(None, Some(drive_operations), &block_info.epoch)
     }
 
+    /// Inserts a new identity or replaces an existing one, as the node API always did.
+    /// A balance change of an existing identity is recorded in the credits ledger
+    /// as a top up or a debit, so credits are minted only for the change.
+    pub fn insert_or_update_identity(
+        &self,
+        mut identity: Identity,
+        block_info: BlockInfo,
+        apply: bool,
+        storage_flags: Option<&StorageFlags>,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        if !self.has_identity(&identity.id.buffer, transaction)? {
+            return self.insert_identity(identity, block_info, apply, storage_flags, transaction);
+        }
+
+        // The update and the balance change are applied in one transaction to keep them atomic
+        if apply && transaction.is_none() {
+            let transaction = self.grove.start_transaction();
+
+            let fee_result = self.insert_or_update_identity(
+                identity,
+                block_info,
+                apply,
+                storage_flags,
+                Some(&transaction),
+            )?;
+
+            self.commit_transaction(transaction)?;
+
+            return Ok(fee_result);
+        }
+
+        let identity_id = identity.id.buffer;
+
+        let (stored_identity, _) = self.fetch_identity(&identity_id, transaction)?;
+
+        let balance = identity.balance;
+
+        identity.balance = stored_identity.balance;
+
+        let mut fee_result = self.update_identity(
+            identity,
+            block_info.clone(),
+            apply,
+            storage_flags,
+            transaction,
+        )?;
+
+        let balance_fee_result = match balance.cmp(&stored_identity.balance) {
+            Ordering::Greater => Some(self.add_to_identity_balance(
+                &identity_id,
+                balance - stored_identity.balance,
+                block_info,
+                apply,
+                transaction,
+            )?),
+            Ordering::Less => Some(self.remove_from_identity_balance(
+                &identity_id,
+                stored_identity.balance - balance,
+                block_info,
+                apply,
+                transaction,
+            )?),
+            Ordering::Equal => None,
+        };
+
+        if let Some(balance_fee_result) = balance_fee_result {
+            fee_result.checked_add_assign(balance_fee_result)?;
+        }
+
+        Ok(fee_result)
+    }
+
+    /// Returns true if the identity subtree exists
+    pub fn has_identity(&self, id: &[u8], transaction: TransactionArg) -> Result<bool, Error> {
+        self.grove
+            .get(
+                [Into::<&[u8; 1]>::into(RootTree::Identities).as_slice()],
+                id,
+                transaction,
+            )
+            .unwrap()
+            .map(|_| true)
+            .or_else(|e| match e {
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_) => Ok(false),
+                _ => Err(Error::GroveDB(e)),
+            })
+    }
+
     /// Given an identity, fetches the identity with its flags from storage.
     pub fn fetch_identity(
         &self,
@@ -213,9 +394,12 @@ impl Drive {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::helpers::setup::setup_drive;
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::{setup_drive, setup_drive_with_initial_state_structure};
     use crate::drive::block_info::BlockInfo;
     use crate::drive::flags::StorageFlags;
+    use crate::error::identity::IdentityError;
+    use crate::error::Error;
     use dpp::identity::Identity;
 
     #[test]
@@ -252,4 +436,110 @@ mod tests {
             identity.to_buffer().expect("should serialize")
         );
     }
+
+    #[test]
+    fn test_update_identity_does_not_mint_credits() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let mut identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        drive
+            .add_to_identity_balance(
+                &identity.id.buffer,
+                100,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("should add to balance");
+
+        let credits_ledger = drive
+            .fetch_credits_ledger(Some(&transaction))
+            .expect("should fetch credits ledger");
+
+        match drive.insert_identity(
+            identity.clone(),
+            BlockInfo::default(),
+            true,
+            StorageFlags::optional_default_as_ref(),
+            Some(&transaction),
+        ) {
+            Err(Error::Identity(IdentityError::IdentityAlreadyExists(_))) => {}
+            _ => panic!("should not insert an existing identity"),
+        }
+
+        identity.balance = 100;
+        identity.revision += 1;
+
+        drive
+            .update_identity(
+                identity.clone(),
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&transaction),
+            )
+            .expect("should update identity");
+
+        identity.balance = 1000;
+
+        match drive.update_identity(
+            identity,
+            BlockInfo::default(),
+            true,
+            StorageFlags::optional_default_as_ref(),
+            Some(&transaction),
+        ) {
+            Err(Error::Identity(IdentityError::InvalidIdentityBalanceChange(_))) => {}
+            _ => panic!("should not change the balance with an identity update"),
+        }
+
+        assert_eq!(
+            drive
+                .fetch_credits_ledger(Some(&transaction))
+                .expect("should fetch credits ledger"),
+            credits_ledger
+        );
+    }
+
+    #[test]
+    fn test_insert_or_update_identity_records_balance_change() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let mut identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        identity.balance = 150;
+        identity.revision += 1;
+
+        drive
+            .insert_or_update_identity(
+                identity.clone(),
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&transaction),
+            )
+            .expect("should update identity");
+
+        let (stored_identity, _) = drive
+            .fetch_identity(&identity.id.buffer, Some(&transaction))
+            .expect("should fetch identity");
+
+        assert_eq!(stored_identity.balance, 150);
+        assert_eq!(stored_identity.revision, identity.revision);
+
+        let credits_ledger = drive
+            .fetch_credits_ledger(Some(&transaction))
+            .expect("should fetch credits ledger");
+
+        assert_eq!(credits_ledger.total_minted, 150);
+
+        drive
+            .verify_credit_conservation(Some(&transaction))
+            .expect("credits should be conserved");
+    }
 }
@@ -0,0 +1,810 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Identity Storage Usage.
+//!
+//! This module defines functions within the Drive struct to track how many bytes
+//! identities pay storage fees for. The usage is stored as an item in the identity subtree
+//! and holds the total amount of bytes along with bytes per epoch they were paid in,
+//! so identities can see their storage footprint and expected refunds.
+//!
+
+use std::collections::BTreeMap;
+
+use bincode::Options;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+use serde::{Deserialize, Serialize};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::identity::identity_path_vec;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::fee::FeeError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+use crate::fee::{calculate_fee, FeeResult};
+use crate::fee_pools::epochs::Epoch;
+
+/// Key of the identity storage usage item in the identity subtree
+pub const IDENTITY_STORAGE_USAGE_KEY: [u8; 1] = [2];
+
+/// Bytes an identity pays storage fees for
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityStorageUsage {
+    /// Total amount of bytes
+    pub total_bytes: u64,
+    /// Amount of bytes by the index of the epoch they were paid in
+    pub bytes_per_epoch: BTreeMap<u16, u64>,
+}
+
+impl IdentityStorageUsage {
+    /// Adds bytes paid in the epoch
+    pub fn add_bytes(&mut self, epoch_index: u16, bytes: u64) -> Result<(), Error> {
+        self.total_bytes =
+            self.total_bytes
+                .checked_add(bytes)
+                .ok_or(Error::Fee(FeeError::Overflow(
+                    "storage usage overflow error",
+                )))?;
+
+        let epoch_bytes = self.bytes_per_epoch.entry(epoch_index).or_default();
+
+        *epoch_bytes = epoch_bytes
+            .checked_add(bytes)
+            .ok_or(Error::Fee(FeeError::Overflow(
+                "storage usage overflow error",
+            )))?;
+
+        Ok(())
+    }
+
+    /// Removes bytes paid in the epoch.
+    ///
+    /// Storage flags merge epochs with a negligible refund into their base epoch, so bytes
+    /// paid in later epochs can be removed as bytes of the base epoch. Bytes exceeding
+    /// the usage of the epoch are removed from the following epochs, the same way
+    /// the flags were compacted.
+    pub fn remove_bytes(&mut self, epoch_index: u16, bytes: u64) {
+        // Bytes are refunded to owners of elements, which are not necessarily
+        // identities paid for them, so usage is never decreased below zero
+        let mut remaining_bytes = bytes;
+
+        for (_, epoch_bytes) in self.bytes_per_epoch.range_mut(epoch_index..) {
+            if remaining_bytes == 0 {
+                break;
+            }
+
+            let removed_bytes = remaining_bytes.min(*epoch_bytes);
+
+            *epoch_bytes -= removed_bytes;
+
+            remaining_bytes -= removed_bytes;
+        }
+
+        self.total_bytes = self.total_bytes.saturating_sub(bytes - remaining_bytes);
+
+        self.bytes_per_epoch
+            .retain(|_, epoch_bytes| *epoch_bytes > 0);
+    }
+
+    /// Serializes the storage usage
+    pub fn serialize(&self) -> Result<Vec<u8>, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialize(self)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to serialize identity storage usage",
+                ))
+            })
+    }
+
+    /// Deserializes the storage usage
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .deserialize(bytes)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to deserialize identity storage usage",
+                ))
+            })
+    }
+}
+
+impl Drive {
+    /// Fetches bytes the identity pays storage fees for
+    pub fn fetch_identity_storage_usage(
+        &self,
+        identity_id: &[u8],
+        transaction: TransactionArg,
+    ) -> Result<IdentityStorageUsage, Error> {
+        let element = match self
+            .grove
+            .get(
+                [
+                    Into::<&[u8; 1]>::into(RootTree::Identities).as_slice(),
+                    identity_id,
+                ],
+                &IDENTITY_STORAGE_USAGE_KEY,
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_),
+            ) => return Ok(IdentityStorageUsage::default()),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        if let Element::Item(bytes, _) = element {
+            IdentityStorageUsage::deserialize(&bytes)
+        } else {
+            Err(Error::Drive(
+                DriveError::CorruptedIdentityStorageUsageNotItem(
+                    "identity storage usage must be an item",
+                ),
+            ))
+        }
+    }
+
+    /// Proves bytes the identity pays storage fees for
+    pub fn prove_identity_storage_usage(
+        &self,
+        identity_id: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut query = Query::new();
+
+        query.insert_key(IDENTITY_STORAGE_USAGE_KEY.to_vec());
+
+        let path_query = PathQuery::new(
+            identity_path_vec(identity_id),
+            SizedQuery::new(query, None, None),
+        );
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+
+    /// Calculates the fee of applied operations and updates storage usage of identities
+    /// with bytes added by the owner and bytes removed from identities.
+    ///
+    /// Added and removed bytes are known only once the operations are applied, so storage usage
+    /// is updated right after them in the same transaction. The cost of the update is added
+    /// to the returned fee, so it's charged along with the operations.
+    pub(crate) fn calculate_fee_and_update_identities_storage_usage(
+        &self,
+        owner_id: Option<[u8; 32]>,
+        drive_operations: Vec<DriveOperation>,
+        epoch: &Epoch,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let mut fees = calculate_fee(None, Some(drive_operations), epoch)?;
+
+        let added_bytes_by_owners: BTreeMap<[u8; 32], u64> = owner_id
+            .map(|owner_id| (owner_id, fees.added_bytes as u64))
+            .into_iter()
+            .collect();
+
+        let mut storage_usage_operations: Vec<DriveOperation> = vec![];
+
+        self.update_identities_storage_usage_and_add_to_operations(
+            &added_bytes_by_owners,
+            &fees,
+            epoch,
+            apply,
+            transaction,
+            &mut storage_usage_operations,
+        )?;
+
+        fees.checked_add_assign(calculate_fee(None, Some(storage_usage_operations), epoch)?)?;
+
+        Ok(fees)
+    }
+
+    /// Updates storage usage of identities with bytes added by their owners
+    /// and bytes removed from identities in the fee result.
+    /// Storage usage is tracked only for existing identities.
+    ///
+    /// On dry run the update is estimated for every owner, even if the owner adds no bytes
+    /// or isn't created yet, so the estimated fee is never lower than the applied one.
+    pub(crate) fn update_identities_storage_usage_and_add_to_operations(
+        &self,
+        added_bytes_by_owners: &BTreeMap<[u8; 32], u64>,
+        fee_result: &FeeResult,
+        epoch: &Epoch,
+        apply: bool,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        let mut storage_usages: BTreeMap<[u8; 32], IdentityStorageUsage> = BTreeMap::new();
+
+        for (owner_id, added_bytes) in added_bytes_by_owners {
+            if apply && (*added_bytes == 0 || !self.has_identity(owner_id, transaction)?) {
+                continue;
+            }
+
+            let mut storage_usage = self.fetch_identity_storage_usage(owner_id, transaction)?;
+
+            storage_usage.add_bytes(epoch.index, *added_bytes)?;
+
+            storage_usages.insert(*owner_id, storage_usage);
+        }
+
+        for (identity_id, removed_bytes_per_epoch) in
+            fee_result.removed_bytes_from_identities.iter()
+        {
+            let storage_usage = match storage_usages.get_mut(identity_id) {
+                Some(storage_usage) => storage_usage,
+                None => {
+                    if !self.has_identity(identity_id, transaction)? {
+                        continue;
+                    }
+
+                    let storage_usage =
+                        self.fetch_identity_storage_usage(identity_id, transaction)?;
+
+                    storage_usages.entry(*identity_id).or_insert(storage_usage)
+                }
+            };
+
+            for (epoch_index, removed_bytes) in removed_bytes_per_epoch.iter() {
+                let epoch_index = u16::try_from(*epoch_index).map_err(|_| {
+                    Error::Drive(DriveError::CorruptedElementFlags(
+                        "epoch index must fit in a u16",
+                    ))
+                })?;
+
+                storage_usage.remove_bytes(epoch_index, *removed_bytes as u64);
+            }
+        }
+
+        if storage_usages.is_empty() {
+            return Ok(());
+        }
+
+        let mut batch = GroveDbOpBatch::new();
+
+        for (identity_id, storage_usage) in storage_usages {
+            batch.add_insert(
+                identity_path_vec(&identity_id),
+                IDENTITY_STORAGE_USAGE_KEY.to_vec(),
+                Element::Item(storage_usage.serialize()?, None),
+            );
+        }
+
+        self.apply_batch_grovedb_operations(apply, transaction, batch, drive_operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::{json_document_to_cbor, setup_contract, value_to_cbor};
+    use crate::contract::document::Document;
+    use crate::contract::Contract;
+    use crate::drive::batch::ContractOperationType::ApplyContractWithSerialization;
+    use crate::drive::batch::DocumentOperationType::AddSerializedDocumentForContract;
+    use crate::drive::batch::DriveOperationType::{ContractOperation, DocumentOperation};
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::flags::StorageFlags;
+    use crate::drive::object_size_info::DocumentAndContractInfo;
+    use crate::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
+    use crate::drive::{defaults, Drive};
+    use dpp::data_contract::extra::DriveContractExt;
+    use serde_json::json;
+
+    #[test]
+    fn test_storage_usage_serialization() {
+        let mut storage_usage = IdentityStorageUsage::default();
+
+        storage_usage.add_bytes(0, 100).expect("should add bytes");
+        storage_usage.add_bytes(3, 50).expect("should add bytes");
+
+        storage_usage.remove_bytes(0, 30);
+
+        assert_eq!(storage_usage.total_bytes, 120);
+
+        let deserialized_storage_usage = IdentityStorageUsage::deserialize(
+            &storage_usage
+                .serialize()
+                .expect("should serialize storage usage"),
+        )
+        .expect("should deserialize storage usage");
+
+        assert_eq!(deserialized_storage_usage, storage_usage);
+    }
+
+    #[test]
+    fn test_storage_usage_removes_bytes_of_compacted_epochs() {
+        let mut storage_usage = IdentityStorageUsage::default();
+
+        storage_usage.add_bytes(0, 100).expect("should add bytes");
+        storage_usage.add_bytes(3, 20).expect("should add bytes");
+        storage_usage.add_bytes(5, 10).expect("should add bytes");
+
+        // bytes of epoch 3 were merged into the base epoch of the flags
+        storage_usage.remove_bytes(0, 120);
+
+        assert_eq!(storage_usage.total_bytes, 10);
+        assert_eq!(storage_usage.bytes_per_epoch, BTreeMap::from([(5, 10)]));
+
+        // bytes of earlier epochs are kept
+        storage_usage.remove_bytes(6, 10);
+
+        assert_eq!(storage_usage.total_bytes, 10);
+
+        storage_usage.remove_bytes(5, 10);
+
+        assert_eq!(storage_usage, IdentityStorageUsage::default());
+    }
+
+    #[test]
+    fn test_storage_usage_is_updated_on_document_insert_and_delete() {
+        let drive: Drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        let owner_id = identity.id.buffer;
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            None,
+            None,
+        );
+
+        let document_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/family/person0.json",
+            Some(1),
+        );
+
+        let document = Document::from_cbor(&document_cbor, None, Some(owner_id))
+            .expect("expected to deserialize the document");
+
+        let document_type = contract
+            .document_type_for_name("person")
+            .expect("expected to get document type");
+
+        let storage_flags = Some(StorageFlags::SingleEpochOwned(0, owner_id));
+
+        let document_and_contract_info = || DocumentAndContractInfo {
+            document_info: DocumentRefAndSerialization((
+                &document,
+                &document_cbor,
+                storage_flags.as_ref(),
+            )),
+            contract: &contract,
+            document_type,
+            owner_id: Some(owner_id),
+        };
+
+        // The same document inserted by an owner without identity isn't tracked
+        let drive_without_identity: Drive = setup_drive_with_initial_state_structure();
+
+        setup_contract(
+            &drive_without_identity,
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            None,
+            None,
+        );
+
+        let document_bytes = drive_without_identity
+            .add_document_for_contract(
+                document_and_contract_info(),
+                false,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("expected to insert a document successfully")
+            .added_bytes as u64;
+
+        let insertion_fees = drive
+            .add_document_for_contract(
+                document_and_contract_info(),
+                false,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("expected to insert a document successfully");
+
+        let storage_usage = drive
+            .fetch_identity_storage_usage(&owner_id, None)
+            .expect("should fetch storage usage");
+
+        assert_eq!(storage_usage.total_bytes, document_bytes);
+        assert_eq!(storage_usage.bytes_per_epoch.get(&0), Some(&document_bytes));
+
+        // the storage usage update is charged along with the document
+        assert!(insertion_fees.added_bytes as u64 > document_bytes);
+
+        let proof = drive
+            .prove_identity_storage_usage(&owner_id, None)
+            .expect("should prove storage usage");
+
+        assert!(!proof.is_empty());
+
+        let deletion_fees = drive
+            .delete_document_for_contract(
+                document.id,
+                &contract,
+                "person",
+                Some(owner_id),
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("expected to delete a document successfully");
+
+        let removed_bytes = deletion_fees
+            .removed_bytes_from_identities
+            .get(&owner_id)
+            .and_then(|removed_bytes_per_epoch| removed_bytes_per_epoch.get(0))
+            .copied()
+            .unwrap_or_default();
+
+        let storage_usage = drive
+            .fetch_identity_storage_usage(&owner_id, None)
+            .expect("should fetch storage usage");
+
+        assert_eq!(
+            storage_usage.total_bytes,
+            document_bytes - removed_bytes as u64
+        );
+    }
+
+    #[test]
+    fn test_storage_usage_is_updated_on_apply_drive_operations() {
+        let drive: Drive = setup_drive_with_initial_state_structure();
+
+        let identity = create_test_identity(&drive, [1; 32], None);
+
+        let owner_id = identity.id.buffer;
+
+        let contract_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            Some(1),
+        );
+
+        let mut contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("contract should be deserialized");
+
+        contract.owner_id = identity.id.clone();
+
+        let serialized_contract =
+            DriveContractExt::to_cbor(&contract).expect("contract should be serialized");
+
+        let storage_flags = StorageFlags::SingleEpochOwned(0, owner_id);
+
+        drive
+            .apply_drive_operations(
+                vec![ContractOperation(ApplyContractWithSerialization {
+                    contract: &contract,
+                    serialized_contract,
+                    storage_flags: Some(&storage_flags),
+                })],
+                true,
+                &BlockInfo::default(),
+                None,
+            )
+            .expect("expected to apply contract");
+
+        let storage_usage = drive
+            .fetch_identity_storage_usage(&owner_id, None)
+            .expect("should fetch storage usage");
+
+        assert!(storage_usage.total_bytes > 0);
+        assert_eq!(
+            storage_usage.bytes_per_epoch.get(&0),
+            Some(&storage_usage.total_bytes)
+        );
+    }
+
+    #[test]
+    fn test_storage_usage_is_attributed_per_owner_on_apply_drive_operations() {
+        let drive: Drive = setup_drive_with_initial_state_structure();
+
+        let first_owner_id = create_test_identity(&drive, [1; 32], None).id.buffer;
+        let second_owner_id = create_test_identity(&drive, [2; 32], None).id.buffer;
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            None,
+            None,
+        );
+
+        let first_document_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/family/person0.json",
+            Some(1),
+        );
+
+        let second_document_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/family/person1.json",
+            Some(1),
+        );
+
+        let first_storage_flags = StorageFlags::SingleEpochOwned(0, first_owner_id);
+        let second_storage_flags = StorageFlags::SingleEpochOwned(0, second_owner_id);
+
+        let fee_result = drive
+            .apply_drive_operations(
+                vec![
+                    DocumentOperation(AddSerializedDocumentForContract {
+                        serialized_document: &first_document_cbor,
+                        contract: &contract,
+                        document_type_name: "person",
+                        owner_id: Some(first_owner_id),
+                        override_document: false,
+                        storage_flags: Some(&first_storage_flags),
+                    }),
+                    DocumentOperation(AddSerializedDocumentForContract {
+                        serialized_document: &second_document_cbor,
+                        contract: &contract,
+                        document_type_name: "person",
+                        owner_id: Some(second_owner_id),
+                        override_document: false,
+                        storage_flags: Some(&second_storage_flags),
+                    }),
+                ],
+                true,
+                &BlockInfo::default(),
+                None,
+            )
+            .expect("expected to apply documents");
+
+        let first_storage_usage = drive
+            .fetch_identity_storage_usage(&first_owner_id, None)
+            .expect("should fetch storage usage");
+
+        let second_storage_usage = drive
+            .fetch_identity_storage_usage(&second_owner_id, None)
+            .expect("should fetch storage usage");
+
+        assert!(first_storage_usage.total_bytes > 0);
+        assert!(second_storage_usage.total_bytes > 0);
+
+        // the storage usage update is charged along with the documents
+        assert!(
+            (fee_result.added_bytes as u64)
+                > first_storage_usage.total_bytes + second_storage_usage.total_bytes
+        );
+    }
+
+    #[test]
+    fn test_estimated_fee_includes_storage_usage_update() {
+        let drive: Drive = setup_drive_with_initial_state_structure();
+
+        let owner_id = create_test_identity(&drive, [1; 32], None).id.buffer;
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            None,
+            None,
+        );
+
+        let document_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/family/person0.json",
+            Some(1),
+        );
+
+        let document = Document::from_cbor(&document_cbor, None, Some(owner_id))
+            .expect("expected to deserialize the document");
+
+        let storage_flags = Some(StorageFlags::SingleEpochOwned(0, owner_id));
+
+        let add_document = |owner_id: Option<[u8; 32]>, apply: bool| {
+            drive
+                .add_document_for_contract(
+                    DocumentAndContractInfo {
+                        document_info: DocumentRefAndSerialization((
+                            &document,
+                            &document_cbor,
+                            storage_flags.as_ref(),
+                        )),
+                        contract: &contract,
+                        document_type: contract
+                            .document_type_for_name("person")
+                            .expect("expected to get document type"),
+                        owner_id,
+                    },
+                    false,
+                    BlockInfo::default(),
+                    apply,
+                    None,
+                )
+                .expect("expected to add a document")
+        };
+
+        let estimated_fee_result_without_owner = add_document(None, false);
+        let estimated_fee_result = add_document(Some(owner_id), false);
+
+        assert!(
+            estimated_fee_result.processing_fee > estimated_fee_result_without_owner.processing_fee
+        );
+
+        let applied_fee_result = add_document(Some(owner_id), true);
+
+        assert!(estimated_fee_result.processing_fee >= applied_fee_result.processing_fee);
+        assert!(
+            estimated_fee_result.storage_fee + estimated_fee_result.processing_fee
+                >= applied_fee_result.storage_fee + applied_fee_result.processing_fee
+        );
+
+        let estimated_deletion_fee_result = drive
+            .delete_document_for_contract(
+                document.id,
+                &contract,
+                "person",
+                Some(owner_id),
+                BlockInfo::default(),
+                false,
+                None,
+            )
+            .expect("expected to estimate the document deletion");
+
+        let applied_deletion_fee_result = drive
+            .delete_document_for_contract(
+                document.id,
+                &contract,
+                "person",
+                Some(owner_id),
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("expected to delete the document");
+
+        assert!(
+            estimated_deletion_fee_result.processing_fee
+                >= applied_deletion_fee_result.processing_fee
+        );
+    }
+
+    #[test]
+    fn test_storage_usage_is_zero_after_document_is_updated_in_later_epoch_and_deleted() {
+        let drive: Drive = setup_drive_with_initial_state_structure();
+
+        let owner_id = create_test_identity(&drive, [1; 32], None).id.buffer;
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            None,
+            None,
+        );
+
+        let document_type = contract
+            .document_type_for_name("person")
+            .expect("expected to get document type");
+
+        let document_id = [5u8; 32];
+
+        let person = |last_name: &str| {
+            value_to_cbor(
+                json!({
+                    "$id": document_id,
+                    "$ownerId": owner_id,
+                    "firstName": "Samuel",
+                    "middleName": "Abraham",
+                    "lastName": last_name,
+                    "age": 35,
+                }),
+                Some(defaults::PROTOCOL_VERSION),
+            )
+        };
+
+        let document_cbor = person("Westrich");
+
+        let document = Document::from_cbor(&document_cbor, None, Some(owner_id))
+            .expect("expected to deserialize the document");
+
+        let storage_flags = StorageFlags::SingleEpochOwned(0, owner_id);
+
+        drive
+            .add_document_for_contract(
+                DocumentAndContractInfo {
+                    document_info: DocumentRefAndSerialization((
+                        &document,
+                        &document_cbor,
+                        Some(&storage_flags),
+                    )),
+                    contract: &contract,
+                    document_type,
+                    owner_id: Some(owner_id),
+                },
+                false,
+                BlockInfo::default(),
+                true,
+                None,
+            )
+            .expect("expected to insert a document");
+
+        // the document grows in later epochs
+        for (epoch_index, last_name) in [(1, "Westrich-Abraham"), (3, "Westrich-Abraham-Samuel")] {
+            let updated_document_cbor = person(last_name);
+
+            let updated_document =
+                Document::from_cbor(&updated_document_cbor, None, Some(owner_id))
+                    .expect("expected to deserialize the document");
+
+            drive
+                .update_document_for_contract(
+                    &updated_document,
+                    &updated_document_cbor,
+                    &contract,
+                    "person",
+                    Some(owner_id),
+                    BlockInfo::default_with_epoch(Epoch::new(epoch_index)),
+                    true,
+                    Some(&StorageFlags::SingleEpochOwned(epoch_index, owner_id)),
+                    None,
+                )
+                .expect("expected to update a document");
+        }
+
+        let storage_usage = drive
+            .fetch_identity_storage_usage(&owner_id, None)
+            .expect("should fetch storage usage");
+
+        assert_eq!(
+            storage_usage.bytes_per_epoch.values().sum::<u64>(),
+            storage_usage.total_bytes
+        );
+
+        drive
+            .delete_document_for_contract(
+                document.id,
+                &contract,
+                "person",
+                Some(owner_id),
+                BlockInfo::default_with_epoch(Epoch::new(4)),
+                true,
+                None,
+            )
+            .expect("expected to delete a document");
+
+        let storage_usage = drive
+            .fetch_identity_storage_usage(&owner_id, None)
+            .expect("should fetch storage usage");
+
+        assert_eq!(storage_usage, IdentityStorageUsage::default());
+    }
+}
@@ -0,0 +1,246 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! This module defines functions within the Drive struct related to
+//! withdrawal rate limiting.
+//!
+//! Amounts of withdrawn credits are stored by block time, so the usage of
+//! the rolling 24h window can be calculated and old entries are pruned.
+//!
+
+use std::ops::RangeFull;
+
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::identity::withdrawal_queue::WITHDRAWN_CREDITS_ID;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+
+/// Duration of the rolling window for the daily withdrawal limit
+pub const WITHDRAWAL_LIMITS_WINDOW_MS: u64 = 86_400_000;
+
+/// Current withdrawal limits and their usage
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawalLimitsUsage {
+    /// Maximum amount of credits withdrawn per block
+    pub per_block_limit: u64,
+    /// Maximum amount of credits withdrawn per rolling 24h window
+    pub per_day_limit: u64,
+    /// Amount of credits withdrawn in the last 24h
+    pub withdrawn_in_window: u64,
+    /// Amount of credits which can be withdrawn in the block
+    pub available_in_block: u64,
+}
+
+/// Returns the path to the withdrawn credits
+fn withdrawn_credits_path_vec() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::WithdrawalTransactions as u8],
+        WITHDRAWN_CREDITS_ID.to_vec(),
+    ]
+}
+
+impl Drive {
+    /// Fetches amounts of withdrawn credits by block time
+    fn fetch_withdrawn_credits(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Vec<(u64, u64)>, Error> {
+        let mut query = Query::new();
+
+        query.insert_item(QueryItem::RangeFull(RangeFull));
+
+        let path_query = PathQuery::new(
+            withdrawn_credits_path_vec(),
+            SizedQuery::new(query, None, None),
+        );
+
+        self.grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+            .map_err(Error::GroveDB)?
+            .0
+            .to_key_elements()
+            .into_iter()
+            .map(|(key, element)| {
+                let block_time_ms = u64::from_be_bytes(key.try_into().map_err(|_| {
+                    Error::Drive(DriveError::CorruptedWithdrawnCreditsInvalidLength(
+                        "withdrawn credits block time must be an u64",
+                    ))
+                })?);
+
+                if let Element::Item(amount_bytes, _) = element {
+                    let amount = u64::from_be_bytes(amount_bytes.try_into().map_err(|_| {
+                        Error::Drive(DriveError::CorruptedWithdrawnCreditsInvalidLength(
+                            "withdrawn credits amount must be an u64",
+                        ))
+                    })?);
+
+                    Ok((block_time_ms, amount))
+                } else {
+                    Err(Error::Drive(DriveError::CorruptedWithdrawalNotItem(
+                        "withdrawn credits must be an item",
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    /// Fetches withdrawal limits and their usage at the given block time
+    pub fn fetch_withdrawal_limits_usage(
+        &self,
+        block_time_ms: u64,
+        transaction: TransactionArg,
+    ) -> Result<WithdrawalLimitsUsage, Error> {
+        let window_start_ms = block_time_ms.saturating_sub(WITHDRAWAL_LIMITS_WINDOW_MS);
+
+        let withdrawn_in_window = self
+            .fetch_withdrawn_credits(transaction)?
+            .into_iter()
+            .filter(|(withdrawn_at_ms, _)| *withdrawn_at_ms > window_start_ms)
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(amount))
+            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "withdrawn credits overflow",
+            )))?;
+
+        let per_block_limit = self.config.withdrawal_credits_per_block_limit;
+        let per_day_limit = self.config.withdrawal_credits_per_day_limit;
+
+        Ok(WithdrawalLimitsUsage {
+            per_block_limit,
+            per_day_limit,
+            withdrawn_in_window,
+            available_in_block: per_block_limit
+                .min(per_day_limit.saturating_sub(withdrawn_in_window)),
+        })
+    }
+
+    /// Adds operations to the op batch which store the amount of credits withdrawn at
+    /// the block time and remove amounts which are out of the rolling window
+    pub fn add_update_withdrawn_credits_operations(
+        &self,
+        block_time_ms: u64,
+        amount: u64,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        let window_start_ms = block_time_ms.saturating_sub(WITHDRAWAL_LIMITS_WINDOW_MS);
+
+        let mut withdrawn_at_block_time = amount;
+
+        for (withdrawn_at_ms, withdrawn_amount) in self.fetch_withdrawn_credits(transaction)? {
+            if withdrawn_at_ms == block_time_ms {
+                withdrawn_at_block_time = withdrawn_at_block_time
+                    .checked_add(withdrawn_amount)
+                    .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                        "withdrawn credits overflow",
+                    )))?;
+            } else if withdrawn_at_ms <= window_start_ms {
+                batch.add_delete(
+                    withdrawn_credits_path_vec(),
+                    withdrawn_at_ms.to_be_bytes().to_vec(),
+                );
+            }
+        }
+
+        batch.add_insert(
+            withdrawn_credits_path_vec(),
+            block_time_ms.to_be_bytes().to_vec(),
+            Element::Item(withdrawn_at_block_time.to_be_bytes().to_vec(), None),
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+
+    fn withdraw(drive: &Drive, block_time_ms: u64, amount: u64, transaction: TransactionArg) {
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_update_withdrawn_credits_operations(block_time_ms, amount, transaction, &mut batch)
+            .expect("should add withdrawn credits operations");
+
+        drive
+            .grove_apply_batch(batch, false, transaction)
+            .expect("should apply batch");
+    }
+
+    #[test]
+    fn test_withdrawal_limits_usage_in_rolling_window() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let usage = drive
+            .fetch_withdrawal_limits_usage(1000, Some(&transaction))
+            .expect("should fetch usage");
+
+        assert_eq!(usage.withdrawn_in_window, 0);
+        assert_eq!(usage.available_in_block, usage.per_block_limit);
+
+        withdraw(&drive, 1000, 100, Some(&transaction));
+        withdraw(&drive, 1000, 50, Some(&transaction));
+        withdraw(&drive, 2000, 200, Some(&transaction));
+
+        let usage = drive
+            .fetch_withdrawal_limits_usage(2000, Some(&transaction))
+            .expect("should fetch usage");
+
+        assert_eq!(usage.withdrawn_in_window, 350);
+
+        let usage = drive
+            .fetch_withdrawal_limits_usage(1000 + WITHDRAWAL_LIMITS_WINDOW_MS, Some(&transaction))
+            .expect("should fetch usage");
+
+        assert_eq!(usage.withdrawn_in_window, 200);
+
+        withdraw(
+            &drive,
+            1000 + WITHDRAWAL_LIMITS_WINDOW_MS,
+            10,
+            Some(&transaction),
+        );
+
+        let withdrawn_credits = drive
+            .fetch_withdrawn_credits(Some(&transaction))
+            .expect("should fetch withdrawn credits");
+
+        assert_eq!(
+            withdrawn_credits,
+            vec![(2000, 200), (1000 + WITHDRAWAL_LIMITS_WINDOW_MS, 10)]
+        );
+    }
+}
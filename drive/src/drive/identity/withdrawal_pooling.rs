@@ -0,0 +1,555 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! This module defines functions within the Drive struct related to pooling
+//! of identity credit withdrawals into withdrawal transactions (AssetUnlock).
+//!
+//! Withdrawal requests are collected during the block and pooled at the end of the block.
+//! `Standard` and `IfAvailable` withdrawals to identical output scripts are merged
+//! into one transaction, while `Never` withdrawals are always kept separate.
+//!
+
+use std::collections::BTreeMap;
+use std::ops::RangeFull;
+
+use bincode::Options;
+use dpp::dashcore::blockdata::transaction::special_transaction::asset_unlock::unqualified_asset_unlock::{
+    AssetUnlockBasePayload, AssetUnlockBaseTransactionInfo,
+};
+use dpp::dashcore::consensus::Encodable;
+use dpp::dashcore::{Script, TxOut};
+use dpp::identity::convert_credits_to_satoshi;
+use dpp::identity::state_transition::identity_credit_withdrawal_transition::Pooling;
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+use serde::{Deserialize, Serialize};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::identity::withdrawal_queue::{WITHDRAWAL_REQUESTS_ID, WITHDRAWAL_TRANSITIONS_ID};
+use crate::drive::identity::withdrawal_status::{PooledWithdrawal, WithdrawalTransactionRecord};
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+
+/// Identity credit withdrawal request waiting to be pooled into a withdrawal transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawalRequest {
+    /// Hash of the identity credit withdrawal state transition
+    pub transition_id: [u8; 32],
+    /// Id of the identity requested the withdrawal
+    pub identity_id: [u8; 32],
+    /// Withdrawal amount in credits
+    pub amount: u64,
+    /// Core fee to be paid for the withdrawal
+    pub core_fee: u32,
+    /// Pooling mode
+    pub pooling: Pooling,
+    /// Output script of the withdrawal
+    pub output_script: Vec<u8>,
+}
+
+impl WithdrawalRequest {
+    /// Serialize the request
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialize(self)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to serialize withdrawal request",
+                ))
+            })
+    }
+
+    /// Deserialize the request
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .deserialize(bytes)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to deserialize withdrawal request",
+                ))
+            })
+    }
+}
+
+/// Returns the path to the withdrawal requests
+fn withdrawal_requests_path_vec() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::WithdrawalTransactions as u8],
+        WITHDRAWAL_REQUESTS_ID.to_vec(),
+    ]
+}
+
+/// Returns the path to the withdrawal transitions mapping
+fn withdrawal_transitions_path_vec() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::WithdrawalTransactions as u8],
+        WITHDRAWAL_TRANSITIONS_ID.to_vec(),
+    ]
+}
+
+/// Groups withdrawal requests into withdrawal transactions according to their pooling mode.
+/// A pooled group is split once its amount would exceed the given maximum, so every
+/// withdrawal transaction fits into the per block withdrawal limit.
+/// Groups are ordered by the first request, so pooling is deterministic.
+pub fn group_withdrawal_requests(
+    requests: &[WithdrawalRequest],
+    max_group_amount: u64,
+) -> Vec<Vec<&WithdrawalRequest>> {
+    let mut groups: Vec<Vec<&WithdrawalRequest>> = vec![];
+    let mut pooled_group_positions: BTreeMap<&[u8], (usize, u64)> = BTreeMap::new();
+
+    for request in requests {
+        match request.pooling {
+            Pooling::Never => groups.push(vec![request]),
+            // Within a block window `IfAvailable` is always pooled like `Standard`
+            Pooling::IfAvailable | Pooling::Standard => {
+                let fitting_group = pooled_group_positions
+                    .get(request.output_script.as_slice())
+                    .and_then(|(position, amount)| {
+                        amount
+                            .checked_add(request.amount)
+                            .filter(|amount| *amount <= max_group_amount)
+                            .map(|amount| (*position, amount))
+                    });
+
+                match fitting_group {
+                    Some((position, amount)) => {
+                        groups[position].push(request);
+                        pooled_group_positions
+                            .insert(request.output_script.as_slice(), (position, amount));
+                    }
+                    None => {
+                        pooled_group_positions.insert(
+                            request.output_script.as_slice(),
+                            (groups.len(), request.amount),
+                        );
+                        groups.push(vec![request]);
+                    }
+                }
+            }
+        }
+    }
+
+    groups
+}
+
+/// Builds an unsigned withdrawal transaction paying withdrawals to their common output script
+fn build_withdrawal_transaction(
+    index: u64,
+    requests: &[&WithdrawalRequest],
+) -> Result<Vec<u8>, Error> {
+    let first_request =
+        requests
+            .first()
+            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "withdrawal transaction must have requests",
+            )))?;
+
+    let mut value: u64 = 0;
+    let mut fee: u32 = 0;
+
+    for request in requests {
+        // Each withdrawal is converted separately, so it's paid the same as when not pooled
+        value = value
+            .checked_add(convert_credits_to_satoshi(request.amount))
+            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "withdrawal transaction value overflow",
+            )))?;
+
+        fee = fee.checked_add(request.core_fee).ok_or(Error::Drive(
+            DriveError::CorruptedCodeExecution("withdrawal transaction fee overflow"),
+        ))?;
+    }
+
+    let withdrawal_transaction = AssetUnlockBaseTransactionInfo {
+        version: 1,
+        lock_time: 0,
+        output: vec![TxOut {
+            value,
+            script_pubkey: Script::from(first_request.output_script.clone()),
+        }],
+        base_payload: AssetUnlockBasePayload {
+            version: 1,
+            index,
+            fee,
+        },
+    };
+
+    let mut transaction_buffer: Vec<u8> = vec![];
+
+    withdrawal_transaction
+        .consensus_encode(&mut transaction_buffer)
+        .map_err(|_| {
+            Error::Drive(DriveError::CorruptedSerialization(
+                "unable to encode withdrawal transaction",
+            ))
+        })?;
+
+    Ok(transaction_buffer)
+}
+
+impl Drive {
+    /// Adds operations to the op batch to insert a withdrawal request to be pooled
+    /// at the end of the block
+    pub fn add_enqueue_withdrawal_request_operations(
+        &self,
+        batch: &mut GroveDbOpBatch,
+        request: &WithdrawalRequest,
+    ) -> Result<(), Error> {
+        batch.add_insert(
+            withdrawal_requests_path_vec(),
+            request.transition_id.to_vec(),
+            Element::Item(request.to_bytes()?, None),
+        );
+
+        Ok(())
+    }
+
+    /// Adds operations to the op batch which enqueue a withdrawal transaction of a withdrawal
+    /// that is never pooled and increment the withdrawal index counter.
+    ///
+    /// Returns the record of the enqueued withdrawal transaction
+    pub fn add_enqueue_unpooled_withdrawal_operations(
+        &self,
+        request: &WithdrawalRequest,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<WithdrawalTransactionRecord, Error> {
+        if request.pooling != Pooling::Never {
+            return Err(Error::Drive(DriveError::CorruptedCodeExecution(
+                "only withdrawals which are never pooled can be enqueued without pooling",
+            )));
+        }
+
+        let index = self.fetch_latest_withdrawal_transaction_index(transaction)? + 1;
+
+        let transaction_bytes = build_withdrawal_transaction(index, &[request])?;
+
+        let record =
+            WithdrawalTransactionRecord::new_queued(index, request.identity_id, request.amount);
+
+        batch.add_insert(
+            withdrawal_transitions_path_vec(),
+            request.transition_id.to_vec(),
+            Element::Item(index.to_be_bytes().to_vec(), None),
+        );
+
+        self.add_enqueue_withdrawal_transaction_operations(
+            batch,
+            vec![(record.clone(), transaction_bytes)],
+        )?;
+
+        self.add_update_withdrawal_index_counter_operation(batch, index.to_be_bytes().to_vec());
+
+        Ok(record)
+    }
+
+    /// Fetches withdrawal requests which are not pooled yet
+    pub fn fetch_withdrawal_requests(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Vec<WithdrawalRequest>, Error> {
+        let mut query = Query::new();
+
+        query.insert_item(QueryItem::RangeFull(RangeFull));
+
+        let path_query = PathQuery::new(
+            withdrawal_requests_path_vec(),
+            SizedQuery::new(query, None, None),
+        );
+
+        self.grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+            .map_err(Error::GroveDB)?
+            .0
+            .to_key_elements()
+            .into_iter()
+            .map(|(_, element)| match element {
+                Element::Item(bytes, _) => WithdrawalRequest::from_bytes(&bytes),
+                _ => Err(Error::Drive(DriveError::CorruptedWithdrawalNotItem(
+                    "withdrawal request must be an item",
+                ))),
+            })
+            .collect()
+    }
+
+    /// Fetches the index of the withdrawal transaction the state transition was pooled into
+    pub fn fetch_withdrawal_transaction_index_by_transition(
+        &self,
+        transition_id: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Option<u64>, Error> {
+        let element = match self
+            .grove
+            .get(
+                [
+                    Into::<&[u8; 1]>::into(RootTree::WithdrawalTransactions).as_slice(),
+                    &WITHDRAWAL_TRANSITIONS_ID,
+                ],
+                transition_id,
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_)) => return Ok(None),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        if let Element::Item(index_bytes, _) = element {
+            let index = u64::from_be_bytes(index_bytes.try_into().map_err(|_| {
+                DriveError::CorruptedWithdrawalTransactionIndexInvalidLength(
+                    "withdrawal transaction index must be an u64",
+                )
+            })?);
+
+            Ok(Some(index))
+        } else {
+            Err(Error::Drive(DriveError::CorruptedWithdrawalNotItem(
+                "withdrawal transition mapping must be an item",
+            )))
+        }
+    }
+
+    /// Adds operations to the op batch which pool withdrawal requests collected during the block
+    /// into withdrawal transactions and enqueue them.
+    ///
+    /// Returns records of enqueued withdrawal transactions
+    pub fn add_pool_withdrawal_requests_operations(
+        &self,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<Vec<WithdrawalTransactionRecord>, Error> {
+        let requests = self.fetch_withdrawal_requests(transaction)?;
+
+        if requests.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut index = self.fetch_latest_withdrawal_transaction_index(transaction)?;
+
+        let mut withdrawals = vec![];
+
+        for group in
+            group_withdrawal_requests(&requests, self.config.withdrawal_credits_per_block_limit)
+        {
+            index += 1;
+
+            let transaction_bytes = build_withdrawal_transaction(index, &group)?;
+
+            let pooled_withdrawals = group
+                .iter()
+                .map(|request| PooledWithdrawal {
+                    identity_id: request.identity_id,
+                    amount: request.amount,
+                })
+                .collect();
+
+            withdrawals.push((
+                WithdrawalTransactionRecord::new_queued_pooled(index, pooled_withdrawals),
+                transaction_bytes,
+            ));
+
+            for request in group {
+                batch.add_insert(
+                    withdrawal_transitions_path_vec(),
+                    request.transition_id.to_vec(),
+                    Element::Item(index.to_be_bytes().to_vec(), None),
+                );
+
+                batch.add_delete(
+                    withdrawal_requests_path_vec(),
+                    request.transition_id.to_vec(),
+                );
+            }
+        }
+
+        let records = withdrawals
+            .iter()
+            .map(|(record, _)| record.clone())
+            .collect();
+
+        self.add_enqueue_withdrawal_transaction_operations(batch, withdrawals)?;
+
+        self.add_update_withdrawal_index_counter_operation(batch, index.to_be_bytes().to_vec());
+
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+
+    fn create_withdrawal_request(
+        transition_id: u8,
+        identity_id: u8,
+        pooling: Pooling,
+        output_script: Vec<u8>,
+    ) -> WithdrawalRequest {
+        WithdrawalRequest {
+            transition_id: [transition_id; 32],
+            identity_id: [identity_id; 32],
+            amount: 10000,
+            core_fee: 1,
+            pooling,
+            output_script,
+        }
+    }
+
+    #[test]
+    fn test_group_withdrawal_requests() {
+        let requests = vec![
+            create_withdrawal_request(1, 1, Pooling::Standard, vec![1]),
+            create_withdrawal_request(2, 2, Pooling::Never, vec![1]),
+            create_withdrawal_request(3, 3, Pooling::IfAvailable, vec![1]),
+            create_withdrawal_request(4, 1, Pooling::Standard, vec![2]),
+            create_withdrawal_request(5, 1, Pooling::Never, vec![2]),
+        ];
+
+        let groups = group_withdrawal_requests(&requests, u64::MAX);
+
+        let group_transition_ids: Vec<Vec<u8>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|request| request.transition_id[0])
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            group_transition_ids,
+            vec![vec![1, 3], vec![2], vec![4], vec![5]]
+        );
+    }
+
+    #[test]
+    fn test_group_withdrawal_requests_within_max_amount() {
+        let requests = vec![
+            create_withdrawal_request(1, 1, Pooling::Standard, vec![1]),
+            create_withdrawal_request(2, 2, Pooling::Standard, vec![1]),
+            create_withdrawal_request(3, 3, Pooling::Standard, vec![1]),
+        ];
+
+        let groups = group_withdrawal_requests(&requests, 20000);
+
+        let group_transition_ids: Vec<Vec<u8>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|request| request.transition_id[0])
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(group_transition_ids, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_pool_withdrawal_requests() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let requests = vec![
+            create_withdrawal_request(1, 1, Pooling::Standard, vec![1]),
+            create_withdrawal_request(2, 2, Pooling::IfAvailable, vec![1]),
+            create_withdrawal_request(3, 3, Pooling::Never, vec![1]),
+        ];
+
+        let mut batch = GroveDbOpBatch::new();
+
+        for request in requests.iter() {
+            drive
+                .add_enqueue_withdrawal_request_operations(&mut batch, request)
+                .expect("should add withdrawal request");
+        }
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let mut batch = GroveDbOpBatch::new();
+
+        let records = drive
+            .add_pool_withdrawal_requests_operations(Some(&transaction), &mut batch)
+            .expect("should pool withdrawal requests");
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].index, 1);
+        assert_eq!(records[0].withdrawals.len(), 2);
+        assert_eq!(records[1].index, 2);
+        assert_eq!(records[1].withdrawals.len(), 1);
+
+        let transition_indexes: Vec<Option<u64>> = requests
+            .iter()
+            .map(|request| {
+                drive
+                    .fetch_withdrawal_transaction_index_by_transition(
+                        &request.transition_id,
+                        Some(&transaction),
+                    )
+                    .expect("should fetch transition mapping")
+            })
+            .collect();
+
+        assert_eq!(transition_indexes, vec![Some(1), Some(1), Some(2)]);
+
+        let latest_index = drive
+            .fetch_latest_withdrawal_transaction_index(Some(&transaction))
+            .expect("should fetch latest index");
+
+        assert_eq!(latest_index, 2);
+
+        let pending_requests = drive
+            .fetch_withdrawal_requests(Some(&transaction))
+            .expect("should fetch withdrawal requests");
+
+        assert!(pending_requests.is_empty());
+
+        let withdrawal_transactions = drive
+            .dequeue_withdrawal_transactions(16, 1, 1, Some(&transaction))
+            .expect("should dequeue withdrawal transactions");
+
+        assert_eq!(withdrawal_transactions.len(), 2);
+    }
+}
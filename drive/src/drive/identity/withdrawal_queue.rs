@@ -36,6 +36,7 @@ use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
 use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
 
 use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::identity::withdrawal_status::{WithdrawalStatus, WithdrawalTransactionRecord};
 use crate::drive::{Drive, RootTree};
 use crate::error::drive::DriveError;
 use crate::error::Error;
@@ -45,6 +46,18 @@ use crate::fee::op::DriveOperation;
 pub const WITHDRAWAL_TRANSACTIONS_COUNTER_ID: [u8; 1] = [0];
 /// constant id for subtree containing transactions queue
 pub const WITHDRAWAL_TRANSACTIONS_QUEUE_ID: [u8; 1] = [1];
+/// constant id for subtree containing withdrawal transaction status records
+pub const WITHDRAWAL_TRANSACTIONS_STATUSES_ID: [u8; 1] = [2];
+/// constant id for subtree containing references to status records by identity
+pub const WITHDRAWAL_TRANSACTIONS_BY_IDENTITY_ID: [u8; 1] = [3];
+/// constant id for subtree containing references to pooled and broadcasted transactions
+pub const WITHDRAWAL_TRANSACTIONS_IN_FLIGHT_ID: [u8; 1] = [4];
+/// constant id for subtree containing withdrawal requests to be pooled at the end of the block
+pub const WITHDRAWAL_REQUESTS_ID: [u8; 1] = [5];
+/// constant id for subtree mapping withdrawal state transitions to asset unlock indexes
+pub const WITHDRAWAL_TRANSITIONS_ID: [u8; 1] = [6];
+/// constant id for subtree containing amounts of credits withdrawn by block time
+pub const WITHDRAWN_CREDITS_ID: [u8; 1] = [7];
 
 type WithdrawalTransaction = (Vec<u8>, Vec<u8>);
 
@@ -62,9 +75,75 @@ pub fn add_initial_withdrawal_state_structure_operations(batch: &mut GroveDbOpBa
         vec![vec![RootTree::WithdrawalTransactions as u8]],
         WITHDRAWAL_TRANSACTIONS_QUEUE_ID.to_vec(),
     );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::WithdrawalTransactions as u8]],
+        WITHDRAWAL_TRANSACTIONS_STATUSES_ID.to_vec(),
+    );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::WithdrawalTransactions as u8]],
+        WITHDRAWAL_TRANSACTIONS_BY_IDENTITY_ID.to_vec(),
+    );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::WithdrawalTransactions as u8]],
+        WITHDRAWAL_TRANSACTIONS_IN_FLIGHT_ID.to_vec(),
+    );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::WithdrawalTransactions as u8]],
+        WITHDRAWAL_REQUESTS_ID.to_vec(),
+    );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::WithdrawalTransactions as u8]],
+        WITHDRAWAL_TRANSITIONS_ID.to_vec(),
+    );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::WithdrawalTransactions as u8]],
+        WITHDRAWN_CREDITS_ID.to_vec(),
+    );
 }
 
 impl Drive {
+    /// Adds operations to the op batch which create withdrawal state subtrees
+    /// missing on chains initialized before the withdrawal lifecycle was tracked
+    pub fn add_missing_withdrawal_state_structure_operations(
+        &self,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        for subtree_id in [
+            WITHDRAWAL_TRANSACTIONS_STATUSES_ID,
+            WITHDRAWAL_TRANSACTIONS_BY_IDENTITY_ID,
+            WITHDRAWAL_TRANSACTIONS_IN_FLIGHT_ID,
+            WITHDRAWAL_REQUESTS_ID,
+            WITHDRAWAL_TRANSITIONS_ID,
+            WITHDRAWN_CREDITS_ID,
+        ] {
+            let has_subtree = self
+                .grove
+                .has_raw(
+                    [Into::<&[u8; 1]>::into(RootTree::WithdrawalTransactions).as_slice()],
+                    &subtree_id,
+                    transaction,
+                )
+                .unwrap()
+                .map_err(Error::GroveDB)?;
+
+            if !has_subtree {
+                batch.add_insert_empty_tree(
+                    vec![vec![RootTree::WithdrawalTransactions as u8]],
+                    subtree_id.to_vec(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get latest withdrawal index in a queue
     pub fn fetch_latest_withdrawal_transaction_index(
         &self,
@@ -116,28 +195,35 @@ impl Drive {
         );
     }
 
-    /// Add insert operations for withdrawal transactions to the batch
+    /// Add insert operations for withdrawal transactions and their queued status records to the batch
     pub fn add_enqueue_withdrawal_transaction_operations(
         &self,
         batch: &mut GroveDbOpBatch,
-        withdrawals: Vec<(Vec<u8>, Vec<u8>)>,
-    ) {
-        for (id, bytes) in withdrawals {
+        withdrawals: Vec<(WithdrawalTransactionRecord, Vec<u8>)>,
+    ) -> Result<(), Error> {
+        for (record, bytes) in withdrawals {
             batch.add_insert(
                 vec![
                     vec![RootTree::WithdrawalTransactions as u8],
                     WITHDRAWAL_TRANSACTIONS_QUEUE_ID.to_vec(),
                 ],
-                id,
+                record.index.to_be_bytes().to_vec(),
                 Element::Item(bytes, None),
             );
+
+            self.add_insert_withdrawal_transaction_record_operations(batch, &record)?;
         }
+
+        Ok(())
     }
 
-    /// Get specified amount of withdrawal transactions from the DB
+    /// Get specified amount of withdrawal transactions from the DB in FIFO order
+    /// within withdrawal limits and mark them as pooled at the given core chain locked height
     pub fn dequeue_withdrawal_transactions(
         &self,
         num_of_transactions: u16,
+        core_chain_locked_height: u32,
+        block_time_ms: u64,
         transaction: TransactionArg,
     ) -> Result<Vec<WithdrawalTransaction>, Error> {
         let mut query = Query::new();
@@ -164,7 +250,7 @@ impl Drive {
             .0
             .to_key_elements();
 
-        let withdrawals = result_items
+        let queued_withdrawals = result_items
             .into_iter()
             .map(|(id, element)| match element {
                 Element::Item(bytes, _) => Ok((id, bytes)),
@@ -174,42 +260,111 @@ impl Drive {
             })
             .collect::<Result<Vec<(Vec<u8>, Vec<u8>)>, Error>>()?;
 
-        if !withdrawals.is_empty() {
-            let mut batch_operations: Vec<DriveOperation> = vec![];
-            let mut drive_operations: Vec<DriveOperation> = vec![];
-
-            let withdrawals_path: [&[u8]; 2] = [
-                Into::<&[u8; 1]>::into(RootTree::WithdrawalTransactions),
-                &WITHDRAWAL_TRANSACTIONS_QUEUE_ID,
-            ];
-
-            for (id, _) in withdrawals.iter() {
-                self.batch_delete(
-                    withdrawals_path,
-                    id,
-                    true,
-                    true,
-                    transaction,
-                    &mut batch_operations,
-                )?;
+        if queued_withdrawals.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let limits_usage = self.fetch_withdrawal_limits_usage(block_time_ms, transaction)?;
+
+        let mut withdrawals = vec![];
+        let mut withdrawn_credits: u64 = 0;
+
+        let mut batch_operations: Vec<DriveOperation> = vec![];
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let withdrawals_path: [&[u8]; 2] = [
+            Into::<&[u8; 1]>::into(RootTree::WithdrawalTransactions),
+            &WITHDRAWAL_TRANSACTIONS_QUEUE_ID,
+        ];
+
+        let mut status_batch = GroveDbOpBatch::new();
+
+        for (id, bytes) in queued_withdrawals {
+            let index = u64::from_be_bytes(id.as_slice().try_into().map_err(|_| {
+                Error::Drive(
+                    DriveError::CorruptedWithdrawalTransactionIndexInvalidLength(
+                        "withdrawal transaction index must be an u64",
+                    ),
+                )
+            })?);
+
+            // Keep tracking the transaction, so credits can be refunded if it's never mined
+            let mut record = self
+                .fetch_withdrawal_transaction_record(index, transaction)?
+                .ok_or(Error::Drive(
+                    DriveError::CorruptedWithdrawalTransactionRecordNotFound(
+                        "queued withdrawal transaction must have a status record",
+                    ),
+                ))?;
+
+            let total_withdrawn_credits = withdrawn_credits
+                .checked_add(record.total_amount()?)
+                .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                    "withdrawn credits overflow",
+                )))?;
+
+            // Excess withdrawals remain queued in FIFO order. Withdrawal transactions never
+            // exceed the per block limit, so they are dequeued once the window has room.
+            if total_withdrawn_credits > limits_usage.available_in_block {
+                break;
             }
 
-            self.apply_batch_drive_operations(
+            withdrawn_credits = total_withdrawn_credits;
+
+            self.batch_delete(
+                withdrawals_path,
+                &id,
                 true,
+                true,
+                None,
                 transaction,
-                batch_operations,
-                &mut drive_operations,
+                &mut batch_operations,
             )?;
+
+            record.status = WithdrawalStatus::Pooled;
+            record.request_height = Some(core_chain_locked_height);
+
+            self.add_insert_withdrawal_transaction_record_operations(&mut status_batch, &record)?;
+
+            withdrawals.push((id, bytes));
         }
 
+        if withdrawals.is_empty() {
+            return Ok(withdrawals);
+        }
+
+        self.add_update_withdrawn_credits_operations(
+            block_time_ms,
+            withdrawn_credits,
+            transaction,
+            &mut status_batch,
+        )?;
+
+        batch_operations.extend(
+            status_batch
+                .operations
+                .into_iter()
+                .map(DriveOperation::GroveOperation),
+        );
+
+        self.apply_batch_drive_operations(
+            true,
+            transaction,
+            batch_operations,
+            &mut drive_operations,
+        )?;
+
         Ok(withdrawals)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::helpers::setup::{setup_drive, setup_drive_with_initial_state_structure};
     use crate::drive::batch::GroveDbOpBatch;
+    use crate::drive::config::DriveConfig;
+    use crate::drive::identity::withdrawal_limits::WITHDRAWAL_LIMITS_WINDOW_MS;
+    use crate::drive::identity::withdrawal_status::WithdrawalTransactionRecord;
 
     mod queue {
         use super::*;
@@ -220,36 +375,120 @@ mod tests {
 
             let transaction = drive.grove.start_transaction();
 
-            let withdrawals: Vec<(Vec<u8>, Vec<u8>)> = (0..17)
-                .map(|i: u8| (i.to_be_bytes().to_vec(), vec![i; 32]))
+            let withdrawals: Vec<(WithdrawalTransactionRecord, Vec<u8>)> = (0..17)
+                .map(|i: u8| {
+                    (
+                        WithdrawalTransactionRecord::new_queued(i as u64, [i; 32], 100),
+                        vec![i; 32],
+                    )
+                })
                 .collect();
 
             let mut batch = GroveDbOpBatch::new();
 
-            drive.add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals);
+            drive
+                .add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals)
+                .expect("to add enqueue ops");
 
             drive
                 .grove_apply_batch(batch, true, Some(&transaction))
                 .expect("to apply ops");
 
             let withdrawals = drive
-                .dequeue_withdrawal_transactions(16, Some(&transaction))
+                .dequeue_withdrawal_transactions(16, 1, 1, Some(&transaction))
                 .expect("to dequeue withdrawals");
 
             assert_eq!(withdrawals.len(), 16);
 
             let withdrawals = drive
-                .dequeue_withdrawal_transactions(16, Some(&transaction))
+                .dequeue_withdrawal_transactions(16, 1, 1, Some(&transaction))
                 .expect("to dequeue withdrawals");
 
             assert_eq!(withdrawals.len(), 1);
 
             let withdrawals = drive
-                .dequeue_withdrawal_transactions(16, Some(&transaction))
+                .dequeue_withdrawal_transactions(16, 1, 1, Some(&transaction))
                 .expect("to dequeue withdrawals");
 
             assert_eq!(withdrawals.len(), 0);
         }
+
+        #[test]
+        fn test_dequeue_within_withdrawal_limits() {
+            let drive = setup_drive(Some(DriveConfig {
+                withdrawal_credits_per_block_limit: 250,
+                withdrawal_credits_per_day_limit: 300,
+                ..Default::default()
+            }));
+
+            drive
+                .create_initial_state_structure(None)
+                .expect("should create root tree successfully");
+
+            let transaction = drive.grove.start_transaction();
+
+            let withdrawals: Vec<(WithdrawalTransactionRecord, Vec<u8>)> = (0..4)
+                .map(|i: u8| {
+                    (
+                        WithdrawalTransactionRecord::new_queued(i as u64, [i; 32], 100),
+                        vec![i; 32],
+                    )
+                })
+                .collect();
+
+            let mut batch = GroveDbOpBatch::new();
+
+            drive
+                .add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals)
+                .expect("to add enqueue ops");
+
+            drive
+                .grove_apply_batch(batch, true, Some(&transaction))
+                .expect("to apply ops");
+
+            // Limited by the per block limit
+            let withdrawals = drive
+                .dequeue_withdrawal_transactions(16, 1, 1000, Some(&transaction))
+                .expect("to dequeue withdrawals");
+
+            assert_eq!(withdrawals.len(), 2);
+            assert_eq!(withdrawals[0].0, 0u64.to_be_bytes().to_vec());
+            assert_eq!(withdrawals[1].0, 1u64.to_be_bytes().to_vec());
+
+            // Limited by the per day limit
+            let withdrawals = drive
+                .dequeue_withdrawal_transactions(16, 1, 2000, Some(&transaction))
+                .expect("to dequeue withdrawals");
+
+            assert_eq!(withdrawals.len(), 1);
+            assert_eq!(withdrawals[0].0, 2u64.to_be_bytes().to_vec());
+
+            let withdrawals = drive
+                .dequeue_withdrawal_transactions(16, 1, 3000, Some(&transaction))
+                .expect("to dequeue withdrawals");
+
+            assert_eq!(withdrawals.len(), 0);
+
+            let usage = drive
+                .fetch_withdrawal_limits_usage(3000, Some(&transaction))
+                .expect("to fetch withdrawal limits usage");
+
+            assert_eq!(usage.withdrawn_in_window, 300);
+            assert_eq!(usage.available_in_block, 0);
+
+            // The rest is dequeued when the window moves
+            let withdrawals = drive
+                .dequeue_withdrawal_transactions(
+                    16,
+                    1,
+                    1000 + WITHDRAWAL_LIMITS_WINDOW_MS,
+                    Some(&transaction),
+                )
+                .expect("to dequeue withdrawals");
+
+            assert_eq!(withdrawals.len(), 1);
+            assert_eq!(withdrawals[0].0, 3u64.to_be_bytes().to_vec());
+        }
     }
 
     mod index {
@@ -0,0 +1,265 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! This module defines read-only functions within the Drive struct
+//! to inspect the withdrawal transactions queue without consuming it.
+//!
+
+use std::ops::RangeFrom;
+
+use dpp::dashcore::blockdata::transaction::special_transaction::asset_unlock::unqualified_asset_unlock::AssetUnlockBaseTransactionInfo;
+use dpp::dashcore::consensus::deserialize;
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+
+use crate::drive::identity::withdrawal_queue::WITHDRAWAL_TRANSACTIONS_QUEUE_ID;
+use crate::drive::identity::withdrawal_status::PooledWithdrawal;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+
+/// Decoded withdrawal transaction waiting in the queue
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueuedWithdrawalTransaction {
+    /// Asset unlock index
+    pub index: u64,
+    /// Amount paid by the transaction in duffs
+    pub amount: u64,
+    /// Output script of the transaction
+    pub output_script: Vec<u8>,
+    /// Core fee of the transaction
+    pub fee: u32,
+    /// Identity withdrawals pooled into the transaction
+    pub withdrawals: Vec<PooledWithdrawal>,
+}
+
+/// Page of queued withdrawal transactions with totals
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueuedWithdrawalTransactions {
+    /// Queued withdrawal transactions ordered by index
+    pub transactions: Vec<QueuedWithdrawalTransaction>,
+    /// Total amount paid by the transactions in duffs
+    pub total_amount: u64,
+    /// Total core fee of the transactions
+    pub total_fee: u64,
+}
+
+/// Returns a path query for queued withdrawal transactions starting from the index
+fn queued_withdrawal_transactions_path_query(start_index: u64, limit: u16) -> PathQuery {
+    let mut query = Query::new();
+
+    query.insert_item(QueryItem::RangeFrom(RangeFrom {
+        start: start_index.to_be_bytes().to_vec(),
+    }));
+
+    PathQuery::new(
+        vec![
+            vec![RootTree::WithdrawalTransactions as u8],
+            WITHDRAWAL_TRANSACTIONS_QUEUE_ID.to_vec(),
+        ],
+        SizedQuery::new(query, Some(limit), None),
+    )
+}
+
+impl Drive {
+    /// Fetches and decodes queued withdrawal transactions starting from the index
+    /// without removing them from the queue
+    pub fn fetch_queued_withdrawal_transactions(
+        &self,
+        start_index: u64,
+        limit: u16,
+        transaction: TransactionArg,
+    ) -> Result<QueuedWithdrawalTransactions, Error> {
+        let path_query = queued_withdrawal_transactions_path_query(start_index, limit);
+
+        let result_items = self
+            .grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+            .map_err(Error::GroveDB)?
+            .0
+            .to_key_elements();
+
+        let mut queued_withdrawal_transactions = QueuedWithdrawalTransactions::default();
+
+        for (_, element) in result_items {
+            let bytes = match element {
+                Element::Item(bytes, _) => bytes,
+                _ => {
+                    return Err(Error::Drive(DriveError::CorruptedWithdrawalNotItem(
+                        "withdrawal is not an item",
+                    )))
+                }
+            };
+
+            let transaction_info: AssetUnlockBaseTransactionInfo =
+                deserialize(&bytes).map_err(|_| {
+                    Error::Drive(DriveError::CorruptedSerialization(
+                        "unable to decode withdrawal transaction",
+                    ))
+                })?;
+
+            let index = transaction_info.base_payload.index;
+
+            let amount = transaction_info
+                .output
+                .iter()
+                .try_fold(0u64, |total, output| total.checked_add(output.value))
+                .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                    "withdrawal transaction amount overflow",
+                )))?;
+
+            let output_script = transaction_info
+                .output
+                .first()
+                .map(|output| output.script_pubkey.to_bytes())
+                .unwrap_or_default();
+
+            let withdrawals = self
+                .fetch_withdrawal_transaction_record(index, transaction)?
+                .map(|record| record.withdrawals)
+                .unwrap_or_default();
+
+            queued_withdrawal_transactions.total_amount = queued_withdrawal_transactions
+                .total_amount
+                .checked_add(amount)
+                .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                    "withdrawal transactions total amount overflow",
+                )))?;
+
+            queued_withdrawal_transactions.total_fee += transaction_info.base_payload.fee as u64;
+
+            queued_withdrawal_transactions
+                .transactions
+                .push(QueuedWithdrawalTransaction {
+                    index,
+                    amount,
+                    output_script,
+                    fee: transaction_info.base_payload.fee,
+                    withdrawals,
+                });
+        }
+
+        Ok(queued_withdrawal_transactions)
+    }
+
+    /// Proves queued withdrawal transactions starting from the index
+    pub fn prove_queued_withdrawal_transactions(
+        &self,
+        start_index: u64,
+        limit: u16,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let path_query = queued_withdrawal_transactions_path_query(start_index, limit);
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::drive::identity::withdrawal_pooling::WithdrawalRequest;
+    use dpp::identity::state_transition::identity_credit_withdrawal_transition::Pooling;
+
+    #[test]
+    fn test_fetch_queued_withdrawal_transactions() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let mut batch = GroveDbOpBatch::new();
+
+        for i in 1..=3u8 {
+            drive
+                .add_enqueue_withdrawal_request_operations(
+                    &mut batch,
+                    &WithdrawalRequest {
+                        transition_id: [i; 32],
+                        identity_id: [i; 32],
+                        amount: 1000 * i as u64,
+                        core_fee: i as u32,
+                        pooling: Pooling::Never,
+                        output_script: vec![i; 23],
+                    },
+                )
+                .expect("should add withdrawal request");
+        }
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_pool_withdrawal_requests_operations(Some(&transaction), &mut batch)
+            .expect("should pool withdrawal requests");
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let queued_withdrawal_transactions = drive
+            .fetch_queued_withdrawal_transactions(2, 16, Some(&transaction))
+            .expect("should fetch queued withdrawal transactions");
+
+        assert_eq!(queued_withdrawal_transactions.transactions.len(), 2);
+        assert_eq!(queued_withdrawal_transactions.total_amount, 5);
+        assert_eq!(queued_withdrawal_transactions.total_fee, 5);
+
+        let first_transaction = &queued_withdrawal_transactions.transactions[0];
+
+        assert_eq!(first_transaction.index, 2);
+        assert_eq!(first_transaction.amount, 2);
+        assert_eq!(first_transaction.output_script, vec![2; 23]);
+        assert_eq!(first_transaction.fee, 2);
+        assert_eq!(
+            first_transaction.withdrawals,
+            vec![PooledWithdrawal {
+                identity_id: [2; 32],
+                amount: 2000,
+            }]
+        );
+
+        // Fetching doesn't consume the queue
+        let queued_withdrawal_transactions = drive
+            .fetch_queued_withdrawal_transactions(0, 16, Some(&transaction))
+            .expect("should fetch queued withdrawal transactions");
+
+        assert_eq!(queued_withdrawal_transactions.transactions.len(), 3);
+
+        let proof = drive
+            .prove_queued_withdrawal_transactions(0, 16, Some(&transaction))
+            .expect("should prove queued withdrawal transactions");
+
+        assert!(!proof.is_empty());
+    }
+}
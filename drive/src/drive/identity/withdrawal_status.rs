@@ -0,0 +1,607 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! This module defines functions within the Drive struct related to tracking
+//! of withdrawal transaction (AssetUnlock) statuses.
+//!
+//! Each withdrawal is tracked by its `AssetUnlockBasePayload.index` from the moment
+//! it's queued until it's complete or expired. Expired withdrawals are refunded
+//! to the identity.
+//!
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeFull;
+
+use bincode::Options;
+use grovedb::reference_path::ReferencePathType::UpstreamRootHeightReference;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+use serde::{Deserialize, Serialize};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::identity::withdrawal_queue::{
+    WITHDRAWAL_TRANSACTIONS_BY_IDENTITY_ID, WITHDRAWAL_TRANSACTIONS_IN_FLIGHT_ID,
+    WITHDRAWAL_TRANSACTIONS_STATUSES_ID,
+};
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+
+/// Number of core blocks after the request height when an asset unlock transaction
+/// can't be mined anymore
+pub const WITHDRAWAL_TRANSACTION_EXPIRATION_CORE_BLOCKS: u32 = 48;
+
+/// Withdrawal transaction status
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WithdrawalStatus {
+    /// Waiting in the queue to be signed
+    Queued,
+    /// Dequeued and passed to JS Drive for signing and broadcasting
+    Pooled,
+    /// Seen by Core
+    Broadcasted,
+    /// Chain locked by Core
+    Complete,
+    /// Wasn't mined in time, so credits were refunded to the identity
+    Expired,
+}
+
+/// Identity withdrawal included into a withdrawal transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PooledWithdrawal {
+    /// Id of the identity requested the withdrawal
+    pub identity_id: [u8; 32],
+    /// Withdrawal amount in credits
+    pub amount: u64,
+}
+
+/// Withdrawal transaction record
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawalTransactionRecord {
+    /// Asset unlock index
+    pub index: u64,
+    /// Identity withdrawals pooled into the transaction
+    pub withdrawals: Vec<PooledWithdrawal>,
+    /// Current status
+    pub status: WithdrawalStatus,
+    /// Core chain locked height the transaction was requested to be signed at
+    pub request_height: Option<u32>,
+}
+
+impl WithdrawalTransactionRecord {
+    /// Creates a record for a newly queued withdrawal transaction of a single identity
+    pub fn new_queued(index: u64, identity_id: [u8; 32], amount: u64) -> Self {
+        Self::new_queued_pooled(
+            index,
+            vec![PooledWithdrawal {
+                identity_id,
+                amount,
+            }],
+        )
+    }
+
+    /// Creates a record for a newly queued withdrawal transaction pooling multiple withdrawals
+    pub fn new_queued_pooled(index: u64, withdrawals: Vec<PooledWithdrawal>) -> Self {
+        Self {
+            index,
+            withdrawals,
+            status: WithdrawalStatus::Queued,
+            request_height: None,
+        }
+    }
+
+    /// Returns total amount of credits withdrawn with the transaction
+    pub fn total_amount(&self) -> Result<u64, Error> {
+        self.withdrawals
+            .iter()
+            .try_fold(0u64, |total, withdrawal| {
+                total.checked_add(withdrawal.amount)
+            })
+            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "withdrawal transaction amount overflow",
+            )))
+    }
+
+    /// Serialize the record
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialize(self)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to serialize withdrawal transaction record",
+                ))
+            })
+    }
+
+    /// Deserialize the record
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .deserialize(bytes)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to deserialize withdrawal transaction record",
+                ))
+            })
+    }
+
+    /// Returns true if the transaction can't be mined anymore at the given core height
+    pub fn is_expired_at(&self, core_chain_locked_height: u32) -> bool {
+        match self.status {
+            WithdrawalStatus::Pooled | WithdrawalStatus::Broadcasted => {
+                self.request_height.map_or(false, |request_height| {
+                    core_chain_locked_height
+                        > request_height
+                            .saturating_add(WITHDRAWAL_TRANSACTION_EXPIRATION_CORE_BLOCKS)
+                })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the path to the withdrawal transaction statuses
+pub(crate) fn withdrawal_transaction_statuses_path_vec() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::WithdrawalTransactions as u8],
+        WITHDRAWAL_TRANSACTIONS_STATUSES_ID.to_vec(),
+    ]
+}
+
+/// Returns the path to the withdrawal transactions indexed by identity
+pub(crate) fn withdrawal_transactions_by_identity_path_vec() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::WithdrawalTransactions as u8],
+        WITHDRAWAL_TRANSACTIONS_BY_IDENTITY_ID.to_vec(),
+    ]
+}
+
+/// Returns the path to the pooled and broadcasted withdrawal transactions
+pub(crate) fn withdrawal_transactions_in_flight_path_vec() -> Vec<Vec<u8>> {
+    vec![
+        vec![RootTree::WithdrawalTransactions as u8],
+        WITHDRAWAL_TRANSACTIONS_IN_FLIGHT_ID.to_vec(),
+    ]
+}
+
+/// Returns a reference to the withdrawal transaction status record
+/// from a subtree of the `WithdrawalTransactions` tree
+fn make_withdrawal_transaction_record_reference(index: u64) -> Element {
+    Element::Reference(
+        UpstreamRootHeightReference(
+            1,
+            vec![
+                WITHDRAWAL_TRANSACTIONS_STATUSES_ID.to_vec(),
+                index.to_be_bytes().to_vec(),
+            ],
+        ),
+        Some(1),
+        None,
+    )
+}
+
+/// Returns the key of the withdrawal transaction in the by identity index
+fn make_withdrawal_transaction_by_identity_key(identity_id: &[u8; 32], index: u64) -> Vec<u8> {
+    let mut key = identity_id.to_vec();
+    key.extend_from_slice(&index.to_be_bytes());
+    key
+}
+
+impl Drive {
+    /// Adds operations to the op batch to insert or update the withdrawal transaction record
+    pub fn add_insert_withdrawal_transaction_record_operations(
+        &self,
+        batch: &mut GroveDbOpBatch,
+        record: &WithdrawalTransactionRecord,
+    ) -> Result<(), Error> {
+        let index_bytes = record.index.to_be_bytes().to_vec();
+
+        batch.add_insert(
+            withdrawal_transaction_statuses_path_vec(),
+            index_bytes.clone(),
+            Element::Item(record.to_bytes()?, None),
+        );
+
+        // The same identity can have several withdrawals pooled into the transaction
+        let identity_ids: BTreeSet<[u8; 32]> = record
+            .withdrawals
+            .iter()
+            .map(|withdrawal| withdrawal.identity_id)
+            .collect();
+
+        for identity_id in identity_ids {
+            batch.add_insert(
+                withdrawal_transactions_by_identity_path_vec(),
+                make_withdrawal_transaction_by_identity_key(&identity_id, record.index),
+                make_withdrawal_transaction_record_reference(record.index),
+            );
+        }
+
+        match record.status {
+            WithdrawalStatus::Pooled | WithdrawalStatus::Broadcasted => batch.add_insert(
+                withdrawal_transactions_in_flight_path_vec(),
+                index_bytes,
+                make_withdrawal_transaction_record_reference(record.index),
+            ),
+            WithdrawalStatus::Complete | WithdrawalStatus::Expired => {
+                batch.add_delete(withdrawal_transactions_in_flight_path_vec(), index_bytes)
+            }
+            WithdrawalStatus::Queued => {}
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the withdrawal transaction record by asset unlock index
+    pub fn fetch_withdrawal_transaction_record(
+        &self,
+        index: u64,
+        transaction: TransactionArg,
+    ) -> Result<Option<WithdrawalTransactionRecord>, Error> {
+        let element = match self
+            .grove
+            .get(
+                [
+                    Into::<&[u8; 1]>::into(RootTree::WithdrawalTransactions).as_slice(),
+                    &WITHDRAWAL_TRANSACTIONS_STATUSES_ID,
+                ],
+                &index.to_be_bytes(),
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_)) => return Ok(None),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        if let Element::Item(bytes, _) = element {
+            Ok(Some(WithdrawalTransactionRecord::from_bytes(&bytes)?))
+        } else {
+            Err(Error::Drive(DriveError::CorruptedWithdrawalNotItem(
+                "withdrawal transaction record must be an item",
+            )))
+        }
+    }
+
+    /// Returns a path query for all withdrawal transaction records of the identity
+    fn identity_withdrawal_transaction_records_path_query(identity_id: &[u8; 32]) -> PathQuery {
+        let mut query = Query::new();
+
+        query.insert_item(QueryItem::RangeInclusive(
+            make_withdrawal_transaction_by_identity_key(identity_id, 0)
+                ..=make_withdrawal_transaction_by_identity_key(identity_id, u64::MAX),
+        ));
+
+        PathQuery::new(
+            withdrawal_transactions_by_identity_path_vec(),
+            SizedQuery::new(query, None, None),
+        )
+    }
+
+    /// Fetches all withdrawal transaction records of the identity ordered by index
+    pub fn fetch_identity_withdrawal_transaction_records(
+        &self,
+        identity_id: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<WithdrawalTransactionRecord>, Error> {
+        let path_query = Self::identity_withdrawal_transaction_records_path_query(identity_id);
+
+        let (items, _) = self.grove_get_path_query(&path_query, transaction, &mut vec![])?;
+
+        items
+            .iter()
+            .map(|bytes| WithdrawalTransactionRecord::from_bytes(bytes))
+            .collect()
+    }
+
+    /// Proves all withdrawal transaction records of the identity
+    pub fn prove_identity_withdrawal_transaction_records(
+        &self,
+        identity_id: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let path_query = Self::identity_withdrawal_transaction_records_path_query(identity_id);
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+
+    /// Fetches records of pooled and broadcasted withdrawal transactions
+    pub fn fetch_in_flight_withdrawal_transaction_records(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Vec<WithdrawalTransactionRecord>, Error> {
+        let mut query = Query::new();
+
+        query.insert_item(QueryItem::RangeFull(RangeFull));
+
+        let path_query = PathQuery::new(
+            withdrawal_transactions_in_flight_path_vec(),
+            SizedQuery::new(query, None, None),
+        );
+
+        let (items, _) = self.grove_get_path_query(&path_query, transaction, &mut vec![])?;
+
+        items
+            .iter()
+            .map(|bytes| WithdrawalTransactionRecord::from_bytes(bytes))
+            .collect()
+    }
+
+    /// Adds operations to the op batch which move pooled and broadcasted withdrawal transactions
+    /// to the next status according to Core state at the given core chain locked height.
+    /// Credits of expired withdrawal transactions are refunded to identities.
+    ///
+    /// Returns records of expired transactions
+    pub fn add_update_withdrawal_transaction_statuses_operations(
+        &self,
+        core_chain_locked_height: u32,
+        broadcasted_indexes: &[u64],
+        completed_indexes: &[u64],
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<Vec<WithdrawalTransactionRecord>, Error> {
+        let mut expired_records = vec![];
+
+        for mut record in self.fetch_in_flight_withdrawal_transaction_records(transaction)? {
+            if completed_indexes.contains(&record.index) {
+                record.status = WithdrawalStatus::Complete;
+            } else if record.is_expired_at(core_chain_locked_height) {
+                record.status = WithdrawalStatus::Expired;
+            } else if record.status == WithdrawalStatus::Pooled
+                && broadcasted_indexes.contains(&record.index)
+            {
+                record.status = WithdrawalStatus::Broadcasted;
+            } else {
+                continue;
+            }
+
+            self.add_insert_withdrawal_transaction_record_operations(batch, &record)?;
+
+            if record.status == WithdrawalStatus::Expired {
+                expired_records.push(record);
+            }
+        }
+
+        // Identity is fetched once, so multiple refunds to the same identity are summed up
+        let mut refunds: BTreeMap<[u8; 32], u64> = BTreeMap::new();
+
+        for withdrawal in expired_records
+            .iter()
+            .flat_map(|record| record.withdrawals.iter())
+        {
+            let refund = refunds.entry(withdrawal.identity_id).or_default();
+
+            *refund = refund.checked_add(withdrawal.amount).ok_or(Error::Drive(
+                DriveError::CorruptedCodeExecution("withdrawal refund overflow"),
+            ))?;
+        }
+
+        for (identity_id, refund) in refunds {
+            // refunds settle the identity debt first
+            let credited_to_balance = self.add_add_to_identity_balance_operations(
+                &identity_id,
+                refund,
+                transaction,
+                batch,
+            )?;
+
+            self.add_update_credits_ledger_operations(
+                |credits_ledger| credits_ledger.revert_burn(refund, credited_to_balance),
+                transaction,
+                batch,
+            )?;
+        }
+
+        Ok(expired_records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+
+    fn enqueue_withdrawal(
+        drive: &Drive,
+        index: u64,
+        identity_id: [u8; 32],
+        amount: u64,
+        transaction: TransactionArg,
+    ) {
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_enqueue_withdrawal_transaction_operations(
+                &mut batch,
+                vec![(
+                    WithdrawalTransactionRecord::new_queued(index, identity_id, amount),
+                    vec![index as u8; 32],
+                )],
+            )
+            .expect("should add enqueue operations");
+
+        drive
+            .grove_apply_batch(batch, false, transaction)
+            .expect("should apply batch");
+    }
+
+    fn update_statuses(
+        drive: &Drive,
+        core_chain_locked_height: u32,
+        broadcasted_indexes: &[u64],
+        completed_indexes: &[u64],
+        transaction: TransactionArg,
+    ) -> Vec<WithdrawalTransactionRecord> {
+        let mut batch = GroveDbOpBatch::new();
+
+        let expired_records = drive
+            .add_update_withdrawal_transaction_statuses_operations(
+                core_chain_locked_height,
+                broadcasted_indexes,
+                completed_indexes,
+                transaction,
+                &mut batch,
+            )
+            .expect("should update statuses");
+
+        if !batch.is_empty() {
+            drive
+                .grove_apply_batch(batch, false, transaction)
+                .expect("should apply batch");
+        }
+
+        expired_records
+    }
+
+    #[test]
+    fn test_withdrawal_lifecycle_to_complete() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        enqueue_withdrawal(&drive, 1, identity.id.to_buffer(), 100, Some(&transaction));
+
+        let record = drive
+            .fetch_withdrawal_transaction_record(1, Some(&transaction))
+            .expect("should fetch record")
+            .expect("should have record");
+
+        assert_eq!(record.status, WithdrawalStatus::Queued);
+
+        drive
+            .dequeue_withdrawal_transactions(16, 10, 1, Some(&transaction))
+            .expect("should dequeue");
+
+        let record = drive
+            .fetch_withdrawal_transaction_record(1, Some(&transaction))
+            .expect("should fetch record")
+            .expect("should have record");
+
+        assert_eq!(record.status, WithdrawalStatus::Pooled);
+        assert_eq!(record.request_height, Some(10));
+
+        update_statuses(&drive, 11, &[1], &[], Some(&transaction));
+
+        let record = drive
+            .fetch_withdrawal_transaction_record(1, Some(&transaction))
+            .expect("should fetch record")
+            .expect("should have record");
+
+        assert_eq!(record.status, WithdrawalStatus::Broadcasted);
+
+        update_statuses(&drive, 12, &[], &[1], Some(&transaction));
+
+        let records = drive
+            .fetch_identity_withdrawal_transaction_records(
+                &identity.id.to_buffer(),
+                Some(&transaction),
+            )
+            .expect("should fetch identity records");
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, WithdrawalStatus::Complete);
+
+        let in_flight_records = drive
+            .fetch_in_flight_withdrawal_transaction_records(Some(&transaction))
+            .expect("should fetch in flight records");
+
+        assert!(in_flight_records.is_empty());
+    }
+
+    #[test]
+    fn test_expired_withdrawal_is_refunded() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        enqueue_withdrawal(&drive, 1, identity.id.to_buffer(), 100, Some(&transaction));
+        enqueue_withdrawal(&drive, 2, identity.id.to_buffer(), 50, Some(&transaction));
+
+        drive
+            .dequeue_withdrawal_transactions(16, 10, 1, Some(&transaction))
+            .expect("should dequeue");
+
+        let expired_records = update_statuses(
+            &drive,
+            10 + WITHDRAWAL_TRANSACTION_EXPIRATION_CORE_BLOCKS,
+            &[1, 2],
+            &[],
+            Some(&transaction),
+        );
+
+        assert!(expired_records.is_empty());
+
+        let expired_records = update_statuses(
+            &drive,
+            11 + WITHDRAWAL_TRANSACTION_EXPIRATION_CORE_BLOCKS,
+            &[],
+            &[],
+            Some(&transaction),
+        );
+
+        assert_eq!(expired_records.len(), 2);
+
+        let (stored_identity, _) = drive
+            .fetch_identity(&identity.id.to_buffer(), Some(&transaction))
+            .expect("should fetch identity");
+
+        assert_eq!(stored_identity.balance, identity.balance + 150);
+
+        let records = drive
+            .fetch_identity_withdrawal_transaction_records(
+                &identity.id.to_buffer(),
+                Some(&transaction),
+            )
+            .expect("should fetch identity records");
+
+        assert!(records
+            .iter()
+            .all(|record| record.status == WithdrawalStatus::Expired));
+    }
+
+    #[test]
+    fn test_prove_identity_withdrawal_transaction_records() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        enqueue_withdrawal(&drive, 1, [1; 32], 100, Some(&transaction));
+
+        let proof = drive
+            .prove_identity_withdrawal_transaction_records(&[1; 32], Some(&transaction))
+            .expect("should prove records");
+
+        assert!(!proof.is_empty());
+    }
+}
@@ -32,6 +32,7 @@
 
 use crate::drive::batch::GroveDbOpBatch;
 use crate::drive::contract::add_init_contracts_structure_operations;
+use crate::drive::masternode_list::add_initial_masternode_list_structure_operations;
 use crate::drive::{Drive, RootTree};
 use crate::error::Error;
 use crate::fee_pools::add_create_fee_pool_trees_operations;
@@ -56,20 +57,42 @@ impl Drive {
 
         batch.add_insert_empty_tree(vec![], vec![RootTree::Misc as u8]);
 
+        add_initial_masternode_list_structure_operations(&mut batch);
+
         add_initial_withdrawal_state_structure_operations(&mut batch);
 
         // initialize the pools with epochs
-        add_create_fee_pool_trees_operations(&mut batch);
+        add_create_fee_pool_trees_operations(&mut batch, self.config.perpetual_storage_epochs());
 
         self.grove_apply_batch(batch, false, transaction)?;
 
         Ok(())
     }
+
+    /// Creates the parts of the state structure which were added after the chain
+    /// was initialized, so chains started with a previous version keep working.
+    pub fn create_missing_state_structure(&self, transaction: TransactionArg) -> Result<(), Error> {
+        let mut batch = GroveDbOpBatch::new();
+
+        self.add_missing_masternode_list_structure_operations(transaction, &mut batch)?;
+
+        self.add_missing_withdrawal_state_structure_operations(transaction, &mut batch)?;
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        self.grove_apply_batch(batch, false, transaction)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::drive::Drive;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::drive::identity::withdrawal_queue::WITHDRAWN_CREDITS_ID;
+    use crate::drive::masternode_list::KEY_MASTERNODE_LIST;
+    use crate::drive::{Drive, RootTree};
+    use grovedb::batch::GroveDbOp;
     use grovedb::query_result_type::QueryResultType::QueryElementResultType;
     use grovedb::{PathQuery, Query, SizedQuery};
     use tempfile::TempDir;
@@ -103,4 +126,53 @@ mod tests {
             .expect("expected to get root elements");
         assert_eq!(elements.len(), 7);
     }
+
+    #[test]
+    fn test_create_missing_state_structure() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("should open Drive successfully");
+
+        drive
+            .create_initial_state_structure(None)
+            .expect("expected to create structure");
+
+        // Remove subtrees as if the chain was initialized before they were introduced
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.push(GroveDbOp::delete_tree_run_op(
+            vec![vec![RootTree::Misc as u8]],
+            KEY_MASTERNODE_LIST.to_vec(),
+        ));
+
+        batch.push(GroveDbOp::delete_tree_run_op(
+            vec![vec![RootTree::WithdrawalTransactions as u8]],
+            WITHDRAWN_CREDITS_ID.to_vec(),
+        ));
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("should apply batch");
+
+        drive
+            .create_missing_state_structure(None)
+            .expect("expected to create missing structure");
+
+        let masternode_list = drive
+            .fetch_masternode_list(None)
+            .expect("should fetch masternode list");
+
+        assert!(masternode_list.is_empty());
+
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_missing_masternode_list_structure_operations(None, &mut batch)
+            .expect("should add missing masternode list structure operations");
+
+        drive
+            .add_missing_withdrawal_state_structure_operations(None, &mut batch)
+            .expect("should add missing withdrawal state structure operations");
+
+        assert!(batch.is_empty());
+    }
 }
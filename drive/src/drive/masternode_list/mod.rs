@@ -0,0 +1,615 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Simplified Masternode List.
+//!
+//! This module defines functions within the Drive struct to store the simplified
+//! masternode list, update it from masternode list diffs supplied by Core and query it.
+//!
+
+use std::ops::RangeFull;
+
+use bincode::Options;
+use dpp::mocks::{SMLEntry, SMLStore, SimplifiedMNList};
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+use serde::{Deserialize, Serialize};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+
+/// Masternode status in the masternode list
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MasternodeStatus {
+    /// Masternode is valid
+    Valid,
+    /// Masternode is PoSe banned
+    Banned,
+}
+
+/// Simplified masternode list entry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MasternodeListEntry {
+    /// Hash of the ProRegTx
+    pub pro_tx_hash: [u8; 32],
+    /// Hash160 of the owner key
+    pub owner_key_hash: [u8; 20],
+    /// Hash160 of the voting key
+    pub voting_key_hash: [u8; 20],
+    /// Script for masternode reward payouts
+    pub payout_script: Vec<u8>,
+    /// BLS public key of the operator
+    pub operator_public_key: Vec<u8>,
+    /// Masternode status
+    pub status: MasternodeStatus,
+}
+
+impl MasternodeListEntry {
+    /// Serializes the masternode list entry
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialize(self)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to serialize masternode list entry",
+                ))
+            })
+    }
+
+    /// Deserializes the masternode list entry
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .deserialize(bytes)
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "unable to deserialize masternode list entry",
+                ))
+            })
+    }
+
+    /// Returns true if the masternode is valid
+    pub fn is_valid(&self) -> bool {
+        self.status == MasternodeStatus::Valid
+    }
+}
+
+/// Changes of the simplified masternode list since the previous block
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MasternodeListDiff {
+    /// Registered or updated masternodes
+    pub updated_masternodes: Vec<MasternodeListEntry>,
+    /// ProTxHashes of removed masternodes
+    pub removed_masternodes: Vec<[u8; 32]>,
+    /// Updated masternodes are the whole masternode list, which replaces the stored one.
+    /// Core sends it to seed the masternode list of chains started before it was stored
+    #[serde(default)]
+    pub is_full_list: bool,
+}
+
+impl MasternodeListDiff {
+    /// Returns true if there are no changes
+    pub fn is_empty(&self) -> bool {
+        !self.is_full_list
+            && self.updated_masternodes.is_empty()
+            && self.removed_masternodes.is_empty()
+    }
+}
+
+/// Key of the masternode list subtree in the misc tree
+pub(crate) const KEY_MASTERNODE_LIST: &[u8; 1] = b"m";
+
+/// Key of the item in the misc tree marking the masternode list as complete
+pub(crate) const KEY_MASTERNODE_LIST_SEEDED: &[u8; 1] = b"s";
+
+/// Returns the path to the masternode list
+fn masternode_list_path_vec() -> Vec<Vec<u8>> {
+    vec![vec![RootTree::Misc as u8], KEY_MASTERNODE_LIST.to_vec()]
+}
+
+/// Adds an operation to the op batch which marks the masternode list as complete
+fn add_mark_masternode_list_as_seeded_operation(batch: &mut GroveDbOpBatch) {
+    batch.add_insert(
+        vec![vec![RootTree::Misc as u8]],
+        KEY_MASTERNODE_LIST_SEEDED.to_vec(),
+        Element::Item(vec![1], None),
+    );
+}
+
+/// Adds operations to the op batch which create the masternode list subtree.
+/// The masternode list is complete from genesis.
+pub fn add_initial_masternode_list_structure_operations(batch: &mut GroveDbOpBatch) {
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::Misc as u8]],
+        KEY_MASTERNODE_LIST.to_vec(),
+    );
+
+    add_mark_masternode_list_as_seeded_operation(batch);
+}
+
+impl Drive {
+    /// Adds operations to the op batch which create the masternode list subtree
+    /// if the chain was initialized before the masternode list was stored in Drive.
+    /// The list isn't complete until Core sends the full masternode list.
+    pub fn add_missing_masternode_list_structure_operations(
+        &self,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        let has_masternode_list = self
+            .grove
+            .has_raw(
+                [Into::<&[u8; 1]>::into(RootTree::Misc).as_slice()],
+                KEY_MASTERNODE_LIST.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)?;
+
+        if !has_masternode_list {
+            batch.add_insert_empty_tree(
+                vec![vec![RootTree::Misc as u8]],
+                KEY_MASTERNODE_LIST.to_vec(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the stored masternode list is complete, so masternodes
+    /// missing in it are not registered
+    pub fn is_masternode_list_seeded(&self, transaction: TransactionArg) -> Result<bool, Error> {
+        self.grove
+            .has_raw(
+                [Into::<&[u8; 1]>::into(RootTree::Misc).as_slice()],
+                KEY_MASTERNODE_LIST_SEEDED.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map_err(Error::GroveDB)
+    }
+
+    /// Adds operations to the op batch which apply the masternode list diff
+    pub fn add_apply_masternode_list_diff_operations(
+        &self,
+        masternode_list_diff: &MasternodeListDiff,
+        transaction: TransactionArg,
+        batch: &mut GroveDbOpBatch,
+    ) -> Result<(), Error> {
+        if masternode_list_diff.is_full_list {
+            // Masternodes missing in the full list are removed
+            for masternode in self.fetch_masternode_list(transaction)? {
+                if !masternode_list_diff
+                    .updated_masternodes
+                    .iter()
+                    .any(|updated| updated.pro_tx_hash == masternode.pro_tx_hash)
+                {
+                    batch.add_delete(masternode_list_path_vec(), masternode.pro_tx_hash.to_vec());
+                }
+            }
+
+            add_mark_masternode_list_as_seeded_operation(batch);
+        }
+
+        for masternode in &masternode_list_diff.updated_masternodes {
+            batch.add_insert(
+                masternode_list_path_vec(),
+                masternode.pro_tx_hash.to_vec(),
+                Element::Item(masternode.to_bytes()?, None),
+            );
+        }
+
+        for pro_tx_hash in &masternode_list_diff.removed_masternodes {
+            // Core might report removal of a masternode we never received
+            if self.fetch_masternode(pro_tx_hash, transaction)?.is_some() {
+                batch.add_delete(masternode_list_path_vec(), pro_tx_hash.to_vec());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the masternode list diff
+    pub fn apply_masternode_list_diff(
+        &self,
+        masternode_list_diff: &MasternodeListDiff,
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        if masternode_list_diff.is_empty() {
+            return Ok(());
+        }
+
+        let mut batch = GroveDbOpBatch::new();
+
+        self.add_apply_masternode_list_diff_operations(
+            masternode_list_diff,
+            transaction,
+            &mut batch,
+        )?;
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        self.grove_apply_batch(batch, false, transaction)
+    }
+
+    /// Fetches a masternode from the masternode list by its proTxHash
+    pub fn fetch_masternode(
+        &self,
+        pro_tx_hash: &[u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Option<MasternodeListEntry>, Error> {
+        let element = self
+            .grove
+            .get(
+                [
+                    Into::<&[u8; 1]>::into(RootTree::Misc).as_slice(),
+                    KEY_MASTERNODE_LIST.as_slice(),
+                ],
+                pro_tx_hash,
+                transaction,
+            )
+            .unwrap()
+            .map(Some)
+            .or_else(|e| match e {
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_) => Ok(None),
+                _ => Err(e),
+            })
+            .map_err(Error::GroveDB)?;
+
+        match element {
+            Some(Element::Item(bytes, _)) => Ok(Some(MasternodeListEntry::from_bytes(&bytes)?)),
+            Some(_) => Err(Error::Drive(DriveError::CorruptedMasternodeNotItem(
+                "masternode must be an item",
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the whole masternode list ordered by proTxHash
+    pub fn fetch_masternode_list(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Vec<MasternodeListEntry>, Error> {
+        let mut query = Query::new();
+
+        query.insert_item(QueryItem::RangeFull(RangeFull));
+
+        let path_query = PathQuery::new(
+            masternode_list_path_vec(),
+            SizedQuery::new(query, None, None),
+        );
+
+        let key_elements = match self
+            .grove
+            .query_raw(&path_query, QueryKeyElementPairResultType, transaction)
+            .unwrap()
+        {
+            Ok((query_result, _)) => query_result.to_key_elements(),
+            Err(grovedb::Error::PathNotFound(_))
+            | Err(grovedb::Error::PathParentLayerNotFound(_))
+            | Err(grovedb::Error::PathKeyNotFound(_)) => vec![],
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        key_elements
+            .into_iter()
+            .map(|(_, element)| match element {
+                Element::Item(bytes, _) => MasternodeListEntry::from_bytes(&bytes),
+                _ => Err(Error::Drive(DriveError::CorruptedMasternodeNotItem(
+                    "masternode must be an item",
+                ))),
+            })
+            .collect()
+    }
+
+    /// Fetches the valid masternodes of the stored masternode list as a simplified
+    /// masternode list store, as expected by the reward share data trigger.
+    ///
+    /// Drive only keeps the current masternode list, so it is also returned as the
+    /// list by height.
+    pub fn fetch_sml_store(&self, transaction: TransactionArg) -> Result<SMLStore, Error> {
+        let masternodes: Vec<SMLEntry> = self
+            .fetch_masternode_list(transaction)?
+            .into_iter()
+            .filter(MasternodeListEntry::is_valid)
+            .map(|masternode| SMLEntry {
+                pro_reg_tx_hash: hex::encode(masternode.pro_tx_hash),
+                confirmed_hash: String::new(),
+                service: String::new(),
+                pub_key_operator: hex::encode(&masternode.operator_public_key),
+                voting_address: hex::encode(masternode.voting_key_hash),
+                is_valid: true,
+            })
+            .collect();
+
+        Ok(SMLStore {
+            sml_list_by_height: SimplifiedMNList {
+                masternodes: masternodes.clone(),
+            },
+            sml_list_current: SimplifiedMNList { masternodes },
+        })
+    }
+
+    /// Fetches the simplified masternode list store serialized to CBOR
+    pub fn fetch_serialized_sml_store(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let sml_store = self.fetch_sml_store(transaction)?;
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        ciborium::ser::into_writer(&sml_store, &mut buffer).map_err(|_| {
+            Error::Drive(DriveError::CorruptedSerialization(
+                "unable to serialize sml store",
+            ))
+        })?;
+
+        Ok(buffer)
+    }
+
+    /// Proves masternodes by their proTxHashes
+    pub fn prove_masternodes(
+        &self,
+        pro_tx_hashes: &[[u8; 32]],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut query = Query::new();
+
+        for pro_tx_hash in pro_tx_hashes {
+            query.insert_key(pro_tx_hash.to_vec());
+        }
+
+        let path_query = PathQuery::new(
+            masternode_list_path_vec(),
+            SizedQuery::new(query, None, None),
+        );
+
+        self.grove_get_proved_path_query(&path_query, transaction, &mut vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+
+    fn create_masternode(pro_tx_hash: u8, status: MasternodeStatus) -> MasternodeListEntry {
+        MasternodeListEntry {
+            pro_tx_hash: [pro_tx_hash; 32],
+            owner_key_hash: [1; 20],
+            voting_key_hash: [2; 20],
+            payout_script: vec![3; 25],
+            operator_public_key: vec![4; 48],
+            status,
+        }
+    }
+
+    #[test]
+    fn test_apply_masternode_list_diff() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .apply_masternode_list_diff(
+                &MasternodeListDiff {
+                    updated_masternodes: vec![
+                        create_masternode(1, MasternodeStatus::Valid),
+                        create_masternode(2, MasternodeStatus::Valid),
+                        create_masternode(3, MasternodeStatus::Valid),
+                    ],
+                    removed_masternodes: vec![],
+                    is_full_list: false,
+                },
+                Some(&transaction),
+            )
+            .expect("should apply masternode list diff");
+
+        drive
+            .apply_masternode_list_diff(
+                &MasternodeListDiff {
+                    updated_masternodes: vec![create_masternode(2, MasternodeStatus::Banned)],
+                    removed_masternodes: vec![[3; 32], [4; 32]],
+                    is_full_list: false,
+                },
+                Some(&transaction),
+            )
+            .expect("should apply masternode list diff");
+
+        let masternode_list = drive
+            .fetch_masternode_list(Some(&transaction))
+            .expect("should fetch masternode list");
+
+        assert_eq!(
+            masternode_list,
+            vec![
+                create_masternode(1, MasternodeStatus::Valid),
+                create_masternode(2, MasternodeStatus::Banned),
+            ]
+        );
+
+        let masternode = drive
+            .fetch_masternode(&[3; 32], Some(&transaction))
+            .expect("should fetch masternode");
+
+        assert!(masternode.is_none());
+
+        let proof = drive
+            .prove_masternodes(&[[1; 32], [3; 32]], Some(&transaction))
+            .expect("should prove masternodes");
+
+        assert!(!proof.is_empty());
+    }
+
+    #[test]
+    fn test_fetch_sml_store_returns_valid_masternodes() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .apply_masternode_list_diff(
+                &MasternodeListDiff {
+                    updated_masternodes: vec![
+                        create_masternode(1, MasternodeStatus::Valid),
+                        create_masternode(2, MasternodeStatus::Banned),
+                    ],
+                    removed_masternodes: vec![],
+                    is_full_list: false,
+                },
+                Some(&transaction),
+            )
+            .expect("should apply masternode list diff");
+
+        let sml_store = drive
+            .fetch_sml_store(Some(&transaction))
+            .expect("should fetch sml store");
+
+        let valid_masternodes = sml_store
+            .get_current_sml()
+            .expect("should get current sml")
+            .get_valid_master_nodes();
+
+        assert_eq!(valid_masternodes.len(), 1);
+        assert_eq!(valid_masternodes[0].pro_reg_tx_hash, hex::encode([1; 32]));
+        assert_eq!(valid_masternodes[0].pub_key_operator, hex::encode([4; 48]));
+
+        let serialized_sml_store = drive
+            .fetch_serialized_sml_store(Some(&transaction))
+            .expect("should fetch serialized sml store");
+
+        let deserialized_sml_store: SMLStore =
+            ciborium::de::from_reader(serialized_sml_store.as_slice())
+                .expect("should deserialize sml store");
+
+        assert_eq!(deserialized_sml_store.sml_list_current.masternodes.len(), 1);
+    }
+
+    #[test]
+    fn test_full_masternode_list_replaces_stored_list_and_seeds_it() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        // Remove the masternode list as if the chain was initialized before it was stored
+        let mut batch = GroveDbOpBatch::new();
+
+        batch.add_delete(
+            vec![vec![RootTree::Misc as u8]],
+            KEY_MASTERNODE_LIST_SEEDED.to_vec(),
+        );
+
+        batch.push(grovedb::batch::GroveDbOp::delete_tree_run_op(
+            vec![vec![RootTree::Misc as u8]],
+            KEY_MASTERNODE_LIST.to_vec(),
+        ));
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let masternode = drive
+            .fetch_masternode(&[1; 32], Some(&transaction))
+            .expect("should fetch masternode");
+
+        assert!(masternode.is_none());
+
+        let mut batch = GroveDbOpBatch::new();
+
+        drive
+            .add_missing_masternode_list_structure_operations(Some(&transaction), &mut batch)
+            .expect("should add missing masternode list structure operations");
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        assert!(!drive
+            .is_masternode_list_seeded(Some(&transaction))
+            .expect("should check masternode list is seeded"));
+
+        drive
+            .apply_masternode_list_diff(
+                &MasternodeListDiff {
+                    updated_masternodes: vec![
+                        create_masternode(1, MasternodeStatus::Valid),
+                        create_masternode(2, MasternodeStatus::Valid),
+                    ],
+                    removed_masternodes: vec![],
+                    is_full_list: false,
+                },
+                Some(&transaction),
+            )
+            .expect("should apply masternode list diff");
+
+        assert!(!drive
+            .is_masternode_list_seeded(Some(&transaction))
+            .expect("should check masternode list is seeded"));
+
+        drive
+            .apply_masternode_list_diff(
+                &MasternodeListDiff {
+                    updated_masternodes: vec![
+                        create_masternode(2, MasternodeStatus::Valid),
+                        create_masternode(3, MasternodeStatus::Valid),
+                    ],
+                    removed_masternodes: vec![],
+                    is_full_list: true,
+                },
+                Some(&transaction),
+            )
+            .expect("should apply full masternode list");
+
+        assert!(drive
+            .is_masternode_list_seeded(Some(&transaction))
+            .expect("should check masternode list is seeded"));
+
+        let masternode_list = drive
+            .fetch_masternode_list(Some(&transaction))
+            .expect("should fetch masternode list");
+
+        assert_eq!(
+            masternode_list,
+            vec![
+                create_masternode(2, MasternodeStatus::Valid),
+                create_masternode(3, MasternodeStatus::Valid),
+            ]
+        );
+    }
+}
@@ -42,8 +42,12 @@ use crate::error::Error;
 use crate::fee::op::DriveOperation;
 use crate::fee::op::DriveOperation::GroveOperation;
 
+/// App hash module
+pub mod app_hash;
 /// Batch module
 pub mod batch;
+/// Block execution context module
+pub mod block_execution_context;
 /// Block info module
 pub mod block_info;
 /// Drive Cache
@@ -51,6 +55,8 @@ pub mod cache;
 pub mod config;
 /// Contract module
 pub mod contract;
+/// Credits ledger module
+pub mod credits_ledger;
 pub mod defaults;
 /// Document module
 pub mod document;
@@ -63,7 +69,13 @@ mod grove_operations;
 /// Identity module
 pub mod identity;
 pub mod initialization;
+/// Masternode list module
+pub mod masternode_list;
 pub mod object_size_info;
+/// Platform config module
+pub mod platform_config;
+/// Protocol version module
+pub mod protocol_version;
 pub mod query;
 
 use crate::drive::block_info::BlockInfo;
@@ -145,9 +157,12 @@ fn contract_documents_path(contract_id: &[u8]) -> [&[u8]; 3] {
 impl Drive {
     /// Opens a path in groveDB.
     pub fn open<P: AsRef<Path>>(path: P, config: Option<DriveConfig>) -> Result<Self, Error> {
+        let config = config.unwrap_or_default();
+
+        config.validate()?;
+
         match GroveDb::open(path) {
             Ok(grove) => {
-                let config = config.unwrap_or_default();
                 let genesis_time_ms = config.default_genesis_time;
                 let data_contracts_global_cache_size = config.data_contracts_global_cache_size;
                 let data_contracts_transactional_cache_size =
@@ -119,6 +119,15 @@ impl<'a, const N: usize> PathInfo<'a, N> {
     }
 }
 
+/// Estimation of deletion costs when operations are not applied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeletionCostEstimation {
+    /// Every tree up to the stop path height becomes empty and is deleted with the element
+    WorstCase,
+    /// Only the element is deleted, trees above it still hold other elements
+    AverageCase,
+}
+
 /// Key info
 #[derive(Clone)]
 pub enum DriveKeyInfo<'a> {
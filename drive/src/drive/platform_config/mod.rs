@@ -0,0 +1,127 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Platform Config.
+//!
+//! This module defines functions to persist serialized chain parameters of the platform
+//! in the misc tree on chain initialization, so they can't change during the chain lifetime.
+//!
+
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use grovedb::batch::GroveDbOp;
+use grovedb::{Element, TransactionArg};
+
+/// Key of the platform config in the misc tree
+pub(crate) const KEY_PLATFORM_CONFIG: &[u8; 1] = b"p";
+
+/// Returns a groveDB operation which stores the serialized platform config
+pub fn update_platform_config_operation(platform_config_bytes: Vec<u8>) -> GroveDbOp {
+    GroveDbOp::insert_run_op(
+        vec![vec![RootTree::Misc as u8]],
+        KEY_PLATFORM_CONFIG.to_vec(),
+        Element::Item(platform_config_bytes, None),
+    )
+}
+
+impl Drive {
+    /// Stores the serialized platform config
+    pub fn store_platform_config(
+        &self,
+        platform_config_bytes: Vec<u8>,
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        let op = update_platform_config_operation(platform_config_bytes);
+
+        self.grove_apply_operation(op, false, transaction)
+    }
+
+    /// Returns the serialized platform config or `None` if the chain is not initialized yet
+    pub fn fetch_platform_config(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let element = self
+            .grove
+            .get(
+                [Into::<&[u8; 1]>::into(RootTree::Misc).as_slice()],
+                KEY_PLATFORM_CONFIG.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map(Some)
+            .or_else(|e| match e {
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_) => Ok(None),
+                _ => Err(e),
+            })?;
+
+        match element {
+            Some(Element::Item(bytes, _)) => Ok(Some(bytes)),
+            Some(_) => Err(Error::Drive(DriveError::CorruptedPlatformConfigNotItem(
+                "stored platform config must be an item",
+            ))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::{setup_drive, setup_drive_with_initial_state_structure};
+
+    #[test]
+    fn test_returns_none_if_initial_structure_is_not_initiated() {
+        let drive = setup_drive(None);
+
+        let platform_config = drive
+            .fetch_platform_config(None)
+            .expect("should fetch platform config");
+
+        assert!(platform_config.is_none());
+    }
+
+    #[test]
+    fn test_store_platform_config() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .store_platform_config(vec![1, 2, 3], Some(&transaction))
+            .expect("should store platform config");
+
+        let platform_config = drive
+            .fetch_platform_config(Some(&transaction))
+            .expect("should fetch platform config");
+
+        assert_eq!(platform_config, Some(vec![1, 2, 3]));
+    }
+}
@@ -0,0 +1,260 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Protocol Version.
+//!
+//! This module defines functions relevant to the active protocol version of the chain.
+//!
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use crate::fee_pools::epochs::{Epoch, DEFAULT_FEE_MULTIPLIER};
+use dpp::version::{
+    ProtocolVersionValidator, COMPATIBILITY_MAP, INITIAL_PROTOCOL_VERSION, LATEST_VERSION,
+};
+use grovedb::{Element, TransactionArg};
+
+/// Key of the active protocol version in the misc tree
+pub(crate) const KEY_PROTOCOL_VERSION: &[u8; 1] = b"v";
+
+impl Drive {
+    /// Returns the active protocol version from storage.
+    /// Returns `None` if the protocol version is not set yet.
+    pub fn get_protocol_version(&self, transaction: TransactionArg) -> Result<Option<u32>, Error> {
+        let element = self
+            .grove
+            .get(
+                [Into::<&[u8; 1]>::into(RootTree::Misc).as_slice()],
+                KEY_PROTOCOL_VERSION.as_slice(),
+                transaction,
+            )
+            .unwrap()
+            .map(Some)
+            .or_else(|e| match e {
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_) => Ok(None),
+                _ => Err(e),
+            })?;
+
+        match element {
+            Some(Element::Item(item, _)) => {
+                let protocol_version =
+                    u32::from_be_bytes(item.as_slice().try_into().map_err(|_| {
+                        Error::Drive(DriveError::CorruptedProtocolVersion(
+                            "protocol version item have an invalid length",
+                        ))
+                    })?);
+
+                Ok(Some(protocol_version))
+            }
+            Some(_) => Err(Error::Drive(DriveError::CorruptedProtocolVersion(
+                "protocol version must be an item",
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a dpp protocol version validator for the active protocol version from storage,
+    /// so state transitions are validated against the version the chain runs.
+    pub fn get_protocol_version_validator(
+        &self,
+        transaction: TransactionArg,
+    ) -> Result<ProtocolVersionValidator, Error> {
+        let protocol_version = self
+            .get_protocol_version(transaction)?
+            .unwrap_or(INITIAL_PROTOCOL_VERSION);
+
+        Ok(ProtocolVersionValidator::new(
+            protocol_version,
+            LATEST_VERSION,
+            COMPATIBILITY_MAP.clone(),
+        ))
+    }
+
+    /// Returns the epoch with the fee schedule of the active protocol version and
+    /// the fee multiplier of the epoch from storage.
+    /// The default fee multiplier is used if the epoch hasn't started yet.
+    pub fn get_epoch_with_active_protocol_version(
+        &self,
+        epoch_index: u16,
+        transaction: TransactionArg,
+    ) -> Result<Epoch, Error> {
+        let protocol_version = self
+            .get_protocol_version(transaction)?
+            .unwrap_or(INITIAL_PROTOCOL_VERSION);
+
+        let fee_multiplier =
+            match self.get_epoch_fee_multiplier(&Epoch::new(epoch_index), transaction) {
+                Ok(fee_multiplier) => fee_multiplier,
+                Err(Error::GroveDB(
+                    grovedb::Error::PathKeyNotFound(_)
+                    | grovedb::Error::PathNotFound(_)
+                    | grovedb::Error::PathParentLayerNotFound(_),
+                )) => DEFAULT_FEE_MULTIPLIER,
+                Err(e) => return Err(e),
+            };
+
+        Ok(Epoch::new_with_fee_multiplier_and_protocol_version(
+            epoch_index,
+            fee_multiplier,
+            protocol_version,
+        ))
+    }
+
+    /// Adds an operation to the batch which updates the active protocol version
+    pub fn add_update_protocol_version_operation(
+        &self,
+        batch: &mut GroveDbOpBatch,
+        protocol_version: u32,
+    ) {
+        batch.add_insert(
+            vec![vec![RootTree::Misc as u8]],
+            KEY_PROTOCOL_VERSION.to_vec(),
+            Element::Item(protocol_version.to_be_bytes().to_vec(), None),
+        );
+    }
+
+    /// Sets the active protocol version
+    pub fn update_protocol_version(
+        &self,
+        protocol_version: u32,
+        transaction: TransactionArg,
+    ) -> Result<(), Error> {
+        let mut batch = GroveDbOpBatch::new();
+
+        self.add_update_protocol_version_operation(&mut batch, protocol_version);
+
+        self.grove_apply_batch(batch, false, transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::fee_pools::epochs::{Epoch, DEFAULT_FEE_MULTIPLIER};
+    use dpp::version::INITIAL_PROTOCOL_VERSION;
+
+    #[test]
+    fn test_returns_none_if_protocol_version_is_not_set() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let protocol_version = drive
+            .get_protocol_version(None)
+            .expect("should get protocol version");
+
+        assert!(protocol_version.is_none());
+    }
+
+    #[test]
+    fn test_update_protocol_version() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .update_protocol_version(1, Some(&transaction))
+            .expect("should update protocol version");
+
+        drive
+            .update_protocol_version(2, Some(&transaction))
+            .expect("should update protocol version");
+
+        let protocol_version = drive
+            .get_protocol_version(Some(&transaction))
+            .expect("should get protocol version");
+
+        assert_eq!(protocol_version, Some(2));
+    }
+
+    #[test]
+    fn test_protocol_version_validator_uses_active_protocol_version() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let protocol_version_validator = drive
+            .get_protocol_version_validator(Some(&transaction))
+            .expect("should get protocol version validator");
+
+        assert_eq!(
+            protocol_version_validator.protocol_version(),
+            INITIAL_PROTOCOL_VERSION
+        );
+
+        drive
+            .update_protocol_version(2, Some(&transaction))
+            .expect("should update protocol version");
+
+        let protocol_version_validator = drive
+            .get_protocol_version_validator(Some(&transaction))
+            .expect("should get protocol version validator");
+
+        assert_eq!(protocol_version_validator.protocol_version(), 2);
+    }
+
+    #[test]
+    fn test_epoch_with_active_protocol_version() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .update_protocol_version(2, Some(&transaction))
+            .expect("should update protocol version");
+
+        let epoch = drive
+            .get_epoch_with_active_protocol_version(3, Some(&transaction))
+            .expect("should get epoch");
+
+        assert_eq!(epoch.index, 3);
+        assert_eq!(epoch.protocol_version, 2);
+        assert_eq!(epoch.fee_multiplier, DEFAULT_FEE_MULTIPLIER);
+    }
+
+    #[test]
+    fn test_epoch_with_active_protocol_version_has_stored_fee_multiplier() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let mut batch = GroveDbOpBatch::new();
+
+        Epoch::new(3).add_init_current_operations(2.5, 1, 1, &mut batch);
+
+        drive
+            .grove_apply_batch(batch, false, Some(&transaction))
+            .expect("should apply batch");
+
+        let epoch = drive
+            .get_epoch_with_active_protocol_version(3, Some(&transaction))
+            .expect("should get epoch");
+
+        assert_eq!(epoch.fee_multiplier, 2.5);
+    }
+}
@@ -74,6 +74,14 @@ pub enum DriveError {
     #[error("corrupted identity not an item error: {0}")]
     CorruptedIdentityNotItem(&'static str),
 
+    /// Error
+    #[error("corrupted identity debt not an item error: {0}")]
+    CorruptedIdentityDebtNotItem(&'static str),
+
+    /// Error
+    #[error("corrupted identity storage usage not an item error: {0}")]
+    CorruptedIdentityStorageUsageNotItem(&'static str),
+
     /// Error
     #[error("corrupted withdrawal not an item error: {0}")]
     CorruptedWithdrawalNotItem(&'static str),
@@ -83,6 +91,15 @@ pub enum DriveError {
     /// Error
     #[error("orrupted withdrawal transaction not an item: {0}")]
     CorruptedWithdrawalTransactionsCounterNotItem(&'static str),
+    /// Error
+    #[error("corrupted withdrawal transaction index invalid length: {0}")]
+    CorruptedWithdrawalTransactionIndexInvalidLength(&'static str),
+    /// Error
+    #[error("corrupted withdrawal transaction record not found: {0}")]
+    CorruptedWithdrawalTransactionRecordNotFound(&'static str),
+    /// Error
+    #[error("corrupted withdrawn credits invalid length: {0}")]
+    CorruptedWithdrawnCreditsInvalidLength(&'static str),
 
     /// Error
     #[error("corrupted element flags error: {0}")]
@@ -100,6 +117,38 @@ pub enum DriveError {
     #[error("corrupted genesis time invalid item length error: {0}")]
     CorruptedGenesisTimeInvalidItemLength(String),
 
+    /// Error
+    #[error("corrupted block execution context error: {0}")]
+    CorruptedBlockExecutionContext(&'static str),
+
+    /// Error
+    #[error("corrupted platform config not an item error: {0}")]
+    CorruptedPlatformConfigNotItem(&'static str),
+
+    /// Error
+    #[error("corrupted app hash error: {0}")]
+    CorruptedAppHash(&'static str),
+
+    /// Error
+    #[error("invalid drive config error: {0}")]
+    InvalidConfig(&'static str),
+
+    /// Error
+    #[error("corrupted protocol version error: {0}")]
+    CorruptedProtocolVersion(&'static str),
+
+    /// Error
+    #[error("corrupted masternode not an item: {0}")]
+    CorruptedMasternodeNotItem(&'static str),
+
+    /// Error
+    #[error("corrupted credits ledger not an item: {0}")]
+    CorruptedCreditsLedgerNotItem(&'static str),
+
+    /// Error
+    #[error("corrupted credits not balanced: {0}")]
+    CorruptedCreditsNotBalanced(&'static str),
+
     /// Error
     #[error("batch is empty")]
     BatchIsEmpty(),
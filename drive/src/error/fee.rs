@@ -36,6 +36,12 @@ pub enum FeeError {
     /// Corrupted proposer block count invalid item length error
     #[error("corrupted proposer block count invalid item length error: {0}")]
     CorruptedProposerBlockCountItemLength(&'static str),
+    /// Corrupted protocol version vote not an item error
+    #[error("corrupted protocol version vote not an item error: {0}")]
+    CorruptedProtocolVersionVoteNotItem(&'static str),
+    /// Corrupted protocol version vote invalid item length error
+    #[error("corrupted protocol version vote invalid item length error: {0}")]
+    CorruptedProtocolVersionVoteItemLength(&'static str),
     /// Corrupted storage fee pool not an item error
     #[error("corrupted storage fee pool not an item error: {0}")]
     CorruptedStorageFeePoolNotItem(&'static str),
@@ -48,6 +54,9 @@ pub enum FeeError {
     /// Corrupted multiplier invalid item length error
     #[error("corrupted multiplier invalid item length error: {0}")]
     CorruptedMultiplierInvalidItemLength(&'static str),
+    /// Corrupted multiplier invalid value error
+    #[error("corrupted multiplier invalid value error: {0}")]
+    CorruptedMultiplierInvalidValue(&'static str),
     /// Corrupted unpaid epoch index invalid item length error
     #[error("corrupted unpaid epoch index invalid item length error: {0}")]
     CorruptedUnpaidEpochIndexItemLength(&'static str),
@@ -57,6 +66,21 @@ pub enum FeeError {
     /// Corrupted code execution error
     #[error("corrupted removed bytes from identities serialization error: {0}")]
     CorruptedRemovedBytesFromIdentitiesSerialization(&'static str),
+    /// Corrupted proposer payout not an item error
+    #[error("corrupted proposer payout not an item error: {0}")]
+    CorruptedProposerPayoutNotItem(&'static str),
+    /// Corrupted proposer payout serialization error
+    #[error("corrupted proposer payout serialization error: {0}")]
+    CorruptedProposerPayoutSerialization(&'static str),
+    /// Corrupted storage fee cohort not an item error
+    #[error("corrupted storage fee cohort not an item error: {0}")]
+    CorruptedStorageFeeCohortNotItem(&'static str),
+    /// Corrupted storage fee cohort invalid item length error
+    #[error("corrupted storage fee cohort invalid item length error: {0}")]
+    CorruptedStorageFeeCohortItemLength(&'static str),
+    /// Corrupted epoch summary error
+    #[error("corrupted epoch summary error: {0}")]
+    CorruptedEpochSummary(&'static str),
     /// Corrupted code execution error
     #[error("corrupted code execution error: {0}")]
     CorruptedCodeExecution(&'static str),
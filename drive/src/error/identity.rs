@@ -20,4 +20,24 @@ pub enum IdentityError {
     /// Identity serialization error
     #[error("identity serialization error: {0}")]
     IdentitySerialization(&'static str),
+
+    /// Identity insufficient balance error
+    #[error("identity insufficient balance: {0}")]
+    IdentityInsufficientBalance(&'static str),
+
+    /// Identity in debt error
+    #[error("identity in debt: {0}")]
+    IdentityInDebt(&'static str),
+
+    /// Identity already exists error
+    #[error("identity already exists: {0}")]
+    IdentityAlreadyExists(&'static str),
+
+    /// Withdrawal amount above the withdrawal limit error
+    #[error("withdrawal amount above limit: {0}")]
+    WithdrawalAmountAboveLimit(&'static str),
+
+    /// Invalid identity balance change error
+    #[error("invalid identity balance change: {0}")]
+    InvalidIdentityBalanceChange(&'static str),
 }
@@ -1,12 +0,0 @@
-/// Storage disk usage credit per byte
-pub(crate) const STORAGE_DISK_USAGE_CREDIT_PER_BYTE: u64 = 27000;
-/// Storage processing credit per byte
-pub(crate) const STORAGE_PROCESSING_CREDIT_PER_BYTE: u64 = 400;
-/// Storage load credit per byte
-pub(crate) const STORAGE_LOAD_CREDIT_PER_BYTE: u64 = 400;
-/// Non storage load credit per byte
-pub(crate) const NON_STORAGE_LOAD_CREDIT_PER_BYTE: u64 = 30;
-/// Query credit per byte
-pub(crate) const QUERY_CREDIT_PER_BYTE: u64 = 10;
-/// Storage seek cost
-pub(crate) const STORAGE_SEEK_COST: u64 = 4000;
@@ -0,0 +1,848 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Fee Estimation.
+//!
+//! This module estimates the worst case fees of document state transitions and
+//! data contract creation without applying them, so that clients can know how much
+//! a transition will cost before broadcasting it.
+//!
+//! `estimate_document_transitions_fee` and `estimate_document_actions_fee` don't access
+//! GroveDB: they calculate worst case Merk costs from the document type max size,
+//! index definitions and the fee schedule of the epoch, so wallets can use them.
+//! Drive methods estimate fees with GroveDB worst case costs of Drive operations.
+//!
+
+use costs::storage_cost::removal::StorageRemovedBytes::{BasicStorageRemoval, NoStorageRemoval};
+use costs::storage_cost::StorageCost;
+use costs::OperationCost;
+use dpp::data_contract::extra::{DocumentType, DriveContractExt, Index};
+use dpp::document::document_transition::{Action, DocumentTransition};
+
+use crate::contract::Contract;
+use crate::drive::block_info::BlockInfo;
+use crate::drive::defaults::{
+    BASE_CONTRACT_DOCUMENTS_PRIMARY_KEY_PATH, CONTRACT_DOCUMENTS_PATH_HEIGHT, DEFAULT_HASH_SIZE,
+    MAX_INDEX_SIZE,
+};
+use crate::drive::object_size_info::DocumentInfo::DocumentSize;
+use crate::drive::object_size_info::{DeletionCostEstimation, DocumentAndContractInfo};
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::fee::FeeError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+use crate::fee::{calculate_fee, FeeResult};
+use crate::fee_pools::epochs::Epoch;
+
+/// Worst case height of a Merk tree.
+/// An AVL tree of this height holds at least F(34) - 1 = 5702886 nodes.
+const WORST_CASE_MERK_HEIGHT: u64 = 32;
+
+/// Blake3 calls to rehash a Merk node: the key value hash and the node hash
+const MERK_NODE_HASH_CALLS: u64 = 2;
+
+/// Prefix of storage keys, the hash of the subtree path
+const STORAGE_KEY_PREFIX_SIZE: u64 = DEFAULT_HASH_SIZE as u64;
+
+/// Biggest element flags of a new element: option and size bytes,
+/// flags type byte, base epoch and owner id
+const MAX_ELEMENT_FLAGS_SIZE: u64 = 2 + 1 + 2 + DEFAULT_HASH_SIZE as u64;
+
+/// Element type byte and the biggest length prefix of the element value
+const ELEMENT_ENCODING_OVERHEAD_SIZE: u64 = 1 + 3;
+
+/// Node hash and value hash stored with the value of a Merk node,
+/// along with the biggest length prefix of the value
+const MERK_NODE_VALUE_OVERHEAD_SIZE: u64 = 2 * DEFAULT_HASH_SIZE as u64 + 3;
+
+/// Node hash, key length and child heights stored with the link to a Merk node in its parent
+const MERK_LINK_OVERHEAD_SIZE: u64 = DEFAULT_HASH_SIZE as u64 + 1 + 2;
+
+/// Biggest value of a tree element: the option and the root key of the subtree
+const MAX_TREE_VALUE_SIZE: u64 = 1 + MAX_INDEX_SIZE as u64;
+
+/// Key of the latest revision reference and of non unique index references trees
+const ZERO_KEY_SIZE: u64 = 1;
+
+/// Key of a document revision, the encoded block time
+const REVISION_KEY_SIZE: u64 = 8;
+
+/// Biggest value of a sibling reference to the latest revision of a document
+const REVISION_REFERENCE_SIZE: u64 = 2 + REVISION_KEY_SIZE;
+
+/// Storage size of a Merk node holding an element with the given key and value sizes
+fn merk_node_size(key_size: u64, value_size: u64) -> u64 {
+    let key_size_with_prefix = STORAGE_KEY_PREFIX_SIZE + key_size + 1;
+    let value_size_with_overhead = MAX_ELEMENT_FLAGS_SIZE
+        + ELEMENT_ENCODING_OVERHEAD_SIZE
+        + value_size
+        + MERK_NODE_VALUE_OVERHEAD_SIZE;
+    let link_size = key_size + MERK_LINK_OVERHEAD_SIZE;
+
+    key_size_with_prefix + value_size_with_overhead + link_size
+}
+
+/// Worst case costs of Merk operations accumulated over estimated operations
+#[derive(Debug, Default)]
+struct WorstCaseCosts {
+    seek_count: u64,
+    added_bytes: u64,
+    replaced_bytes: u64,
+    removed_bytes: u64,
+    loaded_bytes: u64,
+    hash_node_calls: u64,
+}
+
+impl WorstCaseCosts {
+    /// Adds the cost of inserting an element at the given path height
+    fn add_insertion(&mut self, path_height: u64, key_size: u64, value_size: u64) {
+        self.add_merk_update(path_height, key_size, value_size);
+        self.added_bytes += merk_node_size(key_size, value_size);
+    }
+
+    /// Adds the cost of replacing an element at the given path height
+    fn add_replacement(&mut self, path_height: u64, key_size: u64, value_size: u64) {
+        self.add_merk_update(path_height, key_size, value_size);
+        self.replaced_bytes += merk_node_size(key_size, value_size);
+    }
+
+    /// Adds the cost of removing an element at the given path height
+    fn add_removal(&mut self, path_height: u64, key_size: u64, value_size: u64) {
+        self.add_merk_update(path_height, key_size, value_size);
+        self.removed_bytes += merk_node_size(key_size, value_size);
+    }
+
+    /// Adds the cost of updating a Merk at the given path height. The root hash of the Merk
+    /// changes, so tree elements holding it are rewritten in every Merk up to the root tree.
+    fn add_merk_update(&mut self, path_height: u64, key_size: u64, value_size: u64) {
+        // in the worst case every walked node is as big as the updated one
+        self.add_merk_walk(merk_node_size(key_size, value_size));
+
+        let tree_node_size = merk_node_size(MAX_INDEX_SIZE as u64, MAX_TREE_VALUE_SIZE);
+
+        for _ in 0..path_height {
+            self.add_merk_walk(tree_node_size);
+            self.replaced_bytes += tree_node_size;
+        }
+    }
+
+    /// Adds seeks, loaded bytes and hashing of walking a Merk from its root
+    /// to the deepest node and rehashing the walked nodes
+    fn add_merk_walk(&mut self, node_size: u64) {
+        self.seek_count += WORST_CASE_MERK_HEIGHT;
+        self.loaded_bytes += WORST_CASE_MERK_HEIGHT * node_size;
+        self.hash_node_calls += WORST_CASE_MERK_HEIGHT * MERK_NODE_HASH_CALLS;
+    }
+
+    /// Adds the cost of inserting a document into primary storage and all indices
+    fn add_document_insertion(&mut self, document_type: &DocumentType) {
+        let primary_key_path_height = CONTRACT_DOCUMENTS_PATH_HEIGHT as u64 + 1;
+        let document_size = document_type.max_size() as u64;
+
+        if document_type.documents_keep_history {
+            // the document id is a tree of revisions keyed by time
+            // with a reference to the latest one
+            self.add_insertion(
+                primary_key_path_height,
+                DEFAULT_HASH_SIZE as u64,
+                MAX_TREE_VALUE_SIZE,
+            );
+            self.add_insertion(
+                primary_key_path_height + 1,
+                REVISION_KEY_SIZE,
+                document_size,
+            );
+            self.add_insertion(
+                primary_key_path_height + 1,
+                ZERO_KEY_SIZE,
+                REVISION_REFERENCE_SIZE,
+            );
+        } else {
+            self.add_insertion(
+                primary_key_path_height,
+                DEFAULT_HASH_SIZE as u64,
+                document_size,
+            );
+        }
+
+        for index in &document_type.indices {
+            self.add_index_insertion(document_type, index);
+        }
+    }
+
+    /// Adds the cost of replacing a document in primary storage and moving its index references
+    fn add_document_replacement(&mut self, document_type: &DocumentType) {
+        let primary_key_path_height = CONTRACT_DOCUMENTS_PATH_HEIGHT as u64 + 1;
+        let document_size = document_type.max_size() as u64;
+
+        if document_type.documents_keep_history {
+            // a new revision is added next to the previous ones
+            self.add_insertion(
+                primary_key_path_height + 1,
+                REVISION_KEY_SIZE,
+                document_size,
+            );
+            self.add_replacement(
+                primary_key_path_height + 1,
+                ZERO_KEY_SIZE,
+                REVISION_REFERENCE_SIZE,
+            );
+        } else {
+            self.add_replacement(
+                primary_key_path_height,
+                DEFAULT_HASH_SIZE as u64,
+                document_size,
+            );
+        }
+
+        for index in &document_type.indices {
+            self.add_index_removal(document_type, index);
+            self.add_index_insertion(document_type, index);
+        }
+    }
+
+    /// Adds the cost of removing a document from primary storage and all indices
+    fn add_document_removal(&mut self, document_type: &DocumentType) {
+        let primary_key_path_height = CONTRACT_DOCUMENTS_PATH_HEIGHT as u64 + 1;
+
+        self.add_removal(
+            primary_key_path_height,
+            DEFAULT_HASH_SIZE as u64,
+            document_type.max_size() as u64,
+        );
+
+        for index in &document_type.indices {
+            self.add_index_removal(document_type, index);
+        }
+    }
+
+    /// Adds the cost of inserting a document reference into an index, assuming
+    /// none of the index subtrees exist yet. References are assumed to be stored as
+    /// in non unique indices, which is also the case of unique indices with null values.
+    fn add_index_insertion(&mut self, document_type: &DocumentType, index: &Index) {
+        self.add_index_operations(document_type, index, Self::add_insertion);
+    }
+
+    /// Adds the cost of removing a document reference from an index along with
+    /// the subtrees that become empty
+    fn add_index_removal(&mut self, document_type: &DocumentType, index: &Index) {
+        self.add_index_operations(document_type, index, Self::add_removal);
+    }
+
+    /// Adds the cost of an operation on every element of the index path of a document
+    fn add_index_operations(
+        &mut self,
+        document_type: &DocumentType,
+        index: &Index,
+        add_operation: fn(&mut Self, u64, u64, u64),
+    ) {
+        // the tree of the top index property is created with the contract
+        let mut path_height = CONTRACT_DOCUMENTS_PATH_HEIGHT as u64 + 1;
+
+        for (i, property) in index.properties.iter().enumerate() {
+            if i > 0 {
+                add_operation(
+                    self,
+                    path_height,
+                    property.name.len() as u64,
+                    MAX_TREE_VALUE_SIZE,
+                );
+                path_height += 1;
+            }
+            add_operation(
+                self,
+                path_height,
+                Self::index_key_size(document_type, &property.name),
+                MAX_TREE_VALUE_SIZE,
+            );
+            path_height += 1;
+        }
+
+        add_operation(self, path_height, ZERO_KEY_SIZE, MAX_TREE_VALUE_SIZE);
+        add_operation(
+            self,
+            path_height + 1,
+            DEFAULT_HASH_SIZE as u64,
+            Self::reference_size(document_type),
+        );
+    }
+
+    /// Biggest key a property value can have in an index tree
+    fn index_key_size(document_type: &DocumentType, property_name: &str) -> u64 {
+        document_type
+            .document_field_type_for_property(property_name)
+            .and_then(|field_type| field_type.max_byte_size())
+            .map_or(MAX_INDEX_SIZE as u64, |size| {
+                (size as u64).min(MAX_INDEX_SIZE as u64)
+            })
+    }
+
+    /// Biggest reference to the primary storage of a document
+    fn reference_size(document_type: &DocumentType) -> u64 {
+        BASE_CONTRACT_DOCUMENTS_PRIMARY_KEY_PATH as u64
+            + document_type.name.len() as u64
+            + DEFAULT_HASH_SIZE as u64
+    }
+
+    /// Converts the accumulated costs to an operation cost
+    fn to_operation_cost(&self) -> Result<OperationCost, Error> {
+        let overflow_error = || Error::Fee(FeeError::Overflow("estimated cost overflow"));
+
+        let removed_bytes = if self.removed_bytes > 0 {
+            BasicStorageRemoval(
+                self.removed_bytes
+                    .try_into()
+                    .map_err(|_| overflow_error())?,
+            )
+        } else {
+            NoStorageRemoval
+        };
+
+        Ok(OperationCost {
+            seek_count: self.seek_count.try_into().map_err(|_| overflow_error())?,
+            storage_cost: StorageCost {
+                added_bytes: self.added_bytes.try_into().map_err(|_| overflow_error())?,
+                replaced_bytes: self
+                    .replaced_bytes
+                    .try_into()
+                    .map_err(|_| overflow_error())?,
+                removed_bytes,
+            },
+            storage_loaded_bytes: self.loaded_bytes.try_into().map_err(|_| overflow_error())?,
+            hash_node_calls: self
+                .hash_node_calls
+                .try_into()
+                .map_err(|_| overflow_error())?,
+        })
+    }
+}
+
+/// Estimates the worst case fees of applying the given document transitions
+/// without accessing GroveDB.
+pub fn estimate_document_transitions_fee(
+    contract: &Contract,
+    transitions: &[DocumentTransition],
+    epoch: &Epoch,
+) -> Result<FeeResult, Error> {
+    let actions: Vec<(Action, &str)> = transitions
+        .iter()
+        .map(|transition| {
+            let base = transition.base();
+            (base.action, base.document_type.as_str())
+        })
+        .collect();
+
+    estimate_document_actions_fee(contract, &actions, epoch)
+}
+
+/// Estimates the worst case fees of applying actions to documents of the named
+/// document types without accessing GroveDB. Documents are assumed to have the max size
+/// of their document type and to be the only documents of their index subtrees.
+pub fn estimate_document_actions_fee(
+    contract: &Contract,
+    actions: &[(Action, &str)],
+    epoch: &Epoch,
+) -> Result<FeeResult, Error> {
+    let mut drive_operations = Vec::with_capacity(actions.len());
+
+    for (action, document_type_name) in actions {
+        let document_type = contract.document_type_for_name(document_type_name)?;
+
+        let mut worst_case_costs = WorstCaseCosts::default();
+
+        match action {
+            Action::Create => worst_case_costs.add_document_insertion(document_type),
+            Action::Replace | Action::Delete if !document_type.documents_mutable => {
+                return Err(Error::Drive(DriveError::UpdatingReadOnlyImmutableDocument(
+                    "this document type is not mutable and can not be changed",
+                )))
+            }
+            Action::Replace => worst_case_costs.add_document_replacement(document_type),
+            Action::Delete => worst_case_costs.add_document_removal(document_type),
+        }
+
+        drive_operations.push(DriveOperation::CalculatedCostOperation(
+            worst_case_costs.to_operation_cost()?,
+        ));
+    }
+
+    calculate_fee(None, Some(drive_operations), epoch)
+}
+
+impl Drive {
+    /// Estimates the worst case fees of applying the given document transitions
+    /// of the owner.
+    pub fn estimate_document_transitions_fee(
+        &self,
+        contract: &Contract,
+        transitions: &[DocumentTransition],
+        owner_id: Option<[u8; 32]>,
+        block_info: &BlockInfo,
+    ) -> Result<FeeResult, Error> {
+        let actions: Vec<(Action, &str)> = transitions
+            .iter()
+            .map(|transition| {
+                let base = transition.base();
+                (base.action, base.document_type.as_str())
+            })
+            .collect();
+
+        self.estimate_document_actions_fee(contract, &actions, owner_id, block_info)
+    }
+
+    /// Estimates the worst case fees of applying actions of the owner to documents
+    /// of the named document types. Documents are assumed to have the max size
+    /// of their document type.
+    pub fn estimate_document_actions_fee(
+        &self,
+        contract: &Contract,
+        actions: &[(Action, &str)],
+        owner_id: Option<[u8; 32]>,
+        block_info: &BlockInfo,
+    ) -> Result<FeeResult, Error> {
+        let mut fee_result = FeeResult::default();
+
+        for (action, document_type_name) in actions {
+            let document_type = contract.document_type_for_name(document_type_name)?;
+
+            let document_and_contract_info = DocumentAndContractInfo {
+                document_info: DocumentSize(document_type.max_size() as u32),
+                contract,
+                document_type,
+                owner_id,
+            };
+
+            let action_fee_result = match action {
+                Action::Create => self.add_document_for_contract(
+                    document_and_contract_info,
+                    false,
+                    block_info.clone(),
+                    false,
+                    None,
+                )?,
+                Action::Replace => {
+                    let mut drive_operations: Vec<DriveOperation> = vec![];
+
+                    self.update_document_for_contract_apply_and_add_to_operations(
+                        document_and_contract_info,
+                        block_info,
+                        false,
+                        None,
+                        &mut drive_operations,
+                    )?;
+
+                    self.calculate_fee_and_update_identities_storage_usage(
+                        owner_id,
+                        drive_operations,
+                        &block_info.epoch,
+                        false,
+                        None,
+                    )?
+                }
+                Action::Delete => self.estimated_fee_for_document_deletion_with_name(
+                    contract,
+                    document_type_name,
+                    DeletionCostEstimation::WorstCase,
+                    block_info.clone(),
+                )?,
+            };
+
+            fee_result.checked_add_assign(action_fee_result)?;
+        }
+
+        Ok(fee_result)
+    }
+
+    /// Estimates the worst case fees of creating the given data contract.
+    pub fn estimate_contract_creation_fee(
+        &self,
+        contract_cbor: Vec<u8>,
+        block_info: &BlockInfo,
+    ) -> Result<FeeResult, Error> {
+        self.insert_contract_cbor(contract_cbor, None, block_info.clone(), false, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dpp::data_contract::extra::DriveContractExt;
+    use dpp::document::document_transition::Action;
+    use rand::Rng;
+
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::{json_document_to_cbor, setup_contract};
+    use crate::contract::document::Document;
+    use crate::contract::Contract;
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::flags::StorageFlags;
+    use crate::drive::object_size_info::DeletionCostEstimation;
+    use crate::error::drive::DriveError;
+    use crate::error::Error;
+    use crate::fee::estimation::estimate_document_actions_fee;
+    use crate::fee::FeeResult;
+    use crate::fee_pools::epochs::Epoch;
+
+    fn load_contract(path: &str) -> Contract {
+        let contract_cbor =
+            json_document_to_cbor(path, Some(crate::drive::defaults::PROTOCOL_VERSION));
+        <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("expected to deserialize the contract")
+    }
+
+    /// Checks fees estimated with and without GroveDB for the creation and the deletion
+    /// of the document are not lower than fees of applying them
+    fn assert_estimated_fees_are_not_lower_than_applied(
+        contract_path: &str,
+        document_path: &str,
+        document_type_name: &str,
+    ) {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let contract = setup_contract(&drive, contract_path, None, Some(&transaction));
+
+        let owner_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        let block_info = BlockInfo::default();
+
+        let estimate = |action: Action| -> Vec<FeeResult> {
+            vec![
+                drive
+                    .estimate_document_actions_fee(
+                        &contract,
+                        &[(action, document_type_name)],
+                        Some(owner_id),
+                        &block_info,
+                    )
+                    .expect("expected to estimate fees"),
+                estimate_document_actions_fee(
+                    &contract,
+                    &[(action, document_type_name)],
+                    &block_info.epoch,
+                )
+                .expect("expected to estimate fees without GroveDB"),
+            ]
+        };
+
+        let estimated_creation_fee_results = estimate(Action::Create);
+
+        let serialized_document = json_document_to_cbor(document_path, Some(1));
+
+        let document = Document::from_cbor(&serialized_document, None, Some(owner_id))
+            .expect("expected to deserialize the document");
+
+        let applied_creation_fee_result = drive
+            .add_serialized_document_for_contract(
+                &serialized_document,
+                &contract,
+                document_type_name,
+                Some(owner_id),
+                false,
+                block_info.clone(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&transaction),
+            )
+            .expect("expected to insert a document successfully");
+
+        for fee_result in estimated_creation_fee_results {
+            assert!(fee_result.storage_fee >= applied_creation_fee_result.storage_fee);
+            assert!(fee_result.processing_fee >= applied_creation_fee_result.processing_fee);
+        }
+
+        let mut estimated_deletion_fee_results = estimate(Action::Delete);
+
+        estimated_deletion_fee_results.push(
+            drive
+                .delete_document_for_contract(
+                    document.id,
+                    &contract,
+                    document_type_name,
+                    Some(owner_id),
+                    block_info.clone(),
+                    false,
+                    Some(&transaction),
+                )
+                .expect("expected to estimate the document deletion"),
+        );
+
+        estimated_deletion_fee_results.push(
+            drive
+                .estimated_fee_for_document_deletion_with_name(
+                    &contract,
+                    document_type_name,
+                    DeletionCostEstimation::WorstCase,
+                    block_info.clone(),
+                )
+                .expect("expected to estimate the document type deletion"),
+        );
+
+        let applied_deletion_fee_result = drive
+            .delete_document_for_contract(
+                document.id,
+                &contract,
+                document_type_name,
+                Some(owner_id),
+                block_info,
+                true,
+                Some(&transaction),
+            )
+            .expect("expected to delete the document");
+
+        for fee_result in estimated_deletion_fee_results {
+            assert!(fee_result.processing_fee >= applied_deletion_fee_result.processing_fee);
+            assert!(
+                fee_result.storage_fee + fee_result.processing_fee
+                    >= applied_deletion_fee_result.storage_fee
+                        + applied_deletion_fee_result.processing_fee
+            );
+        }
+    }
+
+    #[test]
+    fn test_estimated_fees_are_not_lower_than_applied_for_family_person() {
+        assert_estimated_fees_are_not_lower_than_applied(
+            "tests/supporting_files/contract/family/family-contract-reduced.json",
+            "tests/supporting_files/contract/family/person0.json",
+            "person",
+        );
+    }
+
+    #[test]
+    fn test_estimated_fees_are_not_lower_than_applied_for_dashpay_profile() {
+        assert_estimated_fees_are_not_lower_than_applied(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            "tests/supporting_files/contract/dashpay/profile0.json",
+            "profile",
+        );
+    }
+
+    #[test]
+    fn test_estimated_fees_are_not_lower_than_applied_for_dashpay_contact_request() {
+        assert_estimated_fees_are_not_lower_than_applied(
+            "tests/supporting_files/contract/dashpay/dashpay-contract-all-mutable.json",
+            "tests/supporting_files/contract/dashpay/contact-request0.json",
+            "contactRequest",
+        );
+    }
+
+    #[test]
+    fn test_estimated_fees_are_not_lower_than_applied_for_dpns_domain() {
+        assert_estimated_fees_are_not_lower_than_applied(
+            "tests/supporting_files/contract/dpns/dpns-contract.json",
+            "tests/supporting_files/contract/dpns/domain0.json",
+            "domain",
+        );
+    }
+
+    #[test]
+    fn test_estimated_contract_creation_fee_is_not_lower_than_applied() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let contract_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(crate::drive::defaults::PROTOCOL_VERSION),
+        );
+
+        let estimated_fee_result = drive
+            .estimate_contract_creation_fee(contract_cbor.clone(), &BlockInfo::default())
+            .expect("expected to estimate fees");
+
+        let applied_fee_result = drive
+            .insert_contract_cbor(
+                contract_cbor,
+                None,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("expected to insert the contract");
+
+        assert!(estimated_fee_result.storage_fee >= applied_fee_result.storage_fee);
+        assert!(estimated_fee_result.processing_fee >= applied_fee_result.processing_fee);
+    }
+
+    #[test]
+    fn test_document_creation_estimation_has_storage_and_processing_fees() {
+        let contract =
+            load_contract("tests/supporting_files/contract/family/family-contract-reduced.json");
+
+        let fee_result =
+            estimate_document_actions_fee(&contract, &[(Action::Create, "person")], &Epoch::new(0))
+                .expect("expected to estimate fees");
+
+        assert!(fee_result.storage_fee > 0);
+        assert!(fee_result.processing_fee > 0);
+    }
+
+    #[test]
+    fn test_document_deletion_estimation_has_no_storage_fee() {
+        let contract =
+            load_contract("tests/supporting_files/contract/family/family-contract-reduced.json");
+
+        let fee_result =
+            estimate_document_actions_fee(&contract, &[(Action::Delete, "person")], &Epoch::new(0))
+                .expect("expected to estimate fees");
+
+        assert_eq!(fee_result.storage_fee, 0);
+        assert!(fee_result.processing_fee > 0);
+    }
+
+    #[test]
+    fn test_document_replacement_estimation_costs_less_storage_than_creation() {
+        let contract =
+            load_contract("tests/supporting_files/contract/family/family-contract-reduced.json");
+
+        let creation_fee_result =
+            estimate_document_actions_fee(&contract, &[(Action::Create, "person")], &Epoch::new(0))
+                .expect("expected to estimate fees");
+
+        let replacement_fee_result = estimate_document_actions_fee(
+            &contract,
+            &[(Action::Replace, "person")],
+            &Epoch::new(0),
+        )
+        .expect("expected to estimate fees");
+
+        assert!(replacement_fee_result.storage_fee < creation_fee_result.storage_fee);
+    }
+
+    #[test]
+    fn test_estimation_adds_up_transitions() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let contract =
+            load_contract("tests/supporting_files/contract/family/family-contract-reduced.json");
+
+        let single_fee_results = [
+            drive
+                .estimate_document_actions_fee(
+                    &contract,
+                    &[(Action::Create, "person")],
+                    None,
+                    &BlockInfo::default(),
+                )
+                .expect("expected to estimate fees"),
+            estimate_document_actions_fee(&contract, &[(Action::Create, "person")], &Epoch::new(0))
+                .expect("expected to estimate fees"),
+        ];
+
+        let double_fee_results = [
+            drive
+                .estimate_document_actions_fee(
+                    &contract,
+                    &[(Action::Create, "person"), (Action::Create, "person")],
+                    None,
+                    &BlockInfo::default(),
+                )
+                .expect("expected to estimate fees"),
+            estimate_document_actions_fee(
+                &contract,
+                &[(Action::Create, "person"), (Action::Create, "person")],
+                &Epoch::new(0),
+            )
+            .expect("expected to estimate fees"),
+        ];
+
+        for (single_fee_result, double_fee_result) in
+            single_fee_results.iter().zip(double_fee_results.iter())
+        {
+            assert_eq!(
+                double_fee_result.storage_fee,
+                single_fee_result.storage_fee * 2
+            );
+            assert_eq!(
+                double_fee_result.processing_fee,
+                single_fee_result.processing_fee * 2
+            );
+        }
+    }
+
+    #[test]
+    fn test_estimation_of_immutable_document_deletion_fails() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let contract =
+            load_contract("tests/supporting_files/contract/dashpay/dashpay-contract.json");
+
+        let result = drive.estimate_document_actions_fee(
+            &contract,
+            &[(Action::Delete, "contactRequest")],
+            None,
+            &BlockInfo::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Drive(DriveError::UpdatingReadOnlyImmutableDocument(
+                _
+            )))
+        ));
+
+        let result = estimate_document_actions_fee(
+            &contract,
+            &[(Action::Delete, "contactRequest")],
+            &Epoch::new(0),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Drive(DriveError::UpdatingReadOnlyImmutableDocument(
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_estimation_of_unknown_document_type_fails() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let contract =
+            load_contract("tests/supporting_files/contract/family/family-contract-reduced.json");
+
+        let result = drive.estimate_document_actions_fee(
+            &contract,
+            &[(Action::Create, "unknown")],
+            None,
+            &BlockInfo::default(),
+        );
+
+        assert!(matches!(result, Err(Error::Contract(_))));
+
+        let result = estimate_document_actions_fee(
+            &contract,
+            &[(Action::Create, "unknown")],
+            &Epoch::new(0),
+        );
+
+        assert!(matches!(result, Err(Error::Contract(_))));
+    }
+}
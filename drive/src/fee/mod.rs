@@ -34,22 +34,26 @@ use std::collections::BTreeMap;
 
 use crate::error::fee::FeeError;
 use crate::error::Error;
-use crate::fee::op::{BaseOp, DriveOperation};
+use crate::fee::op::{apply_fee_multiplier, BaseOp, DriveOperation};
 use crate::fee::removed_bytes_from_epochs_by_identities::RemovedBytesFromEpochsByIdentities;
 use crate::fee_pools::epochs::Epoch;
 
-/// Default costs module
-pub mod default_costs;
+pub mod estimation;
 pub mod op;
+pub mod refunds;
 mod removed_bytes_from_epochs_by_identities;
 
 /// Fee Result
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct FeeResult {
-    /// Storage fee
+    /// Storage fee, the epoch fee multiplier is applied to processing fees only
     pub storage_fee: u64,
     /// Processing fee
     pub processing_fee: u64,
+    /// Processing fee before applying the epoch fee multiplier
+    pub base_processing_fee: u64,
+    /// Bytes added to storage
+    pub added_bytes: u32,
     /// Removed bytes from identities
     pub removed_bytes_from_identities: RemovedBytesFromEpochsByIdentities,
     /// Removed bytes not needing to be refunded to identities
@@ -65,11 +69,11 @@ pub fn calculate_fee(
     let mut aggregate_fee_result = FeeResult::default();
     if let Some(base_operations) = base_operations {
         for (base_op, count) in base_operations.iter() {
-            match base_op.cost().checked_mul(*count) {
+            match base_op.cost(epoch).checked_mul(*count) {
                 None => return Err(Error::Fee(FeeError::Overflow("overflow error"))),
-                Some(cost) => match aggregate_fee_result.processing_fee.checked_add(cost) {
+                Some(cost) => match aggregate_fee_result.base_processing_fee.checked_add(cost) {
                     None => return Err(Error::Fee(FeeError::Overflow("overflow error"))),
-                    Some(value) => aggregate_fee_result.processing_fee = value,
+                    Some(value) => aggregate_fee_result.base_processing_fee = value,
                 },
             }
         }
@@ -82,6 +86,11 @@ pub fn calculate_fee(
         }
     }
 
+    // The multiplier is applied once to the aggregate, as dpp does, so rounding up
+    // doesn't add a credit per operation
+    aggregate_fee_result.processing_fee =
+        apply_fee_multiplier(aggregate_fee_result.base_processing_fee, epoch)?;
+
     Ok(aggregate_fee_result)
 }
 
@@ -98,6 +107,16 @@ impl FeeResult {
                 .ok_or(Error::Fee(FeeError::Overflow(
                     "processing fee overflow error",
                 )))?;
+        self.base_processing_fee = self
+            .base_processing_fee
+            .checked_add(rhs.base_processing_fee)
+            .ok_or(Error::Fee(FeeError::Overflow(
+                "base processing fee overflow error",
+            )))?;
+        self.added_bytes = self
+            .added_bytes
+            .checked_add(rhs.added_bytes)
+            .ok_or(Error::Fee(FeeError::Overflow("added bytes overflow error")))?;
         self.removed_bytes_from_identities
             .checked_add_assign(rhs.removed_bytes_from_identities)?;
         self.removed_bytes_from_system = self
@@ -109,3 +128,248 @@ impl FeeResult {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    mod calculate_fee {
+        use crate::error::fee::FeeError;
+        use crate::error::Error;
+        use crate::fee::calculate_fee;
+        use crate::fee::op::{BaseOp, DriveCost, DriveOperation};
+        use crate::fee_pools::epochs::Epoch;
+        use costs::storage_cost::StorageCost;
+        use costs::OperationCost;
+        use dpp::state_transition::fee::calculate_operations_fees;
+        use dpp::state_transition::fee::fee_schedule::FEE_SCHEDULE_V1;
+        use dpp::state_transition::fee::operations::{
+            Operation, PreCalculatedOperation, WriteOperation,
+        };
+        use dpp::version::INITIAL_PROTOCOL_VERSION;
+        use enum_map::EnumMap;
+
+        fn create_operations() -> (EnumMap<BaseOp, u64>, Vec<DriveOperation>) {
+            let mut base_operations: EnumMap<BaseOp, u64> = EnumMap::default();
+
+            base_operations[BaseOp::Add] = 10;
+
+            let drive_operations = vec![DriveOperation::CalculatedCostOperation(OperationCost {
+                seek_count: 1,
+                storage_cost: StorageCost {
+                    added_bytes: 10,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })];
+
+            (base_operations, drive_operations)
+        }
+
+        #[test]
+        fn test_default_fee_multiplier_does_not_change_costs() {
+            let (base_operations, drive_operations) = create_operations();
+
+            let fee_result = calculate_fee(
+                Some(base_operations),
+                Some(drive_operations),
+                &Epoch::new(0),
+            )
+            .expect("should calculate fee");
+
+            assert_eq!(
+                fee_result.processing_fee,
+                BaseOp::Add.cost(&Epoch::new(0)) * 10
+                    + FEE_SCHEDULE_V1.storage_seek_cost
+                    + FEE_SCHEDULE_V1.storage_processing_credit_per_byte * 10
+            );
+            assert_eq!(
+                fee_result.storage_fee,
+                FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte * 10
+            );
+        }
+
+        #[test]
+        fn test_epoch_fee_multiplier_is_applied_to_processing_fee_only() {
+            let (base_operations, drive_operations) = create_operations();
+
+            let fee_result = calculate_fee(
+                Some(base_operations),
+                Some(drive_operations),
+                &Epoch::new_with_fee_multiplier_and_protocol_version(
+                    0,
+                    2.0,
+                    INITIAL_PROTOCOL_VERSION,
+                ),
+            )
+            .expect("should calculate fee");
+
+            assert_eq!(
+                fee_result.processing_fee,
+                (BaseOp::Add.cost(&Epoch::new(0)) * 10
+                    + FEE_SCHEDULE_V1.storage_seek_cost
+                    + FEE_SCHEDULE_V1.storage_processing_credit_per_byte * 10)
+                    * 2
+            );
+            assert_eq!(
+                fee_result.storage_fee,
+                FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte * 10
+            );
+        }
+
+        #[test]
+        fn test_fee_result_keeps_fees_before_multiplier() {
+            let (base_operations, drive_operations) = create_operations();
+
+            let fee_result = calculate_fee(
+                Some(base_operations),
+                Some(drive_operations),
+                &Epoch::new_with_fee_multiplier_and_protocol_version(
+                    0,
+                    2.0,
+                    INITIAL_PROTOCOL_VERSION,
+                ),
+            )
+            .expect("should calculate fee");
+
+            assert_eq!(
+                fee_result.base_processing_fee,
+                BaseOp::Add.cost(&Epoch::new(0)) * 10
+                    + FEE_SCHEDULE_V1.storage_seek_cost
+                    + FEE_SCHEDULE_V1.storage_processing_credit_per_byte * 10
+            );
+            assert_eq!(
+                fee_result.processing_fee,
+                fee_result.base_processing_fee * 2
+            );
+            assert_eq!(
+                fee_result.storage_fee,
+                FEE_SCHEDULE_V1.storage_disk_usage_credit_per_byte * 10
+            );
+        }
+
+        #[test]
+        fn test_storage_fee_matches_state_transition_storage_cost() {
+            let epoch = Epoch::new_with_fee_multiplier_and_protocol_version(
+                0,
+                2.0,
+                INITIAL_PROTOCOL_VERSION,
+            );
+
+            let drive_operations = vec![DriveOperation::CalculatedCostOperation(OperationCost {
+                storage_cost: StorageCost {
+                    added_bytes: 10,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })];
+
+            let fee_result =
+                calculate_fee(None, Some(drive_operations), &epoch).expect("should calculate fee");
+
+            let state_transition_fees = calculate_operations_fees(
+                [Operation::Write(WriteOperation::new(5, 5))],
+                epoch.fee_schedule(),
+                epoch.fee_multiplier,
+            )
+            .expect("should calculate state transition fees");
+
+            assert_eq!(fee_result.storage_fee as i64, state_transition_fees.storage);
+        }
+
+        #[test]
+        fn test_fee_multiplier_is_applied_as_in_state_transition_fees() {
+            let epoch = Epoch::new_with_fee_multiplier_and_protocol_version(
+                0,
+                1.3,
+                INITIAL_PROTOCOL_VERSION,
+            );
+
+            let mut base_operations: EnumMap<BaseOp, u64> = EnumMap::default();
+
+            base_operations[BaseOp::Add] = 7;
+
+            let operation_costs: Vec<OperationCost> = (1..4)
+                .map(|i| OperationCost {
+                    seek_count: i,
+                    storage_cost: StorageCost {
+                        added_bytes: 3 * i as u32,
+                        ..Default::default()
+                    },
+                    storage_loaded_bytes: 11 * i as u32,
+                    hash_node_calls: i as u32,
+                })
+                .collect();
+
+            let mut operations = vec![Operation::PreCalculated(PreCalculatedOperation::new(
+                0,
+                (BaseOp::Add.cost(&epoch) * 7) as i64,
+            ))];
+
+            for cost in operation_costs.iter() {
+                operations.push(Operation::PreCalculated(PreCalculatedOperation::new(
+                    cost.base_storage_cost(&epoch)
+                        .expect("should get storage cost") as i64,
+                    cost.base_ephemeral_cost(&epoch)
+                        .expect("should get ephemeral cost") as i64,
+                )));
+            }
+
+            let drive_operations = operation_costs
+                .into_iter()
+                .map(DriveOperation::CalculatedCostOperation)
+                .collect();
+
+            let fee_result = calculate_fee(Some(base_operations), Some(drive_operations), &epoch)
+                .expect("should calculate fee");
+
+            let state_transition_fees =
+                calculate_operations_fees(operations, epoch.fee_schedule(), epoch.fee_multiplier)
+                    .expect("should calculate state transition fees");
+
+            assert_eq!(
+                fee_result.processing_fee as i64,
+                state_transition_fees.processing
+            );
+            assert_eq!(fee_result.storage_fee as i64, state_transition_fees.storage);
+        }
+
+        #[test]
+        fn test_negative_fee_multiplier_is_rejected() {
+            let (base_operations, drive_operations) = create_operations();
+
+            let result = calculate_fee(
+                Some(base_operations),
+                Some(drive_operations),
+                &Epoch::new_with_fee_multiplier_and_protocol_version(
+                    0,
+                    -1.0,
+                    INITIAL_PROTOCOL_VERSION,
+                ),
+            );
+
+            assert!(matches!(
+                result,
+                Err(Error::Fee(FeeError::CorruptedMultiplierInvalidValue(_)))
+            ));
+        }
+
+        #[test]
+        fn test_zero_fee_multiplier_is_rejected() {
+            let (base_operations, drive_operations) = create_operations();
+
+            let result = calculate_fee(
+                Some(base_operations),
+                Some(drive_operations),
+                &Epoch::new_with_fee_multiplier_and_protocol_version(
+                    0,
+                    0.0,
+                    INITIAL_PROTOCOL_VERSION,
+                ),
+            );
+
+            assert!(matches!(
+                result,
+                Err(Error::Fee(FeeError::CorruptedMultiplierInvalidValue(_)))
+            ));
+        }
+    }
+}
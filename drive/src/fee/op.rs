@@ -45,10 +45,6 @@ use crate::drive::flags::StorageFlags;
 use crate::error::drive::DriveError;
 use crate::error::fee::FeeError;
 use crate::error::Error;
-use crate::fee::default_costs::{
-    STORAGE_DISK_USAGE_CREDIT_PER_BYTE, STORAGE_LOAD_CREDIT_PER_BYTE,
-    STORAGE_PROCESSING_CREDIT_PER_BYTE, STORAGE_SEEK_COST,
-};
 use crate::fee::op::DriveOperation::{
     CalculatedCostOperation, CostCalculationDeleteOperation, CostCalculationInsertOperation,
     CostCalculationQueryOperation, GroveOperation, PreCalculatedFeeResult,
@@ -107,31 +103,32 @@ pub enum BaseOp {
 }
 
 impl BaseOp {
-    /// Match the op and get the cost
-    pub fn cost(&self) -> u64 {
+    /// Match the op and get the cost from the epoch fee schedule
+    pub fn cost(&self, epoch: &Epoch) -> u64 {
+        let fee_schedule = epoch.fee_schedule();
+
         match self {
             BaseOp::Stop => 0,
-            BaseOp::Add => 12,
-            BaseOp::Mul => 20,
-            BaseOp::Sub => 12,
-            BaseOp::Div => 20,
-            BaseOp::Sdiv => 20,
-            BaseOp::Mod => 20,
-            BaseOp::Smod => 20,
-            BaseOp::Addmod => 32,
-            BaseOp::Mulmod => 32,
-            BaseOp::Signextend => 20,
-            BaseOp::Lt => 12,
-            BaseOp::Gt => 12,
-            BaseOp::Slt => 12,
-            BaseOp::Sgt => 12,
-            BaseOp::Eq => 12,
-            BaseOp::Iszero => 12,
-            BaseOp::And => 12,
-            BaseOp::Or => 12,
-            BaseOp::Xor => 12,
-            BaseOp::Not => 12,
-            BaseOp::Byte => 12,
+            BaseOp::Add
+            | BaseOp::Sub
+            | BaseOp::Lt
+            | BaseOp::Gt
+            | BaseOp::Slt
+            | BaseOp::Sgt
+            | BaseOp::Eq
+            | BaseOp::Iszero
+            | BaseOp::And
+            | BaseOp::Or
+            | BaseOp::Xor
+            | BaseOp::Not
+            | BaseOp::Byte => fee_schedule.simple_arithmetic_operation_cost,
+            BaseOp::Mul
+            | BaseOp::Div
+            | BaseOp::Sdiv
+            | BaseOp::Mod
+            | BaseOp::Smod
+            | BaseOp::Signextend => fee_schedule.complex_arithmetic_operation_cost,
+            BaseOp::Addmod | BaseOp::Mulmod => fee_schedule.modular_arithmetic_operation_cost,
         }
     }
 }
@@ -148,12 +145,14 @@ pub enum FunctionOp {
 }
 
 impl FunctionOp {
-    /// Cost
-    pub fn cost(&self, _epoch: &Epoch) -> u64 {
+    /// Cost from the epoch fee schedule
+    pub fn cost(&self, epoch: &Epoch) -> u64 {
+        let fee_schedule = epoch.fee_schedule();
+
         match self {
-            FunctionOp::Sha256 => 4000,
-            FunctionOp::Sha256_2 => 8000,
-            FunctionOp::Blake3 => 1000,
+            FunctionOp::Sha256 => fee_schedule.sha256_cost,
+            FunctionOp::Sha256_2 => fee_schedule.sha256_2_cost,
+            FunctionOp::Blake3 => fee_schedule.blake3_cost,
         }
     }
 }
@@ -391,6 +390,8 @@ pub enum DriveOperation {
 
 impl DriveOperation {
     /// Returns a list of the costs of the Drive operations.
+    /// The epoch fee multiplier is not applied to their processing fees, `calculate_fee`
+    /// applies it once to the aggregated base processing fee.
     pub fn consume_to_fees(
         drive_operation: Vec<DriveOperation>,
         epoch: &Epoch,
@@ -401,8 +402,9 @@ impl DriveOperation {
                 PreCalculatedFeeResult(f) => Ok(f),
                 _ => {
                     let cost = operation.operation_cost()?;
-                    let storage_fee = cost.storage_cost(epoch)?;
-                    let processing_fee = cost.ephemeral_cost(epoch)?;
+                    // Storage is paid at the fee schedule price, so refunds match what was paid
+                    let storage_fee = cost.base_storage_cost(epoch)?;
+                    let base_processing_fee = cost.base_ephemeral_cost(epoch)?;
                     let (removed_bytes_from_identities, removed_bytes_from_system) =
                         match cost.storage_cost.removed_bytes {
                             NoStorageRemoval => (BTreeMap::default(), 0),
@@ -419,7 +421,9 @@ impl DriveOperation {
                         };
                     Ok(FeeResult {
                         storage_fee,
-                        processing_fee,
+                        processing_fee: base_processing_fee,
+                        base_processing_fee,
+                        added_bytes: cost.storage_cost.added_bytes,
                         removed_bytes_from_identities: RemovedBytesFromEpochsByIdentities(
                             removed_bytes_from_identities,
                         ),
@@ -541,60 +545,82 @@ impl DriveOperation {
 
 /// Drive cost trait
 pub trait DriveCost {
-    /// Ephemeral cost
-    fn ephemeral_cost(&self, epoch: &Epoch) -> Result<u64, Error>;
-    /// Storage cost
-    fn storage_cost(&self, epoch: &Epoch) -> Result<u64, Error>;
+    /// Ephemeral cost before applying the epoch fee multiplier
+    fn base_ephemeral_cost(&self, epoch: &Epoch) -> Result<u64, Error>;
+    /// Storage cost, the epoch fee multiplier is not applied to it
+    fn base_storage_cost(&self, epoch: &Epoch) -> Result<u64, Error>;
 }
 
 fn get_overflow_error(str: &'static str) -> Error {
     Error::Fee(FeeError::Overflow(str))
 }
 
+/// Applies the epoch fee multiplier to the processing cost
+pub(crate) fn apply_fee_multiplier(cost: u64, epoch: &Epoch) -> Result<u64, Error> {
+    if !epoch.fee_multiplier.is_finite() || epoch.fee_multiplier <= 0.0 {
+        return Err(Error::Fee(FeeError::CorruptedMultiplierInvalidValue(
+            "fee multiplier must be a finite number above zero",
+        )));
+    }
+
+    if epoch.fee_multiplier == 1.0 {
+        return Ok(cost);
+    }
+
+    let multiplied_cost = (cost as f64 * epoch.fee_multiplier).ceil();
+
+    if multiplied_cost >= u64::MAX as f64 {
+        return Err(get_overflow_error("fee multiplier cost overflow"));
+    }
+
+    Ok(multiplied_cost as u64)
+}
+
 impl DriveCost for OperationCost {
     /// Return the ephemeral cost from the operation
-    fn ephemeral_cost(&self, epoch: &Epoch) -> Result<u64, Error> {
-        //todo: deal with epochs
+    fn base_ephemeral_cost(&self, epoch: &Epoch) -> Result<u64, Error> {
         let OperationCost {
             seek_count,
             storage_cost,
             storage_loaded_bytes,
             hash_node_calls,
         } = self;
+        let fee_schedule = epoch.fee_schedule();
         let seek_cost = (*seek_count as u64)
-            .checked_mul(STORAGE_SEEK_COST)
+            .checked_mul(fee_schedule.storage_seek_cost)
             .ok_or_else(|| get_overflow_error("seek cost overflow"))?;
         let storage_added_bytes_ephemeral_cost = (storage_cost.added_bytes as u64)
-            .checked_mul(STORAGE_PROCESSING_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_processing_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))?;
         let storage_replaced_bytes_ephemeral_cost = (storage_cost.replaced_bytes as u64)
-            .checked_mul(STORAGE_PROCESSING_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_processing_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))?;
         let storage_removed_bytes_ephemeral_cost =
             (storage_cost.removed_bytes.total_removed_bytes() as u64)
-                .checked_mul(STORAGE_PROCESSING_CREDIT_PER_BYTE)
+                .checked_mul(fee_schedule.storage_processing_credit_per_byte)
                 .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))?;
         let storage_loaded_bytes_cost = (*storage_loaded_bytes as u64)
-            .checked_mul(STORAGE_LOAD_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_load_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage loaded cost overflow"))?;
         let hash_node_cost = (*hash_node_calls as u64)
             .checked_mul(FunctionOp::Blake3.cost(epoch))
             .ok_or_else(|| get_overflow_error("hash node cost overflow"))?;
-        seek_cost
+        let ephemeral_cost = seek_cost
             .checked_add(storage_added_bytes_ephemeral_cost)
             .and_then(|c| c.checked_add(storage_replaced_bytes_ephemeral_cost))
             .and_then(|c| c.checked_add(storage_loaded_bytes_cost))
             .and_then(|c| c.checked_add(storage_removed_bytes_ephemeral_cost))
             .and_then(|c| c.checked_add(hash_node_cost))
-            .ok_or_else(|| get_overflow_error("ephemeral cost addition overflow"))
+            .ok_or_else(|| get_overflow_error("ephemeral cost addition overflow"))?;
+
+        Ok(ephemeral_cost)
     }
 
     /// Return the storage cost from the operation
-    fn storage_cost(&self, _epoch: &Epoch) -> Result<u64, Error> {
-        //todo: deal with epochs
+    fn base_storage_cost(&self, epoch: &Epoch) -> Result<u64, Error> {
         let OperationCost { storage_cost, .. } = self;
         (storage_cost.added_bytes as u64)
-            .checked_mul(STORAGE_DISK_USAGE_CREDIT_PER_BYTE)
+            .checked_mul(epoch.fee_schedule().storage_disk_usage_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))
     }
 }
@@ -0,0 +1,210 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Storage Fee Refunds.
+//!
+//! Storage fees are distributed over the perpetual storage epochs following the epoch
+//! they were paid in. Bytes removed before the end of the distribution are refunded
+//! the part of their fee which wasn't distributed yet. Refunds too small to be worth
+//! tracking are negligible, and their epochs are compacted in storage flags.
+//!
+
+use dpp::state_transition::fee::fee_schedule::FeeSchedule;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::drive::config::DriveConfig;
+
+/// Parameters to calculate storage refunds and to decide which refunds are negligible
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageRefundParameters {
+    /// Number of epochs in a year
+    pub epochs_per_year: u16,
+    /// The amount of the storage fee distributed per perpetual storage year
+    pub fee_distribution_table: Vec<Decimal>,
+    /// Credits paid per stored byte
+    pub storage_credits_per_byte: u64,
+    /// Refunds below this amount of credits are negligible
+    pub negligible_refund_credits: u64,
+    /// Refunds with fewer epochs left to distribute their fees are negligible
+    pub negligible_refund_remaining_epochs: u16,
+}
+
+impl StorageRefundParameters {
+    /// Creates refund parameters from the Drive config and the fee schedule
+    /// of the active protocol version
+    pub fn from_config(config: &DriveConfig, fee_schedule: &FeeSchedule) -> Self {
+        Self {
+            epochs_per_year: config.epochs_per_year,
+            fee_distribution_table: config.fee_distribution_table.clone(),
+            storage_credits_per_byte: fee_schedule.storage_disk_usage_credit_per_byte,
+            negligible_refund_credits: config.negligible_refund_credits,
+            negligible_refund_remaining_epochs: config.negligible_refund_remaining_epochs,
+        }
+    }
+
+    /// Number of epochs storage fees are distributed to
+    pub fn perpetual_storage_epochs(&self) -> u16 {
+        (self.fee_distribution_table.len() as u16).saturating_mul(self.epochs_per_year)
+    }
+
+    /// Returns the number of epochs left to distribute fees paid in the epoch
+    pub fn remaining_epochs(&self, paid_epoch_index: u16, current_epoch_index: u16) -> u16 {
+        let elapsed_epochs = current_epoch_index.saturating_sub(paid_epoch_index);
+
+        self.perpetual_storage_epochs()
+            .saturating_sub(elapsed_epochs)
+    }
+
+    /// Calculates the refund of bytes paid in the epoch and removed in the current epoch.
+    /// Fees are refunded by the shares of the distribution table left to distribute,
+    /// each floored per epoch as they are when fees are distributed.
+    pub fn calculate_refund(
+        &self,
+        bytes: u32,
+        paid_epoch_index: u16,
+        current_epoch_index: u16,
+    ) -> u64 {
+        if self.epochs_per_year == 0 {
+            return 0;
+        }
+
+        let storage_fee = Decimal::from(bytes) * Decimal::from(self.storage_credits_per_byte);
+
+        let epochs_per_year = Decimal::from(self.epochs_per_year);
+
+        let elapsed_epochs = current_epoch_index.saturating_sub(paid_epoch_index) as u32;
+
+        self.fee_distribution_table.iter().enumerate().fold(
+            0u64,
+            |refund, (year, distribution_for_that_year_ratio)| {
+                let year_start_epoch = year as u32 * self.epochs_per_year as u32;
+
+                let elapsed_year_epochs = elapsed_epochs
+                    .saturating_sub(year_start_epoch)
+                    .min(self.epochs_per_year as u32);
+
+                let remaining_year_epochs = self.epochs_per_year as u32 - elapsed_year_epochs;
+
+                if remaining_year_epochs == 0 {
+                    return refund;
+                }
+
+                let epoch_fee_share = (storage_fee * *distribution_for_that_year_ratio
+                    / epochs_per_year)
+                    .floor()
+                    .to_u64()
+                    .unwrap_or(u64::MAX);
+
+                refund.saturating_add(epoch_fee_share.saturating_mul(remaining_year_epochs as u64))
+            },
+        )
+    }
+
+    /// Returns true if the refund of bytes paid in the epoch is negligible in the current epoch
+    pub fn is_refund_negligible(
+        &self,
+        bytes: u32,
+        paid_epoch_index: u16,
+        current_epoch_index: u16,
+    ) -> bool {
+        let remaining_epochs = self.remaining_epochs(paid_epoch_index, current_epoch_index);
+
+        remaining_epochs == 0
+            || remaining_epochs < self.negligible_refund_remaining_epochs
+            || self.calculate_refund(bytes, paid_epoch_index, current_epoch_index)
+                < self.negligible_refund_credits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn refund_parameters() -> StorageRefundParameters {
+        StorageRefundParameters {
+            epochs_per_year: 20,
+            fee_distribution_table: vec![dec!(0.2); 5],
+            storage_credits_per_byte: 1000,
+            negligible_refund_credits: 5000,
+            negligible_refund_remaining_epochs: 10,
+        }
+    }
+
+    #[test]
+    fn test_calculate_refund() {
+        let refund_parameters = refund_parameters();
+
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 0), 10000);
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 25), 7500);
+        assert_eq!(refund_parameters.calculate_refund(10, 5, 105), 0);
+        assert_eq!(refund_parameters.calculate_refund(10, 5, 200), 0);
+    }
+
+    #[test]
+    fn test_calculate_refund_by_distribution_table() {
+        let refund_parameters = StorageRefundParameters {
+            epochs_per_year: 10,
+            fee_distribution_table: vec![dec!(0.5), dec!(0.3), dec!(0.2)],
+            ..refund_parameters()
+        };
+
+        // 500, 300 and 200 credits are distributed to every epoch of each year
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 0), 10000);
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 5), 7500);
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 15), 3500);
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 30), 0);
+
+        let refund_parameters = StorageRefundParameters {
+            storage_credits_per_byte: 7,
+            ..refund_parameters
+        };
+
+        // epoch shares of 70 credits are floored as they are when fees are distributed
+        assert_eq!(refund_parameters.calculate_refund(10, 0, 0), 60);
+    }
+
+    #[test]
+    fn test_is_refund_negligible() {
+        let refund_parameters = refund_parameters();
+
+        // 7500 credits left with 75 epochs
+        assert!(!refund_parameters.is_refund_negligible(10, 0, 25));
+
+        // 4000 credits left
+        assert!(refund_parameters.is_refund_negligible(10, 0, 60));
+
+        // 9 epochs left, although 45000 credits would be refunded
+        assert!(refund_parameters.is_refund_negligible(500, 0, 91));
+
+        // fees were fully distributed
+        assert!(refund_parameters.is_refund_negligible(u32::MAX, 0, 100));
+    }
+}
@@ -10,5 +10,9 @@ pub const KEY_START_BLOCK_HEIGHT: &[u8; 1] = b"c";
 pub const KEY_PROPOSERS: &[u8; 1] = b"m";
 /// Fee multiplier key
 pub const KEY_FEE_MULTIPLIER: &[u8; 1] = b"x";
+/// Protocol version votes key
+pub const KEY_PROTOCOL_VERSION_VOTES: &[u8; 1] = b"v";
+/// Proposer payouts key
+pub const KEY_PROPOSER_PAYOUTS: &[u8; 1] = b"r";
 /// Epoch storage offset
 pub(crate) const EPOCH_STORAGE_OFFSET: u16 = 256;
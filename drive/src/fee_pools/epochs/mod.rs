@@ -3,26 +3,70 @@ pub mod epoch_key_constants;
 pub mod operations_factory;
 pub mod paths;
 
+pub use dpp::state_transition::fee::constants::DEFAULT_FEE_MULTIPLIER;
+use dpp::state_transition::fee::fee_schedule::FeeSchedule;
+use dpp::version::INITIAL_PROTOCOL_VERSION;
 use serde::{Deserialize, Serialize};
 
 // TODO: I would call it EpochTree because it represent pool,
 //  not just Epoch which is more abstract thing that we will probably need in future too
 
+fn default_fee_multiplier() -> f64 {
+    DEFAULT_FEE_MULTIPLIER
+}
+
 /// Epoch struct
-#[derive(Serialize, Deserialize, Default, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Epoch {
     /// Epoch index
     pub index: u16,
     /// Epoch key
     pub(crate) key: [u8; 2],
+    /// Fee multiplier applied to costs of operations performed in the epoch
+    #[serde(default = "default_fee_multiplier")]
+    pub fee_multiplier: f64,
+    /// Protocol version selecting the fee schedule for operations performed in the epoch
+    pub protocol_version: u32,
+}
+
+impl Default for Epoch {
+    fn default() -> Self {
+        Self::new(0)
+    }
 }
 
 impl Epoch {
-    /// Create new epoch
+    /// Create new epoch of the genesis protocol version with the default fee multiplier.
+    /// Use it to address epoch trees; block execution must build the epoch with
+    /// the active protocol version and fee multiplier stored in state.
     pub fn new(index: u16) -> Self {
+        Self::new_with_fee_multiplier_and_protocol_version(
+            index,
+            DEFAULT_FEE_MULTIPLIER,
+            INITIAL_PROTOCOL_VERSION,
+        )
+    }
+
+    /// Create new epoch with the fee multiplier and the fee schedule of the protocol version
+    /// applied to costs of its operations
+    pub fn new_with_fee_multiplier_and_protocol_version(
+        index: u16,
+        fee_multiplier: f64,
+        protocol_version: u32,
+    ) -> Self {
         let key = paths::encode_epoch_index_key(index).expect("epoch index is too high");
 
-        Self { index, key }
+        Self {
+            index,
+            key,
+            fee_multiplier,
+            protocol_version,
+        }
+    }
+
+    /// Returns the fee schedule of the epoch protocol version
+    pub fn fee_schedule(&self) -> &'static FeeSchedule {
+        FeeSchedule::for_protocol_version(self.protocol_version)
     }
 }
@@ -33,6 +33,7 @@
 //!
 
 use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::fee_pools::epochs::proposer_payouts::ProposerPayout;
 use crate::drive::fee_pools::pools_vec_path;
 use crate::drive::Drive;
 use crate::error::Error;
@@ -94,6 +95,10 @@ impl Epoch {
 
         batch.push(self.init_proposers_tree_operation());
 
+        batch.push(self.init_protocol_version_votes_tree_operation());
+
+        batch.push(self.init_proposer_payouts_tree_operation());
+
         batch.push(self.update_fee_multiplier_operation(multiplier));
 
         batch.push(self.update_start_time_operation(start_time_ms));
@@ -196,6 +201,49 @@ impl Epoch {
         )
     }
 
+    /// Returns a groveDB op which inserts an empty tree into the epoch protocol version votes path.
+    pub fn init_protocol_version_votes_tree_operation(&self) -> GroveDbOp {
+        GroveDbOp::insert_run_op(
+            self.get_vec_path(),
+            epoch_key_constants::KEY_PROTOCOL_VERSION_VOTES.to_vec(),
+            Element::empty_tree(),
+        )
+    }
+
+    /// Returns a groveDB op which inserts an empty tree into the epoch proposer payouts path.
+    pub fn init_proposer_payouts_tree_operation(&self) -> GroveDbOp {
+        GroveDbOp::insert_run_op(
+            self.get_vec_path(),
+            epoch_key_constants::KEY_PROPOSER_PAYOUTS.to_vec(),
+            Element::empty_tree(),
+        )
+    }
+
+    /// Returns a groveDB op which stores the payout receipt of the given proposer.
+    pub fn update_proposer_payout_operation(
+        &self,
+        proposer_payout: &ProposerPayout,
+    ) -> Result<GroveDbOp, Error> {
+        Ok(GroveDbOp::insert_run_op(
+            self.get_proposer_payouts_vec_path(),
+            proposer_payout.pro_tx_hash.to_vec(),
+            Element::Item(proposer_payout.to_bytes()?, None),
+        ))
+    }
+
+    /// Returns a groveDB op which updates the protocol version supported by the given proposer.
+    pub fn update_protocol_version_vote_operation(
+        &self,
+        proposer_pro_tx_hash: &[u8; 32],
+        protocol_version: u32,
+    ) -> GroveDbOp {
+        GroveDbOp::insert_run_op(
+            self.get_protocol_version_votes_vec_path(),
+            proposer_pro_tx_hash.to_vec(),
+            Element::Item(protocol_version.to_be_bytes().to_vec(), None),
+        )
+    }
+
     /// Adds a groveDB op to the batch which deletes the given epoch proposers from the proposers tree.
     pub fn add_delete_proposers_operations(
         &self,
@@ -407,6 +455,12 @@ mod tests {
                 .expect("should get proposers");
 
             assert_eq!(proposers, vec!());
+
+            let protocol_version_votes = drive
+                .get_epoch_protocol_version_votes(&epoch, Some(&transaction))
+                .expect("should get protocol version votes");
+
+            assert!(protocol_version_votes.is_empty());
         }
     }
 
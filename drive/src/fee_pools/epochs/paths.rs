@@ -58,6 +58,33 @@ impl Epoch {
         ]
     }
 
+    /// Get the path to the protocol version votes tree of this epoch as a fixed length path
+    pub fn get_protocol_version_votes_path(&self) -> [&[u8]; 3] {
+        [
+            Into::<&[u8; 1]>::into(RootTree::Pools),
+            &self.key,
+            epoch_key_constants::KEY_PROTOCOL_VERSION_VOTES.as_slice(),
+        ]
+    }
+
+    /// Get the path to the protocol version votes tree of this epoch as a vector
+    pub fn get_protocol_version_votes_vec_path(&self) -> Vec<Vec<u8>> {
+        vec![
+            vec![RootTree::Pools as u8],
+            self.key.to_vec(),
+            epoch_key_constants::KEY_PROTOCOL_VERSION_VOTES.to_vec(),
+        ]
+    }
+
+    /// Get the path to the proposer payouts tree of this epoch as a vector
+    pub fn get_proposer_payouts_vec_path(&self) -> Vec<Vec<u8>> {
+        vec![
+            vec![RootTree::Pools as u8],
+            self.key.to_vec(),
+            epoch_key_constants::KEY_PROPOSER_PAYOUTS.to_vec(),
+        ]
+    }
+
     /// Get the path to this epoch as a fixed size path
     pub fn get_path(&self) -> [&[u8]; 2] {
         [Into::<&[u8; 1]>::into(RootTree::Pools), &self.key]
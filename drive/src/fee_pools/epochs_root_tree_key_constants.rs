@@ -2,3 +2,5 @@
 pub const KEY_STORAGE_FEE_POOL: &[u8; 1] = b"s";
 /// Unpaid epoch index key
 pub const KEY_UNPAID_EPOCH_INDEX: &[u8; 1] = b"u";
+/// Storage fee distribution cohorts key
+pub const KEY_STORAGE_FEE_COHORTS: &[u8; 1] = b"c";
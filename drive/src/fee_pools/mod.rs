@@ -28,11 +28,14 @@
 //
 
 use crate::drive::batch::GroveDbOpBatch;
-use crate::drive::fee_pools::epochs::constants::{GENESIS_EPOCH_INDEX, PERPETUAL_STORAGE_EPOCHS};
-use crate::drive::fee_pools::pools_vec_path;
+use crate::drive::fee_pools::epochs::constants::GENESIS_EPOCH_INDEX;
+use crate::drive::fee_pools::storage_fee_cohorts::StorageFeeCohort;
+use crate::drive::fee_pools::{pools_vec_path, storage_fee_cohorts_vec_path};
+use crate::error::Error;
+use crate::fee_pools::epochs::paths::encode_epoch_index_key;
 use crate::fee_pools::epochs::Epoch;
 use crate::fee_pools::epochs_root_tree_key_constants::{
-    KEY_STORAGE_FEE_POOL, KEY_UNPAID_EPOCH_INDEX,
+    KEY_STORAGE_FEE_COHORTS, KEY_STORAGE_FEE_POOL, KEY_UNPAID_EPOCH_INDEX,
 };
 use grovedb::batch::GroveDbOp;
 use grovedb::Element;
@@ -43,16 +46,23 @@ pub mod epochs;
 pub mod epochs_root_tree_key_constants;
 
 /// Adds the operations to groveDB op batch to create the fee pool trees
-pub fn add_create_fee_pool_trees_operations(batch: &mut GroveDbOpBatch) {
+/// with the given number of perpetual storage epochs
+pub fn add_create_fee_pool_trees_operations(
+    batch: &mut GroveDbOpBatch,
+    perpetual_storage_epochs: u16,
+) {
     // Init storage credit pool
     batch.push(update_storage_fee_distribution_pool_operation(0));
 
     // Init next epoch to pay
     batch.push(update_unpaid_epoch_index_operation(GENESIS_EPOCH_INDEX));
 
+    // Init storage fee distribution cohorts
+    batch.push(init_storage_fee_cohorts_tree_operation());
+
     // We need to insert 50 years worth of epochs,
-    // with 20 epochs per year that's 1000 epochs
-    for i in GENESIS_EPOCH_INDEX..PERPETUAL_STORAGE_EPOCHS {
+    // with 20 epochs per year that's 1000 epochs by default
+    for i in GENESIS_EPOCH_INDEX..perpetual_storage_epochs {
         let epoch = Epoch::new(i);
         epoch.add_init_empty_operations(batch);
     }
@@ -67,6 +77,24 @@ pub fn update_storage_fee_distribution_pool_operation(storage_fee: u64) -> Grove
     )
 }
 
+/// Inserts an empty tree for the storage fee distribution cohorts
+pub fn init_storage_fee_cohorts_tree_operation() -> GroveDbOp {
+    GroveDbOp::insert_run_op(
+        pools_vec_path(),
+        KEY_STORAGE_FEE_COHORTS.to_vec(),
+        Element::empty_tree(),
+    )
+}
+
+/// Updates the storage fee distribution cohort started at the cohort's epoch
+pub fn update_storage_fee_cohort_operation(cohort: &StorageFeeCohort) -> Result<GroveDbOp, Error> {
+    Ok(GroveDbOp::insert_run_op(
+        storage_fee_cohorts_vec_path(),
+        encode_epoch_index_key(cohort.start_epoch_index)?.to_vec(),
+        Element::new_item(cohort.to_bytes()),
+    ))
+}
+
 /// Updates the unpaid epoch index
 pub fn update_unpaid_epoch_index_operation(epoch_index: u16) -> GroveDbOp {
     GroveDbOp::insert_run_op(
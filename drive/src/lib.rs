@@ -24,6 +24,8 @@ pub mod fee_pools;
 /// Query module
 pub mod query;
 
+/// Costs module
+pub use costs;
 /// DPP module
 pub use dpp;
 /// GroveDB module
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use dpp::data_contract::extra::DriveContractExt;
+use dpp::document::document_transition::Action;
+use rs_drive::contract::Contract;
+use rs_drive::fee::estimation::estimate_document_actions_fee;
+use rs_drive::fee_pools::epochs::Epoch;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentActionWasm {
+    action: Action,
+    document_type: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EstimatedFeesWasm {
+    storage_fee: u64,
+    processing_fee: u64,
+}
+
+/// Estimates worst case fees of document transitions without accessing the platform state.
+/// Transitions are passed as `{ action, documentType }` objects.
+#[wasm_bindgen(js_name=estimateDocumentTransitionsFee)]
+pub fn estimate_document_transitions_fee_wasm(
+    data_contract_buffer: Vec<u8>,
+    document_transitions: Vec<JsValue>,
+    epoch_index: u16,
+    fee_multiplier: f64,
+    protocol_version: u32,
+) -> Result<JsValue, JsValue> {
+    let contract = <Contract as DriveContractExt>::from_cbor(&data_contract_buffer, None)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let document_actions = document_transitions
+        .iter()
+        .map(|document_transition| {
+            JsValue::into_serde::<DocumentActionWasm>(document_transition)
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+        .collect::<Result<Vec<DocumentActionWasm>, JsValue>>()?;
+
+    let actions: Vec<(Action, &str)> = document_actions
+        .iter()
+        .map(|document_action| {
+            (
+                document_action.action,
+                document_action.document_type.as_str(),
+            )
+        })
+        .collect();
+
+    let epoch = Epoch::new_with_fee_multiplier_and_protocol_version(
+        epoch_index,
+        fee_multiplier,
+        protocol_version,
+    );
+
+    let fee_result = estimate_document_actions_fee(&contract, &actions, &epoch)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    JsValue::from_serde(&EstimatedFeesWasm {
+        storage_fee: fee_result.storage_fee,
+        processing_fee: fee_result.processing_fee,
+    })
+    .map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -16,11 +16,27 @@ const {
   driveCreateDocument,
   driveUpdateDocument,
   driveDeleteDocument,
+  driveEstimateDocumentTransitionsFee,
+  driveEstimateContractCreationFee,
   driveQueryDocuments,
   driveProveDocumentsQuery,
   driveInsertIdentity,
+  driveUpdateIdentity,
+  driveAddToIdentityBalance,
+  driveRemoveFromIdentityBalance,
+  driveChargeIdentityFees,
+  driveFetchIdentityWithDebt,
+  driveFetchSmlStore,
+  driveProveIdentity,
   driveFetchLatestWithdrawalTransactionIndex,
   driveEnqueueWithdrawalTransaction,
+  driveEnqueueWithdrawalRequest,
+  driveApplyIdentityCreditWithdrawal,
+  driveFetchQueuedWithdrawalTransactions,
+  driveProveQueuedWithdrawalTransactions,
+  driveGetEpochsInfo,
+  driveProveEpochsInfo,
+  abciInfo,
   abciInitChain,
   abciBlockBegin,
   abciBlockEnd,
@@ -43,6 +59,12 @@ const driveCreateInitialStateStructureAsync = appendStack(
 const driveFetchContractAsync = appendStack(promisify(driveFetchContract));
 const driveCreateContractAsync = appendStack(promisify(driveCreateContract));
 const driveUpdateContractAsync = appendStack(promisify(driveUpdateContract));
+const driveEstimateDocumentTransitionsFeeAsync = appendStack(
+  promisify(driveEstimateDocumentTransitionsFee),
+);
+const driveEstimateContractCreationFeeAsync = appendStack(
+  promisify(driveEstimateContractCreationFee),
+);
 const driveCreateDocumentAsync = appendStack(promisify(driveCreateDocument));
 const driveUpdateDocumentAsync = appendStack(promisify(driveUpdateDocument));
 const driveDeleteDocumentAsync = appendStack(promisify(driveDeleteDocument));
@@ -54,7 +76,31 @@ const driveFetchLatestWithdrawalTransactionIndexAsync = appendStack(
 const driveEnqueueWithdrawalTransactionAsync = appendStack(
   promisify(driveEnqueueWithdrawalTransaction),
 );
+const driveEnqueueWithdrawalRequestAsync = appendStack(
+  promisify(driveEnqueueWithdrawalRequest),
+);
+const driveApplyIdentityCreditWithdrawalAsync = appendStack(
+  promisify(driveApplyIdentityCreditWithdrawal),
+);
+const driveFetchQueuedWithdrawalTransactionsAsync = appendStack(
+  promisify(driveFetchQueuedWithdrawalTransactions),
+);
+const driveProveQueuedWithdrawalTransactionsAsync = appendStack(
+  promisify(driveProveQueuedWithdrawalTransactions),
+);
+const driveGetEpochsInfoAsync = appendStack(promisify(driveGetEpochsInfo));
+const driveProveEpochsInfoAsync = appendStack(promisify(driveProveEpochsInfo));
 const driveInsertIdentityAsync = appendStack(promisify(driveInsertIdentity));
+const driveUpdateIdentityAsync = appendStack(promisify(driveUpdateIdentity));
+const driveAddToIdentityBalanceAsync = appendStack(promisify(driveAddToIdentityBalance));
+const driveRemoveFromIdentityBalanceAsync = appendStack(
+  promisify(driveRemoveFromIdentityBalance),
+);
+const driveChargeIdentityFeesAsync = appendStack(promisify(driveChargeIdentityFees));
+const driveFetchIdentityWithDebtAsync = appendStack(promisify(driveFetchIdentityWithDebt));
+const driveFetchSmlStoreAsync = appendStack(promisify(driveFetchSmlStore));
+const driveProveIdentityAsync = appendStack(promisify(driveProveIdentity));
+const abciInfoAsync = appendStack(promisify(abciInfo));
 const abciInitChainAsync = appendStack(promisify(abciInitChain));
 const abciBlockBeginAsync = appendStack(promisify(abciBlockBegin));
 const abciBlockEndAsync = appendStack(promisify(abciBlockEnd));
@@ -67,6 +113,7 @@ class Drive {
    * @param {Object} config
    * @param {number} config.dataContractsGlobalCacheSize
    * @param {number} config.dataContractsTransactionalCacheSize
+   * @param {string} [config.platformConfigPath] - JSON file with chain parameters
    */
   constructor(dbPath, config) {
     this.drive = driveOpen(dbPath, config);
@@ -220,6 +267,50 @@ class Drive {
     );
   }
 
+  /**
+   * Estimate worst case fees of document transitions without applying them
+   *
+   * @param {DataContract} dataContract
+   * @param {AbstractDocumentTransition[]} documentTransitions
+   * @param {BlockInfo} blockInfo
+   * @param {Buffer|Identifier} [ownerId=undefined]
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async estimateDocumentTransitionsFee(
+    dataContract,
+    documentTransitions,
+    blockInfo,
+    ownerId = undefined,
+  ) {
+    return driveEstimateDocumentTransitionsFeeAsync.call(
+      this.drive,
+      dataContract.toBuffer(),
+      documentTransitions.map((documentTransition) => ({
+        action: documentTransition.getAction(),
+        documentType: documentTransition.getType(),
+      })),
+      ownerId ? Buffer.from(ownerId) : undefined,
+      blockInfo,
+    );
+  }
+
+  /**
+   * Estimate worst case fees of creating a data contract without applying it
+   *
+   * @param {DataContract} dataContract
+   * @param {BlockInfo} blockInfo
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async estimateContractCreationFee(dataContract, blockInfo) {
+    return driveEstimateContractCreationFeeAsync.call(
+      this.drive,
+      dataContract.toBuffer(),
+      blockInfo,
+    );
+  }
+
   /**
    *
    * @param {DataContract} dataContract
@@ -295,6 +386,9 @@ class Drive {
   }
 
   /**
+   * Insert an identity or replace an existing one.
+   * A balance change of an existing identity is recorded as a top up or a debit.
+   *
    * @param {Identity} identity
    * @param {BlockInfo} blockInfo
    * @param {GroveDBTransaction} [transaction=undefined]
@@ -312,6 +406,158 @@ class Drive {
     );
   }
 
+  /**
+   * Update an existing identity keeping its balance.
+   * Use addToIdentityBalance and removeFromIdentityBalance to change the balance.
+   *
+   * @param {Identity} identity
+   * @param {BlockInfo} blockInfo
+   * @param {GroveDBTransaction} [transaction=undefined]
+   * @param {boolean} [dryRun=false]
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async updateIdentity(identity, blockInfo, transaction = undefined, dryRun = false) {
+    return driveUpdateIdentityAsync.call(
+      this.drive,
+      identity.toBuffer(),
+      blockInfo,
+      !dryRun,
+      transaction,
+    );
+  }
+
+  /**
+   * Credit the identity with newly minted credits, settling its debt first
+   *
+   * @param {Buffer|Identifier} identityId
+   * @param {number} amount - amount in credits
+   * @param {BlockInfo} blockInfo
+   * @param {GroveDBTransaction} [transaction=undefined]
+   * @param {boolean} [dryRun=false]
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async addToIdentityBalance(
+    identityId,
+    amount,
+    blockInfo,
+    transaction = undefined,
+    dryRun = false,
+  ) {
+    return driveAddToIdentityBalanceAsync.call(
+      this.drive,
+      Buffer.from(identityId),
+      amount,
+      blockInfo,
+      !dryRun,
+      transaction,
+    );
+  }
+
+  /**
+   * Debit the identity, recording a debt if its balance is insufficient
+   *
+   * @param {Buffer|Identifier} identityId
+   * @param {number} amount - amount in credits
+   * @param {BlockInfo} blockInfo
+   * @param {GroveDBTransaction} [transaction=undefined]
+   * @param {boolean} [dryRun=false]
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async removeFromIdentityBalance(
+    identityId,
+    amount,
+    blockInfo,
+    transaction = undefined,
+    dryRun = false,
+  ) {
+    return driveRemoveFromIdentityBalanceAsync.call(
+      this.drive,
+      Buffer.from(identityId),
+      amount,
+      blockInfo,
+      !dryRun,
+      transaction,
+    );
+  }
+
+  /**
+   * Charge the fees of an executed state transition from the identity balance,
+   * recording a debt if its balance is insufficient
+   *
+   * @param {Buffer|Identifier} identityId
+   * @param {FeeResult} feeResult - fees of the executed state transition
+   * @param {BlockInfo} blockInfo
+   * @param {GroveDBTransaction} [transaction=undefined]
+   * @param {boolean} [dryRun=false]
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async chargeIdentityFees(
+    identityId,
+    feeResult,
+    blockInfo,
+    transaction = undefined,
+    dryRun = false,
+  ) {
+    return driveChargeIdentityFeesAsync.call(
+      this.drive,
+      Buffer.from(identityId),
+      feeResult,
+      blockInfo,
+      !dryRun,
+      transaction,
+    );
+  }
+
+  /**
+   * Fetch the serialized identity along with its debt
+   *
+   * @param {Buffer|Identifier} identityId
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<[Buffer, number]>}
+   */
+  async fetchIdentityWithDebt(identityId, transaction = undefined) {
+    return driveFetchIdentityWithDebtAsync.call(
+      this.drive,
+      Buffer.from(identityId),
+      transaction,
+    );
+  }
+
+  /**
+   * Fetch the CBOR serialized simplified masternode list store
+   *
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<Buffer>}
+   */
+  async fetchSmlStore(transaction = undefined) {
+    return driveFetchSmlStoreAsync.call(
+      this.drive,
+      transaction,
+    );
+  }
+
+  /**
+   * Prove the identity along with its debt
+   *
+   * @param {Buffer|Identifier} identityId
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<Buffer>}
+   */
+  async proveIdentity(identityId, transaction = undefined) {
+    return driveProveIdentityAsync.call(
+      this.drive,
+      Buffer.from(identityId),
+      transaction,
+    );
+  }
+
   /**
    * Fetch the latest index of the withdrawal transaction in a queue
    *
@@ -330,20 +576,153 @@ class Drive {
    * Enqueue withdrawal transaction into the queue
    *
    * @param {number} index
+   * @param {Buffer} identityId
+   * @param {number} amount - withdrawal amount in credits
    * @param {Buffer} transactionBytes
    * @param {GroveDBTransaction} [transaction=undefined]
    *
    * @returns {Promise<void>}
    */
-  async enqueueWithdrawalTransaction(index, transactionBytes, transaction = undefined) {
+  async enqueueWithdrawalTransaction(
+    index,
+    identityId,
+    amount,
+    transactionBytes,
+    transaction = undefined,
+  ) {
     return driveEnqueueWithdrawalTransactionAsync.call(
       this.drive,
       index,
+      identityId,
+      amount,
       transactionBytes,
       transaction,
     );
   }
 
+  /**
+   * Enqueue withdrawal request to be pooled into a withdrawal transaction at the end of the block
+   *
+   * @param {WithdrawalRequest} request
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<void>}
+   */
+  async enqueueWithdrawalRequest(request, transaction = undefined) {
+    return driveEnqueueWithdrawalRequestAsync.call(
+      this.drive,
+      request.transitionId,
+      request.identityId,
+      request.amount,
+      request.coreFee,
+      request.pooling,
+      request.outputScript,
+      transaction,
+    );
+  }
+
+  /**
+   * Enqueue withdrawal request and debit the identity balance in a single batch
+   *
+   * @param {WithdrawalRequest} request
+   * @param {BlockInfo} blockInfo
+   * @param {GroveDBTransaction} [transaction=undefined]
+   * @param {boolean} [dryRun=false]
+   *
+   * @returns {Promise<FeeResult>}
+   */
+  async applyIdentityCreditWithdrawal(
+    request,
+    blockInfo,
+    transaction = undefined,
+    dryRun = false,
+  ) {
+    return driveApplyIdentityCreditWithdrawalAsync.call(
+      this.drive,
+      request.transitionId,
+      request.identityId,
+      request.amount,
+      request.coreFee,
+      request.pooling,
+      request.outputScript,
+      blockInfo,
+      !dryRun,
+      transaction,
+    );
+  }
+
+  /**
+   * Fetch queued withdrawal transactions without removing them from the queue
+   *
+   * @param {number} startIndex - asset unlock index to start from
+   * @param {number} limit
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<QueuedWithdrawalTransactions>}
+   */
+  async fetchQueuedWithdrawalTransactions(startIndex, limit, transaction = undefined) {
+    return driveFetchQueuedWithdrawalTransactionsAsync.call(
+      this.drive,
+      startIndex,
+      limit,
+      transaction,
+    );
+  }
+
+  /**
+   * Prove queued withdrawal transactions
+   *
+   * @param {number} startIndex - asset unlock index to start from
+   * @param {number} limit
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<Buffer>}
+   */
+  async proveQueuedWithdrawalTransactions(startIndex, limit, transaction = undefined) {
+    return driveProveQueuedWithdrawalTransactionsAsync.call(
+      this.drive,
+      startIndex,
+      limit,
+      transaction,
+    );
+  }
+
+  /**
+   * Get summaries of existing epochs
+   *
+   * @param {number} startEpochIndex
+   * @param {number} endEpochIndex - exclusive
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<EpochSummary[]>}
+   */
+  async getEpochsInfo(startEpochIndex, endEpochIndex, transaction = undefined) {
+    return driveGetEpochsInfoAsync.call(
+      this.drive,
+      startEpochIndex,
+      endEpochIndex,
+      transaction,
+    );
+  }
+
+  /**
+   * Prove summaries of existing epochs
+   *
+   * @param {number} startEpochIndex
+   * @param {number} endEpochIndex - exclusive
+   * @param {GroveDBTransaction} [transaction=undefined]
+   *
+   * @returns {Promise<Buffer>}
+   */
+  async proveEpochsInfo(startEpochIndex, endEpochIndex, transaction = undefined) {
+    return driveProveEpochsInfoAsync.call(
+      this.drive,
+      startEpochIndex,
+      endEpochIndex,
+      transaction,
+    );
+  }
+
   /**
    * Get the ABCI interface
    * @returns {RSAbci}
@@ -355,6 +734,31 @@ class Drive {
      * @typedef RSAbci
      */
     return {
+      /**
+       * ABCI info
+       *
+       * @param {InfoRequest} request
+       *
+       * @returns {Promise<InfoResponse>}
+       */
+      async info(request) {
+        const requestBytes = cbor.encode(request);
+
+        const responseBytes = await abciInfoAsync.call(
+          drive,
+          requestBytes,
+        );
+
+        const response = cbor.decode(responseBytes);
+
+        return {
+          ...response,
+          lastBlockAppHash: response.lastBlockAppHash
+            ? Buffer.from(response.lastBlockAppHash)
+            : null,
+        };
+      },
+
       /**
        * ABCI init chain
        *
@@ -417,7 +821,12 @@ class Drive {
           transaction,
         );
 
-        return cbor.decode(responseBytes);
+        const response = cbor.decode(responseBytes);
+
+        return {
+          ...response,
+          appHash: Buffer.from(response.appHash),
+        };
       },
 
       /**
@@ -451,6 +860,19 @@ class Drive {
  * @property {number} height
  * @property {number} epoch
  * @property {number} timeMs
+ * @property {number} [feeMultiplier] - fee multiplier of the epoch from BlockBeginResponse
+ * @property {number} [protocolVersion] - protocol version from BlockBeginResponse selecting
+ *   the fee schedule, the current protocol version by default
+ */
+
+/**
+ * @typedef InfoRequest
+ */
+
+/**
+ * @typedef InfoResponse
+ * @property {number} lastBlockHeight
+ * @property {Buffer|null} lastBlockAppHash
  */
 
 /**
@@ -461,19 +883,85 @@ class Drive {
  * @typedef InitChainResponse
  */
 
+/**
+ * @typedef WithdrawalRequest
+ * @property {Buffer} transitionId - hash of the identity credit withdrawal state transition
+ * @property {Buffer} identityId
+ * @property {number} amount - withdrawal amount in credits
+ * @property {number} coreFee
+ * @property {number} pooling - 0 - never, 1 - if available, 2 - standard
+ * @property {Buffer} outputScript
+ */
+
+/**
+ * @typedef PooledWithdrawal
+ * @property {Buffer} identityId
+ * @property {number} amount - withdrawal amount in credits
+ */
+
+/**
+ * @typedef QueuedWithdrawalTransaction
+ * @property {number} index - asset unlock index
+ * @property {number} amount - amount in duffs
+ * @property {Buffer} outputScript
+ * @property {number} fee
+ * @property {PooledWithdrawal[]} withdrawals
+ */
+
+/**
+ * @typedef QueuedWithdrawalTransactions
+ * @property {QueuedWithdrawalTransaction[]} transactions
+ * @property {number} totalAmount - amount in duffs
+ * @property {number} totalFee
+ */
+
+/**
+ * @typedef EpochSummary
+ * @property {number} index
+ * @property {number|null} startTimeMs
+ * @property {number|null} startBlockHeight
+ * @property {number|null} feeMultiplier
+ * @property {number} processingCreditsForDistribution
+ * @property {number} storageCreditsForDistribution
+ * @property {number} proposerCount - number of proposers which are not paid yet
+ * @property {boolean} isPaid
+ */
+
 /**
  * @typedef BlockBeginRequest
  * @property {number} blockHeight
  * @property {number} blockTimeMs - timestamp in milliseconds
- * @property {number} [previousBlockTimeMs] - timestamp in milliseconds
  * @property {Buffer} proposerProTxHash
  * @property {Buffer} validatorSetQuorumHash
+ * @property {number} proposedProtocolVersion - protocol version supported by the proposer
+ * @property {number} coreChainLockedHeight
+ * @property {number[]} broadcastedWithdrawalTransactionIndexes - asset unlock indexes seen by Core
+ * @property {number[]} completedWithdrawalTransactionIndexes - asset unlock indexes chain locked by Core
+ * @property {MasternodeListDiff} [masternodeListDiff]
+ */
+
+/**
+ * @typedef MasternodeListEntry
+ * @property {Buffer} proTxHash
+ * @property {Buffer} ownerKeyHash
+ * @property {Buffer} votingKeyHash
+ * @property {Buffer} payoutScript
+ * @property {Buffer} operatorPublicKey
+ * @property {string} status - 'valid' or 'banned'
+ */
+
+/**
+ * @typedef MasternodeListDiff
+ * @property {MasternodeListEntry[]} updatedMasternodes
+ * @property {Buffer[]} removedMasternodes - proTxHashes
  */
 
 /**
  * @typedef BlockBeginResponse
  * @property {Buffer[]} unsignedWithdrawalTransactions
  * @property {EpochInfo} epochInfo
+ * @property {number} protocolVersion - active protocol version
+ * @property {number} feeMultiplier - fee multiplier of the current epoch
  */
 
 /**
@@ -498,6 +986,14 @@ class Drive {
  * @typedef BlockEndResponse
  * @property {number} [proposersPaidCount]
  * @property {number} [paidEpochIndex]
+ * @property {Buffer} appHash
+ * @property {ConsensusParamsUpdate} [consensusParamUpdates]
+ */
+
+/**
+ * @typedef ConsensusParamsUpdate
+ * @property {{ maxBytes: number|null, maxGas: number|null }} [block]
+ * @property {{ appVersion: number|null }} [version]
  */
 
 /**
@@ -341,6 +341,7 @@ class GroveDB {
  * @typedef FeeResult
  * @property {number} processingFee
  * @property {number} storageFee
+ * @property {number} baseProcessingFee - processing fee before applying the epoch fee multiplier
  * @property {{
  *   identifier: Uint8Array,
  *   epochsToBytes: Object<string, number>
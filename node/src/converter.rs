@@ -1,10 +1,11 @@
 use neon::prelude::*;
 use neon::types::buffer::TypedArray;
 use num::FromPrimitive;
+use rs_drive::dpp::version::LATEST_VERSION;
 use rs_drive::drive::block_info::BlockInfo;
 use rs_drive::drive::flags::StorageFlags;
 use rs_drive::fee::FeeResult;
-use rs_drive::fee_pools::epochs::Epoch;
+use rs_drive::fee_pools::epochs::{Epoch, DEFAULT_FEE_MULTIPLIER};
 use rs_drive::grovedb::reference_path::ReferencePathType;
 use rs_drive::grovedb::{Element, PathQuery, Query, SizedQuery};
 use std::borrow::Borrow;
@@ -396,8 +397,31 @@ pub fn js_object_to_block_info<'a, C: Context<'a>>(
     let js_height: Handle<JsNumber> = js_object.get(cx, "height")?;
     let js_epoch: Handle<JsNumber> = js_object.get(cx, "epoch")?;
     let js_time: Handle<JsNumber> = js_object.get(cx, "timeMs")?;
+    let js_maybe_fee_multiplier: Handle<JsValue> = js_object.get(cx, "feeMultiplier")?;
 
-    let epoch = Epoch::new(js_epoch.value(cx) as u16);
+    let fee_multiplier = if !js_maybe_fee_multiplier.is_a::<JsUndefined, _>(cx) {
+        js_maybe_fee_multiplier
+            .downcast_or_throw::<JsNumber, _>(cx)?
+            .value(cx)
+    } else {
+        DEFAULT_FEE_MULTIPLIER
+    };
+
+    let js_maybe_protocol_version: Handle<JsValue> = js_object.get(cx, "protocolVersion")?;
+
+    let protocol_version = if !js_maybe_protocol_version.is_a::<JsUndefined, _>(cx) {
+        js_maybe_protocol_version
+            .downcast_or_throw::<JsNumber, _>(cx)?
+            .value(cx) as u32
+    } else {
+        LATEST_VERSION
+    };
+
+    let epoch = Epoch::new_with_fee_multiplier_and_protocol_version(
+        js_epoch.value(cx) as u16,
+        fee_multiplier,
+        protocol_version,
+    );
 
     let block_info = BlockInfo {
         height: js_height.value(cx) as u64,
@@ -408,6 +432,33 @@ pub fn js_object_to_block_info<'a, C: Context<'a>>(
     Ok(block_info)
 }
 
+pub fn js_object_to_fee_result<'a, C: Context<'a>>(
+    js_object: Handle<JsObject>,
+    cx: &mut C,
+) -> NeonResult<FeeResult> {
+    let js_storage_fee: Handle<JsNumber> = js_object.get(cx, "storageFee")?;
+    let js_processing_fee: Handle<JsNumber> = js_object.get(cx, "processingFee")?;
+    let js_maybe_base_processing_fee: Handle<JsValue> = js_object.get(cx, "baseProcessingFee")?;
+
+    let processing_fee = js_processing_fee.value(cx) as u64;
+
+    // Fees calculated without the epoch fee multiplier have no separate base processing fee
+    let base_processing_fee = if !js_maybe_base_processing_fee.is_a::<JsUndefined, _>(cx) {
+        js_maybe_base_processing_fee
+            .downcast_or_throw::<JsNumber, _>(cx)?
+            .value(cx) as u64
+    } else {
+        processing_fee
+    };
+
+    Ok(FeeResult {
+        storage_fee: js_storage_fee.value(cx) as u64,
+        processing_fee,
+        base_processing_fee,
+        ..Default::default()
+    })
+}
+
 pub fn fee_result_to_js_object<'a, C: Context<'a>>(
     cx: &mut C,
     fee_result: FeeResult,
@@ -415,6 +466,7 @@ pub fn fee_result_to_js_object<'a, C: Context<'a>>(
     // TODO: We can't go with f64 because we can lose costs
     let js_processing_fee = cx.number(fee_result.processing_fee as f64);
     let js_storage_fee = cx.number(fee_result.storage_fee as f64);
+    let js_base_processing_fee = cx.number(fee_result.base_processing_fee as f64);
 
     let js_removed_from_identities: Handle<JsObject> = cx.empty_object();
 
@@ -438,6 +490,7 @@ pub fn fee_result_to_js_object<'a, C: Context<'a>>(
 
     js_fee_results.set(cx, "processingFee", js_processing_fee)?;
     js_fee_results.set(cx, "storageFee", js_storage_fee)?;
+    js_fee_results.set(cx, "baseProcessingFee", js_base_processing_fee)?;
     js_fee_results.set(cx, "removedFromIdentities", js_removed_from_identities)?;
 
     Ok(js_fee_results)
@@ -6,17 +6,24 @@ use std::{option::Option::None, path::Path, sync::mpsc, thread};
 
 use dash_abci::abci::handlers::TenderdashAbci;
 use dash_abci::abci::messages::{
-    AfterFinalizeBlockRequest, BlockBeginRequest, BlockEndRequest, InitChainRequest, Serializable,
+    AfterFinalizeBlockRequest, BlockBeginRequest, BlockEndRequest, InfoRequest, InitChainRequest,
+    Serializable,
 };
+use dash_abci::config::PlatformConfig;
 use dash_abci::platform::Platform;
 use neon::prelude::*;
+use rs_drive::contract::Contract;
+use rs_drive::dpp::data_contract::extra::DriveContractExt;
+use rs_drive::dpp::document::document_transition::Action;
+use rs_drive::dpp::identity::state_transition::identity_credit_withdrawal_transition::Pooling;
 use rs_drive::dpp::identity::Identity;
 use rs_drive::drive::batch::GroveDbOpBatch;
 use rs_drive::drive::config::DriveConfig;
 use rs_drive::drive::flags::StorageFlags;
+use rs_drive::drive::identity::withdrawal_pooling::WithdrawalRequest;
+use rs_drive::drive::identity::withdrawal_status::WithdrawalTransactionRecord;
 use rs_drive::error::drive::DriveError;
 use rs_drive::error::Error;
-use rs_drive::fee_pools::epochs::Epoch;
 use rs_drive::grovedb::{PathQuery, Transaction};
 
 type TransactionPointerAddress = usize;
@@ -104,9 +111,36 @@ impl PlatformWrapper {
                 |_| cx.throw_range_error("`dataContractsTransactionalCacheSize` must fit in u64"),
             )?;
 
+        // Chain parameters are loaded from an optional file, defaults are used otherwise
+        let js_platform_config_path: Handle<JsValue> =
+            drive_config.get(cx, "platformConfigPath")?;
+        let mut platform_config = if js_platform_config_path.is_a::<JsString, _>(cx) {
+            let platform_config_path = js_platform_config_path
+                .downcast_or_throw::<JsString, _>(cx)?
+                .value(cx);
+
+            PlatformConfig::from_file(platform_config_path)
+                .or_else(|err| cx.throw_error(err.to_string()))?
+        } else {
+            PlatformConfig::default()
+        };
+
+        platform_config.drive = DriveConfig {
+            data_contracts_global_cache_size,
+            data_contracts_transactional_cache_size,
+            ..Default::default()
+        };
+
+        platform_config
+            .validate()
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
         // Channel for sending callbacks to execute on the Drive connection thread
         let (tx, rx) = mpsc::channel::<PlatformWrapperMessage>();
 
+        // Channel for reporting the result of opening Platform on the Drive connection thread
+        let (open_result_tx, open_result_rx) = mpsc::channel::<Result<(), String>>();
+
         // Create an `Channel` for calling back to JavaScript. It is more efficient
         // to create a single channel and re-use it for all database callbacks.
         // The JavaScript process will not exit as long as this channel has not been
@@ -121,15 +155,19 @@ impl PlatformWrapper {
         thread::spawn(move || {
             let path = Path::new(&path_string);
             // Open a connection to groveDb, this will be moved to a separate thread
+            let platform: Platform = match Platform::open(path, Some(platform_config)) {
+                Ok(platform) => {
+                    // The receiver is waiting for the result until the thread is spawned
+                    let _ = open_result_tx.send(Ok(()));
 
-            let drive_config = DriveConfig {
-                data_contracts_global_cache_size,
-                data_contracts_transactional_cache_size,
-                ..Default::default()
-            };
+                    platform
+                }
+                Err(err) => {
+                    let _ = open_result_tx.send(Err(err.to_string()));
 
-            // TODO: think how to pass this error to JS
-            let platform: Platform = Platform::open(path, Some(drive_config)).unwrap();
+                    return;
+                }
+            };
 
             let mut transactions: HashMap<TransactionPointerAddress, Transaction> = HashMap::new();
 
@@ -244,6 +282,11 @@ impl PlatformWrapper {
             }
         });
 
+        open_result_rx
+            .recv()
+            .or_else(|err| cx.throw_error(err.to_string()))?
+            .or_else(|err| cx.throw_error(err))?;
+
         Ok(Self { tx })
     }
 
@@ -431,15 +474,15 @@ impl PlatformWrapper {
 
         let contract_id = converter::js_buffer_to_identifier(&mut cx, js_contract_id)?;
 
-        let maybe_epoch: Option<Epoch> = if !js_maybe_epoch_index.is_a::<JsUndefined, _>(&mut cx) {
+        let maybe_epoch_index: Option<u16> = if !js_maybe_epoch_index
+            .is_a::<JsUndefined, _>(&mut cx)
+        {
             let js_epoch_index = js_maybe_epoch_index.downcast_or_throw::<JsNumber, _>(&mut cx)?;
 
             let epoch_index = u16::try_from(js_epoch_index.value(&mut cx) as i64)
                 .or_else(|_| cx.throw_range_error("`epochs` must fit in u16"))?;
 
-            let epoch = Epoch::new(epoch_index);
-
-            Some(epoch)
+            Some(epoch_index)
         } else {
             None
         };
@@ -467,6 +510,15 @@ impl PlatformWrapper {
                     };
 
                 let result = transaction_result.and_then(|transaction_arg| {
+                    let maybe_epoch = maybe_epoch_index
+                        .map(|epoch_index| {
+                            platform.drive.get_epoch_with_active_protocol_version(
+                                epoch_index,
+                                transaction_arg,
+                            )
+                        })
+                        .transpose()?;
+
                     platform.drive.get_contract_with_fetch_info(
                         contract_id,
                         maybe_epoch.as_ref(),
@@ -929,6 +981,130 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
+    fn js_estimate_document_transitions_fee(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_contract_cbor = cx.argument::<JsBuffer>(0)?;
+        let js_transitions = cx.argument::<JsArray>(1)?;
+        let js_owner_id = cx.argument::<JsValue>(2)?;
+        let js_block_info = cx.argument::<JsObject>(3)?;
+        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let contract_cbor = converter::js_buffer_to_vec_u8(js_contract_cbor, &mut cx);
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+
+        let owner_id = if !js_owner_id.is_a::<JsUndefined, _>(&mut cx) {
+            let js_owner_id = js_owner_id.downcast_or_throw::<JsBuffer, _>(&mut cx)?;
+
+            Some(converter::js_buffer_to_identifier(&mut cx, js_owner_id)?)
+        } else {
+            None
+        };
+
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        let mut actions: Vec<(Action, String)> = Vec::new();
+
+        for js_transition in js_transitions.to_vec(&mut cx)? {
+            let js_transition = js_transition.downcast_or_throw::<JsObject, _>(&mut cx)?;
+            let js_action: Handle<JsNumber> = js_transition.get(&mut cx, "action")?;
+            let js_document_type: Handle<JsString> = js_transition.get(&mut cx, "documentType")?;
+
+            let action = Action::try_from(js_action.value(&mut cx) as u8)
+                .or_else(|err| cx.throw_error(err.to_string()))?;
+
+            actions.push((action, js_document_type.value(&mut cx)));
+        }
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, _transactions, channel| {
+                let actions: Vec<(Action, &str)> = actions
+                    .iter()
+                    .map(|(action, document_type_name)| (*action, document_type_name.as_str()))
+                    .collect();
+
+                let result = platform.drive.estimate_document_actions_fee(
+                    &contract,
+                    &actions,
+                    owner_id,
+                    &block_info,
+                );
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(fee_result) => {
+                            let js_fee_result =
+                                converter::fee_result_to_js_object(&mut task_context, fee_result)?;
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_fee_result.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_estimate_contract_creation_fee(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_contract_cbor = cx.argument::<JsBuffer>(0)?;
+        let js_block_info = cx.argument::<JsObject>(1)?;
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let contract_cbor = converter::js_buffer_to_vec_u8(js_contract_cbor, &mut cx);
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, _transactions, channel| {
+                let result = platform
+                    .drive
+                    .estimate_contract_creation_fee(contract_cbor, &block_info);
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(fee_result) => {
+                            let js_fee_result =
+                                converter::fee_result_to_js_object(&mut task_context, fee_result)?;
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_fee_result.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
     fn js_insert_identity_cbor(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         let js_identity_cbor = cx.argument::<JsBuffer>(0)?;
         let js_block_info = cx.argument::<JsObject>(1)?;
@@ -976,7 +1152,7 @@ impl PlatformWrapper {
                 );
 
                 let result = transaction_result.and_then(|transaction_arg| {
-                    platform.drive.insert_identity(
+                    platform.drive.insert_or_update_identity(
                         identity,
                         block_info,
                         apply,
@@ -1012,13 +1188,11 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_query_documents(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_query_cbor = cx.argument::<JsBuffer>(0)?;
-        let js_contract_id = cx.argument::<JsBuffer>(1)?;
-        let js_document_type_name = cx.argument::<JsString>(2)?;
-        let js_maybe_epoch_index = cx.argument::<JsValue>(3)?;
-        // TODO We need dry run for validation
-        let js_transaction = cx.argument::<JsValue>(4)?;
+    fn js_update_identity_cbor(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_identity_cbor = cx.argument::<JsBuffer>(0)?;
+        let js_block_info = cx.argument::<JsObject>(1)?;
+        let js_apply = cx.argument::<JsBoolean>(2)?;
+        let js_transaction = cx.argument::<JsValue>(3)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1029,28 +1203,18 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(5)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
 
         let drive = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let query_cbor = converter::js_buffer_to_vec_u8(js_query_cbor, &mut cx);
-        let contract_id = converter::js_buffer_to_identifier(&mut cx, js_contract_id)?;
-        let document_type_name = js_document_type_name.value(&mut cx);
-
-        let maybe_epoch: Option<Epoch> = if !js_maybe_epoch_index.is_a::<JsUndefined, _>(&mut cx) {
-            let js_epoch_index = js_maybe_epoch_index.downcast_or_throw::<JsNumber, _>(&mut cx)?;
-
-            let epoch_index = u16::try_from(js_epoch_index.value(&mut cx) as i64)
-                .or_else(|_| cx.throw_range_error("`epochs` must fit in u16"))?;
-
-            let epoch = Epoch::new(epoch_index);
+        let identity_cbor = converter::js_buffer_to_vec_u8(js_identity_cbor, &mut cx);
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+        let apply = js_apply.value(&mut cx);
 
-            Some(epoch)
-        } else {
-            None
-        };
+        let identity =
+            Identity::from_buffer(identity_cbor).or_else(|e| cx.throw_error(e.to_string()))?;
 
         drive
             .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
@@ -1065,12 +1229,17 @@ impl PlatformWrapper {
                         None => Ok(None),
                     };
 
+                let storage_flags = StorageFlags::new_single_epoch(
+                    block_info.epoch.index,
+                    Some(identity.id.to_buffer()),
+                );
+
                 let result = transaction_result.and_then(|transaction_arg| {
-                    platform.drive.query_documents(
-                        &query_cbor,
-                        contract_id,
-                        document_type_name.as_str(),
-                        maybe_epoch.as_ref(),
+                    platform.drive.update_identity(
+                        identity,
+                        block_info,
+                        apply,
+                        Some(storage_flags).as_ref(),
                         transaction_arg,
                     )
                 });
@@ -1078,18 +1247,14 @@ impl PlatformWrapper {
                 channel.send(move |mut task_context| {
                     let callback = js_callback.into_inner(&mut task_context);
                     let this = task_context.undefined();
-                    let callback_arguments: Vec<Handle<JsValue>> = match result {
-                        Ok((value, skipped, cost)) => {
-                            let js_array: Handle<JsArray> = task_context.empty_array();
-                            let js_vecs = converter::nested_vecs_to_js(&mut task_context, value)?;
-                            let js_num = task_context.number(skipped).upcast::<JsValue>();
-                            let js_cost = task_context.number(cost as f64).upcast::<JsValue>();
 
-                            js_array.set(&mut task_context, 0, js_vecs)?;
-                            js_array.set(&mut task_context, 1, js_num)?;
-                            js_array.set(&mut task_context, 2, js_cost)?;
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(fee_result) => {
+                            let js_fee_result =
+                                converter::fee_result_to_js_object(&mut task_context, fee_result)?;
 
-                            vec![task_context.null().upcast(), js_array.upcast()]
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_fee_result.upcast()]
                         }
 
                         // Convert the error to a JavaScript exception on failure
@@ -1106,11 +1271,12 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_prove_documents_query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_query_cbor = cx.argument::<JsBuffer>(0)?;
-        let js_contract_id = cx.argument::<JsBuffer>(1)?;
-        let js_document_type_name = cx.argument::<JsString>(2)?;
-        let js_transaction = cx.argument::<JsValue>(3)?;
+    fn js_add_to_identity_balance(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_identity_id = cx.argument::<JsBuffer>(0)?;
+        let js_amount = cx.argument::<JsNumber>(1)?;
+        let js_block_info = cx.argument::<JsObject>(2)?;
+        let js_apply = cx.argument::<JsBoolean>(3)?;
+        let js_transaction = cx.argument::<JsValue>(4)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1121,15 +1287,16 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(5)?.root(&mut cx);
 
         let drive = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let query_cbor = converter::js_buffer_to_vec_u8(js_query_cbor, &mut cx);
-        let contract_id = converter::js_buffer_to_identifier(&mut cx, js_contract_id)?;
-        let document_type_name = js_document_type_name.value(&mut cx);
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+        let amount = js_amount.value(&mut cx) as u64;
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+        let apply = js_apply.value(&mut cx);
 
         drive
             .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
@@ -1145,12 +1312,11 @@ impl PlatformWrapper {
                     };
 
                 let result = transaction_result.and_then(|transaction_arg| {
-                    platform.drive.query_documents_as_grove_proof(
-                        &query_cbor,
-                        contract_id,
-                        document_type_name.as_str(),
-                        None,
-                        None,
+                    platform.drive.add_to_identity_balance(
+                        &identity_id,
+                        amount,
+                        block_info,
+                        apply,
                         transaction_arg,
                     )
                 });
@@ -1158,16 +1324,14 @@ impl PlatformWrapper {
                 channel.send(move |mut task_context| {
                     let callback = js_callback.into_inner(&mut task_context);
                     let this = task_context.undefined();
-                    let callback_arguments: Vec<Handle<JsValue>> = match result {
-                        Ok((proof, processing_cost)) => {
-                            let js_array: Handle<JsArray> = task_context.empty_array();
-                            let js_buffer = JsBuffer::external(&mut task_context, proof);
-                            let js_processing_cost = task_context.number(processing_cost as f64);
 
-                            js_array.set(&mut task_context, 0, js_buffer)?;
-                            js_array.set(&mut task_context, 1, js_processing_cost)?;
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(fee_result) => {
+                            let js_fee_result =
+                                converter::fee_result_to_js_object(&mut task_context, fee_result)?;
 
-                            vec![task_context.null().upcast(), js_array.upcast()]
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_fee_result.upcast()]
                         }
 
                         // Convert the error to a JavaScript exception on failure
@@ -1184,39 +1348,12 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_start_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
-
-        let db = cx
-            .this()
-            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
-
-        db.start_transaction(|tx, transaction_raw_pointer_address, channel| {
-            let transaction_address = PlatformWrapperTransactionAddress {
-                address: transaction_raw_pointer_address,
-                tx,
-            };
-
-            channel.send(move |mut task_context| {
-                let callback = js_callback.into_inner(&mut task_context);
-                let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = vec![
-                    task_context.null().upcast(),
-                    task_context.boxed(transaction_address).upcast(),
-                ];
-
-                callback.call(&mut task_context, this, callback_arguments)?;
-
-                Ok(())
-            });
-        })
-        .or_else(|err| cx.throw_error(err.to_string()))?;
-
-        Ok(cx.undefined())
-    }
-
-    fn js_grove_db_commit_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_transaction = cx.argument::<JsValue>(0)?;
+    fn js_remove_from_identity_balance(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_identity_id = cx.argument::<JsBuffer>(0)?;
+        let js_amount = cx.argument::<JsNumber>(1)?;
+        let js_block_info = cx.argument::<JsObject>(2)?;
+        let js_apply = cx.argument::<JsBoolean>(3)?;
+        let js_transaction = cx.argument::<JsValue>(4)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1227,23 +1364,666 @@ impl PlatformWrapper {
             None
         };
 
-        let transaction_address =
-            maybe_boxed_transaction_address.expect("transaction address should be available");
-
-        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(5)?.root(&mut cx);
 
-        let db = cx
+        let drive = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        db.commit_transaction(transaction_address, |channel, maybe_error| {
-            channel.send(move |mut task_context| {
-                let callback = js_callback.into_inner(&mut task_context);
-                let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = match maybe_error {
-                    Ok(_) => vec![task_context.null().upcast()],
-                    Err(err) => vec![task_context.error(err)?.upcast()],
-                };
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+        let amount = js_amount.value(&mut cx) as u64;
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+        let apply = js_apply.value(&mut cx);
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    platform.drive.remove_from_identity_balance(
+                        &identity_id,
+                        amount,
+                        block_info,
+                        apply,
+                        transaction_arg,
+                    )
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(fee_result) => {
+                            let js_fee_result =
+                                converter::fee_result_to_js_object(&mut task_context, fee_result)?;
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_fee_result.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_charge_identity_fees(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_identity_id = cx.argument::<JsBuffer>(0)?;
+        let js_fee_result = cx.argument::<JsObject>(1)?;
+        let js_block_info = cx.argument::<JsObject>(2)?;
+        let js_apply = cx.argument::<JsBoolean>(3)?;
+        let js_transaction = cx.argument::<JsValue>(4)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(5)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+        let fee_result = converter::js_object_to_fee_result(js_fee_result, &mut cx)?;
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+        let apply = js_apply.value(&mut cx);
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    platform.drive.charge_identity_fees(
+                        &identity_id,
+                        &fee_result,
+                        block_info,
+                        apply,
+                        transaction_arg,
+                    )
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(fee_result) => {
+                            let js_fee_result =
+                                converter::fee_result_to_js_object(&mut task_context, fee_result)?;
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_fee_result.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_fetch_identity_with_debt(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_identity_id = cx.argument::<JsBuffer>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    platform
+                        .drive
+                        .fetch_identity_with_debt(&identity_id, transaction_arg)
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok((identity, debt)) => {
+                            let identity_cbor = identity.to_buffer().or_else(|_| {
+                                task_context.throw_range_error("can't serialize identity")
+                            })?;
+
+                            let js_array: Handle<JsArray> = task_context.empty_array();
+                            let js_identity = JsBuffer::external(&mut task_context, identity_cbor);
+                            let js_debt = task_context.number(debt as f64);
+
+                            js_array.set(&mut task_context, 0, js_identity)?;
+                            js_array.set(&mut task_context, 1, js_debt)?;
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_array.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_fetch_sml_store(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transaction = cx.argument::<JsValue>(0)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    platform.drive.fetch_serialized_sml_store(transaction_arg)
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(sml_store_cbor) => {
+                            let js_sml_store =
+                                JsBuffer::external(&mut task_context, sml_store_cbor);
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_sml_store.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_prove_identity(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_identity_id = cx.argument::<JsBuffer>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    platform.drive.prove_identity(&identity_id, transaction_arg)
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok(proof) => {
+                            let js_proof = JsBuffer::external(&mut task_context, proof);
+
+                            // First parameter of JS callbacks is error, which is null in this case
+                            vec![task_context.null().upcast(), js_proof.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_query_documents(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_query_cbor = cx.argument::<JsBuffer>(0)?;
+        let js_contract_id = cx.argument::<JsBuffer>(1)?;
+        let js_document_type_name = cx.argument::<JsString>(2)?;
+        let js_maybe_epoch_index = cx.argument::<JsValue>(3)?;
+        // TODO We need dry run for validation
+        let js_transaction = cx.argument::<JsValue>(4)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(5)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let query_cbor = converter::js_buffer_to_vec_u8(js_query_cbor, &mut cx);
+        let contract_id = converter::js_buffer_to_identifier(&mut cx, js_contract_id)?;
+        let document_type_name = js_document_type_name.value(&mut cx);
+
+        let maybe_epoch_index: Option<u16> = if !js_maybe_epoch_index
+            .is_a::<JsUndefined, _>(&mut cx)
+        {
+            let js_epoch_index = js_maybe_epoch_index.downcast_or_throw::<JsNumber, _>(&mut cx)?;
+
+            let epoch_index = u16::try_from(js_epoch_index.value(&mut cx) as i64)
+                .or_else(|_| cx.throw_range_error("`epochs` must fit in u16"))?;
+
+            Some(epoch_index)
+        } else {
+            None
+        };
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    let maybe_epoch = maybe_epoch_index
+                        .map(|epoch_index| {
+                            platform.drive.get_epoch_with_active_protocol_version(
+                                epoch_index,
+                                transaction_arg,
+                            )
+                        })
+                        .transpose()?;
+
+                    platform.drive.query_documents(
+                        &query_cbor,
+                        contract_id,
+                        document_type_name.as_str(),
+                        maybe_epoch.as_ref(),
+                        transaction_arg,
+                    )
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok((value, skipped, cost)) => {
+                            let js_array: Handle<JsArray> = task_context.empty_array();
+                            let js_vecs = converter::nested_vecs_to_js(&mut task_context, value)?;
+                            let js_num = task_context.number(skipped).upcast::<JsValue>();
+                            let js_cost = task_context.number(cost as f64).upcast::<JsValue>();
+
+                            js_array.set(&mut task_context, 0, js_vecs)?;
+                            js_array.set(&mut task_context, 1, js_num)?;
+                            js_array.set(&mut task_context, 2, js_cost)?;
+
+                            vec![task_context.null().upcast(), js_array.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_prove_documents_query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_query_cbor = cx.argument::<JsBuffer>(0)?;
+        let js_contract_id = cx.argument::<JsBuffer>(1)?;
+        let js_document_type_name = cx.argument::<JsString>(2)?;
+        let js_transaction = cx.argument::<JsValue>(3)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+        let drive = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let query_cbor = converter::js_buffer_to_vec_u8(js_query_cbor, &mut cx);
+        let contract_id = converter::js_buffer_to_identifier(&mut cx, js_contract_id)?;
+        let document_type_name = js_document_type_name.value(&mut cx);
+
+        drive
+            .send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+                let transaction_result: Result<Option<&Transaction>, Error> =
+                    match maybe_boxed_transaction_address {
+                        Some(address) => transactions
+                            .get(&address)
+                            .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                                "invalid transaction pointer address",
+                            )))
+                            .map(Some),
+                        None => Ok(None),
+                    };
+
+                let result = transaction_result.and_then(|transaction_arg| {
+                    platform.drive.query_documents_as_grove_proof(
+                        &query_cbor,
+                        contract_id,
+                        document_type_name.as_str(),
+                        None,
+                        None,
+                        transaction_arg,
+                    )
+                });
+
+                channel.send(move |mut task_context| {
+                    let callback = js_callback.into_inner(&mut task_context);
+                    let this = task_context.undefined();
+                    let callback_arguments: Vec<Handle<JsValue>> = match result {
+                        Ok((proof, processing_cost)) => {
+                            let js_array: Handle<JsArray> = task_context.empty_array();
+                            let js_buffer = JsBuffer::external(&mut task_context, proof);
+                            let js_processing_cost = task_context.number(processing_cost as f64);
+
+                            js_array.set(&mut task_context, 0, js_buffer)?;
+                            js_array.set(&mut task_context, 1, js_processing_cost)?;
+
+                            vec![task_context.null().upcast(), js_array.upcast()]
+                        }
+
+                        // Convert the error to a JavaScript exception on failure
+                        Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    };
+
+                    callback.call(&mut task_context, this, callback_arguments)?;
+
+                    Ok(())
+                });
+            })
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_start_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.start_transaction(|tx, transaction_raw_pointer_address, channel| {
+            let transaction_address = PlatformWrapperTransactionAddress {
+                address: transaction_raw_pointer_address,
+                tx,
+            };
+
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = vec![
+                    task_context.null().upcast(),
+                    task_context.boxed(transaction_address).upcast(),
+                ];
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_commit_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transaction = cx.argument::<JsValue>(0)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let transaction_address =
+            maybe_boxed_transaction_address.expect("transaction address should be available");
+
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.commit_transaction(transaction_address, |channel, maybe_error| {
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = match maybe_error {
+                    Ok(_) => vec![task_context.null().upcast()],
+                    Err(err) => vec![task_context.error(err)?.upcast()],
+                };
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_rollback_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transaction = cx.argument::<JsValue>(0)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let transaction_address =
+            maybe_boxed_transaction_address.expect("transaction address should be available");
+
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.rollback_transaction(transaction_address, |channel, maybe_error| {
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = match maybe_error {
+                    Ok(_) => vec![task_context.null().upcast()],
+                    Err(err) => vec![task_context.error(err)?.upcast()],
+                };
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_abort_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transaction = cx.argument::<JsValue>(0)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let transaction_address =
+            maybe_boxed_transaction_address.expect("transaction address should be available");
+
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.abort_transaction(transaction_address, |channel, maybe_error| {
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = match maybe_error {
+                    Ok(_) => vec![task_context.null().upcast()],
+                    Err(err) => vec![task_context.error(err)?.upcast()],
+                };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
 
@@ -1255,8 +2035,10 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_rollback_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_transaction = cx.argument::<JsValue>(0)?;
+    fn js_grove_db_get(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path = cx.argument::<JsArray>(0)?;
+        let js_key = cx.argument::<JsBuffer>(1)?;
+        let js_transaction = cx.argument::<JsValue>(2)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1267,22 +2049,254 @@ impl PlatformWrapper {
             None
         };
 
-        let transaction_address =
-            maybe_boxed_transaction_address.expect("transaction address should be available");
+        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
 
-        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
+        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
 
+        // Get the `this` value as a `JsBox<Database>`
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        db.rollback_transaction(transaction_address, |channel, maybe_error| {
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
+
+            let grove_db = &platform.drive.grove;
+            let path_slice = path.iter().map(|fragment| fragment.as_slice());
+            let result = transaction_result.and_then(|transaction_arg| {
+                grove_db
+                    .get(path_slice, &key, transaction_arg)
+                    .unwrap()
+                    .map_err(Error::GroveDB)
+            });
+
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = match maybe_error {
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(element) => {
+                        // First parameter of JS callbacks is error, which is null in this case
+                        vec![
+                            task_context.null().upcast(),
+                            converter::element_to_js_object(&mut task_context, element)?,
+                        ]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                };
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        // The result is returned through the callback, not through direct return
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_insert(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path = cx.argument::<JsArray>(0)?;
+        let js_key = cx.argument::<JsBuffer>(1)?;
+        let js_element = cx.argument::<JsObject>(2)?;
+        let js_transaction = cx.argument::<JsValue>(3)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
+        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
+        let element = converter::js_object_to_element(&mut cx, js_element)?;
+
+        // Get the `this` value as a `JsBox<Database>`
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
+
+            let grove_db = &platform.drive.grove;
+            let path_slice = path.iter().map(|fragment| fragment.as_slice());
+            let result = transaction_result.and_then(|transaction_arg| {
+                grove_db
+                    .insert(path_slice, &key, element, None, transaction_arg)
+                    .unwrap()
+                    .map_err(Error::GroveDB)
+            });
+
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
                     Ok(_) => vec![task_context.null().upcast()],
-                    Err(err) => vec![task_context.error(err)?.upcast()],
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                };
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_insert_if_not_exists(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path = cx.argument::<JsArray>(0)?;
+        let js_key = cx.argument::<JsBuffer>(1)?;
+        let js_element = cx.argument::<JsObject>(2)?;
+        let js_transaction = cx.argument::<JsValue>(3)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
+        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
+        let element = converter::js_object_to_element(&mut cx, js_element)?;
+
+        // Get the `this` value as a `JsBox<Database>`
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
+
+            let grove_db = &platform.drive.grove;
+
+            let path_slice: Vec<&[u8]> = path.iter().map(|fragment| fragment.as_slice()).collect();
+            let result = transaction_result.and_then(|transaction_arg| {
+                grove_db
+                    .insert_if_not_exists(path_slice, key.as_slice(), element, transaction_arg)
+                    .unwrap()
+                    .map_err(Error::GroveDB)
+            });
+
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(is_inserted) => vec![
+                        task_context.null().upcast(),
+                        task_context
+                            .boolean(is_inserted)
+                            .as_value(&mut task_context),
+                    ],
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                };
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    fn js_grove_db_put_aux(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_key = cx.argument::<JsBuffer>(0)?;
+        let js_value = cx.argument::<JsBuffer>(1)?;
+        let js_transaction = cx.argument::<JsValue>(2)?;
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
+
+        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
+        let value = converter::js_buffer_to_vec_u8(js_value, &mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
+
+            let grove_db = &platform.drive.grove;
+
+            let result = transaction_result.and_then(|transaction_arg| {
+                grove_db
+                    .put_aux(&key, &value, None, transaction_arg)
+                    .unwrap()
+                    .map_err(Error::GroveDB)
+            });
+
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(()) => {
+                        vec![task_context.null().upcast()]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -1292,11 +2306,13 @@ impl PlatformWrapper {
         })
         .or_else(|err| cx.throw_error(err.to_string()))?;
 
+        // The result is returned through the callback, not through direct return
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_abort_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_transaction = cx.argument::<JsValue>(0)?;
+    fn js_grove_db_delete_aux(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_key = cx.argument::<JsBuffer>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1307,22 +2323,45 @@ impl PlatformWrapper {
             None
         };
 
-        let transaction_address =
-            maybe_boxed_transaction_address.expect("transaction address should be available");
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
 
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        db.abort_transaction(transaction_address, |channel, maybe_error| {
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
+
+            let grove_db = &platform.drive.grove;
+
+            let result = transaction_result.and_then(|transaction_arg| {
+                grove_db
+                    .delete_aux(&key, None, transaction_arg)
+                    .unwrap()
+                    .map_err(Error::GroveDB)
+            });
+
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = match maybe_error {
-                    Ok(_) => vec![task_context.null().upcast()],
-                    Err(err) => vec![task_context.error(err)?.upcast()],
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(()) => {
+                        vec![task_context.null().upcast()]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -1332,13 +2371,13 @@ impl PlatformWrapper {
         })
         .or_else(|err| cx.throw_error(err.to_string()))?;
 
+        // The result is returned through the callback, not through direct return
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_get(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path = cx.argument::<JsArray>(0)?;
-        let js_key = cx.argument::<JsBuffer>(1)?;
-        let js_transaction = cx.argument::<JsValue>(2)?;
+    fn js_grove_db_get_aux(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_key = cx.argument::<JsBuffer>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1349,12 +2388,10 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
         let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
 
-        // Get the `this` value as a `JsBox<Database>`
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
@@ -1372,10 +2409,10 @@ impl PlatformWrapper {
                 };
 
             let grove_db = &platform.drive.grove;
-            let path_slice = path.iter().map(|fragment| fragment.as_slice());
+
             let result = transaction_result.and_then(|transaction_arg| {
                 grove_db
-                    .get(path_slice, &key, transaction_arg)
+                    .get_aux(&key, transaction_arg)
                     .unwrap()
                     .map_err(Error::GroveDB)
             });
@@ -1384,12 +2421,15 @@ impl PlatformWrapper {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(element) => {
-                        // First parameter of JS callbacks is error, which is null in this case
-                        vec![
-                            task_context.null().upcast(),
-                            converter::element_to_js_object(&mut task_context, element)?,
-                        ]
+                    Ok(value) => {
+                        if let Some(value) = value {
+                            vec![
+                                task_context.null().upcast(),
+                                JsBuffer::external(&mut task_context, value).upcast(),
+                            ]
+                        } else {
+                            vec![task_context.null().upcast(), task_context.null().upcast()]
+                        }
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -1407,11 +2447,9 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_insert(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path = cx.argument::<JsArray>(0)?;
-        let js_key = cx.argument::<JsBuffer>(1)?;
-        let js_element = cx.argument::<JsObject>(2)?;
-        let js_transaction = cx.argument::<JsValue>(3)?;
+    fn js_grove_db_query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path_query = cx.argument::<JsObject>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1422,13 +2460,10 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
-        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
-        let element = converter::js_object_to_element(&mut cx, js_element)?;
+        let path_query = converter::js_path_query_to_path_query(js_path_query, &mut cx)?;
 
-        // Get the `this` value as a `JsBox<Database>`
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
@@ -1446,10 +2481,10 @@ impl PlatformWrapper {
                 };
 
             let grove_db = &platform.drive.grove;
-            let path_slice = path.iter().map(|fragment| fragment.as_slice());
+
             let result = transaction_result.and_then(|transaction_arg| {
                 grove_db
-                    .insert(path_slice, &key, element, None, transaction_arg)
+                    .query(&path_query, transaction_arg)
                     .unwrap()
                     .map_err(Error::GroveDB)
             });
@@ -1457,26 +2492,35 @@ impl PlatformWrapper {
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
-
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(_) => vec![task_context.null().upcast()],
+                    Ok((value, skipped)) => {
+                        let js_array: Handle<JsArray> = task_context.empty_array();
+                        let js_vecs = converter::nested_vecs_to_js(&mut task_context, value)?;
+                        let js_num = task_context.number(skipped).upcast::<JsValue>();
+                        js_array.set(&mut task_context, 0, js_vecs)?;
+                        js_array.set(&mut task_context, 1, js_num)?;
+
+                        vec![task_context.null().upcast(), js_array.upcast()]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
                     Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
+
                 Ok(())
             });
         })
         .or_else(|err| cx.throw_error(err.to_string()))?;
 
+        // The result is returned through the callback, not through direct return
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_insert_if_not_exists(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path = cx.argument::<JsArray>(0)?;
-        let js_key = cx.argument::<JsBuffer>(1)?;
-        let js_element = cx.argument::<JsObject>(2)?;
-        let js_transaction = cx.argument::<JsValue>(3)?;
+    fn js_grove_db_prove_query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path_query = cx.argument::<JsObject>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1487,13 +2531,10 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
-        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
-        let element = converter::js_object_to_element(&mut cx, js_element)?;
+        let path_query = converter::js_path_query_to_path_query(js_path_query, &mut cx)?;
 
-        // Get the `this` value as a `JsBox<Database>`
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
@@ -1512,10 +2553,9 @@ impl PlatformWrapper {
 
             let grove_db = &platform.drive.grove;
 
-            let path_slice: Vec<&[u8]> = path.iter().map(|fragment| fragment.as_slice()).collect();
             let result = transaction_result.and_then(|transaction_arg| {
                 grove_db
-                    .insert_if_not_exists(path_slice, key.as_slice(), element, transaction_arg)
+                    .get_proved_path_query(&path_query, transaction_arg)
                     .unwrap()
                     .map_err(Error::GroveDB)
             });
@@ -1524,12 +2564,14 @@ impl PlatformWrapper {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(is_inserted) => vec![
-                        task_context.null().upcast(),
-                        task_context
-                            .boolean(is_inserted)
-                            .as_value(&mut task_context),
-                    ],
+                    Ok(proof) => {
+                        let js_buffer = JsBuffer::external(&mut task_context, proof);
+                        let js_value = js_buffer.as_value(&mut task_context);
+
+                        vec![task_context.null().upcast(), js_value.upcast()]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
                     Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
@@ -1540,13 +2582,13 @@ impl PlatformWrapper {
         })
         .or_else(|err| cx.throw_error(err.to_string()))?;
 
+        // The result is returned through the callback, not through direct return
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_put_aux(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_key = cx.argument::<JsBuffer>(0)?;
-        let js_value = cx.argument::<JsBuffer>(1)?;
-        let js_transaction = cx.argument::<JsValue>(2)?;
+    fn js_grove_db_prove_query_many(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path_queries = cx.argument::<JsArray>(0)?;
+        let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1557,42 +2599,44 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
+        if maybe_boxed_transaction_address.is_none() {
+            cx.throw_type_error("transaction address is undefined")?;
+        }
 
-        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
-        let value = converter::js_buffer_to_vec_u8(js_value, &mut cx);
+        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+        let js_path_queries = js_path_queries.to_vec(&mut cx)?;
+        let mut path_queries: Vec<PathQuery> = Vec::with_capacity(js_path_queries.len());
+
+        for js_path_query in js_path_queries {
+            let js_path_query = js_path_query.downcast_or_throw::<JsObject, _>(&mut cx)?;
+            path_queries.push(converter::js_path_query_to_path_query(
+                js_path_query,
+                &mut cx,
+            )?);
+        }
 
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
-            let transaction_result: Result<Option<&Transaction>, Error> =
-                match maybe_boxed_transaction_address {
-                    Some(address) => transactions
-                        .get(&address)
-                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
-                            "invalid transaction pointer address",
-                        )))
-                        .map(Some),
-                    None => Ok(None),
-                };
-
+        db.send_to_drive_thread(move |platform: &Platform, _, channel| {
             let grove_db = &platform.drive.grove;
 
-            let result = transaction_result.and_then(|transaction_arg| {
-                grove_db
-                    .put_aux(&key, &value, None, transaction_arg)
-                    .unwrap()
-                    .map_err(Error::GroveDB)
-            });
+            let path_queries = path_queries.iter().collect();
+
+            let result = grove_db.prove_query_many(path_queries).unwrap();
 
             channel.send(move |mut task_context| {
-                let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(()) => {
-                        vec![task_context.null().upcast()]
+                let callback = js_callback.into_inner(&mut task_context);
+
+                let callback_arguments = match result {
+                    Ok(proof) => {
+                        let js_buffer = JsBuffer::external(&mut task_context, proof);
+                        let js_value = js_buffer.as_value(&mut task_context);
+
+                        vec![task_context.null().upcast(), js_value.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -1610,9 +2654,34 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_delete_aux(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_key = cx.argument::<JsBuffer>(0)?;
-        let js_transaction = cx.argument::<JsValue>(1)?;
+    /// Flush data on disc and then calls js callback passed as a first
+    /// argument to the function
+    fn js_grove_db_flush(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        db.flush(|channel| {
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+                let callback_arguments: Vec<Handle<JsValue>> = vec![task_context.null().upcast()];
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        Ok(cx.undefined())
+    }
+
+    /// Returns root hash or empty buffer
+    fn js_grove_db_root_hash(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transaction = cx.argument::<JsValue>(0)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1623,9 +2692,7 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
-
-        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
 
         let db = cx
             .this()
@@ -1647,7 +2714,7 @@ impl PlatformWrapper {
 
             let result = transaction_result.and_then(|transaction_arg| {
                 grove_db
-                    .delete_aux(&key, None, transaction_arg)
+                    .root_hash(transaction_arg)
                     .unwrap()
                     .map_err(Error::GroveDB)
             });
@@ -1655,12 +2722,12 @@ impl PlatformWrapper {
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(()) => {
-                        vec![task_context.null().upcast()]
-                    }
 
-                    // Convert the error to a JavaScript exception on failure
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(hash) => vec![
+                        task_context.null().upcast(),
+                        JsBuffer::external(&mut task_context, hash).upcast(),
+                    ],
                     Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
@@ -1675,9 +2742,11 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_get_aux(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_key = cx.argument::<JsBuffer>(0)?;
-        let js_transaction = cx.argument::<JsValue>(1)?;
+    fn js_grove_db_delete(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_path = cx.argument::<JsArray>(0)?;
+        let js_key = cx.argument::<JsBuffer>(1)?;
+
+        let js_transaction = cx.argument::<JsValue>(2)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1688,8 +2757,9 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
+        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
 
+        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
         let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
 
         let db = cx
@@ -1710,9 +2780,10 @@ impl PlatformWrapper {
 
             let grove_db = &platform.drive.grove;
 
+            let path_slice: Vec<&[u8]> = path.iter().map(|fragment| fragment.as_slice()).collect();
             let result = transaction_result.and_then(|transaction_arg| {
                 grove_db
-                    .get_aux(&key, transaction_arg)
+                    .delete(path_slice, key.as_slice(), None, transaction_arg)
                     .unwrap()
                     .map_err(Error::GroveDB)
             });
@@ -1721,15 +2792,8 @@ impl PlatformWrapper {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(value) => {
-                        if let Some(value) = value {
-                            vec![
-                                task_context.null().upcast(),
-                                JsBuffer::external(&mut task_context, value).upcast(),
-                            ]
-                        } else {
-                            vec![task_context.null().upcast(), task_context.null().upcast()]
-                        }
+                    Ok(()) => {
+                        vec![task_context.null().upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -1747,8 +2811,49 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path_query = cx.argument::<JsObject>(0)?;
+    fn js_abci_info(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_request = cx.argument::<JsBuffer>(0)?;
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+        let db = cx
+            .this()
+            .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
+
+        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+
+        db.send_to_drive_thread(move |platform: &Platform, _, channel| {
+            let result = InfoRequest::from_bytes(&request_bytes)
+                .and_then(|request| platform.info(request))
+                .and_then(|response| response.to_bytes());
+
+            channel.send(move |mut task_context| {
+                let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
+
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(response_bytes) => {
+                        let value = JsBuffer::external(&mut task_context, response_bytes);
+
+                        vec![task_context.null().upcast(), value.upcast()]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                };
+
+                callback.call(&mut task_context, this, callback_arguments)?;
+
+                Ok(())
+            });
+        })
+        .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        // The result is returned through the callback, not through direct return
+        Ok(cx.undefined())
+    }
+
+    fn js_abci_init_chain(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_request = cx.argument::<JsBuffer>(0)?;
         let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
@@ -1762,12 +2867,12 @@ impl PlatformWrapper {
 
         let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let path_query = converter::js_path_query_to_path_query(js_path_query, &mut cx)?;
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
+        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
                 match maybe_boxed_transaction_address {
@@ -1780,31 +2885,29 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let grove_db = &platform.drive.grove;
-
-            let result = transaction_result.and_then(|transaction_arg| {
-                grove_db
-                    .query(&path_query, transaction_arg)
-                    .unwrap()
-                    .map_err(Error::GroveDB)
-            });
+            let result =
+                transaction_result
+                    .map_err(|e| e.to_string())
+                    .and_then(|transaction_arg| {
+                        InitChainRequest::from_bytes(&request_bytes)
+                            .and_then(|request| platform.init_chain(request, transaction_arg))
+                            .and_then(|response| response.to_bytes())
+                            .map_err(|e| e.to_string())
+                    });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
+
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok((value, skipped)) => {
-                        let js_array: Handle<JsArray> = task_context.empty_array();
-                        let js_vecs = converter::nested_vecs_to_js(&mut task_context, value)?;
-                        let js_num = task_context.number(skipped).upcast::<JsValue>();
-                        js_array.set(&mut task_context, 0, js_vecs)?;
-                        js_array.set(&mut task_context, 1, js_num)?;
+                    Ok(response_bytes) => {
+                        let value = JsBuffer::external(&mut task_context, response_bytes);
 
-                        vec![task_context.null().upcast(), js_array.upcast()]
+                        vec![task_context.null().upcast(), value.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
-                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    Err(err) => vec![task_context.error(err)?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -1818,8 +2921,8 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_prove_query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path_query = cx.argument::<JsObject>(0)?;
+    fn js_abci_block_begin(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_request = cx.argument::<JsBuffer>(0)?;
         let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
@@ -1833,12 +2936,12 @@ impl PlatformWrapper {
 
         let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let path_query = converter::js_path_query_to_path_query(js_path_query, &mut cx)?;
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
+        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
                 match maybe_boxed_transaction_address {
@@ -1851,28 +2954,29 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let grove_db = &platform.drive.grove;
-
-            let result = transaction_result.and_then(|transaction_arg| {
-                grove_db
-                    .get_proved_path_query(&path_query, transaction_arg)
-                    .unwrap()
-                    .map_err(Error::GroveDB)
-            });
+            let result =
+                transaction_result
+                    .map_err(|e| e.to_string())
+                    .and_then(|transaction_arg| {
+                        BlockBeginRequest::from_bytes(&request_bytes)
+                            .and_then(|request| platform.block_begin(request, transaction_arg))
+                            .and_then(|response| response.to_bytes())
+                            .map_err(|e| e.to_string())
+                    });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
+
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(proof) => {
-                        let js_buffer = JsBuffer::external(&mut task_context, proof);
-                        let js_value = js_buffer.as_value(&mut task_context);
+                    Ok(response_bytes) => {
+                        let value = JsBuffer::external(&mut task_context, response_bytes);
 
-                        vec![task_context.null().upcast(), js_value.upcast()]
+                        vec![task_context.null().upcast(), value.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
-                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    Err(err) => vec![task_context.error(err)?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -1886,8 +2990,8 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_prove_query_many(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path_queries = cx.argument::<JsArray>(0)?;
+    fn js_abci_block_end(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_request = cx.argument::<JsBuffer>(0)?;
         let js_transaction = cx.argument::<JsValue>(1)?;
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
@@ -1899,48 +3003,49 @@ impl PlatformWrapper {
             None
         };
 
-        if maybe_boxed_transaction_address.is_none() {
-            cx.throw_type_error("transaction address is undefined")?;
-        }
-
         let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
 
-        let js_path_queries = js_path_queries.to_vec(&mut cx)?;
-        let mut path_queries: Vec<PathQuery> = Vec::with_capacity(js_path_queries.len());
-
-        for js_path_query in js_path_queries {
-            let js_path_query = js_path_query.downcast_or_throw::<JsObject, _>(&mut cx)?;
-            path_queries.push(converter::js_path_query_to_path_query(
-                js_path_query,
-                &mut cx,
-            )?);
-        }
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        db.send_to_drive_thread(move |platform: &Platform, _, channel| {
-            let grove_db = &platform.drive.grove;
+        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
 
-            let path_queries = path_queries.iter().collect();
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
 
-            let result = grove_db.prove_query_many(path_queries).unwrap();
+            let result =
+                transaction_result
+                    .map_err(|e| e.to_string())
+                    .and_then(|transaction_arg| {
+                        BlockEndRequest::from_bytes(&request_bytes)
+                            .and_then(|request| platform.block_end(request, transaction_arg))
+                            .and_then(|response| response.to_bytes())
+                            .map_err(|e| e.to_string())
+                    });
 
             channel.send(move |mut task_context| {
-                let this = task_context.undefined();
                 let callback = js_callback.into_inner(&mut task_context);
+                let this = task_context.undefined();
 
-                let callback_arguments = match result {
-                    Ok(proof) => {
-                        let js_buffer = JsBuffer::external(&mut task_context, proof);
-                        let js_value = js_buffer.as_value(&mut task_context);
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(response_bytes) => {
+                        let value = JsBuffer::external(&mut task_context, response_bytes);
 
-                        vec![task_context.null().upcast(), js_value.upcast()]
+                        vec![task_context.null().upcast(), value.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
-                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                    Err(err) => vec![task_context.error(err)?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -1954,20 +3059,35 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    /// Flush data on disc and then calls js callback passed as a first
-    /// argument to the function
-    fn js_grove_db_flush(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+    fn js_abci_after_finalize_block(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_request = cx.argument::<JsBuffer>(0)?;
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
 
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        db.flush(|channel| {
+        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+
+        db.send_to_drive_thread(move |platform: &Platform, _, channel| {
+            let result = AfterFinalizeBlockRequest::from_bytes(&request_bytes)
+                .and_then(|request| platform.after_finalize_block(request))
+                .and_then(|response| response.to_bytes());
+
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
-                let callback_arguments: Vec<Handle<JsValue>> = vec![task_context.null().upcast()];
+
+                let callback_arguments: Vec<Handle<JsValue>> = match result {
+                    Ok(response_bytes) => {
+                        let value = JsBuffer::external(&mut task_context, response_bytes);
+
+                        vec![task_context.null().upcast(), value.upcast()]
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
+                };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
 
@@ -1976,12 +3096,15 @@ impl PlatformWrapper {
         })
         .or_else(|err| cx.throw_error(err.to_string()))?;
 
+        // The result is returned through the callback, not through direct return
         Ok(cx.undefined())
     }
 
-    /// Returns root hash or empty buffer
-    fn js_grove_db_root_hash(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    fn js_fetch_latest_withdrawal_transaction_index(
+        mut cx: FunctionContext,
+    ) -> JsResult<JsUndefined> {
         let js_transaction = cx.argument::<JsValue>(0)?;
+        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -1992,8 +3115,6 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
@@ -2010,13 +3131,10 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let grove_db = &platform.drive.grove;
-
             let result = transaction_result.and_then(|transaction_arg| {
-                grove_db
-                    .root_hash(transaction_arg)
-                    .unwrap()
-                    .map_err(Error::GroveDB)
+                platform
+                    .drive
+                    .fetch_latest_withdrawal_transaction_index(transaction_arg)
             });
 
             channel.send(move |mut task_context| {
@@ -2024,10 +3142,20 @@ impl PlatformWrapper {
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(hash) => vec![
-                        task_context.null().upcast(),
-                        JsBuffer::external(&mut task_context, hash).upcast(),
-                    ],
+                    Ok(index) => {
+                        let index_f64 = index as f64;
+                        if index_f64 as u64 != index {
+                            vec![task_context
+                                .error("could not convert withdrawal transaction index to f64")?
+                                .upcast()]
+                        } else {
+                            let value = JsNumber::new(&mut task_context, index_f64);
+
+                            vec![task_context.null().upcast(), value.upcast()]
+                        }
+                    }
+
+                    // Convert the error to a JavaScript exception on failure
                     Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
@@ -2042,14 +3170,17 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_grove_db_delete(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_path = cx.argument::<JsArray>(0)?;
-        let js_key = cx.argument::<JsBuffer>(1)?;
-
-        let js_transaction = cx.argument::<JsValue>(2)?;
+    fn js_enqueue_withdrawal_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_index = cx.argument::<JsNumber>(0)?;
+        let js_identity_id = cx.argument::<JsBuffer>(1)?;
+        let js_amount = cx.argument::<JsNumber>(2)?;
+        let js_core_transaction = cx.argument::<JsBuffer>(3)?;
+        let js_db_transaction = cx.argument::<JsValue>(4)?;
+        let js_callback = cx.argument::<JsFunction>(5)?.root(&mut cx);
 
-        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
-            let handle = js_transaction
+        let maybe_boxed_transaction_address = if !js_db_transaction.is_a::<JsUndefined, _>(&mut cx)
+        {
+            let handle = js_db_transaction
                 .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
 
             Some(***handle)
@@ -2057,15 +3188,15 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
-
-        let path = converter::js_array_of_buffers_to_vec(js_path, &mut cx)?;
-        let key = converter::js_buffer_to_vec_u8(js_key, &mut cx);
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
+        let index = js_index.value(&mut cx);
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+        let amount = js_amount.value(&mut cx);
+        let transaction_bytes = converter::js_buffer_to_vec_u8(js_core_transaction, &mut cx);
+
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
                 match maybe_boxed_transaction_address {
@@ -2078,22 +3209,36 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let grove_db = &platform.drive.grove;
+            let mut batch = GroveDbOpBatch::new();
+
+            let index_bytes = (index as u64).to_be_bytes().to_vec();
+
+            let withdrawals = vec![(
+                WithdrawalTransactionRecord::new_queued(index as u64, identity_id, amount as u64),
+                transaction_bytes,
+            )];
+
+            platform
+                .drive
+                .add_update_withdrawal_index_counter_operation(&mut batch, index_bytes);
 
-            let path_slice: Vec<&[u8]> = path.iter().map(|fragment| fragment.as_slice()).collect();
             let result = transaction_result.and_then(|transaction_arg| {
-                grove_db
-                    .delete(path_slice, key.as_slice(), None, transaction_arg)
-                    .unwrap()
-                    .map_err(Error::GroveDB)
+                platform
+                    .drive
+                    .add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals)?;
+
+                platform
+                    .drive
+                    .grove_apply_batch(batch, false, transaction_arg)
             });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
+
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(()) => {
-                        vec![task_context.null().upcast()]
+                    Ok(_) => {
+                        vec![task_context.null().upcast(), task_context.null().upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -2111,12 +3256,19 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_abci_init_chain(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_request = cx.argument::<JsBuffer>(0)?;
-        let js_transaction = cx.argument::<JsValue>(1)?;
+    fn js_enqueue_withdrawal_request(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transition_id = cx.argument::<JsBuffer>(0)?;
+        let js_identity_id = cx.argument::<JsBuffer>(1)?;
+        let js_amount = cx.argument::<JsNumber>(2)?;
+        let js_core_fee = cx.argument::<JsNumber>(3)?;
+        let js_pooling = cx.argument::<JsNumber>(4)?;
+        let js_output_script = cx.argument::<JsBuffer>(5)?;
+        let js_db_transaction = cx.argument::<JsValue>(6)?;
+        let js_callback = cx.argument::<JsFunction>(7)?.root(&mut cx);
 
-        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
-            let handle = js_transaction
+        let maybe_boxed_transaction_address = if !js_db_transaction.is_a::<JsUndefined, _>(&mut cx)
+        {
+            let handle = js_db_transaction
                 .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
 
             Some(***handle)
@@ -2124,13 +3276,30 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+        let transition_id = converter::js_buffer_to_identifier(&mut cx, js_transition_id)?;
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+        let amount = js_amount.value(&mut cx);
+        let core_fee = js_core_fee.value(&mut cx);
+        let pooling = match js_pooling.value(&mut cx) as u8 {
+            0 => Pooling::Never,
+            1 => Pooling::IfAvailable,
+            2 => Pooling::Standard,
+            _ => return cx.throw_type_error("invalid pooling mode"),
+        };
+        let output_script = converter::js_buffer_to_vec_u8(js_output_script, &mut cx);
+
+        let request = WithdrawalRequest {
+            transition_id,
+            identity_id,
+            amount: amount as u64,
+            core_fee: core_fee as u32,
+            pooling,
+            output_script,
+        };
 
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
@@ -2144,29 +3313,29 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let result =
-                transaction_result
-                    .map_err(|e| e.to_string())
-                    .and_then(|transaction_arg| {
-                        InitChainRequest::from_bytes(&request_bytes)
-                            .and_then(|request| platform.init_chain(request, transaction_arg))
-                            .and_then(|response| response.to_bytes())
-                            .map_err(|e| e.to_string())
-                    });
+            let mut batch = GroveDbOpBatch::new();
+
+            let result = transaction_result.and_then(|transaction_arg| {
+                platform
+                    .drive
+                    .add_enqueue_withdrawal_request_operations(&mut batch, &request)?;
+
+                platform
+                    .drive
+                    .grove_apply_batch(batch, false, transaction_arg)
+            });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(response_bytes) => {
-                        let value = JsBuffer::external(&mut task_context, response_bytes);
-
-                        vec![task_context.null().upcast(), value.upcast()]
+                    Ok(_) => {
+                        vec![task_context.null().upcast(), task_context.null().upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
-                    Err(err) => vec![task_context.error(err)?.upcast()],
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -2180,12 +3349,21 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_abci_block_begin(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_request = cx.argument::<JsBuffer>(0)?;
-        let js_transaction = cx.argument::<JsValue>(1)?;
+    fn js_apply_identity_credit_withdrawal(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_transition_id = cx.argument::<JsBuffer>(0)?;
+        let js_identity_id = cx.argument::<JsBuffer>(1)?;
+        let js_amount = cx.argument::<JsNumber>(2)?;
+        let js_core_fee = cx.argument::<JsNumber>(3)?;
+        let js_pooling = cx.argument::<JsNumber>(4)?;
+        let js_output_script = cx.argument::<JsBuffer>(5)?;
+        let js_block_info = cx.argument::<JsObject>(6)?;
+        let js_apply = cx.argument::<JsBoolean>(7)?;
+        let js_db_transaction = cx.argument::<JsValue>(8)?;
+        let js_callback = cx.argument::<JsFunction>(9)?.root(&mut cx);
 
-        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
-            let handle = js_transaction
+        let maybe_boxed_transaction_address = if !js_db_transaction.is_a::<JsUndefined, _>(&mut cx)
+        {
+            let handle = js_db_transaction
                 .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
 
             Some(***handle)
@@ -2193,13 +3371,32 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+        let transition_id = converter::js_buffer_to_identifier(&mut cx, js_transition_id)?;
+        let identity_id = converter::js_buffer_to_identifier(&mut cx, js_identity_id)?;
+        let amount = js_amount.value(&mut cx);
+        let core_fee = js_core_fee.value(&mut cx);
+        let pooling = match js_pooling.value(&mut cx) as u8 {
+            0 => Pooling::Never,
+            1 => Pooling::IfAvailable,
+            2 => Pooling::Standard,
+            _ => return cx.throw_type_error("invalid pooling mode"),
+        };
+        let output_script = converter::js_buffer_to_vec_u8(js_output_script, &mut cx);
+        let block_info = converter::js_object_to_block_info(js_block_info, &mut cx)?;
+        let apply = js_apply.value(&mut cx);
+
+        let request = WithdrawalRequest {
+            transition_id,
+            identity_id,
+            amount: amount as u64,
+            core_fee: core_fee as u32,
+            pooling,
+            output_script,
+        };
 
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
@@ -2213,29 +3410,30 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let result =
-                transaction_result
-                    .map_err(|e| e.to_string())
-                    .and_then(|transaction_arg| {
-                        BlockBeginRequest::from_bytes(&request_bytes)
-                            .and_then(|request| platform.block_begin(request, transaction_arg))
-                            .and_then(|response| response.to_bytes())
-                            .map_err(|e| e.to_string())
-                    });
+            let result = transaction_result.and_then(|transaction_arg| {
+                platform.drive.apply_identity_credit_withdrawal(
+                    request,
+                    block_info,
+                    apply,
+                    transaction_arg,
+                )
+            });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(response_bytes) => {
-                        let value = JsBuffer::external(&mut task_context, response_bytes);
+                    Ok(fee_result) => {
+                        let js_fee_result =
+                            converter::fee_result_to_js_object(&mut task_context, fee_result)?;
 
-                        vec![task_context.null().upcast(), value.upcast()]
+                        // First parameter of JS callbacks is error, which is null in this case
+                        vec![task_context.null().upcast(), js_fee_result.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
-                    Err(err) => vec![task_context.error(err)?.upcast()],
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -2249,9 +3447,11 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_abci_block_end(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_request = cx.argument::<JsBuffer>(0)?;
-        let js_transaction = cx.argument::<JsValue>(1)?;
+    fn js_fetch_queued_withdrawal_transactions(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_start_index = cx.argument::<JsNumber>(0)?;
+        let js_limit = cx.argument::<JsNumber>(1)?;
+        let js_transaction = cx.argument::<JsValue>(2)?;
+        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -2262,13 +3462,12 @@ impl PlatformWrapper {
             None
         };
 
-        let js_callback = cx.argument::<JsFunction>(2)?.root(&mut cx);
-
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+        let start_index = js_start_index.value(&mut cx) as u64;
+        let limit = js_limit.value(&mut cx) as u16;
 
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
@@ -2282,29 +3481,97 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let result =
-                transaction_result
-                    .map_err(|e| e.to_string())
-                    .and_then(|transaction_arg| {
-                        BlockEndRequest::from_bytes(&request_bytes)
-                            .and_then(|request| platform.block_end(request, transaction_arg))
-                            .and_then(|response| response.to_bytes())
-                            .map_err(|e| e.to_string())
-                    });
+            let result = transaction_result.and_then(|transaction_arg| {
+                platform.drive.fetch_queued_withdrawal_transactions(
+                    start_index,
+                    limit,
+                    transaction_arg,
+                )
+            });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(response_bytes) => {
-                        let value = JsBuffer::external(&mut task_context, response_bytes);
+                    Ok(queued_withdrawal_transactions) => {
+                        let js_result = task_context.empty_object();
+                        let js_transactions = task_context.empty_array();
+
+                        for (i, queued_transaction) in queued_withdrawal_transactions
+                            .transactions
+                            .into_iter()
+                            .enumerate()
+                        {
+                            let js_transaction = task_context.empty_object();
+
+                            let js_index = task_context.number(queued_transaction.index as f64);
+                            js_transaction.set(&mut task_context, "index", js_index)?;
+
+                            let js_amount = task_context.number(queued_transaction.amount as f64);
+                            js_transaction.set(&mut task_context, "amount", js_amount)?;
+
+                            let js_output_script = JsBuffer::external(
+                                &mut task_context,
+                                queued_transaction.output_script,
+                            );
+                            js_transaction.set(
+                                &mut task_context,
+                                "outputScript",
+                                js_output_script,
+                            )?;
+
+                            let js_fee = task_context.number(queued_transaction.fee as f64);
+                            js_transaction.set(&mut task_context, "fee", js_fee)?;
+
+                            let js_withdrawals = task_context.empty_array();
+
+                            for (j, withdrawal) in
+                                queued_transaction.withdrawals.into_iter().enumerate()
+                            {
+                                let js_withdrawal = task_context.empty_object();
+
+                                let js_identity_id = JsBuffer::external(
+                                    &mut task_context,
+                                    withdrawal.identity_id.to_vec(),
+                                );
+                                js_withdrawal.set(
+                                    &mut task_context,
+                                    "identityId",
+                                    js_identity_id,
+                                )?;
+
+                                let js_withdrawal_amount =
+                                    task_context.number(withdrawal.amount as f64);
+                                js_withdrawal.set(
+                                    &mut task_context,
+                                    "amount",
+                                    js_withdrawal_amount,
+                                )?;
+
+                                js_withdrawals.set(&mut task_context, j as u32, js_withdrawal)?;
+                            }
 
-                        vec![task_context.null().upcast(), value.upcast()]
+                            js_transaction.set(&mut task_context, "withdrawals", js_withdrawals)?;
+
+                            js_transactions.set(&mut task_context, i as u32, js_transaction)?;
+                        }
+
+                        js_result.set(&mut task_context, "transactions", js_transactions)?;
+
+                        let js_total_amount =
+                            task_context.number(queued_withdrawal_transactions.total_amount as f64);
+                        js_result.set(&mut task_context, "totalAmount", js_total_amount)?;
+
+                        let js_total_fee =
+                            task_context.number(queued_withdrawal_transactions.total_fee as f64);
+                        js_result.set(&mut task_context, "totalFee", js_total_fee)?;
+
+                        vec![task_context.null().upcast(), js_result.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
-                    Err(err) => vec![task_context.error(err)?.upcast()],
+                    Err(err) => vec![task_context.error(err.to_string())?.upcast()],
                 };
 
                 callback.call(&mut task_context, this, callback_arguments)?;
@@ -2318,30 +3585,57 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_abci_after_finalize_block(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_request = cx.argument::<JsBuffer>(0)?;
-        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+    fn js_prove_queued_withdrawal_transactions(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_start_index = cx.argument::<JsNumber>(0)?;
+        let js_limit = cx.argument::<JsNumber>(1)?;
+        let js_transaction = cx.argument::<JsValue>(2)?;
+        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
+                .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
+
+            Some(***handle)
+        } else {
+            None
+        };
 
         let db = cx
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let request_bytes = converter::js_buffer_to_vec_u8(js_request, &mut cx);
+        let start_index = js_start_index.value(&mut cx) as u64;
+        let limit = js_limit.value(&mut cx) as u16;
 
-        db.send_to_drive_thread(move |platform: &Platform, _, channel| {
-            let result = AfterFinalizeBlockRequest::from_bytes(&request_bytes)
-                .and_then(|request| platform.after_finalize_block(request))
-                .and_then(|response| response.to_bytes());
+        db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
+            let transaction_result: Result<Option<&Transaction>, Error> =
+                match maybe_boxed_transaction_address {
+                    Some(address) => transactions
+                        .get(&address)
+                        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "invalid transaction pointer address",
+                        )))
+                        .map(Some),
+                    None => Ok(None),
+                };
+
+            let result = transaction_result.and_then(|transaction_arg| {
+                platform.drive.prove_queued_withdrawal_transactions(
+                    start_index,
+                    limit,
+                    transaction_arg,
+                )
+            });
 
             channel.send(move |mut task_context| {
                 let callback = js_callback.into_inner(&mut task_context);
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(response_bytes) => {
-                        let value = JsBuffer::external(&mut task_context, response_bytes);
+                    Ok(proof) => {
+                        let js_buffer = JsBuffer::external(&mut task_context, proof);
 
-                        vec![task_context.null().upcast(), value.upcast()]
+                        vec![task_context.null().upcast(), js_buffer.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -2358,12 +3652,11 @@ impl PlatformWrapper {
         // The result is returned through the callback, not through direct return
         Ok(cx.undefined())
     }
-
-    fn js_fetch_latest_withdrawal_transaction_index(
-        mut cx: FunctionContext,
-    ) -> JsResult<JsUndefined> {
-        let js_transaction = cx.argument::<JsValue>(0)?;
-        let js_callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+    fn js_get_epochs_info(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_start_epoch_index = cx.argument::<JsNumber>(0)?;
+        let js_end_epoch_index = cx.argument::<JsNumber>(1)?;
+        let js_transaction = cx.argument::<JsValue>(2)?;
+        let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
 
         let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
             let handle = js_transaction
@@ -2378,6 +3671,9 @@ impl PlatformWrapper {
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
+        let start_epoch_index = js_start_epoch_index.value(&mut cx) as u16;
+        let end_epoch_index = js_end_epoch_index.value(&mut cx) as u16;
+
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
                 match maybe_boxed_transaction_address {
@@ -2393,7 +3689,7 @@ impl PlatformWrapper {
             let result = transaction_result.and_then(|transaction_arg| {
                 platform
                     .drive
-                    .fetch_latest_withdrawal_transaction_index(transaction_arg)
+                    .get_epochs_info(start_epoch_index..end_epoch_index, transaction_arg)
             });
 
             channel.send(move |mut task_context| {
@@ -2401,17 +3697,89 @@ impl PlatformWrapper {
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(index) => {
-                        let index_f64 = index as f64;
-                        if index_f64 as u64 != index {
-                            vec![task_context
-                                .error("could not convert withdrawal transaction index to f64")?
-                                .upcast()]
-                        } else {
-                            let value = JsNumber::new(&mut task_context, index_f64);
-
-                            vec![task_context.null().upcast(), value.upcast()]
+                    Ok(epoch_summaries) => {
+                        let js_epoch_summaries = task_context.empty_array();
+
+                        for (i, epoch_summary) in epoch_summaries.into_iter().enumerate() {
+                            let js_epoch_summary = task_context.empty_object();
+
+                            let js_index = task_context.number(epoch_summary.index);
+                            js_epoch_summary.set(&mut task_context, "index", js_index)?;
+
+                            let js_start_time_ms: Handle<JsValue> =
+                                match epoch_summary.start_time_ms {
+                                    Some(start_time_ms) => {
+                                        task_context.number(start_time_ms as f64).upcast()
+                                    }
+                                    None => task_context.null().upcast(),
+                                };
+                            js_epoch_summary.set(
+                                &mut task_context,
+                                "startTimeMs",
+                                js_start_time_ms,
+                            )?;
+
+                            let js_start_block_height: Handle<JsValue> =
+                                match epoch_summary.start_block_height {
+                                    Some(start_block_height) => {
+                                        task_context.number(start_block_height as f64).upcast()
+                                    }
+                                    None => task_context.null().upcast(),
+                                };
+                            js_epoch_summary.set(
+                                &mut task_context,
+                                "startBlockHeight",
+                                js_start_block_height,
+                            )?;
+
+                            let js_fee_multiplier: Handle<JsValue> =
+                                match epoch_summary.fee_multiplier {
+                                    Some(fee_multiplier) => {
+                                        task_context.number(fee_multiplier).upcast()
+                                    }
+                                    None => task_context.null().upcast(),
+                                };
+                            js_epoch_summary.set(
+                                &mut task_context,
+                                "feeMultiplier",
+                                js_fee_multiplier,
+                            )?;
+
+                            let js_processing_credits = task_context
+                                .number(epoch_summary.processing_credits_for_distribution as f64);
+                            js_epoch_summary.set(
+                                &mut task_context,
+                                "processingCreditsForDistribution",
+                                js_processing_credits,
+                            )?;
+
+                            let js_storage_credits = task_context
+                                .number(epoch_summary.storage_credits_for_distribution as f64);
+                            js_epoch_summary.set(
+                                &mut task_context,
+                                "storageCreditsForDistribution",
+                                js_storage_credits,
+                            )?;
+
+                            let js_proposer_count =
+                                task_context.number(epoch_summary.proposer_count);
+                            js_epoch_summary.set(
+                                &mut task_context,
+                                "proposerCount",
+                                js_proposer_count,
+                            )?;
+
+                            let js_is_paid = task_context.boolean(epoch_summary.is_paid);
+                            js_epoch_summary.set(&mut task_context, "isPaid", js_is_paid)?;
+
+                            js_epoch_summaries.set(
+                                &mut task_context,
+                                i as u32,
+                                js_epoch_summary,
+                            )?;
                         }
+
+                        vec![task_context.null().upcast(), js_epoch_summaries.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -2429,15 +3797,14 @@ impl PlatformWrapper {
         Ok(cx.undefined())
     }
 
-    fn js_enqueue_withdrawal_transaction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-        let js_index = cx.argument::<JsNumber>(0)?;
-        let js_core_transaction = cx.argument::<JsBuffer>(1)?;
-        let js_db_transaction = cx.argument::<JsValue>(2)?;
+    fn js_prove_epochs_info(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let js_start_epoch_index = cx.argument::<JsNumber>(0)?;
+        let js_end_epoch_index = cx.argument::<JsNumber>(1)?;
+        let js_transaction = cx.argument::<JsValue>(2)?;
         let js_callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
 
-        let maybe_boxed_transaction_address = if !js_db_transaction.is_a::<JsUndefined, _>(&mut cx)
-        {
-            let handle = js_db_transaction
+        let maybe_boxed_transaction_address = if !js_transaction.is_a::<JsUndefined, _>(&mut cx) {
+            let handle = js_transaction
                 .downcast_or_throw::<JsBox<PlatformWrapperTransactionAddress>, _>(&mut cx)?;
 
             Some(***handle)
@@ -2449,8 +3816,8 @@ impl PlatformWrapper {
             .this()
             .downcast_or_throw::<JsBox<PlatformWrapper>, _>(&mut cx)?;
 
-        let index = js_index.value(&mut cx);
-        let transaction_bytes = converter::js_buffer_to_vec_u8(js_core_transaction, &mut cx);
+        let start_epoch_index = js_start_epoch_index.value(&mut cx) as u16;
+        let end_epoch_index = js_end_epoch_index.value(&mut cx) as u16;
 
         db.send_to_drive_thread(move |platform: &Platform, transactions, channel| {
             let transaction_result: Result<Option<&Transaction>, Error> =
@@ -2464,24 +3831,10 @@ impl PlatformWrapper {
                     None => Ok(None),
                 };
 
-            let mut batch = GroveDbOpBatch::new();
-
-            let index_bytes = (index as u64).to_be_bytes().to_vec();
-
-            let withdrawals = vec![(index_bytes.clone(), transaction_bytes)];
-
-            platform
-                .drive
-                .add_enqueue_withdrawal_transaction_operations(&mut batch, withdrawals);
-
-            platform
-                .drive
-                .add_update_withdrawal_index_counter_operation(&mut batch, index_bytes);
-
             let result = transaction_result.and_then(|transaction_arg| {
                 platform
                     .drive
-                    .grove_apply_batch(batch, false, transaction_arg)
+                    .prove_epochs_info(start_epoch_index..end_epoch_index, transaction_arg)
             });
 
             channel.send(move |mut task_context| {
@@ -2489,8 +3842,10 @@ impl PlatformWrapper {
                 let this = task_context.undefined();
 
                 let callback_arguments: Vec<Handle<JsValue>> = match result {
-                    Ok(_) => {
-                        vec![task_context.null().upcast(), task_context.null().upcast()]
+                    Ok(proof) => {
+                        let js_buffer = JsBuffer::external(&mut task_context, proof);
+
+                        vec![task_context.null().upcast(), js_buffer.upcast()]
                     }
 
                     // Convert the error to a JavaScript exception on failure
@@ -2523,10 +3878,40 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("driveCreateDocument", PlatformWrapper::js_create_document)?;
     cx.export_function("driveUpdateDocument", PlatformWrapper::js_update_document)?;
     cx.export_function("driveDeleteDocument", PlatformWrapper::js_delete_document)?;
+    cx.export_function(
+        "driveEstimateDocumentTransitionsFee",
+        PlatformWrapper::js_estimate_document_transitions_fee,
+    )?;
+    cx.export_function(
+        "driveEstimateContractCreationFee",
+        PlatformWrapper::js_estimate_contract_creation_fee,
+    )?;
     cx.export_function(
         "driveInsertIdentity",
         PlatformWrapper::js_insert_identity_cbor,
     )?;
+    cx.export_function(
+        "driveUpdateIdentity",
+        PlatformWrapper::js_update_identity_cbor,
+    )?;
+    cx.export_function(
+        "driveAddToIdentityBalance",
+        PlatformWrapper::js_add_to_identity_balance,
+    )?;
+    cx.export_function(
+        "driveRemoveFromIdentityBalance",
+        PlatformWrapper::js_remove_from_identity_balance,
+    )?;
+    cx.export_function(
+        "driveChargeIdentityFees",
+        PlatformWrapper::js_charge_identity_fees,
+    )?;
+    cx.export_function(
+        "driveFetchIdentityWithDebt",
+        PlatformWrapper::js_fetch_identity_with_debt,
+    )?;
+    cx.export_function("driveFetchSmlStore", PlatformWrapper::js_fetch_sml_store)?;
+    cx.export_function("driveProveIdentity", PlatformWrapper::js_prove_identity)?;
     cx.export_function("driveQueryDocuments", PlatformWrapper::js_query_documents)?;
 
     cx.export_function(
@@ -2542,6 +3927,27 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         "driveEnqueueWithdrawalTransaction",
         PlatformWrapper::js_enqueue_withdrawal_transaction,
     )?;
+    cx.export_function(
+        "driveEnqueueWithdrawalRequest",
+        PlatformWrapper::js_enqueue_withdrawal_request,
+    )?;
+    cx.export_function(
+        "driveApplyIdentityCreditWithdrawal",
+        PlatformWrapper::js_apply_identity_credit_withdrawal,
+    )?;
+    cx.export_function(
+        "driveFetchQueuedWithdrawalTransactions",
+        PlatformWrapper::js_fetch_queued_withdrawal_transactions,
+    )?;
+    cx.export_function(
+        "driveProveQueuedWithdrawalTransactions",
+        PlatformWrapper::js_prove_queued_withdrawal_transactions,
+    )?;
+    cx.export_function("driveGetEpochsInfo", PlatformWrapper::js_get_epochs_info)?;
+    cx.export_function(
+        "driveProveEpochsInfo",
+        PlatformWrapper::js_prove_epochs_info,
+    )?;
 
     cx.export_function("groveDbInsert", PlatformWrapper::js_grove_db_insert)?;
     cx.export_function(
@@ -2581,6 +3987,7 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     )?;
     cx.export_function("groveDbRootHash", PlatformWrapper::js_grove_db_root_hash)?;
 
+    cx.export_function("abciInfo", PlatformWrapper::js_abci_info)?;
     cx.export_function("abciInitChain", PlatformWrapper::js_abci_init_chain)?;
     cx.export_function("abciBlockBegin", PlatformWrapper::js_abci_block_begin)?;
     cx.export_function("abciBlockEnd", PlatformWrapper::js_abci_block_end)?;
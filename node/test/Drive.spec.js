@@ -1,4 +1,5 @@
 const fs = require('fs');
+const cbor = require('cbor');
 
 const { expect, use } = require('chai');
 use(require('dirty-chai'));
@@ -7,6 +8,7 @@ const Document = require('@dashevo/dpp/lib/document/Document');
 
 const getDataContractFixture = require('@dashevo/dpp/lib/test/fixtures/getDataContractFixture');
 const getDocumentsFixture = require('@dashevo/dpp/lib/test/fixtures/getDocumentsFixture');
+const getDocumentTransitionsFixture = require('@dashevo/dpp/lib/test/fixtures/getDocumentTransitionsFixture');
 const getIdentityFixture = require('@dashevo/dpp/lib/test/fixtures/getIdentityFixture');
 
 const {
@@ -38,6 +40,7 @@ describe('Drive', () => {
       height: 1,
       epoch: 1,
       timeMs: new Date().getTime(),
+      protocolVersion: 1,
     };
 
     documents = getDocumentsFixture(dataContract);
@@ -129,6 +132,22 @@ describe('Drive', () => {
 
       expect(await drive.getGroveDB().getRootHash()).to.deep.equals(initialRootHash);
     });
+
+    it('should charge fees of the current protocol version if protocol version is not set', async () => {
+      const { protocolVersion, ...blockInfoWithoutProtocolVersion } = blockInfo;
+
+      const result = await drive.createContract(
+        dataContract,
+        blockInfoWithoutProtocolVersion,
+        undefined,
+        true,
+      );
+
+      const expectedResult = await drive.createContract(dataContract, blockInfo, undefined, true);
+
+      expect(protocolVersion).to.equal(1);
+      expect(result).to.deep.equal(expectedResult);
+    });
   });
 
   describe('#updateContract', () => {
@@ -365,6 +384,47 @@ describe('Drive', () => {
     });
   });
 
+  describe('#estimateDocumentTransitionsFee', () => {
+    it('should estimate fees without changing the state', async () => {
+      await drive.createInitialStateStructure();
+
+      initialRootHash = await drive.getGroveDB().getRootHash();
+
+      const documentTransitions = getDocumentTransitionsFixture({
+        create: documents,
+      });
+
+      const result = await drive.estimateDocumentTransitionsFee(
+        dataContract,
+        documentTransitions,
+        blockInfo,
+      );
+
+      expect(result).to.have.property('processingFee');
+      expect(result).to.have.property('storageFee');
+
+      expect(result.processingFee).to.be.greaterThan(0, 'processing fee must be higher than 0');
+      expect(result.storageFee).to.be.greaterThan(0, 'storage fee must be higher than 0');
+
+      expect(await drive.getGroveDB().getRootHash()).to.deep.equals(initialRootHash);
+    });
+  });
+
+  describe('#estimateContractCreationFee', () => {
+    it('should estimate fees without changing the state', async () => {
+      await drive.createInitialStateStructure();
+
+      initialRootHash = await drive.getGroveDB().getRootHash();
+
+      const result = await drive.estimateContractCreationFee(dataContract, blockInfo);
+
+      expect(result.processingFee).to.be.greaterThan(0, 'processing fee must be higher than 0');
+      expect(result.storageFee).to.be.greaterThan(0, 'storage fee must be higher than 0');
+
+      expect(await drive.getGroveDB().getRootHash()).to.deep.equals(initialRootHash);
+    });
+  });
+
   describe('#queryDocuments', () => {
     beforeEach(async () => {
       await drive.createInitialStateStructure();
@@ -462,6 +522,146 @@ describe('Drive', () => {
 
       expect(await drive.getGroveDB().getRootHash()).to.not.deep.equals(initialRootHash);
     });
+
+    it('should update identity if exists', async () => {
+      await drive.insertIdentity(identity, blockInfo);
+
+      const insertedRootHash = await drive.getGroveDB().getRootHash();
+
+      identity.setRevision(identity.getRevision() + 1);
+      identity.setBalance(identity.getBalance() + 100);
+
+      const result = await drive.insertIdentity(identity, blockInfo);
+
+      expectFeeResult(result);
+
+      expect(await drive.getGroveDB().getRootHash()).to.not.deep.equals(insertedRootHash);
+    });
+  });
+
+  describe('#updateIdentity', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+    });
+
+    it('should update identity', async () => {
+      identity.setRevision(identity.getRevision() + 1);
+
+      const result = await drive.updateIdentity(identity, blockInfo);
+
+      expectFeeResult(result);
+    });
+
+    it('should throw an error if identity balance is changed', async () => {
+      identity.setBalance(identity.getBalance() + 1);
+
+      try {
+        await drive.updateIdentity(identity, blockInfo);
+
+        expect.fail('should throw an error');
+      } catch (e) {
+        expect(e.message).to.contain('invalid identity balance change');
+      }
+    });
+  });
+
+  describe('#addToIdentityBalance', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+    });
+
+    it('should add to identity balance', async () => {
+      const result = await drive.addToIdentityBalance(identity.getId(), 100, blockInfo);
+
+      expectFeeResult(result);
+    });
+  });
+
+  describe('#removeFromIdentityBalance', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+    });
+
+    it('should remove from identity balance', async () => {
+      const result = await drive.removeFromIdentityBalance(identity.getId(), 1, blockInfo);
+
+      expectFeeResult(result);
+    });
+  });
+
+  describe('#chargeIdentityFees', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+    });
+
+    it('should record a debt if fees exceed identity balance', async () => {
+      const result = await drive.chargeIdentityFees(
+        identity.getId(),
+        { storageFee: identity.getBalance(), processingFee: 10 },
+        blockInfo,
+      );
+
+      expectFeeResult(result);
+
+      const [identityBuffer, debt] = await drive.fetchIdentityWithDebt(identity.getId());
+
+      expect(identityBuffer).to.be.an.instanceOf(Buffer);
+      expect(debt).to.equal(10);
+    });
+  });
+
+  describe('#fetchIdentityWithDebt', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+    });
+
+    it('should return identity without debt', async () => {
+      const [identityBuffer, debt] = await drive.fetchIdentityWithDebt(identity.getId());
+
+      expect(identityBuffer).to.be.an.instanceOf(Buffer);
+      expect(debt).to.equal(0);
+    });
+  });
+
+  describe('#fetchSmlStore', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+    });
+
+    it('should return serialized empty masternode list store', async () => {
+      const smlStoreBuffer = await drive.fetchSmlStore();
+
+      expect(smlStoreBuffer).to.be.an.instanceOf(Buffer);
+      expect(cbor.decode(smlStoreBuffer)).to.deep.equal({
+        sml_list_by_height: { masternodes: [] },
+        sml_list_current: { masternodes: [] },
+      });
+    });
+  });
+
+  describe('#proveIdentity', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+    });
+
+    it('should return proof', async () => {
+      const result = await drive.proveIdentity(identity.getId());
+
+      expect(result).to.be.an.instanceOf(Buffer);
+      expect(result.length).to.be.greaterThan(0);
+    });
   });
 
   describe('#fetchLatestWithdrawalTransactionIndex', () => {
@@ -482,7 +682,7 @@ describe('Drive', () => {
     });
 
     it('should enqueue withdrawal transaction into the queue', async () => {
-      await drive.enqueueWithdrawalTransaction(1, Buffer.alloc(32, 1));
+      await drive.enqueueWithdrawalTransaction(1, Buffer.alloc(32, 2), 100, Buffer.alloc(32, 1));
 
       const result = await drive.fetchLatestWithdrawalTransactionIndex();
 
@@ -490,7 +690,121 @@ describe('Drive', () => {
     });
   });
 
+  describe('#applyIdentityCreditWithdrawal', () => {
+    let request;
+
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+
+      await drive.insertIdentity(identity, blockInfo);
+
+      request = {
+        transitionId: Buffer.alloc(32, 1),
+        identityId: identity.getId().toBuffer(),
+        amount: identity.getBalance(),
+        coreFee: 1,
+        pooling: 0,
+        outputScript: Buffer.alloc(23, 2),
+      };
+    });
+
+    it('should enqueue withdrawal request and debit identity balance', async () => {
+      const result = await drive.applyIdentityCreditWithdrawal(request, blockInfo);
+
+      expectFeeResult(result);
+    });
+
+    it('should throw an error if identity balance is insufficient', async () => {
+      request.amount = identity.getBalance() + 1;
+
+      try {
+        await drive.applyIdentityCreditWithdrawal(request, blockInfo);
+
+        expect.fail('should throw an error');
+      } catch (e) {
+        expect(e.message).to.contain('identity insufficient balance');
+      }
+    });
+  });
+
+  describe('#fetchQueuedWithdrawalTransactions', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+    });
+
+    it('should return empty result if the queue is empty', async () => {
+      const result = await drive.fetchQueuedWithdrawalTransactions(0, 10);
+
+      expect(result).to.deep.equal({
+        transactions: [],
+        totalAmount: 0,
+        totalFee: 0,
+      });
+    });
+  });
+
+  describe('#proveQueuedWithdrawalTransactions', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+    });
+
+    it('should return proof', async () => {
+      const result = await drive.proveQueuedWithdrawalTransactions(0, 10);
+
+      expect(result).to.be.an.instanceOf(Buffer);
+      expect(result.length).to.be.greaterThan(0);
+    });
+  });
+
+  describe('#getEpochsInfo', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+    });
+
+    it('should return summaries of not started epochs', async () => {
+      const result = await drive.getEpochsInfo(0, 2);
+
+      expect(result).to.have.lengthOf(2);
+      expect(result[1]).to.deep.equal({
+        index: 1,
+        startTimeMs: null,
+        startBlockHeight: null,
+        feeMultiplier: null,
+        processingCreditsForDistribution: 0,
+        storageCreditsForDistribution: 0,
+        proposerCount: 0,
+        isPaid: false,
+      });
+    });
+  });
+
+  describe('#proveEpochsInfo', () => {
+    beforeEach(async () => {
+      await drive.createInitialStateStructure();
+    });
+
+    it('should return proof', async () => {
+      const result = await drive.proveEpochsInfo(0, 2);
+
+      expect(result).to.be.an.instanceOf(Buffer);
+      expect(result.length).to.be.greaterThan(0);
+    });
+  });
+
   describe('ABCI', () => {
+    describe('Info', () => {
+      it('should return empty info if there are no committed blocks', async () => {
+        await drive.getAbci().initChain({});
+
+        const response = await drive.getAbci().info({});
+
+        expect(response).to.deep.equal({
+          lastBlockHeight: 0,
+          lastBlockAppHash: null,
+        });
+      });
+    });
+
     describe('InitChain', () => {
       it('should successfully init chain', async () => {
         const request = {};
@@ -512,11 +826,17 @@ describe('Drive', () => {
           blockTimeMs: (new Date()).getTime(),
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          proposedProtocolVersion: 1,
+          coreChainLockedHeight: 1,
+          broadcastedWithdrawalTransactionIndexes: [],
+          completedWithdrawalTransactionIndexes: [],
         };
 
         const response = await drive.getAbci().blockBegin(request);
 
         expect(response.unsignedWithdrawalTransactions).to.be.empty();
+        expect(response.protocolVersion).to.equal(1);
+        expect(response.feeMultiplier).to.equal(2);
         expect(response.epochInfo).to.deep.equal({
           currentEpochIndex: 0,
           isEpochChange: true,
@@ -524,7 +844,7 @@ describe('Drive', () => {
         });
       });
 
-      it('should process a block with previous block time', async () => {
+      it('should process a block with previous block time from the last committed block', async () => {
         const blockTimeMs = (new Date()).getTime();
 
         await drive.getAbci().blockBegin({
@@ -532,17 +852,37 @@ describe('Drive', () => {
           blockTimeMs,
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          proposedProtocolVersion: 1,
+          coreChainLockedHeight: 1,
+          broadcastedWithdrawalTransactionIndexes: [],
+          completedWithdrawalTransactionIndexes: [],
+        });
+
+        await drive.getAbci().blockEnd({
+          fees: {
+            storageFees: 100,
+            processingFees: 100,
+          },
+        });
+
+        await drive.getAbci().afterFinalizeBlock({
+          updatedDataContractIds: [],
         });
 
         const response = await drive.getAbci().blockBegin({
           blockHeight: 2,
           blockTimeMs: blockTimeMs + 100,
           proposerProTxHash: Buffer.alloc(32, 1),
-          previousBlockTimeMs: blockTimeMs,
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          proposedProtocolVersion: 1,
+          coreChainLockedHeight: 1,
+          broadcastedWithdrawalTransactionIndexes: [],
+          completedWithdrawalTransactionIndexes: [],
         });
 
         expect(response.unsignedWithdrawalTransactions).to.be.empty();
+        expect(response.protocolVersion).to.equal(1);
+        expect(response.feeMultiplier).to.equal(2);
         expect(response.epochInfo).to.deep.equal({
           currentEpochIndex: 0,
           isEpochChange: false,
@@ -559,6 +899,10 @@ describe('Drive', () => {
           blockTimeMs: (new Date()).getTime(),
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          proposedProtocolVersion: 1,
+          coreChainLockedHeight: 1,
+          broadcastedWithdrawalTransactionIndexes: [],
+          completedWithdrawalTransactionIndexes: [],
         });
       });
 
@@ -574,6 +918,31 @@ describe('Drive', () => {
 
         expect(response).to.have.property('proposersPaidCount');
         expect(response).to.have.property('paidEpochIndex');
+        expect(response.appHash).to.be.an.instanceOf(Buffer);
+        expect(response.appHash).to.have.lengthOf(32);
+        expect(response.consensusParamUpdates).to.be.null();
+      });
+
+      it('should pool withdrawal requests into withdrawal transactions', async () => {
+        await drive.enqueueWithdrawalRequest({
+          transitionId: Buffer.alloc(32, 1),
+          identityId: Buffer.alloc(32, 2),
+          amount: 10000,
+          coreFee: 1,
+          pooling: 2,
+          outputScript: Buffer.alloc(23, 3),
+        });
+
+        await drive.getAbci().blockEnd({
+          fees: {
+            storageFees: 100,
+            processingFees: 100,
+          },
+        });
+
+        const result = await drive.fetchLatestWithdrawalTransactionIndex();
+
+        expect(result).to.equal(1);
       });
     });
 
@@ -590,6 +959,10 @@ describe('Drive', () => {
           blockTimeMs: (new Date()).getTime(),
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          proposedProtocolVersion: 1,
+          coreChainLockedHeight: 1,
+          broadcastedWithdrawalTransactionIndexes: [],
+          completedWithdrawalTransactionIndexes: [],
         });
         await drive.getAbci().blockEnd({
           fees: {